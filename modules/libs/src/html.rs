@@ -3,62 +3,137 @@ use std::collections::{HashMap, HashSet};
 
 use comrak::{
     format_html,
-    nodes::{AstNode, NodeCodeBlock, NodeLink, NodeValue, NodeWikiLink},
-    options::Extension,
-    parse_document, Arena, Options,
+    nodes::{Ast, AstNode, NodeCodeBlock, NodeLink, NodeValue, NodeWikiLink},
+    Arena, Options,
 };
 use url::Url;
 
+use crate::markdown::{
+    document::Document,
+    query::{options, parse},
+};
 use crate::model::{
     base_url::BaseUrl,
     content::{Content, ContentElement},
-    file::ScrapFileStem,
+    context::Ctx,
+    file::{AssetPath, ScrapFileStem},
     key::ScrapKey,
+    scrap::Scrap,
     title::Title,
 };
 use crate::{model::tag::Tag, slugify};
 
 pub enum EmbedMode<'a> {
-    Expand(&'a HashMap<ScrapKey, String>),
+    /// Expand `![[embed]]` with the body of the scrap it names.
+    Expand(&'a HashMap<ScrapKey, &'a Scrap>),
     Preserve,
 }
 
-fn options() -> Options<'static> {
-    Options {
-        extension: Extension {
-            wikilinks_title_after_pipe: true,
-            autolink: true,
-            table: true,
-            strikethrough: true,
-            tasklist: true,
-            footnotes: true,
-            superscript: true,
-            math_dollars: true,
-            ..Extension::default()
-        },
-        ..Options::default()
-    }
+/// Render `text`, a scrap in `ctx`, to HTML content. Relative image and link
+/// URLs are resolved against the scrap's directory; those naming one of
+/// `assets` point at its copy under `scraps/`, the rest are left as written.
+pub fn to_content(
+    text: &str,
+    ctx: &Option<Ctx>,
+    assets: &HashSet<AssetPath>,
+    base_url: &BaseUrl,
+    embed_mode: EmbedMode<'_>,
+) -> Content {
+    to_content_inner(
+        text,
+        ctx,
+        assets,
+        base_url,
+        &embed_mode,
+        &mut HashSet::new(),
+    )
 }
 
-pub fn to_content(text: &str, base_url: &BaseUrl, embed_mode: EmbedMode<'_>) -> Content {
-    to_content_inner(text, base_url, &embed_mode, &mut HashSet::new())
+/// [`to_content`] for an already parsed `doc`, rendered from its tree.
+pub(crate) fn document_to_content(
+    doc: &Document,
+    ctx: &Option<Ctx>,
+    assets: &HashSet<AssetPath>,
+    base_url: &BaseUrl,
+    embed_mode: EmbedMode<'_>,
+) -> Content {
+    document_content(doc, ctx, assets, base_url, &embed_mode, &mut HashSet::new())
+}
+
+fn document_content(
+    doc: &Document,
+    ctx: &Option<Ctx>,
+    assets: &HashSet<AssetPath>,
+    base_url: &BaseUrl,
+    embed_mode: &EmbedMode<'_>,
+    visited_embeds: &mut HashSet<ScrapKey>,
+) -> Content {
+    let arena = Arena::new();
+    let root = doc.root(&arena, matches!(embed_mode, EmbedMode::Expand(_)));
+    render(
+        &arena,
+        root,
+        doc.text(),
+        ctx,
+        assets,
+        base_url,
+        embed_mode,
+        visited_embeds,
+    )
+}
+
+/// URLs standing alone in a top-level paragraph, which `to_content` emits as
+/// `ContentElement::Autolink`.
+pub fn autolinks(text: &str) -> Vec<Url> {
+    let arena = Arena::new();
+    let root = parse(&arena, text);
+    root.children()
+        .filter_map(autolink_paragraph_url)
+        .filter_map(|url| Url::parse(&url).ok())
+        .collect()
 }
 
 fn to_content_inner(
     text: &str,
+    ctx: &Option<Ctx>,
+    assets: &HashSet<AssetPath>,
     base_url: &BaseUrl,
     embed_mode: &EmbedMode<'_>,
     visited_embeds: &mut HashSet<ScrapKey>,
 ) -> Content {
     let arena = Arena::new();
-    let opts = options();
     let parse_text = match embed_mode {
         EmbedMode::Expand(_) => expose_embed_wikilinks(text),
         EmbedMode::Preserve => Cow::Borrowed(text),
     };
-    let root = parse_document(&arena, &parse_text, &opts);
+    let root = parse(&arena, &parse_text);
+    render(
+        &arena,
+        root,
+        text,
+        ctx,
+        assets,
+        base_url,
+        embed_mode,
+        visited_embeds,
+    )
+}
 
-    transform_wiki_refs(root, text, base_url, embed_mode, visited_embeds);
+#[allow(clippy::too_many_arguments)]
+fn render<'a>(
+    arena: &'a Arena<'a>,
+    root: &'a AstNode<'a>,
+    text: &str,
+    ctx: &Option<Ctx>,
+    assets: &HashSet<AssetPath>,
+    base_url: &BaseUrl,
+    embed_mode: &EmbedMode<'_>,
+    visited_embeds: &mut HashSet<ScrapKey>,
+) -> Content {
+    let opts = options();
+    restore_relaxed_task_items(arena, root);
+    transform_asset_urls(root, ctx, assets, base_url);
+    transform_wiki_refs(root, text, assets, base_url, embed_mode, visited_embeds);
 
     let mut elements = Vec::new();
     for child in root.children() {
@@ -122,9 +197,110 @@ fn autolink_paragraph_url<'a>(node: &'a AstNode<'a>) -> Option<String> {
     }
 }
 
+/// The shared parse options also match `[-]` and other symbols as task items,
+/// for the queries. Only `[ ]`, `[x]` and `[X]` are checkboxes in HTML, so
+/// turn the rest back into list items that start with their marker.
+fn restore_relaxed_task_items<'a>(arena: &'a Arena<'a>, root: &'a AstNode<'a>) {
+    let relaxed: Vec<_> = root
+        .descendants()
+        .filter_map(|node| match &node.data().value {
+            NodeValue::TaskItem(task) => match task.symbol {
+                Some(symbol) if !matches!(symbol, 'x' | 'X') => Some((node, symbol)),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect();
+    for (node, symbol) in relaxed {
+        let Some(NodeValue::List(list)) = node.parent().map(|p| p.data().value.clone()) else {
+            continue;
+        };
+        let start = node.data().sourcepos.start;
+        node.data_mut().value = NodeValue::Item(list);
+
+        let marker = NodeValue::Text(Cow::Owned(format!("[{symbol}] ")));
+        let marker = arena.alloc(Ast::new(marker, start).into());
+        match node.first_child() {
+            Some(first) if matches!(first.data().value, NodeValue::Paragraph) => {
+                first.prepend(marker)
+            }
+            _ => {
+                let paragraph = arena.alloc(Ast::new(NodeValue::Paragraph, start).into());
+                paragraph.append(marker);
+                node.prepend(paragraph);
+            }
+        }
+    }
+}
+
+fn transform_asset_urls<'a>(
+    root: &'a AstNode<'a>,
+    ctx: &Option<Ctx>,
+    assets: &HashSet<AssetPath>,
+    base_url: &BaseUrl,
+) {
+    for node in root.descendants() {
+        let mut data = node.data_mut();
+        let (NodeValue::Link(link) | NodeValue::Image(link)) = &mut data.value else {
+            continue;
+        };
+        if let Some(url) = asset_url(&link.url, ctx, assets, base_url) {
+            link.url = url;
+        }
+    }
+}
+
+/// URL of the co-located asset a relative `url` in a scrap of `ctx` refers
+/// to, when it is one of `assets`. Absolute URLs, fragments, links to other
+/// Markdown files, paths climbing above the wiki root and files that were not
+/// found are left as written.
+fn asset_url(
+    url: &str,
+    ctx: &Option<Ctx>,
+    assets: &HashSet<AssetPath>,
+    base_url: &BaseUrl,
+) -> Option<String> {
+    if url.is_empty() || url.starts_with(['/', '#', '?']) || Url::parse(url).is_ok() {
+        return None;
+    }
+    let (path, suffix) = url.split_at(url.find(['?', '#']).unwrap_or(url.len()));
+    if matches!(path.rsplit('/').next(), Some("" | "." | "..")) || path.ends_with(".md") {
+        return None;
+    }
+
+    let mut segments: Vec<String> = ctx
+        .as_ref()
+        .map(|ctx| ctx.segments().to_vec())
+        .unwrap_or_default();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop()?;
+            }
+            segment => segments.push(
+                percent_encoding::percent_decode_str(segment)
+                    .decode_utf8_lossy()
+                    .into_owned(),
+            ),
+        }
+    }
+    let asset = AssetPath::new(&segments).filter(|asset| assets.contains(asset))?;
+
+    let mut asset_url = base_url.as_url().clone();
+    asset_url
+        .path_segments_mut()
+        .ok()?
+        .pop_if_empty()
+        .push("scraps")
+        .extend(asset.segments());
+    Some(format!("{asset_url}{suffix}"))
+}
+
 fn transform_wiki_refs<'a>(
     root: &'a AstNode<'a>,
     source_text: &str,
+    assets: &HashSet<AssetPath>,
     base_url: &BaseUrl,
     embed_mode: &EmbedMode<'_>,
     visited_embeds: &mut HashSet<ScrapKey>,
@@ -152,8 +328,8 @@ fn transform_wiki_refs<'a>(
         }
 
         if prefix == Some(&b'!') {
-            if let EmbedMode::Expand(scrap_texts) = embed_mode {
-                transform_embed(node, base_url, &url, scrap_texts, visited_embeds);
+            if let EmbedMode::Expand(scraps) = embed_mode {
+                transform_embed(node, assets, base_url, &url, scraps, visited_embeds);
             }
             continue;
         }
@@ -202,9 +378,10 @@ fn transform_tag_link<'a>(node: &'a AstNode<'a>, base_url: &BaseUrl, url: &str)
 
 fn transform_embed<'a>(
     node: &'a AstNode<'a>,
+    assets: &HashSet<AssetPath>,
     base_url: &BaseUrl,
     url: &str,
-    scrap_texts: &HashMap<ScrapKey, String>,
+    scraps: &HashMap<ScrapKey, &Scrap>,
     visited_embeds: &mut HashSet<ScrapKey>,
 ) {
     let (path, heading) = match url.split_once('#') {
@@ -213,7 +390,7 @@ fn transform_embed<'a>(
     };
     let scrap_key = ScrapKey::from_path_str(path);
 
-    let Some(text) = scrap_texts.get(&scrap_key) else {
+    let Some(scrap) = scraps.get(&scrap_key) else {
         detach_children(node);
         node.data_mut().value = NodeValue::Raw(format!(
             "<div class=\"scrap-embed scrap-embed-missing\">{}</div>",
@@ -231,15 +408,26 @@ fn transform_embed<'a>(
         return;
     }
 
-    let embed_text = heading
-        .and_then(|h| crate::markdown::query::section(text, &slugify::by_dash(h)))
-        .unwrap_or(text);
-    let embedded = to_content_inner(
-        embed_text,
-        base_url,
-        &EmbedMode::Expand(scrap_texts),
-        visited_embeds,
-    );
+    let doc = scrap.document();
+    let embed_mode = EmbedMode::Expand(scraps);
+    let embedded = match heading.and_then(|h| doc.section(&slugify::by_dash(h))) {
+        Some(section) => to_content_inner(
+            section,
+            scrap_key.ctx(),
+            assets,
+            base_url,
+            &embed_mode,
+            visited_embeds,
+        ),
+        None => document_content(
+            doc,
+            scrap_key.ctx(),
+            assets,
+            base_url,
+            &embed_mode,
+            visited_embeds,
+        ),
+    };
     visited_embeds.remove(&scrap_key);
 
     detach_children(node);
@@ -333,6 +521,11 @@ mod tests {
         BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap()
     }
 
+    fn scrap(path: &str, text: &str) -> Scrap {
+        let key = ScrapKey::from_path_str(path);
+        Scrap::new(&Title::from(&key).to_string(), key.ctx(), text)
+    }
+
     #[rstest]
     #[case::inline_code("`[[quote block]]`", "<p><code>[[quote block]]</code></p>\n")]
     #[case::code_block(
@@ -348,7 +541,13 @@ mod tests {
         "<pre><code class=\"language-mermaid mermaid\">flowchart LR\nid\n</code></pre>\n"
     )]
     fn it_to_html_code(base_url: BaseUrl, #[case] input: &str, #[case] expected: &str) {
-        let content = to_content(input, &base_url, EmbedMode::Preserve);
+        let content = to_content(
+            input,
+            &None,
+            &HashSet::new(),
+            &base_url,
+            EmbedMode::Preserve,
+        );
         assert_eq!(content.to_string(), expected);
     }
 
@@ -378,7 +577,13 @@ mod tests {
         "<p><a href=\"http://localhost:1112/scraps/expect-slugify.html\">expect slugify</a></p>\n"
     )]
     fn it_to_html_link(base_url: BaseUrl, #[case] input: &str, #[case] expected: &str) {
-        let content = to_content(input, &base_url, EmbedMode::Preserve);
+        let content = to_content(
+            input,
+            &None,
+            &HashSet::new(),
+            &base_url,
+            EmbedMode::Preserve,
+        );
         assert_eq!(content.to_string(), expected);
     }
 
@@ -389,23 +594,28 @@ mod tests {
     )]
     #[case::nested("#[[Programming/Rust]]", "<p>#<a href=\"http://localhost:1112/tags/programming/rust.html\">Programming/Rust</a></p>\n")]
     fn it_to_html_tag_link(base_url: BaseUrl, #[case] input: &str, #[case] expected: &str) {
-        let content = to_content(input, &base_url, EmbedMode::Preserve);
+        let content = to_content(
+            input,
+            &None,
+            &HashSet::new(),
+            &base_url,
+            EmbedMode::Preserve,
+        );
         assert_eq!(content.to_string(), expected);
     }
 
     #[test]
     fn it_to_html_embed() {
         let base_url = base_url();
-        let mut scrap_texts = HashMap::new();
-        scrap_texts.insert(
-            ScrapKey::from_path_str("target"),
-            "embedded **body**".to_string(),
-        );
+        let target = scrap("target", "embedded **body**");
+        let scraps = HashMap::from([(target.self_key(), &target)]);
 
         let content = to_content(
             "before ![[target]] after",
+            &None,
+            &HashSet::new(),
             &base_url,
-            EmbedMode::Expand(&scrap_texts),
+            EmbedMode::Expand(&scraps),
         );
 
         assert_eq!(
@@ -417,28 +627,83 @@ mod tests {
     #[test]
     fn it_to_html_embed_section() {
         let base_url = base_url();
-        let mut scrap_texts = HashMap::new();
-        scrap_texts.insert(
-            ScrapKey::from_path_str("target"),
-            "## Keep\n\nwanted\n\n## Skip\n\nignored\n".to_string(),
-        );
+        let target = scrap("target", "## Keep\n\nwanted\n\n## Skip\n\nignored\n");
+        let scraps = HashMap::from([(target.self_key(), &target)]);
 
         let content = to_content(
             "![[target#Keep]]",
+            &None,
+            &HashSet::new(),
             &base_url,
-            EmbedMode::Expand(&scrap_texts),
+            EmbedMode::Expand(&scraps),
         );
 
         assert!(content.to_string().contains("wanted"));
         assert!(!content.to_string().contains("ignored"));
     }
 
+    #[rstest]
+    #[case::open(
+        "- [ ] a\n",
+        "<ul>\n<li><input type=\"checkbox\" disabled=\"\" /> a</li>\n</ul>\n"
+    )]
+    #[case::done(
+        "- [x] a\n",
+        "<ul>\n<li><input type=\"checkbox\" checked=\"\" disabled=\"\" /> a</li>\n</ul>\n"
+    )]
+    #[case::deferred("- [-] a\n", "<ul>\n<li>[-] a</li>\n</ul>\n")]
+    fn it_to_html_task_items(base_url: BaseUrl, #[case] input: &str, #[case] expected: &str) {
+        let content = to_content(
+            input,
+            &None,
+            &HashSet::new(),
+            &base_url,
+            EmbedMode::Preserve,
+        );
+        assert_eq!(content.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case::plain("# A\n\n[[b]] #[[c]] ![](d.png)\n\n- [-] e\n")]
+    #[case::embed("before ![[target]] after\n\n`![[code]]`\n")]
+    fn it_renders_documents_like_text(base_url: BaseUrl, #[case] text: &str) {
+        let target = scrap("target", "body");
+        let scraps = HashMap::from([(target.self_key(), &target)]);
+        let doc = Document::parse(text);
+
+        assert_eq!(
+            doc.to_content(
+                &None,
+                &HashSet::new(),
+                &base_url,
+                EmbedMode::Expand(&scraps)
+            ),
+            to_content(
+                text,
+                &None,
+                &HashSet::new(),
+                &base_url,
+                EmbedMode::Expand(&scraps)
+            )
+        );
+        assert_eq!(
+            doc.to_content(&None, &HashSet::new(), &base_url, EmbedMode::Preserve),
+            to_content(text, &None, &HashSet::new(), &base_url, EmbedMode::Preserve)
+        );
+    }
+
     #[rstest]
     #[case::https("<https://example.com>", "https://example.com")]
     #[case::http("<http://example.com>", "http://example.com")]
     fn it_to_html_autolink(base_url: BaseUrl, #[case] input: &str, #[case] expected_url: &str) {
         assert_eq!(
-            to_content(input, &base_url, EmbedMode::Preserve),
+            to_content(
+                input,
+                &None,
+                &HashSet::new(),
+                &base_url,
+                EmbedMode::Preserve
+            ),
             Content::new(vec![
                 ContentElement::Raw("<p>".to_string()),
                 ContentElement::Autolink(Url::parse(expected_url).unwrap()),
@@ -446,4 +711,69 @@ mod tests {
             ])
         )
     }
+
+    #[test]
+    fn it_autolinks() {
+        let text = "<https://example.com>\n\nsee <https://inline.example.com>\n\n- <https://list.example.com>\n\nhttps://example.org/page\n";
+        assert_eq!(
+            autolinks(text),
+            vec![
+                Url::parse("https://example.com").unwrap(),
+                Url::parse("https://example.org/page").unwrap(),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::same_dir(
+        "![diagram](ownership.png)",
+        r#"<img src="http://localhost:1112/scraps/programming/rust/ownership.png" alt="diagram" />"#
+    )]
+    #[case::parent_dir(
+        "[slides](../Talks/deck%201.pdf#page=2)",
+        r#"<a href="http://localhost:1112/scraps/programming/talks/deck%201.pdf#page=2">slides</a>"#
+    )]
+    #[case::angle_brackets(
+        "![](<./My Chart.svg>)",
+        r#"<img src="http://localhost:1112/scraps/programming/rust/My%20Chart.svg" alt="" />"#
+    )]
+    #[case::absolute(
+        "![](https://example.com/a.png)",
+        r#"<img src="https://example.com/a.png" alt="" />"#
+    )]
+    #[case::root_relative("[a](/a.png)", r#"<a href="/a.png">a</a>"#)]
+    #[case::fragment("[a](#usage)", r##"<a href="#usage">a</a>"##)]
+    #[case::markdown("[a](borrowing.md)", r#"<a href="borrowing.md">a</a>"#)]
+    #[case::above_root("[a](../../../a.png)", r#"<a href="../../../a.png">a</a>"#)]
+    #[case::missing("![](missing.png)", r#"<img src="missing.png" alt="" />"#)]
+    fn it_to_html_asset_url(base_url: BaseUrl, #[case] input: &str, #[case] expected: &str) {
+        let ctx = Some(Ctx::from("Programming/Rust"));
+        let assets = HashSet::from([
+            AssetPath::new(&["Programming", "Rust", "ownership.png"]).unwrap(),
+            AssetPath::new(&["Programming", "Talks", "deck 1.pdf"]).unwrap(),
+            AssetPath::new(&["Programming", "Rust", "My Chart.svg"]).unwrap(),
+            AssetPath::new(&["a.png"]).unwrap(),
+        ]);
+        let content = to_content(input, &ctx, &assets, &base_url, EmbedMode::Preserve);
+        assert_eq!(content.to_string(), format!("<p>{expected}</p>\n"));
+    }
+
+    #[test]
+    fn it_to_html_asset_url_in_embed() {
+        let base_url = base_url();
+        let target = scrap("Book/target", "![](cover.png)");
+        let scraps = HashMap::from([(target.self_key(), &target)]);
+
+        let content = to_content(
+            "![[Book/target]]",
+            &None,
+            &HashSet::from([AssetPath::new(&["Book", "cover.png"]).unwrap()]),
+            &base_url,
+            EmbedMode::Expand(&scraps),
+        );
+
+        assert!(content
+            .to_string()
+            .contains(r#"<img src="http://localhost:1112/scraps/book/cover.png" alt="" />"#));
+    }
 }
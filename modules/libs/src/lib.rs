@@ -8,6 +8,10 @@ pub mod lang;
 pub mod markdown;
 #[cfg(feature = "model")]
 pub mod model;
+#[cfg(feature = "pandoc")]
+pub mod pandoc;
+#[cfg(feature = "readable")]
+pub mod readable;
 #[cfg(feature = "search")]
 pub mod search;
 #[cfg(feature = "slugify")]
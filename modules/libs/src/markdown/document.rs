@@ -0,0 +1,322 @@
+//! A markdown body parsed once, answering every `query` from that parse.
+//!
+//! Each free function in [`super::query`] parses its input on its own. When
+//! several of them are needed for the same text, parse a [`Document`]
+//! instead: the AST is walked once per query kind up front and the results
+//! are kept, so lookups are free afterwards. Text containing `![[embed]]`
+//! needs a second parse for the wiki references (see `wiki_refs`); all other
+//! text is parsed exactly once. The tree itself is kept too, and
+//! [`Document::to_content`] renders HTML from a copy of it.
+
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use comrak::{
+    nodes::{Ast, AstNode, NodeValue, NodeWikiLink},
+    Arena,
+};
+use url::Url;
+
+use super::query::{
+    block_context_in, collect_blocks, collect_code_blocks, collect_headings, collect_images,
+    collect_section_headings, collect_task_items, collect_wiki_refs, expose_embed_wikilinks, parse,
+    section_in, BlockContext, BlockSpan, CodeBlock, EmbedRef, Heading, SectionHeading, SourceSpan,
+    TagRef, TaskItem, WikiLinkRef, WikiRef,
+};
+
+/// An arena-free copy of a parsed tree, so a [`Document`] can own it. Nodes
+/// are kept flat in pre-order with the index of their parent, so copying in
+/// and out is a loop rather than a recursion as deep as the nesting.
+#[derive(Debug, Clone, PartialEq)]
+struct Tree {
+    nodes: Vec<(Ast, Option<usize>)>,
+}
+
+impl Tree {
+    fn new<'a>(root: &'a AstNode<'a>) -> Tree {
+        let mut nodes = Vec::new();
+        let mut open: Vec<(&'a AstNode<'a>, usize)> = Vec::new();
+        for node in root.descendants() {
+            while let Some((ancestor, _)) = open.last() {
+                if node
+                    .parent()
+                    .is_some_and(|parent| parent.same_node(ancestor))
+                {
+                    break;
+                }
+                open.pop();
+            }
+            nodes.push((node.data().clone(), open.last().map(|(_, index)| *index)));
+            open.push((node, nodes.len() - 1));
+        }
+        Tree { nodes }
+    }
+
+    #[cfg(feature = "html")]
+    fn to_node<'a>(&self, arena: &'a Arena<'a>) -> &'a AstNode<'a> {
+        let mut allocated: Vec<&'a AstNode<'a>> = Vec::with_capacity(self.nodes.len());
+        for (ast, parent) in &self.nodes {
+            let node = arena.alloc(ast.clone().into());
+            if let Some(parent) = parent {
+                allocated[*parent].append(node);
+            }
+            allocated.push(node);
+        }
+        allocated[0]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    text: Arc<str>,
+    tree: Tree,
+    /// The tree of `expose_embed_wikilinks(text)`, when that differs.
+    exposed_tree: Option<Tree>,
+    wiki_refs: Vec<(WikiRef, SourceSpan)>,
+    images: Vec<Url>,
+    headings: Vec<Heading>,
+    code_blocks: Vec<CodeBlock>,
+    task_items: Vec<TaskItem>,
+    blocks: Vec<BlockSpan>,
+    section_headings: Vec<SectionHeading>,
+}
+
+impl Document {
+    /// Parse `text`. Passing an `Arc<str>` shares it instead of copying.
+    pub fn parse(text: impl Into<Arc<str>>) -> Document {
+        let text: Arc<str> = text.into();
+        let arena = Arena::new();
+        let root = parse(&arena, &text);
+        let (wiki_refs, exposed_tree) = match expose_embed_wikilinks(&text) {
+            Cow::Borrowed(_) => (collect_wiki_refs(root, &text), None),
+            Cow::Owned(exposed) => {
+                let exposed_root = parse(&arena, &exposed);
+                (
+                    collect_wiki_refs(exposed_root, &text),
+                    Some(Tree::new(exposed_root)),
+                )
+            }
+        };
+
+        Document {
+            tree: Tree::new(root),
+            exposed_tree,
+            wiki_refs,
+            images: collect_images(root),
+            headings: collect_headings(root),
+            code_blocks: collect_code_blocks(root),
+            task_items: collect_task_items(root),
+            blocks: collect_blocks(root),
+            section_headings: collect_section_headings(root),
+            text,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Every `[[]]`-family occurrence in source order, with its span.
+    pub fn wiki_refs_with_spans(&self) -> &[(WikiRef, SourceSpan)] {
+        &self.wiki_refs
+    }
+
+    pub fn wiki_refs(&self) -> impl Iterator<Item = &WikiRef> {
+        self.wiki_refs.iter().map(|(wref, _)| wref)
+    }
+
+    pub fn wikilinks(&self) -> impl Iterator<Item = &WikiLinkRef> {
+        self.wiki_refs().filter_map(|w| match w {
+            WikiRef::Link(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    pub fn tags(&self) -> impl Iterator<Item = &TagRef> {
+        self.wiki_refs().filter_map(|w| match w {
+            WikiRef::Tag(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    pub fn embeds(&self) -> impl Iterator<Item = &EmbedRef> {
+        self.wiki_refs().filter_map(|w| match w {
+            WikiRef::Embed(r) => Some(r),
+            _ => None,
+        })
+    }
+
+    pub fn images(&self) -> &[Url] {
+        &self.images
+    }
+
+    pub fn headings(&self) -> &[Heading] {
+        &self.headings
+    }
+
+    pub fn code_blocks(&self) -> &[CodeBlock] {
+        &self.code_blocks
+    }
+
+    pub fn task_items(&self) -> &[TaskItem] {
+        &self.task_items
+    }
+
+    pub fn section(&self, heading_slug: &str) -> Option<&str> {
+        if self.text.is_empty() {
+            return None;
+        }
+        section_in(&self.text, &self.section_headings, heading_slug)
+    }
+
+    pub fn block_context(&self, line: usize) -> BlockContext {
+        block_context_in(&self.text, &self.blocks, &self.headings, line)
+    }
+
+    /// Text of the paragraphs, headings and table rows starting on `lines`,
+    /// without markdown syntax and on one line. A wikilink without a label
+    /// reads as the title it links to, as in the HTML.
+    pub fn plain_text(&self, lines: RangeInclusive<usize>) -> String {
+        let nodes = &self.tree.nodes;
+        let mut included = vec![false; nodes.len()];
+        let mut out = String::new();
+        for (index, (ast, parent)) in nodes.iter().enumerate() {
+            let starts_block = matches!(
+                ast.value,
+                NodeValue::Paragraph | NodeValue::Heading(_) | NodeValue::TableRow(_)
+            ) && lines.contains(&ast.sourcepos.start.line);
+            if !(starts_block || parent.is_some_and(|parent| included[parent])) {
+                continue;
+            }
+            included[index] = true;
+            match &ast.value {
+                NodeValue::Text(text) => {
+                    let link_url = parent.and_then(|parent| match &nodes[parent].0.value {
+                        NodeValue::WikiLink(NodeWikiLink { url }) => Some(url),
+                        _ => None,
+                    });
+                    match link_url {
+                        Some(url) if url == text => {
+                            let key = crate::model::key::ScrapKey::from_path_str(url);
+                            out.push_str(&crate::model::title::Title::from(&key).to_string());
+                        }
+                        _ => out.push_str(text),
+                    }
+                }
+                NodeValue::Code(code) => out.push_str(&code.literal),
+                NodeValue::SoftBreak
+                | NodeValue::LineBreak
+                | NodeValue::Paragraph
+                | NodeValue::Heading(_)
+                | NodeValue::TableCell => out.push(' '),
+                _ => {}
+            }
+        }
+        out.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    #[cfg(feature = "html")]
+    /// A fresh copy of the parsed tree in `arena`. With `expose_embeds` the
+    /// tree has `![[embed]]` parsed as wikilinks, as `to_content` expands them.
+    pub(crate) fn root<'a>(&self, arena: &'a Arena<'a>, expose_embeds: bool) -> &'a AstNode<'a> {
+        match &self.exposed_tree {
+            Some(tree) if expose_embeds => tree.to_node(arena),
+            _ => self.tree.to_node(arena),
+        }
+    }
+
+    /// Render the body, a scrap in `ctx`, to HTML content without parsing it
+    /// again. See [`crate::html::to_content`] for `assets`.
+    #[cfg(feature = "html")]
+    pub fn to_content(
+        &self,
+        ctx: &Option<crate::model::context::Ctx>,
+        assets: &std::collections::HashSet<crate::model::file::AssetPath>,
+        base_url: &crate::model::base_url::BaseUrl,
+        embed_mode: crate::html::EmbedMode<'_>,
+    ) -> crate::model::content::Content {
+        crate::html::document_to_content(self, ctx, assets, base_url, embed_mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::query;
+    use super::*;
+    use rstest::rstest;
+
+    const TEXT: &str = "# Guide #[[rust]]\n\
+        \n\
+        ## Setup\n\
+        \n\
+        See [[cargo#install|Cargo]] and ![[snippet]].\n\
+        \n\
+        ![logo](https://example.com/logo.png)\n\
+        \n\
+        - [ ] try it\n\
+        - [x] read [[Book/rustup]]\n\
+        \n\
+        ```sh\n\
+        cargo new ![[not-an-embed]]\n\
+        ```\n\
+        \n\
+        ## Next\n\
+        \n\
+        done\n";
+
+    #[rstest]
+    #[case::with_embeds(TEXT)]
+    #[case::without_embeds("# A\n\n[[x]] #[[t]]\n\n## B\n\n- [-] later\n")]
+    #[case::empty("")]
+    fn it_matches_the_free_functions(#[case] text: &str) {
+        let doc = Document::parse(text);
+
+        assert_eq!(doc.text(), text);
+        assert_eq!(
+            doc.wiki_refs_with_spans(),
+            query::wiki_refs_with_spans(text)
+        );
+        assert_eq!(
+            doc.wikilinks().cloned().collect::<Vec<_>>(),
+            query::wikilinks(text)
+        );
+        assert_eq!(doc.tags().cloned().collect::<Vec<_>>(), query::tags(text));
+        assert_eq!(
+            doc.embeds().cloned().collect::<Vec<_>>(),
+            query::embeds(text)
+        );
+        assert_eq!(doc.images(), query::images(text));
+        assert_eq!(doc.headings(), query::headings(text));
+        assert_eq!(doc.code_blocks(), query::code_blocks(text));
+        assert_eq!(doc.task_items(), query::task_items(text));
+        for slug in ["guide-rust", "setup", "next", "missing"] {
+            assert_eq!(doc.section(slug), query::section(text, slug));
+        }
+        for line in 1..=text.lines().count() {
+            assert_eq!(doc.block_context(line), query::block_context(text, line));
+        }
+    }
+
+    #[rstest]
+    #[case::paragraph(18..=18, "done")]
+    #[case::inline_syntax(5..=5, "See Cargo and ![[snippet]].")]
+    #[case::list_item(10..=10, "read rustup")]
+    #[case::heading(1..=1, "Guide #rust")]
+    #[case::span(3..=5, "Setup See Cargo and ![[snippet]].")]
+    fn it_plain_text(#[case] lines: RangeInclusive<usize>, #[case] expected: &str) {
+        assert_eq!(Document::parse(TEXT).plain_text(lines), expected);
+    }
+
+    #[cfg(feature = "html")]
+    #[test]
+    fn it_copies_deeply_nested_trees_without_recursion() {
+        let text = format!("{} deep", "> ".repeat(20_000));
+        let doc = Document::parse(text.as_str());
+        let arena = Arena::new();
+        let root = doc.root(&arena, false);
+        assert!(doc.tree.nodes.len() > 20_000);
+        assert_eq!(root.descendants().count(), doc.tree.nodes.len());
+        assert_eq!(Tree::new(root), doc.tree);
+    }
+}
@@ -1,20 +1,39 @@
+mod block_context;
 mod code_blocks;
 mod common;
 mod embeds;
 mod headings;
 mod images;
+mod mentions;
 mod section;
 mod tags;
 mod task_items;
 mod wiki_ref;
 mod wikilinks;
 
+pub use block_context::{block_context, BlockContext};
 pub use code_blocks::{code_blocks, CodeBlock};
 pub use embeds::{embeds, EmbedRef};
 pub use headings::{headings, Heading};
 pub use images::images;
+pub use mentions::{mentions, table_cell_ranges};
 pub use section::{heading_slug, section};
 pub use tags::{tags, TagRef};
 pub use task_items::{task_items, TaskItem, TaskStatus};
-pub use wiki_ref::{wiki_refs, WikiRef};
+pub use wiki_ref::{wiki_refs, wiki_refs_with_spans, SourceSpan, WikiRef};
 pub use wikilinks::{wikilinks, WikiLinkRef};
+
+pub(crate) use block_context::{block_context_in, collect_blocks, BlockSpan};
+pub(crate) use code_blocks::collect_code_blocks;
+#[cfg(feature = "html")]
+pub(crate) use common::options;
+pub(crate) use common::parse;
+#[cfg(test)]
+pub(crate) use common::parse_count;
+#[cfg(any(feature = "pandoc", feature = "readable"))]
+pub(crate) use common::{collect_text, line_col_to_byte, line_starts, split_heading};
+pub(crate) use headings::collect_headings;
+pub(crate) use images::collect_images;
+pub(crate) use section::{collect_section_headings, section_in, SectionHeading};
+pub(crate) use task_items::collect_task_items;
+pub(crate) use wiki_ref::{collect_wiki_refs, expose_embed_wikilinks};
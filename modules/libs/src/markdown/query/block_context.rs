@@ -0,0 +1,150 @@
+//! The surroundings of a source line: the block it belongs to and the
+//! headings it is nested under. Used to show where a reference occurs.
+
+use std::ops::RangeInclusive;
+
+use comrak::{
+    nodes::{AstNode, NodeValue},
+    Arena,
+};
+
+use super::common::parse;
+use super::headings::{collect_headings, Heading};
+
+/// The block enclosing a line, with the heading labels above it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockContext {
+    /// Source text of the enclosing paragraph or list item, trimmed.
+    pub text: String,
+    /// 1-based lines `text` was taken from.
+    pub lines: RangeInclusive<usize>,
+    /// Labels of the enclosing headings, outermost first.
+    pub heading_path: Vec<String>,
+}
+
+/// Describe the block around the 1-based `line` of `text`.
+///
+/// The innermost paragraph, heading, or table row containing the line is
+/// used. A paragraph directly inside a list item widens to the item, so the
+/// bullet marker is kept. Lines outside any such block fall back to the line
+/// itself.
+pub fn block_context(text: &str, line: usize) -> BlockContext {
+    let arena = Arena::new();
+    let root = parse(&arena, text);
+    block_context_in(text, &collect_blocks(root), &collect_headings(root), line)
+}
+
+/// Line span of a block a line can belong to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BlockSpan {
+    start: usize,
+    end: usize,
+    /// Start of the excerpt: the enclosing list item's line for a paragraph
+    /// directly inside one, `start` otherwise.
+    excerpt_start: usize,
+}
+
+/// Spans of the blocks a line can belong to, in pre-order.
+pub(crate) fn collect_blocks<'a>(root: &'a AstNode<'a>) -> Vec<BlockSpan> {
+    root.descendants()
+        .filter(|n| {
+            matches!(
+                n.data().value,
+                NodeValue::Paragraph | NodeValue::Heading(_) | NodeValue::TableRow(_)
+            )
+        })
+        .map(|n| {
+            let pos = n.data().sourcepos;
+            let excerpt_start = n
+                .parent()
+                .filter(|p| matches!(p.data().value, NodeValue::Item(_) | NodeValue::TaskItem(_)))
+                .map_or(pos.start.line, |p| p.data().sourcepos.start.line);
+            BlockSpan {
+                start: pos.start.line,
+                end: pos.end.line,
+                excerpt_start,
+            }
+        })
+        .collect()
+}
+
+/// [`block_context`] over already collected blocks and headings of `text`.
+pub(crate) fn block_context_in(
+    text: &str,
+    blocks: &[BlockSpan],
+    headings: &[Heading],
+    line: usize,
+) -> BlockContext {
+    let lines: Vec<&str> = text.lines().collect();
+    let (start, end) = enclosing_lines(blocks, line).unwrap_or((line, line));
+    let block = lines
+        .get(start.saturating_sub(1)..end.min(lines.len()))
+        .map(|ls| ls.join("\n"))
+        .unwrap_or_default();
+
+    BlockContext {
+        text: block.trim().to_string(),
+        lines: start..=end,
+        heading_path: heading_path(headings, line),
+    }
+}
+
+fn enclosing_lines(blocks: &[BlockSpan], line: usize) -> Option<(usize, usize)> {
+    // Blocks are in pre-order, so the last containing block is the innermost
+    // one.
+    blocks
+        .iter()
+        .rfind(|b| b.start <= line && line <= b.end)
+        .map(|b| (b.excerpt_start, b.end))
+}
+
+fn heading_path(headings: &[Heading], line: usize) -> Vec<String> {
+    let mut stack: Vec<(u8, &str)> = Vec::new();
+    for heading in headings.iter().take_while(|h| h.line < line) {
+        while matches!(stack.last(), Some((lvl, _)) if *lvl >= heading.level) {
+            stack.pop();
+        }
+        stack.push((heading.level, &heading.text));
+    }
+    stack
+        .into_iter()
+        .map(|(_, label)| label.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_the_enclosing_paragraph() {
+        let text = "# Guide\n\n## Setup\n\nFirst line\nsee [[cargo]] here\n\nOther";
+        let ctx = block_context(text, 6);
+        assert_eq!(ctx.text, "First line\nsee [[cargo]] here");
+        assert_eq!(ctx.heading_path, vec!["Guide", "Setup"]);
+    }
+
+    #[test]
+    fn it_widens_to_the_list_item() {
+        let text = "- one\n- two [[cargo]]\n  continued\n- three";
+        let ctx = block_context(text, 2);
+        assert_eq!(ctx.text, "- two [[cargo]]\n  continued");
+        assert_eq!(ctx.lines, 2..=3);
+        assert!(ctx.heading_path.is_empty());
+    }
+
+    #[test]
+    fn it_pops_sibling_headings() {
+        let text = "# A\n\n## B\n\n## C\n\n[[x]]";
+        let ctx = block_context(text, 7);
+        assert_eq!(ctx.heading_path, vec!["A", "C"]);
+    }
+
+    #[test]
+    fn it_uses_the_heading_itself() {
+        let text = "# About [[x]]\n\nbody";
+        let ctx = block_context(text, 1);
+        assert_eq!(ctx.text, "# About [[x]]");
+        assert!(ctx.heading_path.is_empty());
+    }
+}
@@ -1,9 +1,9 @@
 use comrak::{
-    nodes::{NodeCodeBlock, NodeValue},
-    parse_document, Arena,
+    nodes::{AstNode, NodeCodeBlock, NodeValue},
+    Arena,
 };
 
-use super::common::options;
+use super::common::parse;
 
 /// A fenced code block extracted from a markdown document.
 ///
@@ -29,9 +29,11 @@ pub fn code_blocks(text: &str) -> Vec<CodeBlock> {
         return Vec::new();
     }
     let arena = Arena::new();
-    let opts = options();
-    let root = parse_document(&arena, text, &opts);
+    let root = parse(&arena, text);
+    collect_code_blocks(root)
+}
 
+pub(crate) fn collect_code_blocks<'a>(root: &'a AstNode<'a>) -> Vec<CodeBlock> {
     let mut out = Vec::new();
     for n in root.descendants() {
         let NodeValue::CodeBlock(cb) = &n.data().value else {
@@ -1,17 +1,54 @@
+#[cfg(test)]
+use std::cell::Cell;
+
 use comrak::{
     nodes::{AstNode, NodeValue},
-    Options,
+    options::Extension,
+    parse_document, Arena, Options,
 };
 
-pub(super) fn options() -> Options<'static> {
-    let mut opts = Options::default();
-    opts.extension.wikilinks_title_after_pipe = true;
-    opts.extension.tasklist = true;
+/// The one option set every parse uses, so a tree parsed for queries can
+/// also be rendered to HTML.
+pub(crate) fn options() -> Options<'static> {
+    let mut opts = Options {
+        extension: Extension {
+            wikilinks_title_after_pipe: true,
+            autolink: true,
+            table: true,
+            strikethrough: true,
+            tasklist: true,
+            footnotes: true,
+            superscript: true,
+            math_dollars: true,
+            ..Extension::default()
+        },
+        ..Options::default()
+    };
+    // `[-]` and friends are task items to the queries; HTML restores them as
+    // plain list items.
     opts.parse.relaxed_tasklist_matching = true;
     opts
 }
 
-pub(super) fn collect_text<'a>(node: &'a AstNode<'a>) -> String {
+#[cfg(test)]
+thread_local! {
+    static PARSE_COUNT: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Parse `text` with [`options`]. Every markdown parse goes through here.
+pub(crate) fn parse<'a>(arena: &'a Arena<'a>, text: &str) -> &'a AstNode<'a> {
+    #[cfg(test)]
+    PARSE_COUNT.with(|count| count.set(count.get() + 1));
+    parse_document(arena, text, &options())
+}
+
+/// Parses run so far on the current thread.
+#[cfg(test)]
+pub(crate) fn parse_count() -> usize {
+    PARSE_COUNT.with(Cell::get)
+}
+
+pub(crate) fn collect_text<'a>(node: &'a AstNode<'a>) -> String {
     let mut s = String::new();
     for d in node.descendants() {
         if let NodeValue::Text(t) = &d.data().value {
@@ -21,6 +58,15 @@ pub(super) fn collect_text<'a>(node: &'a AstNode<'a>) -> String {
     s
 }
 
+/// Split a wikilink url into its path and `#heading`.
+#[cfg(any(feature = "pandoc", feature = "readable"))]
+pub(crate) fn split_heading(url: &str) -> (&str, Option<&str>) {
+    match url.split_once('#') {
+        Some((path, heading)) => (path, Some(heading)),
+        None => (url, None),
+    }
+}
+
 pub(super) fn parse_wikilink_url(url: &str) -> (Vec<String>, String, Option<String>) {
     let (path, heading) = match url.split_once('#') {
         Some((p, h)) => (p.to_string(), Some(h.to_string())),
@@ -31,7 +77,7 @@ pub(super) fn parse_wikilink_url(url: &str) -> (Vec<String>, String, Option<Stri
     (parts, title, heading)
 }
 
-pub(super) fn line_starts(text: &str) -> Vec<usize> {
+pub(crate) fn line_starts(text: &str) -> Vec<usize> {
     let mut v = vec![0];
     for (i, b) in text.bytes().enumerate() {
         if b == b'\n' {
@@ -41,7 +87,7 @@ pub(super) fn line_starts(text: &str) -> Vec<usize> {
     v
 }
 
-pub(super) fn line_col_to_byte(starts: &[usize], line: usize, col: usize) -> usize {
+pub(crate) fn line_col_to_byte(starts: &[usize], line: usize, col: usize) -> usize {
     let li = line.saturating_sub(1);
     let base = starts.get(li).copied().unwrap_or(0);
     base + col.saturating_sub(1)
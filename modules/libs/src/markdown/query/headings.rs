@@ -1,6 +1,9 @@
-use comrak::{nodes::NodeValue, parse_document, Arena};
+use comrak::{
+    nodes::{AstNode, NodeValue},
+    Arena,
+};
 
-use super::common::{collect_text, options};
+use super::common::{collect_text, parse};
 
 /// Structural information for a single markdown heading.
 ///
@@ -26,14 +29,16 @@ pub fn headings(text: &str) -> Vec<Heading> {
         return Vec::new();
     }
     let arena = Arena::new();
-    let opts = options();
-    let root = parse_document(&arena, text, &opts);
+    let root = parse(&arena, text);
+    collect_headings(root)
+}
 
+pub(crate) fn collect_headings<'a>(root: &'a AstNode<'a>) -> Vec<Heading> {
     let mut out: Vec<Heading> = Vec::new();
     let mut stack: Vec<(u8, String)> = Vec::new();
     for n in root.descendants() {
         if let NodeValue::Heading(h) = &n.data().value {
-            let level = h.level as u8;
+            let level = h.level;
             let label = collect_text(n);
             let line = n.data().sourcepos.start.line;
 
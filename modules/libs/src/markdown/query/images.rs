@@ -1,12 +1,18 @@
-use comrak::{nodes::NodeValue, parse_document, Arena};
+use comrak::{
+    nodes::{AstNode, NodeValue},
+    Arena,
+};
 use url::Url;
 
-use super::common::options;
+use super::common::parse;
 
 pub fn images(text: &str) -> Vec<Url> {
     let arena = Arena::new();
-    let opts = options();
-    let root = parse_document(&arena, text, &opts);
+    let root = parse(&arena, text);
+    collect_images(root)
+}
+
+pub(crate) fn collect_images<'a>(root: &'a AstNode<'a>) -> Vec<Url> {
     root.descendants()
         .filter_map(|node| match &node.data().value {
             NodeValue::Image(node_link) => Url::parse(&node_link.url).ok(),
@@ -0,0 +1,184 @@
+//! Plain-text mentions of a phrase, typically a scrap title that is written
+//! out without being linked. Occurrences inside code, links, raw HTML, and
+//! `[[]]`-family syntax are not mentions.
+
+use comrak::{nodes::NodeValue, Arena};
+
+use super::common::{line_col_to_byte, line_starts, parse};
+use super::wiki_ref::{wiki_refs_with_spans, SourceSpan};
+
+/// Find every mention of `phrase` in `text`, ASCII case-insensitively.
+///
+/// A side of the phrase that starts or ends with a word character must sit on
+/// a word boundary, so `Rust` does not match inside `Rustacean`. CJK scripts
+/// do not separate words with spaces, so CJK characters never form a
+/// boundary violation on either side.
+pub fn mentions(text: &str, phrase: &str) -> Vec<SourceSpan> {
+    let phrase = phrase.trim();
+    if phrase.is_empty() || text.is_empty() {
+        return Vec::new();
+    }
+    let excluded = excluded_ranges(text);
+    let starts = line_starts(text);
+    let haystack = text.to_ascii_lowercase();
+    let needle = phrase.to_ascii_lowercase();
+    let check_before = phrase.chars().next().is_some_and(needs_boundary);
+    let check_after = phrase.chars().next_back().is_some_and(needs_boundary);
+
+    haystack
+        .match_indices(&needle)
+        .map(|(start, m)| (start, start + m.len()))
+        .filter(|&(start, end)| {
+            let glued_before = check_before
+                && text[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(needs_boundary);
+            let glued_after = check_after && text[end..].chars().next().is_some_and(needs_boundary);
+            !glued_before && !glued_after
+        })
+        .filter(|&(start, end)| !excluded.iter().any(|&(s, e)| start < e && s < end))
+        .map(|(start, end)| SourceSpan {
+            line: starts.partition_point(|&s| s <= start),
+            start,
+            end,
+        })
+        .collect()
+}
+
+/// Byte ranges of the GFM table cells in `text`. A `|` written inside one
+/// ends the cell unless escaped as `\|`.
+pub fn table_cell_ranges(text: &str) -> Vec<(usize, usize)> {
+    let arena = Arena::new();
+    let root = parse(&arena, text);
+    let starts = line_starts(text);
+
+    root.descendants()
+        .filter(|node| matches!(node.data().value, NodeValue::TableCell))
+        .map(|node| {
+            let pos = node.data().sourcepos;
+            let start = line_col_to_byte(&starts, pos.start.line, pos.start.column);
+            let end = line_col_to_byte(&starts, pos.end.line, pos.end.column) + 1;
+            (start, end.min(text.len()))
+        })
+        .collect()
+}
+
+fn excluded_ranges(text: &str) -> Vec<(usize, usize)> {
+    let arena = Arena::new();
+    let root = parse(&arena, text);
+    let starts = line_starts(text);
+
+    let mut ranges: Vec<(usize, usize)> = root
+        .descendants()
+        .filter(|node| {
+            matches!(
+                node.data().value,
+                NodeValue::CodeBlock(_)
+                    | NodeValue::Code(_)
+                    | NodeValue::Link(_)
+                    | NodeValue::Image(_)
+                    | NodeValue::HtmlBlock(_)
+                    | NodeValue::HtmlInline(_)
+            )
+        })
+        .map(|node| {
+            let pos = node.data().sourcepos;
+            let start = line_col_to_byte(&starts, pos.start.line, pos.start.column);
+            let end = line_col_to_byte(&starts, pos.end.line, pos.end.column) + 1;
+            (start, end.min(text.len()))
+        })
+        .collect();
+    ranges.extend(
+        wiki_refs_with_spans(text)
+            .into_iter()
+            .map(|(_, span)| (span.start, span.end)),
+    );
+    ranges
+}
+
+fn needs_boundary(c: char) -> bool {
+    is_word_char(c) && !is_cjk(c)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+            | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+            | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+            | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+            | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+            | '\u{FF66}'..='\u{FF9F}' // Halfwidth Katakana
+            | '\u{20000}'..='\u{2FA1F}' // Supplementary ideographs
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched<'a>(text: &'a str, phrase: &str) -> Vec<&'a str> {
+        mentions(text, phrase)
+            .into_iter()
+            .map(|s| &text[s.start..s.end])
+            .collect()
+    }
+
+    #[test]
+    fn it_matches_on_word_boundaries_case_insensitively() {
+        let text = "Rust and rust, not Rustacean or trust.";
+        assert_eq!(matched(text, "Rust"), vec!["Rust", "rust"]);
+    }
+
+    #[test]
+    fn it_skips_code_links_and_wiki_refs() {
+        let text = concat!(
+            "Rust `Rust` [[Rust]] #[[Rust]] ![[Rust]] [Rust](https://rust-lang.org)\n",
+            "\n",
+            "```\n",
+            "Rust\n",
+            "```\n",
+            "\n",
+            "<span>Rust</span> and Rust again\n",
+        );
+        let spans = mentions(text, "Rust");
+        assert_eq!(spans.len(), 3);
+        assert_eq!((spans[0].line, spans[0].start, spans[0].end), (1, 0, 4));
+        assert_eq!(spans[1].line, 7);
+        assert_eq!(spans[2].line, 7);
+        assert_eq!(&text[spans[2].start..spans[2].end], "Rust");
+    }
+
+    #[test]
+    fn it_matches_cjk_as_substring() {
+        assert_eq!(matched("所有権と借用のルール", "借用"), vec!["借用"]);
+        assert_eq!(matched("私はRustが好き", "Rust"), vec!["Rust"]);
+        assert!(matched("Rustyが好き", "Rust").is_empty());
+    }
+
+    #[test]
+    fn it_finds_table_cells() {
+        let text = "Rust\n\n| a | Rust |\n|---|---|\n| Rust | b |\n";
+        let cells = table_cell_ranges(text);
+        let in_cell: Vec<bool> = mentions(text, "Rust")
+            .iter()
+            .map(|span| cells.iter().any(|&(s, e)| s <= span.start && span.end <= e))
+            .collect();
+        assert_eq!(in_cell, vec![false, true, true]);
+    }
+
+    #[test]
+    fn it_handles_multi_word_titles() {
+        assert_eq!(
+            matched("Read Domain Driven Design first.", "domain driven design"),
+            vec!["Domain Driven Design"]
+        );
+        assert!(mentions("", "x").is_empty());
+        assert!(mentions("text", " ").is_empty());
+    }
+}
@@ -1,6 +1,9 @@
-use comrak::{nodes::NodeValue, parse_document, Arena};
+use comrak::{
+    nodes::{AstNode, NodeValue},
+    Arena,
+};
 
-use super::common::{collect_text, line_byte_offset, line_starts, options};
+use super::common::{collect_text, line_byte_offset, line_starts, parse};
 
 /// Slugify a heading text using GitHub Flavored Markdown rules.
 ///
@@ -35,7 +38,10 @@ fn gfm_slug(s: &str) -> String {
     collapsed.trim_matches('-').to_string()
 }
 
-struct HeadingInfo {
+/// Line span, level and slug of a top-level heading, enough to cut its
+/// section out of the source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SectionHeading {
     start_line: usize,
     end_line: usize,
     level: u8,
@@ -47,24 +53,36 @@ pub fn section<'a>(text: &'a str, heading_slug: &str) -> Option<&'a str> {
         return None;
     }
     let arena = Arena::new();
-    let opts = options();
-    let root = parse_document(&arena, text, &opts);
-    let starts = line_starts(text);
-    let total = text.len();
+    let root = parse(&arena, text);
+    section_in(text, &collect_section_headings(root), heading_slug)
+}
 
-    let mut hs: Vec<HeadingInfo> = Vec::new();
+pub(crate) fn collect_section_headings<'a>(root: &'a AstNode<'a>) -> Vec<SectionHeading> {
+    let mut hs: Vec<SectionHeading> = Vec::new();
     for n in root.children() {
         if let NodeValue::Heading(h) = &n.data().value {
             let pos = n.data().sourcepos;
             let label = collect_text(n);
-            hs.push(HeadingInfo {
+            hs.push(SectionHeading {
                 start_line: pos.start.line,
                 end_line: pos.end.line,
-                level: h.level as u8,
+                level: h.level,
                 slug: gfm_slug(&label),
             });
         }
     }
+    hs
+}
+
+/// The body of the section under the heading with `heading_slug`, given the
+/// top-level headings of `text`.
+pub(crate) fn section_in<'t>(
+    text: &'t str,
+    hs: &[SectionHeading],
+    heading_slug: &str,
+) -> Option<&'t str> {
+    let starts = line_starts(text);
+    let total = text.len();
 
     let idx = hs.iter().position(|h| h.slug == heading_slug)?;
     let target_level = hs[idx].level;
@@ -186,7 +204,7 @@ deep body
 
 s-body
 ";
-        assert_eq!(section(input, "top").unwrap().contains("intro"), true);
+        assert!(section(input, "top").unwrap().contains("intro"));
         let first = section(input, "first").unwrap();
         assert!(first.contains("f-body"));
         assert!(first.contains("### deep"));
@@ -1,9 +1,9 @@
 use comrak::{
     nodes::{AstNode, NodeValue},
-    parse_document, Arena,
+    Arena,
 };
 
-use super::common::options;
+use super::common::parse;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TaskStatus {
@@ -36,8 +36,11 @@ fn task_item_body<'a>(node: &'a AstNode<'a>) -> String {
 
 pub fn task_items(text: &str) -> Vec<TaskItem> {
     let arena = Arena::new();
-    let opts = options();
-    let root = parse_document(&arena, text, &opts);
+    let root = parse(&arena, text);
+    collect_task_items(root)
+}
+
+pub(crate) fn collect_task_items<'a>(root: &'a AstNode<'a>) -> Vec<TaskItem> {
     let mut out = Vec::new();
     for node in root.descendants() {
         let NodeValue::TaskItem(item) = &node.data().value else {
@@ -3,12 +3,14 @@
 //! lets comrak extract the bracketed shape first, then classifies each
 //! occurrence by the prefix immediately attached to the `[[`.
 
+use std::borrow::Cow;
+
 use comrak::{
-    nodes::{NodeValue, NodeWikiLink},
-    parse_document, Arena,
+    nodes::{AstNode, NodeValue, NodeWikiLink},
+    Arena,
 };
 
-use super::common::{collect_text, line_col_to_byte, line_starts, options, parse_wikilink_url};
+use super::common::{collect_text, line_col_to_byte, line_starts, parse, parse_wikilink_url};
 use super::embeds::EmbedRef;
 use super::tags::TagRef;
 use super::wikilinks::WikiLinkRef;
@@ -24,14 +26,40 @@ pub enum WikiRef {
     Embed(EmbedRef),
 }
 
+/// Source location of a `[[]]`-family occurrence. `start..end` is the byte
+/// range in the markdown body, including a `#` or `!` prefix; `line` is
+/// 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceSpan {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
 /// Extract every `[[]]`-family occurrence from the markdown body in source
 /// order. Comrak owns extraction of wiki-shaped syntax; scraps only classifies
 /// the extracted node by the prefix immediately before `[[`.
 pub fn wiki_refs(text: &str) -> Vec<WikiRef> {
+    wiki_refs_with_spans(text)
+        .into_iter()
+        .map(|(wref, _)| wref)
+        .collect()
+}
+
+/// Same as [`wiki_refs`], paired with where each occurrence sits in `text`.
+pub fn wiki_refs_with_spans(text: &str) -> Vec<(WikiRef, SourceSpan)> {
     let arena = Arena::new();
-    let opts = options();
     let parse_text = expose_embed_wikilinks(text);
-    let root = parse_document(&arena, &parse_text, &opts);
+    let root = parse(&arena, &parse_text);
+    collect_wiki_refs(root, text)
+}
+
+/// Classify the WikiLink nodes of `root`, parsed from
+/// [`expose_embed_wikilinks`]`(text)`.
+pub(crate) fn collect_wiki_refs<'a>(
+    root: &'a AstNode<'a>,
+    text: &str,
+) -> Vec<(WikiRef, SourceSpan)> {
     let starts = line_starts(text);
 
     root.descendants()
@@ -47,6 +75,22 @@ pub fn wiki_refs(text: &str) -> Vec<WikiRef> {
             let line = pos.start.line;
             let byte = line_col_to_byte(&starts, line, pos.start.column);
             let prefix = byte.checked_sub(1).and_then(|i| text.as_bytes().get(i));
+            let end = line_col_to_byte(&starts, pos.end.line, pos.end.column) + 1;
+            // Table cells drop the backslash of an escaped alias pipe before
+            // inline parsing, leaving the node's end one byte short.
+            let end = text
+                .get(byte..)
+                .and_then(|rest| rest.find("]]"))
+                .map_or(end, |i| end.max(byte + i + 2));
+            let span = SourceSpan {
+                line,
+                start: if matches!(prefix, Some(b'#' | b'!')) {
+                    byte - 1
+                } else {
+                    byte
+                },
+                end: end.min(text.len()),
+            };
             let (ctx_path, title, heading) = parse_wikilink_url(url);
             let label = collect_text(node);
             let display = match &heading {
@@ -60,7 +104,7 @@ pub fn wiki_refs(text: &str) -> Vec<WikiRef> {
                     let mut path = ctx_path;
                     path.push(title);
                     if path.iter().all(|s| !s.is_empty()) {
-                        Some(WikiRef::Tag(TagRef { path, line }))
+                        Some((WikiRef::Tag(TagRef { path, line }), span))
                     } else {
                         None
                     }
@@ -69,20 +113,26 @@ pub fn wiki_refs(text: &str) -> Vec<WikiRef> {
                     if title.is_empty() || alias.is_some() {
                         None
                     } else {
-                        Some(WikiRef::Embed(EmbedRef {
-                            ctx_path,
-                            title,
-                            heading,
-                            line,
-                        }))
+                        Some((
+                            WikiRef::Embed(EmbedRef {
+                                ctx_path,
+                                title,
+                                heading,
+                                line,
+                            }),
+                            span,
+                        ))
                     }
                 }
-                _ => Some(WikiRef::Link(WikiLinkRef {
-                    ctx_path,
-                    title,
-                    heading,
-                    alias,
-                })),
+                _ => Some((
+                    WikiRef::Link(WikiLinkRef {
+                        ctx_path,
+                        title,
+                        heading,
+                        alias,
+                    }),
+                    span,
+                )),
             }
         })
         .collect()
@@ -98,8 +148,12 @@ fn url_path(ctx_path: &[String], title: &str) -> String {
 
 /// Comrak treats `![[x]]` as image-shaped Markdown, not as a WikiLink node.
 /// Replacing only the attached `!` keeps byte positions stable while letting
-/// comrak extract the same `[[x]]` shape that tags and links use.
-fn expose_embed_wikilinks(text: &str) -> String {
+/// comrak extract the same `[[x]]` shape that tags and links use. Text without
+/// embeds is borrowed unchanged.
+pub(crate) fn expose_embed_wikilinks(text: &str) -> Cow<'_, str> {
+    if !text.contains("![[") {
+        return Cow::Borrowed(text);
+    }
     let bytes = text.as_bytes();
     let mut out = bytes.to_vec();
     let mut i = 0;
@@ -109,7 +163,7 @@ fn expose_embed_wikilinks(text: &str) -> String {
         }
         i += 1;
     }
-    String::from_utf8(out).unwrap_or_else(|_| text.to_string())
+    Cow::Owned(String::from_utf8(out).unwrap_or_else(|_| text.to_string()))
 }
 
 #[cfg(test)]
@@ -185,6 +239,21 @@ mod tests {
         assert!(matches!(&res[2], WikiRef::Embed(e) if e.title == "論文"));
     }
 
+    #[test]
+    fn it_reports_spans_including_prefix() {
+        let text = "see [[a]]\n#[[t]] and ![[日本]]";
+        let spans: Vec<&str> = wiki_refs_with_spans(text)
+            .iter()
+            .map(|(_, span)| &text[span.start..span.end])
+            .collect();
+        assert_eq!(spans, vec!["[[a]]", "#[[t]]", "![[日本]]"]);
+        let lines: Vec<usize> = wiki_refs_with_spans(text)
+            .iter()
+            .map(|(_, span)| span.line)
+            .collect();
+        assert_eq!(lines, vec![1, 2, 2]);
+    }
+
     #[test]
     fn it_preserves_link_with_alias_and_heading() {
         let res = wiki_refs("[[Person/Eric Evans#bio|Eric]]");
@@ -197,4 +266,17 @@ mod tests {
         assert_eq!(r.heading.as_deref(), Some("bio"));
         assert_eq!(r.alias.as_deref(), Some("Eric"));
     }
+
+    #[test]
+    fn it_reads_escaped_alias_pipes_in_table_cells() {
+        let text = "| [[Book/DDD\\|ddd]] | b |\n|---|---|\n";
+        let res = wiki_refs_with_spans(text);
+        assert_eq!(res.len(), 1);
+        let (WikiRef::Link(r), span) = &res[0] else {
+            panic!("expected Link");
+        };
+        assert_eq!(r.title, "DDD");
+        assert_eq!(r.alias.as_deref(), Some("ddd"));
+        assert_eq!(&text[span.start..span.end], "[[Book/DDD\\|ddd]]");
+    }
 }
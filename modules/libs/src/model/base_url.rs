@@ -28,6 +28,11 @@ impl BaseUrl {
     pub fn as_url(&self) -> &Url {
         &self.0
     }
+
+    /// Absolute URL of a page, given by its path relative to the site root
+    pub fn page_url(&self, path: &str) -> Url {
+        self.0.join(path).unwrap_or_else(|_| self.0.clone())
+    }
 }
 
 #[cfg(test)]
@@ -46,4 +51,19 @@ mod tests {
         let expected_url = Url::parse(expected).unwrap();
         assert_eq!(base_url.as_url(), &expected_url);
     }
+
+    #[rstest]
+    #[case::root("", "https://example.com/wiki/")]
+    #[case::scrap(
+        "scraps/ctx/title.html",
+        "https://example.com/wiki/scraps/ctx/title.html"
+    )]
+    #[case::non_ascii(
+        "scraps/メモ.html",
+        "https://example.com/wiki/scraps/%E3%83%A1%E3%83%A2.html"
+    )]
+    fn test_page_url(#[case] path: &str, #[case] expected: &str) {
+        let base_url = BaseUrl::new(Url::parse("https://example.com/wiki").unwrap()).unwrap();
+        assert_eq!(base_url.page_url(path).as_str(), expected);
+    }
 }
@@ -21,6 +21,23 @@ impl Ctx {
         &self.segments
     }
 
+    /// The enclosing context, `None` at the top level.
+    pub fn parent(&self) -> Option<Ctx> {
+        (self.depth() > 1).then(|| Ctx {
+            segments: self.segments[..self.depth() - 1].to_vec(),
+        })
+    }
+
+    /// Every context from the top level down to and including this one,
+    /// e.g. `a`, `a/b`, `a/b/c` for `a/b/c`.
+    pub fn prefixes(&self) -> Vec<Ctx> {
+        (1..=self.depth())
+            .map(|depth| Ctx {
+                segments: self.segments[..depth].to_vec(),
+            })
+            .collect()
+    }
+
     /// Parse a `/`-separated string and reject ctx whose depth exceeds
     /// [`Ctx::MAX_DEPTH`]. Empty / collapsed segments are dropped before
     /// the depth check, matching `From<&str>` semantics.
@@ -139,6 +156,22 @@ mod tests {
         assert_eq!(displayed, vec!["a", "a/a", "a/b", "b"]);
     }
 
+    #[test]
+    fn it_parent_drops_the_last_segment() {
+        assert_eq!(Ctx::from("a/b/c").parent(), Some(Ctx::from("a/b")));
+        assert_eq!(Ctx::from("a").parent(), None);
+    }
+
+    #[test]
+    fn it_prefixes_list_ancestors_outermost_first() {
+        let displayed: Vec<String> = Ctx::from("a/b/c")
+            .prefixes()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(displayed, vec!["a", "a/b", "a/b/c"]);
+    }
+
     #[rstest]
     #[case::single_segment("a", 1)]
     #[case::two_segments("a/b", 2)]
@@ -38,6 +38,39 @@ impl Display for ScrapFileStem {
     }
 }
 
+/// Path of a co-located asset under `scraps/`, using slash-separated
+/// segments. Directory segments are slugified like ctx segments, so assets
+/// land next to the pages of the scraps beside them; the file name is kept.
+/// Examples:
+///   - "diagram.png"                    → "diagram.png"
+///   - "Programming/Rust/ownership.png" → "programming/rust/ownership.png"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AssetPath(Vec<String>);
+
+impl AssetPath {
+    /// `segments` are the directories and file name of the asset relative to
+    /// the wiki root. `None` for an empty path.
+    pub fn new<S: AsRef<str>>(segments: &[S]) -> Option<AssetPath> {
+        let (file_name, dirs) = segments.split_last()?;
+        let mut path: Vec<String> = dirs
+            .iter()
+            .map(|dir| slugify::by_dash(dir.as_ref()))
+            .collect();
+        path.push(file_name.as_ref().to_string());
+        Some(AssetPath(path))
+    }
+
+    pub fn segments(&self) -> &[String] {
+        &self.0
+    }
+}
+
+impl Display for AssetPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join("/"))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::model::title::Title;
@@ -77,4 +110,16 @@ mod tests {
         let file_name = ScrapFileStem::from(input);
         assert_eq!(file_name.to_string(), expected);
     }
+
+    #[rstest]
+    #[case::root(&["diagram.png"], "diagram.png")]
+    #[case::nested(&["Programming", "Rust", "Ownership Chart.png"], "programming/rust/Ownership Chart.png")]
+    fn it_asset_path(#[case] segments: &[&str], #[case] expected: &str) {
+        assert_eq!(AssetPath::new(segments).unwrap().to_string(), expected);
+    }
+
+    #[test]
+    fn it_asset_path_empty() {
+        assert_eq!(AssetPath::new::<&str>(&[]), None);
+    }
 }
@@ -1,19 +1,37 @@
 use std::collections::HashSet;
+use std::sync::{Arc, OnceLock};
 
 use url::Url;
 
-use crate::markdown;
+use crate::markdown::{document::Document, query::Heading};
 
 use super::{context::Ctx, key::ScrapKey, tag::Tag, title::Title};
 
-#[derive(PartialEq, Clone, Debug)]
+#[derive(Clone, Debug)]
 pub struct Scrap {
     title: Title,
     ctx: Option<Ctx>,
     links: Vec<ScrapKey>,
     tags: Vec<Tag>,
-    md_text: String,
+    md_text: Arc<str>,
     thumbnail: Option<Url>,
+    headings: Vec<Heading>,
+    /// The parsed body, shared by clones. Set by `new`; a scrap rebuilt by
+    /// `from_parts` parses on first use.
+    document: Arc<OnceLock<Document>>,
+}
+
+/// `document` is derived from `md_text`, so it takes no part in equality.
+impl PartialEq for Scrap {
+    fn eq(&self, other: &Self) -> bool {
+        self.title == other.title
+            && self.ctx == other.ctx
+            && self.links == other.links
+            && self.tags == other.tags
+            && self.md_text == other.md_text
+            && self.thumbnail == other.thumbnail
+            && self.headings == other.headings
+    }
 }
 
 impl Scrap {
@@ -46,24 +64,37 @@ impl Scrap {
     pub fn thumbnail(&self) -> Option<Url> {
         self.thumbnail.clone()
     }
+
+    /// Headings of the body, in occurrence order.
+    pub fn headings(&self) -> &[Heading] {
+        &self.headings
+    }
+
+    /// The body parsed once, for every query and the HTML render.
+    pub fn document(&self) -> &Document {
+        self.document
+            .get_or_init(|| Document::parse(self.md_text.clone()))
+    }
 }
 
 impl Scrap {
     pub fn new(title: &str, ctx: &Option<Ctx>, text: &str) -> Scrap {
-        let links: Vec<ScrapKey> = markdown::query::wikilinks(text)
-            .iter()
+        let md_text: Arc<str> = text.into();
+        let doc = Document::parse(md_text.clone());
+        let links: Vec<ScrapKey> = doc
+            .wikilinks()
             .map(ScrapKey::from)
             .collect::<HashSet<_>>()
             .into_iter()
             .collect();
-        let thumbnail = markdown::query::images(text).into_iter().next();
+        let thumbnail = doc.images().first().cloned();
 
         // Build the tag list from explicit `#[[tag]]` occurrences. Preserve
         // the first occurrence order and drop duplicates within this scrap;
         // cross-scrap aggregation is `Tags::new`'s job.
         let mut seen = HashSet::new();
-        let tags: Vec<Tag> = markdown::query::tags(text)
-            .into_iter()
+        let tags: Vec<Tag> = doc
+            .tags()
             .map(|occ| Tag::from(occ.path.join("/").as_str()))
             .filter(|tag| seen.insert(tag.clone()))
             .collect();
@@ -73,8 +104,33 @@ impl Scrap {
             ctx: ctx.clone(),
             links,
             tags,
-            md_text: text.to_string(),
+            md_text,
+            thumbnail,
+            headings: doc.headings().to_vec(),
+            document: Arc::new(OnceLock::from(doc)),
+        }
+    }
+
+    /// Rebuild a scrap from refs previously extracted from `text`, skipping
+    /// markdown parsing. Callers must guarantee the parts match `text`.
+    pub fn from_parts(
+        title: &str,
+        ctx: &Option<Ctx>,
+        text: &str,
+        links: Vec<ScrapKey>,
+        tags: Vec<Tag>,
+        thumbnail: Option<Url>,
+        headings: Vec<Heading>,
+    ) -> Scrap {
+        Scrap {
+            title: title.into(),
+            ctx: ctx.clone(),
+            links,
+            tags,
+            md_text: text.into(),
             thumbnail,
+            headings,
+            document: Arc::default(),
         }
     }
 }
@@ -105,6 +161,22 @@ mod tests {
         assert_eq!(scrap.thumbnail(), None);
     }
 
+    #[test]
+    fn it_from_parts_matches_new() {
+        let text = "# Intro\n\n#[[rust]] [[Context/link]] ![](https://example.com/a.png)";
+        let parsed = Scrap::new("foo", &None, text);
+        let rebuilt = Scrap::from_parts(
+            "foo",
+            &None,
+            text,
+            parsed.links().to_vec(),
+            parsed.tags().to_vec(),
+            parsed.thumbnail(),
+            parsed.headings().to_vec(),
+        );
+        assert_eq!(rebuilt, parsed);
+    }
+
     #[test]
     fn it_new_with_nested_ctx() {
         let scrap = Scrap::new("borrowing", &Some("programming/rust".into()), "body");
@@ -169,4 +241,40 @@ mod tests {
         let names: Vec<String> = scrap.tags().iter().map(|t| format!("{}", t)).collect();
         assert_eq!(names, vec!["ai".to_string()]);
     }
+
+    #[test]
+    fn it_parses_the_body_once() {
+        let before = crate::markdown::query::parse_count();
+
+        let scrap = Scrap::new("foo", &None, "# Guide\n\n- [ ] try [[bar]] #[[rust]]\n");
+        let clone = scrap.clone();
+        assert_eq!(clone.document().headings().len(), 1);
+        assert_eq!(clone.document().task_items().len(), 1);
+        #[cfg(feature = "html")]
+        {
+            let base_url =
+                crate::model::base_url::BaseUrl::new(Url::parse("http://localhost:1112/").unwrap())
+                    .unwrap();
+            let content = scrap.document().to_content(
+                &None,
+                &std::collections::HashSet::new(),
+                &base_url,
+                crate::html::EmbedMode::Preserve,
+            );
+            assert!(content.to_string().contains("bar.html"));
+        }
+
+        assert_eq!(crate::markdown::query::parse_count() - before, 1);
+    }
+
+    #[test]
+    fn it_parses_rebuilt_scraps_on_first_use() {
+        let before = crate::markdown::query::parse_count();
+
+        let scrap = Scrap::from_parts("foo", &None, "# Guide\n", vec![], vec![], None, vec![]);
+        assert_eq!(crate::markdown::query::parse_count() - before, 0);
+        assert_eq!(scrap.document().headings().len(), 1);
+        assert_eq!(scrap.document().headings().len(), 1);
+        assert_eq!(crate::markdown::query::parse_count() - before, 1);
+    }
 }
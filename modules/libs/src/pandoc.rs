@@ -0,0 +1,740 @@
+//! Pandoc JSON AST emitter. Walks the comrak AST of each scrap and writes the
+//! `pandoc-api-version` 1.23 JSON shape, so a wiki can be piped into
+//! `pandoc -f json` for PDF / EPUB / DOCX output.
+//!
+//! Wiki-shaped syntax has no Pandoc node of its own, so it is lowered:
+//! `[[link]]` becomes an internal `Link` to the target scrap's anchor,
+//! `#[[tag]]` becomes a `Span` with the `tag` class, and `![[embed]]` is
+//! expanded into a `Div` with the `scrap-embed` class.
+
+use std::collections::{HashMap, HashSet};
+
+use comrak::{
+    nodes::{AstNode, ListDelimType, ListType, NodeValue, NodeWikiLink, TableAlignment},
+    Arena,
+};
+use serde_json::{json, Value};
+
+use crate::markdown::query::{
+    collect_text, expose_embed_wikilinks, heading_slug, line_col_to_byte, line_starts, parse,
+    section, split_heading,
+};
+use crate::model::{file::ScrapFileStem, key::ScrapKey};
+
+/// The Pandoc types version the emitted JSON conforms to.
+pub const API_VERSION: [u32; 3] = [1, 23, 1];
+
+/// Anchor identifier of a scrap inside an exported document.
+pub fn scrap_anchor(key: &ScrapKey) -> String {
+    ScrapFileStem::from(key.clone()).to_string()
+}
+
+fn heading_anchor(scrap_anchor: &str, heading: &str) -> String {
+    format!("{}--{}", scrap_anchor, heading_slug(heading))
+}
+
+/// Wrap top-level blocks into a Pandoc document with an optional title.
+pub fn document(title: Option<&str>, blocks: Vec<Value>) -> Value {
+    let meta = match title {
+        Some(title) => json!({ "title": { "t": "MetaInlines", "c": text_inlines(title) } }),
+        None => json!({}),
+    };
+    json!({
+        "pandoc-api-version": API_VERSION,
+        "meta": meta,
+        "blocks": blocks,
+    })
+}
+
+/// Converts scraps into Pandoc blocks. `scrap_texts` feeds embed expansion;
+/// `exported` is the set of scraps present in the output document, so only
+/// links to those become internal links.
+pub struct PandocWriter<'a> {
+    scrap_texts: &'a HashMap<ScrapKey, String>,
+    exported: &'a HashSet<ScrapKey>,
+}
+
+impl<'a> PandocWriter<'a> {
+    pub fn new(
+        scrap_texts: &'a HashMap<ScrapKey, String>,
+        exported: &'a HashSet<ScrapKey>,
+    ) -> PandocWriter<'a> {
+        PandocWriter {
+            scrap_texts,
+            exported,
+        }
+    }
+
+    /// A scrap as a level-1 `Header` carrying its anchor, followed by its body
+    /// with headings shifted one level down.
+    pub fn scrap_blocks(&self, key: &ScrapKey, text: &str) -> Vec<Value> {
+        let anchor = scrap_anchor(key);
+        let ctx = key
+            .ctx()
+            .as_ref()
+            .map(|c| c.to_string())
+            .unwrap_or_default();
+        let mut blocks = vec![json!({
+            "t": "Header",
+            "c": [
+                1,
+                [anchor, ["scrap"], [["ctx", ctx]]],
+                text_inlines(&key.title().to_string()),
+            ],
+        })];
+        blocks.extend(self.blocks(text, Some(&anchor), 1));
+        blocks
+    }
+
+    /// Body blocks of a markdown text. Headings get identifiers under
+    /// `anchor` when given, and their level is raised by `shift`.
+    pub fn blocks(&self, text: &str, anchor: Option<&str>, shift: u8) -> Vec<Value> {
+        self.blocks_inner(text, anchor, shift, &mut HashSet::new())
+    }
+
+    fn blocks_inner(
+        &self,
+        text: &str,
+        anchor: Option<&str>,
+        shift: u8,
+        visited_embeds: &mut HashSet<ScrapKey>,
+    ) -> Vec<Value> {
+        let arena = Arena::new();
+        let root = parse(&arena, &expose_embed_wikilinks(text));
+
+        let footnotes = root
+            .children()
+            .filter_map(|node| match &node.data().value {
+                NodeValue::FootnoteDefinition(def) => Some((def.name.clone(), node)),
+                _ => None,
+            })
+            .collect();
+        let mut state = State {
+            source: text,
+            starts: line_starts(text),
+            anchor,
+            shift,
+            footnotes,
+            visited_embeds,
+        };
+        root.children()
+            .flat_map(|node| self.block(node, &mut state))
+            .collect()
+    }
+
+    fn block<'n>(&self, node: &'n AstNode<'n>, state: &mut State<'_, 'n>) -> Vec<Value> {
+        let value = node.data().value.clone();
+        match value {
+            NodeValue::Paragraph => self.paragraph(node, state, "Para"),
+            NodeValue::Heading(h) => {
+                let label = collect_text(node);
+                let id = state
+                    .anchor
+                    .map(|a| heading_anchor(a, &label))
+                    .unwrap_or_default();
+                let level = (h.level + state.shift).min(6);
+                vec![json!({
+                    "t": "Header",
+                    "c": [level, [id, [], []], self.inlines(node, state)],
+                })]
+            }
+            NodeValue::BlockQuote | NodeValue::MultilineBlockQuote(_) => {
+                vec![json!({ "t": "BlockQuote", "c": self.child_blocks(node, state) })]
+            }
+            NodeValue::List(list) => {
+                let items: Vec<Value> = node
+                    .children()
+                    .map(|item| Value::Array(self.item(item, list.tight, state)))
+                    .collect();
+                match list.list_type {
+                    ListType::Bullet => vec![json!({ "t": "BulletList", "c": items })],
+                    ListType::Ordered => {
+                        let delim = match list.delimiter {
+                            ListDelimType::Period => "Period",
+                            ListDelimType::Paren => "OneParen",
+                        };
+                        vec![json!({
+                            "t": "OrderedList",
+                            "c": [[list.start, { "t": "Decimal" }, { "t": delim }], items],
+                        })]
+                    }
+                }
+            }
+            NodeValue::CodeBlock(cb) => {
+                let classes: Vec<&str> = cb.info.split_whitespace().take(1).collect();
+                let literal = cb.literal.strip_suffix('\n').unwrap_or(&cb.literal);
+                vec![json!({ "t": "CodeBlock", "c": [["", classes, []], literal] })]
+            }
+            NodeValue::HtmlBlock(html) => {
+                vec![json!({ "t": "RawBlock", "c": ["html", html.literal] })]
+            }
+            NodeValue::ThematicBreak => vec![json!({ "t": "HorizontalRule" })],
+            NodeValue::Table(table) => vec![self.table(node, &table.alignments, state)],
+            // Definitions are inlined as `Note`s at their reference.
+            NodeValue::FootnoteDefinition(_) => vec![],
+            _ => {
+                let blocks = self.child_blocks(node, state);
+                if blocks.is_empty() {
+                    vec![]
+                } else {
+                    vec![json!({ "t": "Div", "c": [["", [], []], blocks] })]
+                }
+            }
+        }
+    }
+
+    fn child_blocks<'n>(&self, node: &'n AstNode<'n>, state: &mut State<'_, 'n>) -> Vec<Value> {
+        node.children()
+            .flat_map(|child| self.block(child, state))
+            .collect()
+    }
+
+    fn item<'n>(
+        &self,
+        item: &'n AstNode<'n>,
+        tight: bool,
+        state: &mut State<'_, 'n>,
+    ) -> Vec<Value> {
+        // Symbols other than `x` are kept as written, like in the HTML.
+        let checkbox = match &item.data().value {
+            NodeValue::TaskItem(task) => Some(match task.symbol {
+                Some('x' | 'X') => "☒".to_string(),
+                Some(symbol) => format!("[{symbol}]"),
+                None => "☐".to_string(),
+            }),
+            _ => None,
+        };
+        let mut blocks = Vec::new();
+        for child in item.children() {
+            if tight && matches!(child.data().value, NodeValue::Paragraph) {
+                blocks.extend(self.paragraph(child, state, "Plain"));
+            } else {
+                blocks.extend(self.block(child, state));
+            }
+        }
+        if let Some(mark) = checkbox {
+            match blocks.first_mut().and_then(|b| b.get_mut("c")) {
+                Some(Value::Array(inlines)) => {
+                    inlines.splice(
+                        0..0,
+                        [json!({ "t": "Str", "c": mark }), json!({ "t": "Space" })],
+                    );
+                }
+                _ => blocks.insert(0, json!({ "t": "Plain", "c": [{ "t": "Str", "c": mark }] })),
+            }
+        }
+        blocks
+    }
+
+    /// A paragraph, split around any embeds it contains so the expanded
+    /// blocks sit between the surrounding inline runs.
+    fn paragraph<'n>(
+        &self,
+        node: &'n AstNode<'n>,
+        state: &mut State<'_, 'n>,
+        tag: &str,
+    ) -> Vec<Value> {
+        let mut blocks = Vec::new();
+        let mut run = Vec::new();
+        for child in node.children() {
+            if let Some(url) = self.embed_url(child, state) {
+                trim_trailing_spaces(&mut run);
+                if !run.is_empty() {
+                    blocks.push(json!({ "t": tag, "c": std::mem::take(&mut run) }));
+                }
+                blocks.push(self.embed(&url, state));
+                continue;
+            }
+            self.push_inline(&mut run, child, state);
+        }
+        trim_leading_spaces(&mut run);
+        if !run.is_empty() {
+            blocks.push(json!({ "t": tag, "c": run }));
+        }
+        blocks
+    }
+
+    fn embed_url<'n>(&self, node: &'n AstNode<'n>, state: &State<'_, 'n>) -> Option<String> {
+        let NodeValue::WikiLink(NodeWikiLink { url }) = &node.data().value else {
+            return None;
+        };
+        (state.prefix(node) == Some(b'!')).then(|| url.clone())
+    }
+
+    fn embed(&self, url: &str, state: &mut State<'_, '_>) -> Value {
+        let (path, heading) = split_heading(url);
+        let key = ScrapKey::from_path_str(path);
+        let Some(text) = self.scrap_texts.get(&key) else {
+            return embed_notice("scrap-embed-missing", path);
+        };
+        if !state.visited_embeds.insert(key.clone()) {
+            return embed_notice("scrap-embed-cycle", path);
+        }
+        let embed_text = heading
+            .and_then(|h| section(text, &heading_slug(h)))
+            .unwrap_or(text);
+        // Embedded headings carry no identifier so anchors stay unique when
+        // the embedded scrap is also part of the export.
+        let blocks = self.blocks_inner(embed_text, None, state.shift, state.visited_embeds);
+        state.visited_embeds.remove(&key);
+        json!({ "t": "Div", "c": [["", ["scrap-embed"], [["src", path]]], blocks] })
+    }
+
+    fn inlines<'n>(&self, node: &'n AstNode<'n>, state: &mut State<'_, 'n>) -> Vec<Value> {
+        let mut run = Vec::new();
+        for child in node.children() {
+            self.push_inline(&mut run, child, state);
+        }
+        run
+    }
+
+    fn push_inline<'n>(
+        &self,
+        run: &mut Vec<Value>,
+        node: &'n AstNode<'n>,
+        state: &mut State<'_, 'n>,
+    ) {
+        // The `#` of a `#[[tag]]` ends the preceding text; the tag span
+        // renders it instead.
+        if matches!(node.data().value, NodeValue::WikiLink(_)) && state.prefix(node) == Some(b'#') {
+            strip_trailing_hash(run);
+        }
+        run.extend(self.inline(node, state));
+    }
+
+    fn inline<'n>(&self, node: &'n AstNode<'n>, state: &mut State<'_, 'n>) -> Vec<Value> {
+        let value = node.data().value.clone();
+        match value {
+            NodeValue::Text(t) => text_inlines(&t),
+            NodeValue::SoftBreak => vec![json!({ "t": "SoftBreak" })],
+            NodeValue::LineBreak => vec![json!({ "t": "LineBreak" })],
+            NodeValue::Code(code) => {
+                vec![json!({ "t": "Code", "c": [["", [], []], code.literal] })]
+            }
+            NodeValue::HtmlInline(html) => vec![json!({ "t": "RawInline", "c": ["html", html] })],
+            NodeValue::Math(math) => {
+                let kind = if math.display_math {
+                    "DisplayMath"
+                } else {
+                    "InlineMath"
+                };
+                vec![json!({ "t": "Math", "c": [{ "t": kind }, math.literal] })]
+            }
+            NodeValue::Emph => wrap("Emph", self.inlines(node, state)),
+            NodeValue::Strong => wrap("Strong", self.inlines(node, state)),
+            NodeValue::Strikethrough => wrap("Strikeout", self.inlines(node, state)),
+            NodeValue::Superscript => wrap("Superscript", self.inlines(node, state)),
+            NodeValue::Subscript => wrap("Subscript", self.inlines(node, state)),
+            NodeValue::Underline => wrap("Underline", self.inlines(node, state)),
+            NodeValue::Link(link) => vec![json!({
+                "t": "Link",
+                "c": [["", [], []], self.inlines(node, state), [link.url, link.title]],
+            })],
+            NodeValue::Image(link) => vec![json!({
+                "t": "Image",
+                "c": [["", [], []], self.inlines(node, state), [link.url, link.title]],
+            })],
+            NodeValue::FootnoteReference(reference) => {
+                let blocks = match state.footnotes.get(&reference.name) {
+                    Some(def) => self.child_blocks(def, state),
+                    None => vec![],
+                };
+                vec![json!({ "t": "Note", "c": blocks })]
+            }
+            NodeValue::WikiLink(NodeWikiLink { url }) => match state.prefix(node) {
+                Some(b'#') => {
+                    let (path, _) = split_heading(&url);
+                    vec![json!({
+                        "t": "Span",
+                        "c": [["", ["tag"], [["tag", path]]], text_inlines(&format!("#{path}"))],
+                    })]
+                }
+                // Embeds outside a paragraph (e.g. in a heading) stay a reference.
+                Some(b'!') => vec![json!({
+                    "t": "Span",
+                    "c": [["", ["scrap-embed"], [["src", url]]], text_inlines(&url)],
+                })],
+                _ => self.wikilink(node, &url, state),
+            },
+            _ => self.inlines(node, state),
+        }
+    }
+
+    fn wikilink<'n>(
+        &self,
+        node: &'n AstNode<'n>,
+        url: &str,
+        state: &mut State<'_, 'n>,
+    ) -> Vec<Value> {
+        let (path, heading) = split_heading(url);
+        let key = ScrapKey::from_path_str(path);
+        let label = if collect_text(node) == url {
+            text_inlines(&key.title().to_string())
+        } else {
+            self.inlines(node, state)
+        };
+        if !self.exported.contains(&key) {
+            return vec![json!({ "t": "Span", "c": [["", ["wikilink"], []], label] })];
+        }
+        let anchor = scrap_anchor(&key);
+        let target = match heading {
+            Some(h) => heading_anchor(&anchor, h),
+            None => anchor,
+        };
+        vec![json!({
+            "t": "Link",
+            "c": [["", ["wikilink"], []], label, [format!("#{target}"), ""]],
+        })]
+    }
+
+    fn table<'n>(
+        &self,
+        node: &'n AstNode<'n>,
+        alignments: &[TableAlignment],
+        state: &mut State<'_, 'n>,
+    ) -> Value {
+        let aligns: Vec<&str> = alignments
+            .iter()
+            .map(|a| match a {
+                TableAlignment::None => "AlignDefault",
+                TableAlignment::Left => "AlignLeft",
+                TableAlignment::Center => "AlignCenter",
+                TableAlignment::Right => "AlignRight",
+            })
+            .collect();
+        let colspecs: Vec<Value> = aligns
+            .iter()
+            .map(|a| json!([{ "t": a }, { "t": "ColWidthDefault" }]))
+            .collect();
+        let mut head = Vec::new();
+        let mut body = Vec::new();
+        for row in node.children() {
+            let is_header = matches!(row.data().value, NodeValue::TableRow(true));
+            let cells: Vec<Value> = row
+                .children()
+                .enumerate()
+                .map(|(i, cell)| {
+                    let align = aligns.get(i).copied().unwrap_or("AlignDefault");
+                    let inlines = self.inlines(cell, state);
+                    let blocks = if inlines.is_empty() {
+                        vec![]
+                    } else {
+                        vec![json!({ "t": "Plain", "c": inlines })]
+                    };
+                    json!([["", [], []], { "t": align }, 1, 1, blocks])
+                })
+                .collect();
+            let row = json!([["", [], []], cells]);
+            if is_header {
+                head.push(row);
+            } else {
+                body.push(row);
+            }
+        }
+        json!({
+            "t": "Table",
+            "c": [
+                ["", [], []],
+                [null, []],
+                colspecs,
+                [["", [], []], head],
+                [[["", [], []], 0, [], body]],
+                [["", [], []], []],
+            ],
+        })
+    }
+}
+
+struct State<'s, 'n> {
+    source: &'s str,
+    starts: Vec<usize>,
+    anchor: Option<&'s str>,
+    shift: u8,
+    footnotes: HashMap<String, &'n AstNode<'n>>,
+    visited_embeds: &'s mut HashSet<ScrapKey>,
+}
+
+impl State<'_, '_> {
+    /// The source byte immediately before a `[[`, which classifies it as a
+    /// tag (`#`), an embed (`!`), or a plain link.
+    fn prefix<'n>(&self, node: &'n AstNode<'n>) -> Option<u8> {
+        let pos = node.data().sourcepos;
+        let byte = line_col_to_byte(&self.starts, pos.start.line, pos.start.column);
+        byte.checked_sub(1)
+            .and_then(|i| self.source.as_bytes().get(i))
+            .copied()
+    }
+}
+
+fn wrap(tag: &str, inlines: Vec<Value>) -> Vec<Value> {
+    vec![json!({ "t": tag, "c": inlines })]
+}
+
+fn embed_notice(class: &str, path: &str) -> Value {
+    json!({
+        "t": "Div",
+        "c": [["", ["scrap-embed", class], []], [{ "t": "Para", "c": text_inlines(path) }]],
+    })
+}
+
+/// Split text into Pandoc `Str` / `Space` tokens.
+fn text_inlines(text: &str) -> Vec<Value> {
+    let mut out = Vec::new();
+    let mut word = String::new();
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !word.is_empty() {
+                out.push(json!({ "t": "Str", "c": std::mem::take(&mut word) }));
+            }
+            if !out.last().is_some_and(is_space) {
+                out.push(json!({ "t": "Space" }));
+            }
+        } else {
+            word.push(c);
+        }
+    }
+    if !word.is_empty() {
+        out.push(json!({ "t": "Str", "c": word }));
+    }
+    out
+}
+
+fn is_space(inline: &Value) -> bool {
+    matches!(inline["t"].as_str(), Some("Space" | "SoftBreak"))
+}
+
+fn trim_trailing_spaces(run: &mut Vec<Value>) {
+    while run.last().is_some_and(is_space) {
+        run.pop();
+    }
+}
+
+fn strip_trailing_hash(run: &mut Vec<Value>) {
+    let Some(last) = run.last_mut() else {
+        return;
+    };
+    if last["t"] != "Str" {
+        return;
+    }
+    if let Some(Value::String(s)) = last.get_mut("c") {
+        s.pop();
+        if s.is_empty() {
+            run.pop();
+        }
+    }
+}
+
+fn trim_leading_spaces(run: &mut Vec<Value>) {
+    while run.first().is_some_and(is_space) {
+        run.remove(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn str_(s: &str) -> Value {
+        json!({ "t": "Str", "c": s })
+    }
+
+    fn space() -> Value {
+        json!({ "t": "Space" })
+    }
+
+    fn writer_blocks(text: &str, exported: &[&str]) -> Vec<Value> {
+        let scrap_texts = HashMap::from([(
+            ScrapKey::from_path_str("Book/DDD"),
+            "# DDD\n\n## Layers\n\nlayered body\n\n## Other\n\nhidden\n".to_string(),
+        )]);
+        let exported = exported
+            .iter()
+            .map(|p| ScrapKey::from_path_str(p))
+            .collect();
+        PandocWriter::new(&scrap_texts, &exported).blocks(text, Some("note"), 1)
+    }
+
+    #[test]
+    fn it_document() {
+        let doc = document(Some("Handbook"), vec![]);
+        assert_eq!(doc["pandoc-api-version"], json!([1, 23, 1]));
+        assert_eq!(
+            doc["meta"]["title"],
+            json!({ "t": "MetaInlines", "c": [str_("Handbook")] })
+        );
+        assert_eq!(doc["blocks"], json!([]));
+    }
+
+    #[test]
+    fn it_scrap_blocks_anchor_and_shift() {
+        let scrap_texts = HashMap::new();
+        let exported = HashSet::new();
+        let writer = PandocWriter::new(&scrap_texts, &exported);
+        let key = ScrapKey::from_path_str("Book/Domain Driven");
+
+        let blocks = writer.scrap_blocks(&key, "## Usage\n");
+
+        assert_eq!(
+            blocks,
+            vec![
+                json!({
+                    "t": "Header",
+                    "c": [1, ["book/domain-driven", ["scrap"], [["ctx", "Book"]]],
+                        [str_("Domain"), space(), str_("Driven")]],
+                }),
+                json!({
+                    "t": "Header",
+                    "c": [3, ["book/domain-driven--usage", [], []], [str_("Usage")]],
+                }),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::exported(
+        "[[Book/DDD#Layers|layers]]",
+        &["Book/DDD"],
+        json!({
+            "t": "Link",
+            "c": [["", ["wikilink"], []], [str_("layers")], ["#book/ddd--layers", ""]],
+        })
+    )]
+    #[case::title_label(
+        "[[Book/DDD]]",
+        &["Book/DDD"],
+        json!({
+            "t": "Link",
+            "c": [["", ["wikilink"], []], [str_("DDD")], ["#book/ddd", ""]],
+        })
+    )]
+    #[case::outside_export(
+        "[[Book/DDD]]",
+        &[],
+        json!({ "t": "Span", "c": [["", ["wikilink"], []], [str_("DDD")]] })
+    )]
+    fn it_lowers_wikilinks(
+        #[case] input: &str,
+        #[case] exported: &[&str],
+        #[case] expected: Value,
+    ) {
+        assert_eq!(
+            writer_blocks(input, exported),
+            vec![json!({ "t": "Para", "c": [expected] })]
+        );
+    }
+
+    #[test]
+    fn it_lowers_tags_to_spans() {
+        assert_eq!(
+            writer_blocks("tagged #[[ai/ml]]", &[]),
+            vec![json!({
+                "t": "Para",
+                "c": [
+                    str_("tagged"),
+                    space(),
+                    {
+                        "t": "Span",
+                        "c": [["", ["tag"], [["tag", "ai/ml"]]], [str_("#ai/ml")]],
+                    },
+                ],
+            })]
+        );
+    }
+
+    #[test]
+    fn it_expands_embeds_between_paragraph_runs() {
+        assert_eq!(
+            writer_blocks("before ![[Book/DDD#Layers]] after", &[]),
+            vec![
+                json!({ "t": "Para", "c": [str_("before")] }),
+                json!({
+                    "t": "Div",
+                    "c": [
+                        ["", ["scrap-embed"], [["src", "Book/DDD"]]],
+                        [{ "t": "Para", "c": [str_("layered"), space(), str_("body")] }],
+                    ],
+                }),
+                json!({ "t": "Para", "c": [str_("after")] }),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_marks_missing_embeds() {
+        let blocks = writer_blocks("![[nowhere]]", &[]);
+        assert_eq!(
+            blocks[0]["c"][0],
+            json!(["", ["scrap-embed", "scrap-embed-missing"], []])
+        );
+    }
+
+    #[rstest]
+    #[case::code(
+        "```rust\nfn main() {}\n```",
+        json!({ "t": "CodeBlock", "c": [["", ["rust"], []], "fn main() {}"] })
+    )]
+    #[case::bullet_tight(
+        "- a\n- b",
+        json!({
+            "t": "BulletList",
+            "c": [[{ "t": "Plain", "c": [str_("a")] }], [{ "t": "Plain", "c": [str_("b")] }]],
+        })
+    )]
+    #[case::ordered(
+        "3. a",
+        json!({
+            "t": "OrderedList",
+            "c": [[3, { "t": "Decimal" }, { "t": "Period" }], [[{ "t": "Plain", "c": [str_("a")] }]]],
+        })
+    )]
+    #[case::task(
+        "- [x] done",
+        json!({
+            "t": "BulletList",
+            "c": [[{ "t": "Plain", "c": [str_("☒"), space(), str_("done")] }]],
+        })
+    )]
+    #[case::task_other_symbol(
+        "- [-] dropped",
+        json!({
+            "t": "BulletList",
+            "c": [[{ "t": "Plain", "c": [str_("[-]"), space(), str_("dropped")] }]],
+        })
+    )]
+    #[case::rule("---", json!({ "t": "HorizontalRule" }))]
+    fn it_converts_commonmark_blocks(#[case] input: &str, #[case] expected: Value) {
+        assert_eq!(writer_blocks(input, &[]), vec![expected]);
+    }
+
+    #[test]
+    fn it_inlines_footnotes_as_notes() {
+        let blocks = writer_blocks("text[^1]\n\n[^1]: note body\n", &[]);
+        assert_eq!(
+            blocks,
+            vec![json!({
+                "t": "Para",
+                "c": [
+                    str_("text"),
+                    { "t": "Note", "c": [{ "t": "Para", "c": [str_("note"), space(), str_("body")] }] },
+                ],
+            })]
+        );
+    }
+
+    #[test]
+    fn it_converts_tables() {
+        let blocks = writer_blocks("| a | b |\n|:--|--:|\n| 1 | 2 |\n", &[]);
+        let table = &blocks[0];
+        assert_eq!(table["t"], "Table");
+        assert_eq!(
+            table["c"][2],
+            json!([
+                [{ "t": "AlignLeft" }, { "t": "ColWidthDefault" }],
+                [{ "t": "AlignRight" }, { "t": "ColWidthDefault" }],
+            ])
+        );
+        assert_eq!(table["c"][3][1].as_array().unwrap().len(), 1);
+        assert_eq!(table["c"][4][0][3].as_array().unwrap().len(), 1);
+    }
+}
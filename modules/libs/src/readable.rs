@@ -0,0 +1,176 @@
+//! Plain-markdown rendering of a scrap for readers that cannot follow
+//! `[[]]`-family syntax, such as LLM tools consuming a packed corpus.
+//! Wikilinks become `label (→ ctx/title#heading)`, tags become `#a/b`, and
+//! embeds are inlined as the referenced markdown when expanded.
+
+use std::collections::HashSet;
+
+use comrak::{
+    nodes::{NodeValue, NodeWikiLink},
+    Arena,
+};
+
+use crate::html::EmbedMode;
+use crate::markdown::query::{
+    collect_text, expose_embed_wikilinks, line_col_to_byte, line_starts, parse, split_heading,
+};
+use crate::model::key::ScrapKey;
+use crate::slugify;
+
+/// Rewrite every `[[]]`-family occurrence in `text` into readable markdown.
+/// Everything else, including code blocks, is kept byte-for-byte.
+pub fn to_markdown(text: &str, embed_mode: EmbedMode<'_>) -> String {
+    to_markdown_inner(text, &embed_mode, &mut HashSet::new())
+}
+
+fn to_markdown_inner(
+    text: &str,
+    embed_mode: &EmbedMode<'_>,
+    visited_embeds: &mut HashSet<ScrapKey>,
+) -> String {
+    let arena = Arena::new();
+    let root = parse(&arena, &expose_embed_wikilinks(text));
+    let starts = line_starts(text);
+
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for node in root.descendants() {
+        let NodeValue::WikiLink(NodeWikiLink { url }) = &node.data().value else {
+            continue;
+        };
+        let pos = node.data().sourcepos;
+        let start = line_col_to_byte(&starts, pos.start.line, pos.start.column);
+        let end = line_col_to_byte(&starts, pos.end.line, pos.end.column) + 1;
+        let prefix = start.checked_sub(1).and_then(|i| text.as_bytes().get(i));
+        let (start, replacement) = match prefix {
+            Some(b'#') => (start - 1, tag_ref(url)),
+            Some(b'!') => (start - 1, embed_ref(url, embed_mode, visited_embeds)),
+            _ => (start, link_ref(url, &collect_text(node))),
+        };
+        if start < cursor || end > text.len() {
+            continue;
+        }
+        out.push_str(&text[cursor..start]);
+        out.push_str(&replacement);
+        cursor = end;
+    }
+    out.push_str(&text[cursor..]);
+    out
+}
+
+fn link_ref(url: &str, label: &str) -> String {
+    let (path, heading) = split_heading(url);
+    let reference = match heading {
+        Some(h) => format!("{}#{}", ScrapKey::from_path_str(path), h),
+        None => ScrapKey::from_path_str(path).to_string(),
+    };
+    let label = if label == url {
+        ScrapKey::from_path_str(path).title().to_string()
+    } else {
+        label.to_string()
+    };
+    if label == reference {
+        label
+    } else {
+        format!("{label} (→ {reference})")
+    }
+}
+
+fn tag_ref(url: &str) -> String {
+    let (path, _) = split_heading(url);
+    format!("#{path}")
+}
+
+fn embed_ref(
+    url: &str,
+    embed_mode: &EmbedMode<'_>,
+    visited_embeds: &mut HashSet<ScrapKey>,
+) -> String {
+    let (path, heading) = split_heading(url);
+    let scrap_key = ScrapKey::from_path_str(path);
+    let scraps = match embed_mode {
+        EmbedMode::Expand(scraps) => scraps,
+        EmbedMode::Preserve => return format!("(embed → {url})"),
+    };
+    let Some(scrap) = scraps.get(&scrap_key) else {
+        return format!("(missing embed → {url})");
+    };
+    if !visited_embeds.insert(scrap_key.clone()) {
+        return format!("(cyclic embed → {url})");
+    }
+
+    let doc = scrap.document();
+    let embed_text = heading
+        .and_then(|h| doc.section(&slugify::by_dash(h)))
+        .unwrap_or(doc.text());
+    let expanded = to_markdown_inner(embed_text, embed_mode, visited_embeds);
+    visited_embeds.remove(&scrap_key);
+    expanded.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::scrap::Scrap;
+    use rstest::rstest;
+    use std::collections::HashMap;
+
+    #[rstest]
+    #[case::basic("see [[link]]", "see link")]
+    #[case::alias("see [[link|display]]", "see display (→ link)")]
+    #[case::context("see [[Context/link]]", "see link (→ Context/link)")]
+    #[case::heading("see [[link#Usage]]", "see link (→ link#Usage)")]
+    #[case::alias_heading("see [[Book/DDD#Layers|layers]]", "see layers (→ Book/DDD#Layers)")]
+    #[case::japanese("[[日本語]]と[[文脈/記事]]", "日本語と記事 (→ 文脈/記事)")]
+    fn it_rewrites_links(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(to_markdown(input, EmbedMode::Preserve), expected);
+    }
+
+    #[rstest]
+    #[case::single("tagged #[[ai]]", "tagged #ai")]
+    #[case::nested("tagged #[[ai/ml]] here", "tagged #ai/ml here")]
+    fn it_rewrites_tags(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(to_markdown(input, EmbedMode::Preserve), expected);
+    }
+
+    #[rstest]
+    #[case::fenced_code("```\n[[code]]\n```\n")]
+    #[case::inline_code("`[[code]]` and `#[[tag]]`")]
+    #[case::plain("# Title\n\n- item\n")]
+    fn it_keeps_non_refs(#[case] input: &str) {
+        assert_eq!(to_markdown(input, EmbedMode::Preserve), input);
+    }
+
+    #[test]
+    fn it_preserves_embed_as_reference() {
+        assert_eq!(
+            to_markdown("![[paper]]", EmbedMode::Preserve),
+            "(embed → paper)"
+        );
+    }
+
+    #[test]
+    fn it_expands_embeds() {
+        let paper = Scrap::new(
+            "paper",
+            &None,
+            "# Paper\n\n## Notes\n\nsee [[other]]\n\n## Other\n\nhidden\n",
+        );
+        let cycle = Scrap::new("loop", &None, "before ![[loop]] after");
+        let scraps = HashMap::from([(paper.self_key(), &paper), (cycle.self_key(), &cycle)]);
+        let expand = EmbedMode::Expand(&scraps);
+
+        assert_eq!(
+            to_markdown("intro\n\n![[paper#Notes]]\n", expand),
+            "intro\n\nsee other\n"
+        );
+        assert_eq!(
+            to_markdown("![[loop]]", EmbedMode::Expand(&scraps)),
+            "before (cyclic embed → loop) after"
+        );
+        assert_eq!(
+            to_markdown("![[nowhere]]", EmbedMode::Expand(&scraps)),
+            "(missing embed → nowhere)"
+        );
+    }
+}
@@ -1,3 +1,6 @@
+pub mod bm25_engine;
 pub mod engine;
 pub mod fuzzy_engine;
 pub mod result;
+pub mod snippet;
+pub mod tokenizer;
@@ -0,0 +1,439 @@
+//! Full-text search ranked with BM25 over an inverted index.
+//!
+//! Term frequencies from the title and headings are boosted before BM25
+//! saturation (a BM25F-style field weighting). Quoted `"exact phrase"`
+//! queries match consecutive tokens; a keyword that tokenizes to several
+//! tokens, such as a CJK word or `foo-bar`, is matched as a phrase too.
+
+use std::collections::{HashMap, HashSet};
+
+use super::engine::{SearchEngine, SearchLogic};
+use super::result::SearchItem;
+use super::tokenizer::{is_cjk_unigram, tokenize};
+
+/// Ranking parameters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bm25Params {
+    /// Term frequency saturation.
+    pub k1: f64,
+    /// Document length normalisation.
+    pub b: f64,
+    /// Weight of one title occurrence relative to a body occurrence.
+    pub title_boost: f64,
+    /// Weight of one heading occurrence relative to a body occurrence.
+    pub heading_boost: f64,
+}
+
+impl Default for Bm25Params {
+    fn default() -> Self {
+        Self {
+            k1: 1.2,
+            b: 0.75,
+            title_boost: 3.0,
+            heading_boost: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Heading,
+    Body,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Posting {
+    doc: usize,
+    title_tf: u32,
+    heading_tf: u32,
+    body_tf: u32,
+    /// Token positions across the document's fields, ascending. Fields are
+    /// separated by a gap so phrases never span two of them.
+    positions: Vec<u32>,
+}
+
+/// Inverted index over a set of search items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bm25Index {
+    items: Vec<SearchItem>,
+    doc_lens: Vec<u32>,
+    avg_len: f64,
+    postings: HashMap<String, Vec<Posting>>,
+    params: Bm25Params,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Clause {
+    Term(String),
+    Phrase(Vec<String>),
+}
+
+impl Bm25Index {
+    pub fn new(items: &[SearchItem], params: Bm25Params) -> Self {
+        let mut postings: HashMap<String, Vec<Posting>> = HashMap::new();
+        let mut doc_lens = Vec::with_capacity(items.len());
+
+        for (doc, item) in items.iter().enumerate() {
+            let mut fields = vec![(Field::Title, tokenize(&item.title))];
+            fields.extend(item.headings.iter().map(|h| (Field::Heading, tokenize(h))));
+            let body_tokens = match &item.body_tokens {
+                Some(tokens) => tokens.clone(),
+                None => tokenize(&item.body),
+            };
+            fields.push((Field::Body, body_tokens));
+
+            let mut doc_postings: HashMap<String, Posting> = HashMap::new();
+            let mut position: u32 = 0;
+            let mut len: u32 = 0;
+            for (field, tokens) in fields {
+                for token in tokens {
+                    let posting = doc_postings.entry(token).or_insert_with(|| Posting {
+                        doc,
+                        ..Posting::default()
+                    });
+                    match field {
+                        Field::Title => posting.title_tf += 1,
+                        Field::Heading => posting.heading_tf += 1,
+                        Field::Body => posting.body_tf += 1,
+                    }
+                    posting.positions.push(position);
+                    position += 1;
+                    len += 1;
+                }
+                position += 1;
+            }
+            doc_lens.push(len);
+            for (token, posting) in doc_postings {
+                postings.entry(token).or_default().push(posting);
+            }
+        }
+
+        let avg_len = if items.is_empty() {
+            0.0
+        } else {
+            doc_lens.iter().map(|&l| l as f64).sum::<f64>() / items.len() as f64
+        };
+        Self {
+            items: items.to_vec(),
+            doc_lens,
+            avg_len,
+            postings,
+            params,
+        }
+    }
+
+    /// Best `num` items for `query` with their scores, highest first.
+    pub fn search(&self, query: &str, num: usize, logic: SearchLogic) -> Vec<(SearchItem, f64)> {
+        self.ranked(query, logic)
+            .into_iter()
+            .take(num)
+            .map(|(doc, score)| (self.items[doc].clone(), score))
+            .collect()
+    }
+
+    /// Matching documents and their scores, highest first, ties in index
+    /// order. An empty query matches every item with score zero.
+    fn ranked(&self, query: &str, logic: SearchLogic) -> Vec<(usize, f64)> {
+        let clauses = parse_query(query);
+        if clauses.is_empty() {
+            return (0..self.items.len()).map(|doc| (doc, 0.0)).collect();
+        }
+
+        let mut totals: HashMap<usize, (usize, f64)> = HashMap::new();
+        for clause in &clauses {
+            for (doc, score) in self.clause_scores(clause) {
+                let entry = totals.entry(doc).or_default();
+                entry.0 += 1;
+                entry.1 += score;
+            }
+        }
+
+        let mut ranked: Vec<(usize, f64)> = totals
+            .into_iter()
+            .filter(|(_, (matched, _))| match logic {
+                SearchLogic::And => *matched == clauses.len(),
+                SearchLogic::Or => true,
+            })
+            .map(|(doc, (_, score))| (doc, score))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    fn clause_scores(&self, clause: &Clause) -> HashMap<usize, f64> {
+        match clause {
+            Clause::Term(term) if is_cjk_unigram(term) => {
+                // A lone CJK character is indexed inside bigrams; match any
+                // bigram containing it.
+                let mut scores: HashMap<usize, f64> = HashMap::new();
+                for indexed in self.postings.keys().filter(|t| t.contains(term.as_str())) {
+                    for (doc, score) in self.term_scores(indexed) {
+                        *scores.entry(doc).or_default() += score;
+                    }
+                }
+                scores
+            }
+            Clause::Term(term) => self.term_scores(term),
+            Clause::Phrase(terms) => self.phrase_scores(terms),
+        }
+    }
+
+    fn term_scores(&self, term: &str) -> HashMap<usize, f64> {
+        let Some(postings) = self.postings.get(term) else {
+            return HashMap::new();
+        };
+        let idf = self.idf(postings.len());
+        postings
+            .iter()
+            .map(|p| (p.doc, idf * self.saturate(p)))
+            .collect()
+    }
+
+    fn phrase_scores(&self, terms: &[String]) -> HashMap<usize, f64> {
+        let mut lists: Vec<HashMap<usize, &Posting>> = Vec::with_capacity(terms.len());
+        for term in terms {
+            let Some(postings) = self.postings.get(term) else {
+                return HashMap::new();
+            };
+            lists.push(postings.iter().map(|p| (p.doc, p)).collect());
+        }
+
+        let mut scores = HashMap::new();
+        for (&doc, first) in &lists[0] {
+            let Some(rest): Option<Vec<&Posting>> =
+                lists[1..].iter().map(|l| l.get(&doc).copied()).collect()
+            else {
+                continue;
+            };
+            let consecutive = first.positions.iter().any(|&start| {
+                rest.iter()
+                    .enumerate()
+                    .all(|(i, p)| p.positions.binary_search(&(start + i as u32 + 1)).is_ok())
+            });
+            if consecutive {
+                let score = std::iter::once(first)
+                    .chain(rest.iter())
+                    .zip(terms)
+                    .map(|(p, term)| self.idf(self.postings[term].len()) * self.saturate(p))
+                    .sum();
+                scores.insert(doc, score);
+            }
+        }
+        scores
+    }
+
+    fn idf(&self, df: usize) -> f64 {
+        let n = self.items.len() as f64;
+        let df = df as f64;
+        (1.0 + (n - df + 0.5) / (df + 0.5)).ln()
+    }
+
+    fn saturate(&self, posting: &Posting) -> f64 {
+        let Bm25Params {
+            k1,
+            b,
+            title_boost,
+            heading_boost,
+        } = self.params;
+        let tf = title_boost * posting.title_tf as f64
+            + heading_boost * posting.heading_tf as f64
+            + posting.body_tf as f64;
+        let len = self.doc_lens[posting.doc] as f64;
+        let norm = if self.avg_len > 0.0 {
+            1.0 - b + b * len / self.avg_len
+        } else {
+            1.0
+        };
+        tf * (k1 + 1.0) / (tf + k1 * norm)
+    }
+}
+
+/// Split a query into quoted phrases and keywords. Repeated clauses are
+/// dropped so a word typed twice is not scored twice.
+fn parse_query(query: &str) -> Vec<Clause> {
+    let mut clauses = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        // Odd segments sit between quotes; an unclosed quote still counts.
+        let pieces: Vec<&str> = if i % 2 == 1 {
+            vec![part]
+        } else {
+            part.split_whitespace().collect()
+        };
+        for piece in pieces {
+            let mut tokens = tokenize(piece);
+            let clause = match tokens.len() {
+                0 => continue,
+                1 => Clause::Term(tokens.remove(0)),
+                _ => Clause::Phrase(tokens),
+            };
+            clauses.push(clause);
+        }
+    }
+    let mut seen = HashSet::new();
+    clauses.retain(|c| seen.insert(c.clone()));
+    clauses
+}
+
+/// Search engine building a [`Bm25Index`] over the items on every call.
+pub struct Bm25SearchEngine {
+    params: Bm25Params,
+}
+
+impl Default for Bm25SearchEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bm25SearchEngine {
+    pub fn new() -> Self {
+        Self {
+            params: Bm25Params::default(),
+        }
+    }
+
+    pub fn with_params(params: Bm25Params) -> Self {
+        Self { params }
+    }
+}
+
+impl SearchEngine for Bm25SearchEngine {
+    fn search_scored(
+        &self,
+        items: &[SearchItem],
+        query: &str,
+        num: usize,
+        logic: SearchLogic,
+    ) -> Vec<(SearchItem, f64)> {
+        Bm25Index::new(items, self.params).search(query, num, logic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::{fixture, rstest};
+
+    #[fixture]
+    fn engine() -> Bm25SearchEngine {
+        Bm25SearchEngine::new()
+    }
+
+    fn titles(results: &[SearchItem]) -> Vec<&str> {
+        results.iter().map(|r| r.title.as_str()).collect()
+    }
+
+    #[rstest]
+    fn test_ranks_rarer_and_denser_terms_higher(engine: Bm25SearchEngine) {
+        let items = vec![
+            SearchItem::new("A", "tokio tokio runtime"),
+            SearchItem::new("B", "runtime for async code with many other words here"),
+            SearchItem::new("C", "gardening"),
+        ];
+        let results = engine.search(&items, "tokio runtime", 100, SearchLogic::Or);
+
+        assert_eq!(titles(&results), vec!["A", "B"]);
+    }
+
+    #[rstest]
+    fn test_and_requires_every_clause(engine: Bm25SearchEngine) {
+        let items = vec![
+            SearchItem::new("Rust Guide", ""),
+            SearchItem::new("Python Guide", ""),
+            SearchItem::new("Rust and Python", ""),
+        ];
+        let results = engine.search(&items, "rust python", 100, SearchLogic::And);
+
+        assert_eq!(titles(&results), vec!["Rust and Python"]);
+    }
+
+    #[rstest]
+    fn test_exact_phrase(engine: Bm25SearchEngine) {
+        let items = vec![
+            SearchItem::new("A", "the borrow checker rejects this"),
+            SearchItem::new("B", "checker of the borrow"),
+        ];
+        let results = engine.search(&items, "\"borrow checker\"", 100, SearchLogic::Or);
+
+        assert_eq!(titles(&results), vec!["A"]);
+    }
+
+    #[rstest]
+    fn test_phrase_does_not_span_fields(engine: Bm25SearchEngine) {
+        let items = vec![SearchItem::new("borrow", "checker")];
+        let results = engine.search(&items, "\"borrow checker\"", 100, SearchLogic::Or);
+
+        assert!(results.is_empty());
+    }
+
+    #[rstest]
+    fn test_title_and_heading_boosts(engine: Bm25SearchEngine) {
+        let items = vec![
+            SearchItem::new("Notes", "some words about lifetimes in passing"),
+            SearchItem::new("Other", "some words about lifetimes in passing")
+                .with_headings(vec!["Lifetimes".to_string()]),
+            SearchItem::new("Lifetimes", "some words about lifetimes in passing"),
+        ];
+        let results = engine.search(&items, "lifetimes", 100, SearchLogic::Or);
+
+        assert_eq!(titles(&results), vec!["Lifetimes", "Other", "Notes"]);
+    }
+
+    #[rstest]
+    fn test_pretokenized_body(engine: Bm25SearchEngine) {
+        let scores = |items: &[SearchItem]| -> Vec<(String, f64)> {
+            engine
+                .search_scored(items, "runtime", 100, SearchLogic::Or)
+                .into_iter()
+                .map(|(item, score)| (item.title, score))
+                .collect()
+        };
+        let plain = vec![
+            SearchItem::new("A", "tokio runtime"),
+            SearchItem::new("B", "runtime"),
+        ];
+        let pretokenized = vec![
+            SearchItem::new("A", "ignored").with_body_tokens(tokenize("tokio runtime")),
+            SearchItem::new("B", "runtime"),
+        ];
+
+        assert_eq!(scores(&pretokenized), scores(&plain));
+    }
+
+    #[rstest]
+    fn test_cjk_words_and_single_characters(engine: Bm25SearchEngine) {
+        let items = vec![
+            SearchItem::new("所有権", "所有権と借用のルール"),
+            SearchItem::new("犬", "犬が好き"),
+        ];
+
+        let results = engine.search(&items, "借用", 100, SearchLogic::Or);
+        assert_eq!(titles(&results), vec!["所有権"]);
+
+        let results = engine.search(&items, "ルール", 100, SearchLogic::Or);
+        assert_eq!(titles(&results), vec!["所有権"]);
+
+        let results = engine.search(&items, "好", 100, SearchLogic::Or);
+        assert_eq!(titles(&results), vec!["犬"]);
+    }
+
+    #[rstest]
+    fn test_empty_query_returns_all_up_to_num(engine: Bm25SearchEngine) {
+        let items = vec![SearchItem::new("A", ""), SearchItem::new("B", "")];
+        assert_eq!(engine.search(&items, "", 100, SearchLogic::Or).len(), 2);
+        assert_eq!(engine.search(&items, "  ", 1, SearchLogic::Or).len(), 1);
+    }
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            parse_query("rust \"borrow checker\" foo-bar rust"),
+            vec![
+                Clause::Term("rust".to_string()),
+                Clause::Phrase(vec!["borrow".to_string(), "checker".to_string()]),
+                Clause::Phrase(vec!["foo".to_string(), "bar".to_string()]),
+            ]
+        );
+    }
+}
@@ -11,11 +11,26 @@ pub enum SearchLogic {
 }
 
 pub trait SearchEngine {
+    /// Best `num` items with their scores, highest first. Scores are only
+    /// comparable within one engine.
+    fn search_scored(
+        &self,
+        items: &[SearchItem],
+        query: &str,
+        num: usize,
+        logic: SearchLogic,
+    ) -> Vec<(SearchItem, f64)>;
+
     fn search(
         &self,
         items: &[SearchItem],
         query: &str,
         num: usize,
         logic: SearchLogic,
-    ) -> Vec<SearchItem>;
+    ) -> Vec<SearchItem> {
+        self.search_scored(items, query, num, logic)
+            .into_iter()
+            .map(|(item, _)| item)
+            .collect()
+    }
 }
@@ -23,15 +23,15 @@ impl FuzzySearchEngine {
 }
 
 impl SearchEngine for FuzzySearchEngine {
-    fn search(
+    fn search_scored(
         &self,
         items: &[SearchItem],
         query: &str,
         num: usize,
         logic: SearchLogic,
-    ) -> Vec<SearchItem> {
+    ) -> Vec<(SearchItem, f64)> {
         if query.is_empty() {
-            return items.iter().take(num).cloned().collect();
+            return items.iter().take(num).map(|i| (i.clone(), 0.0)).collect();
         }
 
         // Split query by whitespace
@@ -66,12 +66,12 @@ impl SearchEngine for FuzzySearchEngine {
             })
             .collect();
 
-        results_with_scores.sort_by(|a, b| b.1.cmp(&a.1));
+        results_with_scores.sort_by_key(|r| std::cmp::Reverse(r.1));
 
         results_with_scores
             .into_iter()
             .take(num)
-            .map(|(result, _)| result)
+            .map(|(result, score)| (result, score as f64))
             .collect()
     }
 }
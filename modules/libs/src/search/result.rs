@@ -1,14 +1,44 @@
 #[derive(Debug, Clone, PartialEq)]
 pub struct SearchItem {
+    /// Caller's identifier for mapping results back; the title by default.
+    pub id: String,
     pub title: String,
     pub search_text: String,
+    pub body: String,
+    /// Heading labels, which field-aware engines weigh above body text.
+    pub headings: Vec<String>,
+    /// `body` already run through the tokenizer, e.g. from a cache; token
+    /// based engines use it instead of tokenizing `body` again.
+    pub body_tokens: Option<Vec<String>>,
 }
 
 impl SearchItem {
     pub fn new(title: &str, body: &str) -> Self {
         Self {
+            id: title.to_string(),
             title: title.to_string(),
             search_text: format!("{} {}", title, body),
+            body: body.to_string(),
+            headings: Vec::new(),
+            body_tokens: None,
+        }
+    }
+
+    pub fn with_id(self, id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            ..self
+        }
+    }
+
+    pub fn with_headings(self, headings: Vec<String>) -> Self {
+        Self { headings, ..self }
+    }
+
+    pub fn with_body_tokens(self, body_tokens: Vec<String>) -> Self {
+        Self {
+            body_tokens: Some(body_tokens),
+            ..self
         }
     }
 }
@@ -20,7 +50,10 @@ mod tests {
     #[test]
     fn test_search_item_new() {
         let item = SearchItem::new("Test Title", "body content");
+        assert_eq!(item.id, "Test Title");
         assert_eq!(item.title, "Test Title");
         assert_eq!(item.search_text, "Test Title body content");
+        assert_eq!(item.body, "body content");
+        assert!(item.headings.is_empty());
     }
 }
@@ -0,0 +1,220 @@
+//! Excerpts of a matched document around the query terms.
+//!
+//! Terms are the query's whitespace-separated keywords and `"quoted
+//! phrases"`, matched literally and ASCII case-insensitively. Windows are
+//! ranked by how many distinct terms, then how many matches, they contain.
+
+use std::ops::Range;
+
+/// Characters of context kept before the first match of a window.
+const LEAD_CHARS: usize = 40;
+/// Total characters of a window.
+const WINDOW_CHARS: usize = 160;
+const ELLIPSIS: &str = "…";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// Excerpt on one line, with `…` where the text was cut.
+    pub text: String,
+    /// Byte ranges of the query matches within `text`.
+    pub matches: Vec<Range<usize>>,
+    /// 1-based source line of the first match.
+    pub line: usize,
+}
+
+/// Up to `max` non-overlapping excerpts of `text`, best first. Empty when no
+/// query term occurs literally, e.g. for a fuzzy subsequence match.
+pub fn snippets(text: &str, query: &str, max: usize) -> Vec<Snippet> {
+    let terms = query_terms(query);
+    let found = find_matches(text, &terms);
+    if found.is_empty() {
+        return Vec::new();
+    }
+
+    let mut windows: Vec<(Range<usize>, usize, usize)> = found
+        .iter()
+        .map(|(anchor, _)| {
+            let start = back_chars(text, anchor.start, LEAD_CHARS);
+            let end = forward_chars(text, start, WINDOW_CHARS).max(anchor.end);
+            let inside: Vec<&(Range<usize>, usize)> = found
+                .iter()
+                .filter(|(m, _)| m.start >= start && m.end <= end)
+                .collect();
+            let mut distinct: Vec<usize> = inside.iter().map(|(_, t)| *t).collect();
+            distinct.sort_unstable();
+            distinct.dedup();
+            (start..end, distinct.len(), inside.len())
+        })
+        .collect();
+    // Stable sort keeps earlier windows first among equals.
+    windows.sort_by_key(|w| std::cmp::Reverse((w.1, w.2)));
+
+    let mut chosen: Vec<Range<usize>> = Vec::new();
+    for (window, _, _) in windows {
+        if chosen.len() == max {
+            break;
+        }
+        if chosen
+            .iter()
+            .all(|c| window.end <= c.start || window.start >= c.end)
+        {
+            chosen.push(window);
+        }
+    }
+
+    chosen
+        .into_iter()
+        .map(|window| excerpt(text, window, &found))
+        .collect()
+}
+
+fn excerpt(text: &str, window: Range<usize>, found: &[(Range<usize>, usize)]) -> Snippet {
+    let raw = &text[window.clone()];
+    let trimmed_start = raw.len() - raw.trim_start().len();
+    let body = raw.trim();
+    let start = window.start + trimmed_start;
+    let end = start + body.len();
+
+    let prefix = if start > 0 { ELLIPSIS } else { "" };
+    let suffix = if end < text.len() { ELLIPSIS } else { "" };
+    // Replacing whitespace controls byte-for-byte keeps the offsets valid.
+    let flat: String = body
+        .chars()
+        .map(|c| {
+            if matches!(c, '\n' | '\r' | '\t') {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    let in_window: Vec<&Range<usize>> = found
+        .iter()
+        .map(|(m, _)| m)
+        .filter(|m| m.start >= start && m.end <= end)
+        .collect();
+    let first = in_window.first().map(|m| m.start).unwrap_or(start);
+
+    Snippet {
+        text: format!("{prefix}{flat}{suffix}"),
+        matches: in_window
+            .iter()
+            .map(|m| (m.start - start + prefix.len())..(m.end - start + prefix.len()))
+            .collect(),
+        line: text[..first].matches('\n').count() + 1,
+    }
+}
+
+fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        let pieces: Vec<&str> = if i % 2 == 1 {
+            vec![part.trim()]
+        } else {
+            part.split_whitespace().collect()
+        };
+        for piece in pieces.into_iter().filter(|p| !p.is_empty()) {
+            let term = piece.to_ascii_lowercase();
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+    }
+    terms
+}
+
+/// Non-overlapping matches in source order, tagged with their term index.
+fn find_matches(text: &str, terms: &[String]) -> Vec<(Range<usize>, usize)> {
+    let lower = text.to_ascii_lowercase();
+    let mut found: Vec<(Range<usize>, usize)> = terms
+        .iter()
+        .enumerate()
+        .flat_map(|(t, term)| {
+            lower
+                .match_indices(term.as_str())
+                .map(move |(i, m)| (i..i + m.len(), t))
+        })
+        .collect();
+    found.sort_by(|a, b| {
+        a.0.start
+            .cmp(&b.0.start)
+            .then_with(|| b.0.end.cmp(&a.0.end))
+    });
+
+    let mut kept: Vec<(Range<usize>, usize)> = Vec::with_capacity(found.len());
+    for m in found {
+        if kept.last().is_none_or(|(last, _)| m.0.start >= last.end) {
+            kept.push(m);
+        }
+    }
+    kept
+}
+
+fn back_chars(text: &str, from: usize, n: usize) -> usize {
+    text[..from]
+        .char_indices()
+        .rev()
+        .nth(n.saturating_sub(1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn forward_chars(text: &str, from: usize, n: usize) -> usize {
+    text[from..]
+        .char_indices()
+        .nth(n)
+        .map(|(i, _)| from + i)
+        .unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn highlighted(snippet: &Snippet) -> Vec<&str> {
+        snippet
+            .matches
+            .iter()
+            .map(|m| &snippet.text[m.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn it_excerpts_around_matches_case_insensitively() {
+        let text = "# Intro\n\nRust has a Borrow checker.";
+        let result = snippets(text, "borrow", 3);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].text, "# Intro  Rust has a Borrow checker.");
+        assert_eq!(highlighted(&result[0]), vec!["Borrow"]);
+        assert_eq!(result[0].line, 3);
+    }
+
+    #[test]
+    fn it_prefers_windows_with_more_distinct_terms() {
+        let filler = "lorem ipsum ".repeat(30);
+        let text = format!("rust alone here. {filler}rust and tokio together. {filler}");
+        let result = snippets(&text, "rust tokio", 2);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(highlighted(&result[0]), vec!["rust", "tokio"]);
+        assert!(result[0].text.starts_with(ELLIPSIS));
+        assert!(result[0].text.ends_with(ELLIPSIS));
+        assert_eq!(highlighted(&result[1]), vec!["rust"]);
+    }
+
+    #[test]
+    fn it_matches_quoted_phrases_and_multibyte_text() {
+        let text = "所有権と借用のルール。the borrow checker";
+        let result = snippets(text, "借用 \"borrow checker\"", 1);
+
+        assert_eq!(highlighted(&result[0]), vec!["借用", "borrow checker"]);
+    }
+
+    #[test]
+    fn it_returns_nothing_without_literal_matches() {
+        assert!(snippets("Documentation", "dcmt", 3).is_empty());
+        assert!(snippets("anything", "", 3).is_empty());
+    }
+}
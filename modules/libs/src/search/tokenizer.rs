@@ -0,0 +1,92 @@
+//! Word tokenizer for full-text search.
+//!
+//! Runs of letters and digits become lowercase tokens. CJK scripts do not
+//! separate words with spaces, so CJK runs fall back to overlapping bigrams
+//! (`借用規則` → `借用`, `用規`, `規則`); a lone CJK character is its own token.
+
+/// Split `text` into search tokens, in order.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let mut cjk_run: Vec<char> = Vec::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush_word(&mut word, &mut tokens);
+            cjk_run.push(c);
+        } else if c.is_alphanumeric() || c == '_' {
+            flush_cjk(&mut cjk_run, &mut tokens);
+            word.extend(c.to_lowercase());
+        } else {
+            flush_word(&mut word, &mut tokens);
+            flush_cjk(&mut cjk_run, &mut tokens);
+        }
+    }
+    flush_word(&mut word, &mut tokens);
+    flush_cjk(&mut cjk_run, &mut tokens);
+    tokens
+}
+
+/// Whether a token is a single CJK character, which can only match inside
+/// the bigrams of longer runs.
+pub fn is_cjk_unigram(token: &str) -> bool {
+    let mut chars = token.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if is_cjk(c))
+}
+
+fn flush_word(word: &mut String, tokens: &mut Vec<String>) {
+    if !word.is_empty() {
+        tokens.push(std::mem::take(word));
+    }
+}
+
+fn flush_cjk(run: &mut Vec<char>, tokens: &mut Vec<String>) {
+    match run.len() {
+        0 => {}
+        1 => tokens.push(run[0].to_string()),
+        _ => tokens.extend(run.windows(2).map(|pair| pair.iter().collect::<String>())),
+    }
+    run.clear();
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'   // Hiragana, Katakana
+            | '\u{3400}'..='\u{4DBF}' // CJK Extension A
+            | '\u{4E00}'..='\u{9FFF}' // CJK Unified Ideographs
+            | '\u{AC00}'..='\u{D7AF}' // Hangul Syllables
+            | '\u{F900}'..='\u{FAFF}' // CJK Compatibility Ideographs
+            | '\u{FF66}'..='\u{FF9F}' // Halfwidth Katakana
+            | '\u{20000}'..='\u{2FA1F}' // Supplementary ideographs
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_words_and_lowercases() {
+        assert_eq!(
+            tokenize("Hello, World! snake_case x2"),
+            vec!["hello", "world", "snake_case", "x2"]
+        );
+    }
+
+    #[test]
+    fn it_uses_bigrams_for_cjk_runs() {
+        assert_eq!(
+            tokenize("Rustの借用規則"),
+            vec!["rust", "の借", "借用", "用規", "規則"]
+        );
+        assert_eq!(tokenize("犬 と 猫"), vec!["犬", "と", "猫"]);
+    }
+
+    #[test]
+    fn it_detects_cjk_unigrams() {
+        assert!(is_cjk_unigram("犬"));
+        assert!(!is_cjk_unigram("借用"));
+        assert!(!is_cjk_unigram("a"));
+    }
+}
@@ -1,8 +1,11 @@
 use clap::{Parser, Subcommand, ValueEnum};
 use clap_verbosity_flag::{Verbosity, WarnLevel};
+use std::net::IpAddr;
 use std::path::PathBuf;
 
+use crate::usecase::build::model::target::BuildTarget;
 use crate::usecase::lint::rule::LintRuleName;
+use crate::usecase::search::usecase::{SearchEngineKind, SearchScope};
 use crate::usecase::todo::usecase::StatusFilter;
 use scraps_libs::search::engine::SearchLogic;
 
@@ -26,6 +29,14 @@ pub struct Cli {
     )]
     pub directory: Option<PathBuf>,
 
+    #[arg(
+        long,
+        global = true,
+        env = "SCRAPS_NO_CACHE",
+        help = "Parse every scrap instead of reusing the cache (only build populates it)"
+    )]
+    pub no_cache: bool,
+
     #[command(subcommand)]
     pub command: SubCommands,
 }
@@ -42,6 +53,33 @@ pub enum SubCommands {
             help = "Include git-derived metadata (commited_ts) in HTML output and template variables"
         )]
         git: bool,
+
+        #[arg(
+            long,
+            help = "Render link cards from .scraps-ogp.json only, without fetching linked pages"
+        )]
+        offline: bool,
+
+        #[arg(
+            long,
+            value_enum,
+            default_value_t = CliBuildTarget::Html,
+            help = "Emit target: the static site, llms.txt and llms-full.txt for LLM tools, or scraps.json typed graph"
+        )]
+        target: CliBuildTarget,
+
+        #[arg(
+            long,
+            value_name = "TOKENS",
+            help = "Approximate token budget for llms-full.txt; most-linked scraps are packed first"
+        )]
+        token_budget: Option<usize>,
+    },
+
+    #[command(about = "Export scraps to other document formats")]
+    Export {
+        #[command(subcommand)]
+        export_command: ExportSubCommands,
     },
 
     #[command(about = "Get the markdown body of a scrap by title")]
@@ -88,6 +126,23 @@ pub enum SubCommands {
         #[arg(long, help = "Disambiguate title across contexts")]
         ctx: Option<String>,
 
+        #[arg(long, help = "Follow links breadth-first up to this many hops")]
+        depth: Option<usize>,
+
+        #[arg(
+            long,
+            requires = "depth",
+            help = "With --depth, also report link targets without a scrap"
+        )]
+        include_broken: bool,
+
+        #[arg(
+            long,
+            requires = "depth",
+            help = "With --depth, also hop through explicit tags"
+        )]
+        include_tags: bool,
+
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
@@ -99,11 +154,99 @@ pub enum SubCommands {
         #[arg(long, help = "Disambiguate title across contexts")]
         ctx: Option<String>,
 
+        #[arg(
+            long,
+            help = "Show each link with its line, section and enclosing paragraph"
+        )]
+        context: bool,
+
+        #[arg(
+            long,
+            conflicts_with = "context",
+            help = "Follow backlinks breadth-first up to this many hops"
+        )]
+        depth: Option<usize>,
+
+        #[arg(
+            long,
+            requires = "depth",
+            help = "With --depth, also hop through explicit tags"
+        )]
+        include_tags: bool,
+
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
 
-    #[command(about = "Search scraps by query (fuzzy)")]
+    #[command(about = "Suggest related scraps that are not linked yet")]
+    Related {
+        title: String,
+
+        #[arg(long, help = "Disambiguate title across contexts")]
+        ctx: Option<String>,
+
+        #[arg(long, default_value_t = 10, help = "Maximum number of results")]
+        num: usize,
+
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
+    #[command(about = "Find the shortest wiki-link path between two scraps")]
+    Path {
+        from: String,
+
+        to: String,
+
+        #[arg(long, help = "Disambiguate the starting title across contexts")]
+        from_ctx: Option<String>,
+
+        #[arg(long, help = "Disambiguate the target title across contexts")]
+        to_ctx: Option<String>,
+
+        #[arg(long, help = "Also follow links backwards")]
+        undirected: bool,
+
+        #[arg(long, help = "Allow hops through shared tags")]
+        via_tags: bool,
+
+        #[arg(
+            long,
+            default_value_t = 6,
+            help = "Maximum number of hops; a tag hop counts as two"
+        )]
+        max_length: usize,
+
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
+    #[command(about = "List plain-text mentions of a scrap title that are not linked yet")]
+    Mentions {
+        title: String,
+
+        #[arg(long, help = "Disambiguate title across contexts")]
+        ctx: Option<String>,
+
+        #[arg(long, help = "Rewrite the mentions into wikilinks in place")]
+        link: bool,
+
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
+    #[command(
+        about = "Find scraps matching a structured query",
+        long_about = "Find scraps matching a structured query, e.g.\n  tag:rust/async AND links-to:\"tokio\" AND NOT has:backlinks sort:linked_count\n\nFields: tag, links-to, linked-from, ctx, title, text, has (backlinks|links|tags|tasks|ctx), task (open|done|deferred), sort (linked_count|links_count|title). Bare words match title and body text. Combine with AND, OR, NOT and parentheses."
+    )]
+    Query {
+        expression: String,
+
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
+    #[command(about = "Search scraps by query")]
     Search {
         query: String,
 
@@ -118,6 +261,32 @@ pub enum SubCommands {
         )]
         logic: CliSearchLogic,
 
+        #[arg(
+            long,
+            value_enum,
+            help = "Ranking engine [default: `[search] engine` in config, else fuzzy]"
+        )]
+        engine: Option<CliSearchEngine>,
+
+        #[arg(long, help = "Only scraps in this context or a nested one")]
+        ctx: Option<String>,
+
+        #[arg(
+            long,
+            help = "Skip scraps in this context or a nested one (repeatable)"
+        )]
+        exclude_ctx: Vec<String>,
+
+        #[arg(long, help = "Only scraps with this tag or one of its descendants")]
+        tag: Option<String>,
+
+        #[arg(
+            long = "in",
+            value_enum,
+            help = "Search only this field instead of title and body"
+        )]
+        scope: Option<CliSearchScope>,
+
         #[arg(long, help = "Output as JSON")]
         json: bool,
     },
@@ -129,6 +298,30 @@ pub enum SubCommands {
             help = "Include git-derived metadata (commited_ts) in HTML output and template variables"
         )]
         git: bool,
+
+        #[arg(
+            long,
+            value_name = "IP",
+            default_value = DEFAULT_SERVE_ADDR,
+            help = "Address to listen on"
+        )]
+        addr: IpAddr,
+
+        #[arg(
+            long,
+            default_value_t = DEFAULT_SERVE_PORT,
+            help = "Port to listen on; the next free port is used when it is busy"
+        )]
+        port: u16,
+
+        #[arg(long, help = "Open the site in the default browser")]
+        open: bool,
+
+        #[arg(
+            long,
+            help = "Serve under the path of the configured base_url (e.g. /wiki/) so links match the deployed layout"
+        )]
+        base_path: bool,
     },
 
     #[command(about = "Tag commands")]
@@ -151,6 +344,12 @@ pub enum SubCommands {
         json: bool,
     },
 
+    #[command(about = "Report wiki health and growth metrics")]
+    Stats {
+        #[arg(long, help = "Output as JSON")]
+        json: bool,
+    },
+
     #[command(about = "MCP server commands")]
     Mcp {
         #[command(subcommand)]
@@ -158,6 +357,23 @@ pub enum SubCommands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum ExportSubCommands {
+    #[command(
+        about = "Write a Pandoc JSON AST to stdout: one scrap, a ctx subtree, or the whole wiki README-first"
+    )]
+    Pandoc {
+        #[arg(help = "Export only this scrap")]
+        title: Option<String>,
+
+        #[arg(
+            long,
+            help = "Disambiguate the title across contexts, or export this ctx subtree when no title is given"
+        )]
+        ctx: Option<String>,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum TagSubCommands {
     #[command(about = "List tags")]
@@ -178,6 +394,8 @@ pub enum TagSubCommands {
 /// Loopback IPv4 rather than `localhost`, which resolves to `::1` first on some
 /// systems and would leave IPv4 clients unable to connect.
 const DEFAULT_MCP_HTTP_ADDR: &str = "127.0.0.1:1113";
+const DEFAULT_SERVE_ADDR: &str = "127.0.0.1";
+const DEFAULT_SERVE_PORT: u16 = 1112;
 
 #[derive(Subcommand)]
 pub enum McpSubCommands {
@@ -220,6 +438,16 @@ pub enum CliLintRuleName {
     StaleByGit,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CliBuildTarget {
+    #[value(name = "html")]
+    Html,
+    #[value(name = "llms")]
+    Llms,
+    #[value(name = "json")]
+    Json,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum CliSearchLogic {
     #[value(name = "and")]
@@ -228,6 +456,26 @@ pub enum CliSearchLogic {
     Or,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CliSearchEngine {
+    #[value(name = "fuzzy")]
+    Fuzzy,
+    #[value(name = "bm25")]
+    Bm25,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum CliSearchScope {
+    #[value(name = "title")]
+    Title,
+    #[value(name = "headings")]
+    Headings,
+    #[value(name = "body")]
+    Body,
+    #[value(name = "code")]
+    Code,
+}
+
 #[derive(Clone, Copy, Debug, ValueEnum)]
 pub enum CliTodoStatus {
     #[value(name = "open")]
@@ -251,6 +499,36 @@ impl From<CliTodoStatus> for StatusFilter {
     }
 }
 
+impl From<CliBuildTarget> for BuildTarget {
+    fn from(cli: CliBuildTarget) -> Self {
+        match cli {
+            CliBuildTarget::Html => BuildTarget::Html,
+            CliBuildTarget::Llms => BuildTarget::Llms,
+            CliBuildTarget::Json => BuildTarget::Json,
+        }
+    }
+}
+
+impl From<CliSearchEngine> for SearchEngineKind {
+    fn from(cli: CliSearchEngine) -> Self {
+        match cli {
+            CliSearchEngine::Fuzzy => SearchEngineKind::Fuzzy,
+            CliSearchEngine::Bm25 => SearchEngineKind::Bm25,
+        }
+    }
+}
+
+impl From<CliSearchScope> for SearchScope {
+    fn from(cli: CliSearchScope) -> Self {
+        match cli {
+            CliSearchScope::Title => SearchScope::Title,
+            CliSearchScope::Headings => SearchScope::Headings,
+            CliSearchScope::Body => SearchScope::Body,
+            CliSearchScope::Code => SearchScope::Code,
+        }
+    }
+}
+
 impl From<CliSearchLogic> for SearchLogic {
     fn from(cli: CliSearchLogic) -> Self {
         match cli {
@@ -1,11 +1,17 @@
 pub mod backlinks;
 pub mod build;
+pub mod export;
 pub mod get;
 pub mod init;
 pub mod links;
 pub mod lint;
 pub mod mcp;
+pub mod mentions;
+pub mod path;
+pub mod query;
+pub mod related;
 pub mod search;
 pub mod serve;
+pub mod stats;
 pub mod tag;
 pub mod todo;
@@ -7,24 +7,31 @@ use comfy_table::{Cell, Table};
 use serde::{Deserialize, Serialize};
 
 use crate::cli::config::scrap_config::ScrapConfig;
-use crate::cli::json::scrap::ScrapKeyJson;
+use crate::cli::display::traversal::DisplayTraversalTable;
+use crate::cli::json::scrap::{BacklinkJson, TraversalJson, TraversalResponse};
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::scrap::lookup_backlinks::usecase::LookupScrapBacklinksUsecase;
+use crate::usecase::scrap::traverse::usecase::{TraverseLinksUsecase, TraverseOptions};
 use scraps_libs::model::context::Ctx;
 use scraps_libs::model::title::Title;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct BacklinksResponse {
-    results: Vec<ScrapKeyJson>,
+    results: Vec<BacklinkJson>,
     count: usize,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     title: &str,
     ctx: Option<&str>,
+    traverse: Option<TraverseOptions>,
+    context: bool,
     json: bool,
+    no_cache: bool,
     project_path: Option<&Path>,
     writer: &mut impl Write,
 ) -> ScrapsResult<()> {
@@ -36,42 +43,89 @@ pub fn run(
         path_resolver.output_dir(&config),
     ];
 
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
     let target_title = Title::from(title);
     let target_ctx = ctx.map(Ctx::from);
 
+    if let Some(options) = traverse {
+        let nodes: Vec<TraversalJson> = TraverseLinksUsecase::new()
+            .execute(&scraps, &target_title, &target_ctx, &options)?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        if json {
+            let response = TraversalResponse {
+                count: nodes.len(),
+                results: nodes,
+            };
+            writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        } else if !nodes.is_empty() {
+            writeln!(
+                writer,
+                "{}",
+                DisplayTraversalTable::new(&nodes, options.direction)
+            )?;
+        }
+        return Ok(());
+    }
+
     let usecase = LookupScrapBacklinksUsecase::new();
     let results = usecase.execute(&scraps, &target_title, &target_ctx)?;
 
-    let scrap_keys: Vec<ScrapKeyJson> = results
+    let backlinks: Vec<BacklinkJson> = results
         .into_iter()
-        .map(|r| ScrapKeyJson {
+        .map(|r| BacklinkJson {
             title: r.title.to_string(),
             ctx: r.ctx.map(|c| c.to_string()),
+            occurrences: context.then(|| r.occurrences.into_iter().map(Into::into).collect()),
         })
         .collect();
 
     if json {
-        let count = scrap_keys.len();
+        let count = backlinks.len();
         let response = BacklinksResponse {
-            results: scrap_keys,
+            results: backlinks,
             count,
         };
         writeln!(writer, "{}", serde_json::to_string(&response)?)?;
     } else {
-        if scrap_keys.is_empty() {
+        if backlinks.is_empty() {
             return Ok(());
         }
 
         let mut table = Table::new();
         table.load_style(NOTHING);
-        table.set_header(vec![Cell::new("Title".bold()), Cell::new("Context".bold())]);
-
-        for key in &scrap_keys {
-            table.add_row(vec![
-                Cell::new(&key.title),
-                Cell::new(key.ctx.as_deref().unwrap_or("")),
+        if context {
+            table.set_header(vec![
+                Cell::new("Title".bold()),
+                Cell::new("Context".bold()),
+                Cell::new("Line".bold()),
+                Cell::new("Section".bold()),
+                Cell::new("Excerpt".bold()),
             ]);
+        } else {
+            table.set_header(vec![Cell::new("Title".bold()), Cell::new("Context".bold())]);
+        }
+
+        for backlink in &backlinks {
+            let ctx = backlink.ctx.as_deref().unwrap_or("");
+            match &backlink.occurrences {
+                Some(occurrences) => {
+                    for occurrence in occurrences {
+                        table.add_row(vec![
+                            Cell::new(&backlink.title),
+                            Cell::new(ctx),
+                            Cell::new(occurrence.line),
+                            Cell::new(occurrence.heading_path.join(" > ")),
+                            Cell::new(&occurrence.context),
+                        ]);
+                    }
+                }
+                None => {
+                    table.add_row(vec![Cell::new(&backlink.title), Cell::new(ctx)]);
+                }
+            }
         }
         writeln!(writer, "{table}")?;
     }
@@ -82,6 +136,7 @@ pub fn run(
 mod tests {
     use super::*;
     use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::scrap::traverse::usecase::TraverseDirection;
     use rstest::rstest;
 
     #[rstest]
@@ -96,6 +151,9 @@ mod tests {
         run(
             "cargo",
             None,
+            None,
+            false,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -119,7 +177,10 @@ mod tests {
         run(
             "cargo",
             None,
+            None,
+            false,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -147,7 +208,10 @@ mod tests {
         run(
             "cargo",
             None,
+            None,
+            false,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -178,7 +242,10 @@ mod tests {
         run(
             "Auth",
             Some("Backend"),
+            None,
+            false,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -206,6 +273,9 @@ mod tests {
         let result = run(
             "Auth",
             None,
+            None,
+            false,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -221,6 +291,9 @@ mod tests {
         let result = run(
             "nonexistent",
             None,
+            None,
+            false,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -234,10 +307,111 @@ mod tests {
         let result = run(
             "rust",
             None,
+            None,
+            false,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
         );
         assert!(result.is_err());
     }
+
+    #[rstest]
+    fn run_json_outputs_occurrences_with_context(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"")
+            .add_scrap(
+                "rust.md",
+                b"# Rust\n\n## Tooling\n\n- build with [[cargo]]\n- lint with clippy",
+            )
+            .add_scrap("cargo.md", b"# Cargo");
+
+        let mut buf = Vec::new();
+        run(
+            "cargo",
+            None,
+            None,
+            true,
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: BacklinksResponse = serde_json::from_str(output.trim()).unwrap();
+        let occurrences = response.results[0].occurrences.as_ref().unwrap();
+        assert_eq!(occurrences.len(), 1);
+        assert_eq!(occurrences[0].line, 5);
+        assert_eq!(occurrences[0].context, "- build with [[cargo]]");
+        assert_eq!(occurrences[0].heading_path, vec!["Rust", "Tooling"]);
+    }
+
+    #[rstest]
+    fn run_text_outputs_excerpts_with_context(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"")
+            .add_scrap("rust.md", b"# Rust\n\nBuilt with [[cargo]] daily.")
+            .add_scrap("cargo.md", b"# Cargo");
+
+        let mut buf = Vec::new();
+        run(
+            "cargo",
+            None,
+            None,
+            true,
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("Built with [[cargo]] daily."));
+        assert!(output.contains("Section"));
+    }
+
+    #[rstest]
+    fn run_json_traverses_inbound_to_depth(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("cargo.md", b"# Cargo")
+            .add_scrap("rust.md", b"[[cargo]]")
+            .add_scrap("book.md", b"![[rust]]");
+
+        let mut buf = Vec::new();
+        run(
+            "cargo",
+            None,
+            Some(TraverseOptions {
+                direction: TraverseDirection::Inbound,
+                depth: 2,
+                include_broken: false,
+                include_tags: false,
+            }),
+            false,
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: TraversalResponse = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(response.count, 2);
+        assert_eq!(response.results[0].title.as_deref(), Some("rust"));
+        assert_eq!(response.results[0].via, "link");
+        assert_eq!(response.results[1].title.as_deref(), Some("book"));
+        assert_eq!(response.results[1].distance, 2);
+        assert_eq!(response.results[1].via, "embed");
+        assert_eq!(response.results[1].from, "rust");
+    }
 }
@@ -5,15 +5,18 @@ use tracing::{span, Level};
 use tracing_subscriber::fmt::format::FmtSpan;
 
 use crate::cli::progress::ProgressImpl;
-use crate::error::ScrapsResult;
-use crate::input::file::read_scraps;
+use crate::error::{CliError, ScrapsResult};
+use crate::input::file::{ogp_cache, read_assets, read_scraps, scrap_cache};
+use crate::input::http::ogp_fetcher::OgpFetcherImpl;
 use crate::output::build_renderer::BuildRendererImpl;
+use crate::usecase::build::model::asset::Assets;
 use crate::usecase::build::model::color_scheme::ColorScheme;
 use crate::usecase::build::model::css::CssMetadata;
 use crate::usecase::build::model::html::HtmlMetadata;
 use crate::usecase::build::model::list_view_configs::ListViewConfigs;
 use crate::usecase::build::model::paging::Paging;
 use crate::usecase::build::model::sort::SortKey;
+use crate::usecase::build::model::target::BuildTarget;
 use crate::usecase::build::usecase::BuildUsecase;
 
 use crate::cli::config::scrap_config::ScrapConfig;
@@ -24,6 +27,10 @@ use scraps_libs::git::GitCommandImpl;
 pub fn run(
     verbose: Verbosity<WarnLevel>,
     git: bool,
+    offline: bool,
+    target: BuildTarget,
+    token_budget: Option<usize>,
+    no_cache: bool,
     project_path: Option<&Path>,
 ) -> ScrapsResult<()> {
     let log_level = match verbose.log_level() {
@@ -39,12 +46,22 @@ pub fn run(
         .with_max_level(log_level)
         .init();
     let span_run = span!(Level::INFO, "run").entered();
-    let result = execute(git, project_path);
+    let result = execute(git, offline, target, token_budget, no_cache, project_path);
     span_run.exit();
     result
 }
 
-fn execute(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
+fn execute(
+    git: bool,
+    offline: bool,
+    target: BuildTarget,
+    token_budget: Option<usize>,
+    no_cache: bool,
+    project_path: Option<&Path>,
+) -> ScrapsResult<()> {
+    if token_budget.is_some() && target != BuildTarget::Llms {
+        return Err(CliError::TokenBudgetWithoutLlms.into());
+    }
     let path_resolver = PathResolver::new(project_path)?;
     let config = ScrapConfig::from_path(project_path)?;
     let ssg = config.require_ssg()?;
@@ -59,6 +76,14 @@ fn execute(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
     let exclude_dirs = vec![static_dir_path.clone(), output_dir_path.clone()];
     let (scraps_with_ts, readme_text) =
         read_scraps::to_all_scraps_with_timestamps(&scraps_dir_path, &exclude_dirs, git_command)?;
+    // Keep the parsed scraps for the commands that only read the wiki.
+    if !no_cache {
+        scrap_cache::save(
+            &output_dir_path,
+            &scraps_dir_path,
+            scraps_with_ts.iter().map(|(scrap, _)| scrap),
+        );
+    }
 
     let renderer = BuildRendererImpl::new(&static_dir_path, &output_dir_path);
     let usecase = BuildUsecase::new();
@@ -73,6 +98,28 @@ fn execute(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
         .unwrap_or(&default_lang_code);
     let timezone = config.timezone.unwrap_or(chrono_tz::UTC);
     let html_metadata = HtmlMetadata::new(lang_code, title, &ssg.description, &ssg.favicon);
+
+    if target == BuildTarget::Json {
+        usecase.execute_json(&scraps_with_ts, &progress, &renderer)?;
+        progress.end();
+        return Ok(());
+    }
+
+    if target == BuildTarget::Llms {
+        let scraps: Vec<_> = scraps_with_ts.into_iter().map(|(s, _)| s).collect();
+        usecase.execute_llms(
+            &scraps,
+            &readme_text,
+            &progress,
+            &renderer,
+            &base_url,
+            &html_metadata,
+            token_budget,
+        )?;
+        progress.end();
+        return Ok(());
+    }
+
     let default_color_scheme = ColorScheme::OsSetting;
     let css_metadata = CssMetadata::new(
         ssg.color_scheme
@@ -81,6 +128,7 @@ fn execute(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
             .unwrap_or(&default_color_scheme),
     );
     let build_search_index = ssg.build_search_index.unwrap_or(true);
+    let build_graph = ssg.build_graph.unwrap_or(false);
     let default_sort_key = SortKey::CommittedDate;
     let sort_key = ssg
         .sort_key
@@ -91,8 +139,26 @@ fn execute(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
         None => Paging::Not,
         Some(u) => Paging::By(u),
     };
-    let list_view_configs = ListViewConfigs::new(&build_search_index, sort_key, &paging);
+    let list_view_configs =
+        ListViewConfigs::new(&build_search_index, &build_graph, sort_key, &paging);
+
+    // Link cards: fetch the pages of new autolinks unless --offline, and
+    // keep the cache next to the scraps so it can be committed.
+    let project_root = path_resolver.project_root();
+    let mut ogp_cache = ogp_cache::load(project_root)?;
+    let fetcher = (!offline).then(OgpFetcherImpl::new);
+    let previous_ogp_cache = ogp_cache.clone();
+    usecase.execute_ogp(&scraps_with_ts, &progress, fetcher.as_ref(), &mut ogp_cache)?;
+    if ogp_cache != previous_ogp_cache {
+        ogp_cache::save(project_root, &ogp_cache)?;
+    }
 
+    // Images and other files beside the scraps are copied next to their pages.
+    let scraps: Vec<_> = scraps_with_ts.iter().map(|(s, _)| s.clone()).collect();
+    let assets = Assets::new(
+        &read_assets::to_all_assets(&scraps_dir_path, &exclude_dirs)?,
+        &scraps,
+    );
     usecase.execute(
         &scraps_with_ts,
         &readme_text,
@@ -103,7 +169,26 @@ fn execute(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
         &html_metadata,
         &css_metadata,
         &list_view_configs,
+        &ogp_cache,
+        &assets,
     )?;
+    match ssg.feed_configs() {
+        Some(feed_configs) if git => {
+            usecase.execute_feeds(
+                &scraps_with_ts,
+                &progress,
+                &renderer,
+                &base_url,
+                &html_metadata,
+                &feed_configs,
+                &assets,
+            )?;
+        }
+        Some(_) if ssg.feed.is_some() => {
+            tracing::warn!("Atom feeds are ordered by commit date; build with --git to write them");
+        }
+        _ => {}
+    }
     progress.end();
 
     Ok(())
@@ -112,7 +197,9 @@ fn execute(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::http::ogp_fetcher::tests::stub_server;
     use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::build::model::ogp::OGP_CACHE_FILE_NAME;
     use rstest::rstest;
     use std::fs;
 
@@ -123,7 +210,14 @@ mod tests {
             .add_scrap("test1.md", b"# header1\n## header2\n")
             .add_scrap("test2.md", b"[[test1]]\n");
 
-        let result = execute(false, Some(project.project_root.as_path()));
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
         assert!(result.is_ok());
 
         // Verify scrap HTMLs generated
@@ -154,12 +248,235 @@ mod tests {
             .add_scrap("test1.md", b"# header1\n")
             .add_scrap("test2.md", b"[[test1]]\n");
 
-        let result = execute(false, Some(project.project_root.as_path()));
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
         assert!(result.is_ok());
 
         // Verify search index JSON not generated
         let json = fs::read_to_string(project.output_path("search_index.json"));
         assert!(json.is_err());
+        // The graph is opt-in
+        assert!(!project.output_path("graph.json").exists());
+        let html = fs::read_to_string(project.output_path("scraps/test2.html")).unwrap();
+        assert!(!html.contains("local-graph"));
+    }
+
+    #[rstest]
+    fn run_generates_graph_when_enabled(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(
+                b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"\nbuild_graph = true",
+            )
+            .add_scrap("test1.md", b"#[[ai]]\n")
+            .add_scrap("test2.md", b"[[test1]]\n");
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(project.output_path("graph.json")).unwrap())
+                .unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+        assert_eq!(json["edges"].as_array().unwrap().len(), 2);
+        assert!(project.output_path("graph.js").exists());
+
+        let graph = fs::read_to_string(project.output_path("graph.html")).unwrap();
+        assert!(graph.contains("data-graph=\"http://localhost:1112/graph.json\""));
+        let html = fs::read_to_string(project.output_path("scraps/test2.html")).unwrap();
+        assert!(html.contains("data-key=\"test2\""));
+        let index = fs::read_to_string(project.output_path("index.html")).unwrap();
+        assert!(index.contains("http://localhost:1112/graph.html"));
+    }
+
+    #[rstest]
+    fn run_fetches_link_cards_into_the_cache(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        let server = stub_server(&[(
+            "/page",
+            r#"<head><meta property="og:title" content="Stub page"><meta property="og:image" content="/card.png"></head>"#,
+        )]);
+        let page = server.join("page").unwrap();
+        let missing = server.join("missing").unwrap();
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap("test1.md", format!("<{page}>\n\n<{missing}>\n").as_bytes());
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        let cache = ogp_cache::load(&project.project_root).unwrap();
+        let ogp = cache.get(&page).unwrap();
+        assert_eq!(ogp.title.as_deref(), Some("Stub page"));
+        assert_eq!(ogp.image, Some(server.join("card.png").unwrap()));
+        // Failed fetches are tried again on the next build.
+        assert!(!cache.contains(&missing));
+
+        let html = fs::read_to_string(project.output_path("scraps/test1.html")).unwrap();
+        assert!(html.contains(r#"<div class="ogp-title">Stub page</div>"#));
+        assert!(html.contains(&format!(r#"<a href="{missing}">{missing}</a>"#)));
+        assert!(!html.contains("corsproxy"));
+    }
+
+    #[rstest]
+    fn run_offline_renders_link_cards_from_the_cache(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        let cache = br#"{
+  "https://example.invalid/cached": {
+    "title": "Cached page",
+    "description": "From the committed cache"
+  },
+  "https://example.invalid/unlinked": {
+    "title": "Kept while offline"
+  }
+}
+"#;
+        fs::write(project.project_root.join(OGP_CACHE_FILE_NAME), cache).unwrap();
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap(
+                "test1.md",
+                b"<https://example.invalid/cached>\n\n<https://example.invalid/new>\n",
+            );
+
+        let result = execute(
+            false,
+            true,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        let html = fs::read_to_string(project.output_path("scraps/test1.html")).unwrap();
+        assert!(html.contains(r#"<div class="ogp-title">Cached page</div>"#));
+        assert!(html.contains(r#"<div class="ogp-description">From the committed cache</div>"#));
+        assert!(html
+            .contains(r#"<a href="https://example.invalid/new">https://example.invalid/new</a>"#));
+        assert_eq!(
+            fs::read(project.project_root.join(OGP_CACHE_FILE_NAME)).unwrap(),
+            cache
+        );
+    }
+
+    #[rstest]
+    fn run_rejects_token_budget_outside_llms(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project.add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"");
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            Some(1000),
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert_eq!(
+            result.unwrap_err().downcast::<CliError>().unwrap(),
+            CliError::TokenBudgetWithoutLlms
+        );
+        assert!(!project.output_path("index.html").exists());
+    }
+
+    #[rstest]
+    fn run_copies_assets_next_to_scrap_pages(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap_with_context(
+                "Programming",
+                "Rust.md",
+                b"![ownership](images/Ownership%20Rules.png)\n\n[spec](../spec.pdf#page=2)\n",
+            )
+            .add_asset("Programming/images/Ownership Rules.png", b"png")
+            .add_asset("spec.pdf", b"pdf");
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        assert_eq!(
+            fs::read(project.output_path("scraps/programming/images/Ownership Rules.png")).unwrap(),
+            b"png"
+        );
+        assert_eq!(
+            fs::read(project.output_path("scraps/spec.pdf")).unwrap(),
+            b"pdf"
+        );
+        let html = fs::read_to_string(project.output_path("scraps/programming/rust.html")).unwrap();
+        assert!(html.contains(
+            r#"<img src="http://localhost:1112/scraps/programming/images/Ownership%20Rules.png" alt="ownership" />"#
+        ));
+        assert!(html.contains(r#"<a href="http://localhost:1112/scraps/spec.pdf#page=2">spec</a>"#));
+    }
+
+    #[rstest]
+    fn run_skips_missing_and_colliding_assets_and_removes_deleted_ones(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap(
+                "note.md",
+                b"![](missing.png) [export](note.html) ![](diagram.png)\n",
+            )
+            .add_asset("note.html", b"<p>exported</p>")
+            .add_asset("diagram.png", b"png");
+        let build = || {
+            execute(
+                false,
+                false,
+                BuildTarget::Html,
+                None,
+                false,
+                Some(project.project_root.as_path()),
+            )
+            .unwrap()
+        };
+
+        build();
+        let html = fs::read_to_string(project.output_path("scraps/note.html")).unwrap();
+        assert!(html.contains(r#"<img src="missing.png" alt="" />"#));
+        assert!(html.contains(r#"<a href="note.html">export</a>"#));
+        assert!(html.contains(r#"<img src="http://localhost:1112/scraps/diagram.png" alt="" />"#));
+        assert!(project.output_path("scraps/diagram.png").exists());
+
+        fs::remove_file(project.project_root.join("diagram.png")).unwrap();
+        build();
+        assert!(!project.output_path("scraps/diagram.png").exists());
+        let html = fs::read_to_string(project.output_path("scraps/note.html")).unwrap();
+        assert!(html.contains(r#"<img src="diagram.png" alt="" />"#));
     }
 
     #[rstest]
@@ -175,7 +492,14 @@ mod tests {
                 b"# Target\n\n## Notes\n\nembedded body\n\n## Other\n\nhidden body\n",
             );
 
-        let result = execute(false, Some(project.project_root.as_path()));
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
         assert!(result.is_ok());
 
         let html = fs::read_to_string(project.output_path("scraps/source.html")).unwrap();
@@ -184,6 +508,141 @@ mod tests {
         assert!(!html.contains("hidden body"));
     }
 
+    #[rstest]
+    fn run_generates_sitemap_robots_and_canonical_urls(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"https://example.com/wiki\"\ntitle = \"Test\"")
+            .add_scrap("source.md", b"#[[ai]]")
+            .add_scrap_with_context("notes", "inner.md", b"# Inner");
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        let sitemap = fs::read_to_string(project.output_path("sitemap.xml")).unwrap();
+        for loc in [
+            "https://example.com/wiki/",
+            "https://example.com/wiki/scraps/source.html",
+            "https://example.com/wiki/scraps/notes/inner.html",
+            "https://example.com/wiki/tags/",
+            "https://example.com/wiki/tags/ai.html",
+        ] {
+            assert!(sitemap.contains(&format!("<loc>{loc}</loc>")), "{loc}");
+        }
+        // Without --git there is no commit date to report.
+        assert!(!sitemap.contains("<lastmod>"));
+
+        let robots = fs::read_to_string(project.output_path("robots.txt")).unwrap();
+        assert!(robots.contains("Sitemap: https://example.com/wiki/sitemap.xml"));
+
+        for (page, url) in [
+            ("index.html", "https://example.com/wiki/"),
+            (
+                "scraps/notes/inner.html",
+                "https://example.com/wiki/scraps/notes/inner.html",
+            ),
+            ("tags/index.html", "https://example.com/wiki/tags/"),
+            ("tags/ai.html", "https://example.com/wiki/tags/ai.html"),
+        ] {
+            let html = fs::read_to_string(project.output_path(page)).unwrap();
+            assert!(
+                html.contains(&format!("<link rel=\"canonical\" href=\"{url}\" />")),
+                "{page}"
+            );
+            assert!(
+                html.contains(&format!("<meta property=\"og:url\" content=\"{url}\" />")),
+                "{page}"
+            );
+        }
+    }
+
+    #[rstest]
+    fn run_reuses_unchanged_pages_and_removes_stale_ones(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap("source.md", b"Embed: ![[target]]")
+            .add_scrap("target.md", b"first body")
+            .add_scrap("alone.md", b"# Alone")
+            .add_scrap_with_context("notes", "old.md", b"#[[old-tag]]");
+        let build = || {
+            execute(
+                false,
+                false,
+                BuildTarget::Html,
+                None,
+                false,
+                Some(project.project_root.as_path()),
+            )
+            .unwrap()
+        };
+        build();
+        assert!(project.output_path(".scraps-manifest.json").exists());
+        assert!(project.output_path("scraps/notes/old.html").exists());
+        assert!(project.output_path("tags/old-tag.html").exists());
+
+        // Mark outputs to see which ones the next build writes again.
+        for page in ["scraps/alone.html", "scraps/source.html"] {
+            fs::write(project.output_path(page), "reused").unwrap();
+        }
+        fs::write(project.scrap_path("target.md"), "second body").unwrap();
+        fs::remove_file(project.scrap_path("notes/old.md")).unwrap();
+        build();
+
+        let alone = fs::read_to_string(project.output_path("scraps/alone.html")).unwrap();
+        assert_eq!(alone, "reused");
+        // The embedded scrap changed, so the embedding page is rebuilt.
+        let source = fs::read_to_string(project.output_path("scraps/source.html")).unwrap();
+        assert!(source.contains("second body"));
+        assert!(!project.output_path("scraps/notes").exists());
+        assert!(!project.output_path("tags/old-tag.html").exists());
+    }
+
+    #[rstest]
+    fn run_generates_ctx_pages_with_breadcrumbs(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap_with_context("programming/rust", "ownership.md", b"# Ownership")
+            .add_scrap_with_context("programming/rust", "README.md", b"Notes on Rust.");
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        let scrap =
+            fs::read_to_string(project.output_path("scraps/programming/rust/ownership.html"))
+                .unwrap();
+        assert!(scrap.contains(concat!(
+            "<a href=\"http://localhost:1112/scraps/programming/\">programming</a><span>&#47;</span>",
+            "<a href=\"http://localhost:1112/scraps/programming/rust/\">rust</a><span>&#47;</span>"
+        )));
+
+        let programming =
+            fs::read_to_string(project.output_path("scraps/programming/index.html")).unwrap();
+        assert!(programming.contains("http://localhost:1112/scraps/programming/rust/"));
+        let rust =
+            fs::read_to_string(project.output_path("scraps/programming/rust/index.html")).unwrap();
+        assert!(rust.contains("<p>Notes on Rust.</p>"));
+        assert!(rust.contains("scraps/programming/rust/ownership.html"));
+    }
+
     #[rstest]
     fn run_generates_tag_links_with_tags_path(
         #[from(temp_scrap_project)] project: TempScrapProject,
@@ -192,7 +651,14 @@ mod tests {
             .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
             .add_scrap("source.md", b"#[[ai]]");
 
-        let result = execute(false, Some(project.project_root.as_path()));
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
         assert!(result.is_ok());
 
         let index = fs::read_to_string(project.output_path("index.html")).unwrap();
@@ -207,7 +673,14 @@ mod tests {
             .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
             .add_scrap("test1.md", b"# header1\n");
 
-        let result = execute(true, Some(project.project_root.as_path()));
+        let result = execute(
+            true,
+            false,
+            BuildTarget::Html,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
         assert!(result.is_ok());
 
         // Outside a git repo `commited_ts` is None so the conditional block
@@ -215,4 +688,63 @@ mod tests {
         let html = fs::read_to_string(project.output_path("scraps/test1.html")).unwrap();
         assert!(!html.is_empty());
     }
+
+    #[rstest]
+    fn run_llms_target_writes_packed_corpus(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap("source.md", b"See [[target]]\n\n![[target#Notes]]")
+            .add_scrap("target.md", b"# Target\n\n## Notes\n\nembedded body\n");
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Llms,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        let index = fs::read_to_string(project.output_path("llms.txt")).unwrap();
+        assert!(index.contains("- [target](http://localhost:1112/scraps/target.html)"));
+
+        let full = fs::read_to_string(project.output_path("llms-full.txt")).unwrap();
+        assert!(full.find("# target").unwrap() < full.find("# source").unwrap());
+        assert!(full.contains("See target\n\nembedded body"));
+
+        // The llms target does not emit the static site.
+        assert!(fs::read_to_string(project.output_path("index.html")).is_err());
+    }
+
+    #[rstest]
+    fn run_json_target_writes_typed_graph(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Test\"")
+            .add_scrap("source.md", b"See [[target#Notes]] and [[missing]]")
+            .add_scrap("target.md", b"# Target\n\n## Notes\n");
+
+        let result = execute(
+            false,
+            false,
+            BuildTarget::Json,
+            None,
+            false,
+            Some(project.project_root.as_path()),
+        );
+        assert!(result.is_ok());
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(project.output_path("scraps.json")).unwrap())
+                .unwrap();
+        let source = &json["scraps"][0];
+        assert_eq!(source["key"], "source");
+        assert_eq!(source["refs"][0]["target"], "target");
+        assert_eq!(source["refs"][0]["heading"], "Notes");
+        assert_eq!(source["refs"][0]["resolved"], true);
+        assert_eq!(source["refs"][1]["resolved"], false);
+        assert_eq!(json["scraps"][1]["backlinks"][0], "source");
+        assert!(project.output_path("scraps.schema.json").exists());
+        assert!(fs::read_to_string(project.output_path("index.html")).is_err());
+    }
 }
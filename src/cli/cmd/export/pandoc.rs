@@ -0,0 +1,106 @@
+use std::io::Write;
+use std::path::Path;
+
+use crate::cli::config::scrap_config::ScrapConfig;
+use crate::cli::path_resolver::PathResolver;
+use crate::error::ScrapsResult;
+use crate::input::file::read_scraps;
+use crate::usecase::export::pandoc::usecase::{ExportPandocUsecase, ExportScope};
+use scraps_libs::git::GitCommandImpl;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::title::Title;
+
+pub fn run(
+    title: Option<&str>,
+    ctx: Option<&str>,
+    project_path: Option<&Path>,
+    writer: &mut impl Write,
+) -> ScrapsResult<()> {
+    let path_resolver = PathResolver::new(project_path)?;
+    let config = ScrapConfig::from_path(project_path)?;
+    let scraps_dir_path = path_resolver.scraps_dir();
+    let exclude_dirs = vec![
+        path_resolver.static_dir(),
+        path_resolver.output_dir(&config),
+    ];
+
+    let (scraps_with_ts, readme_text) = read_scraps::to_all_scraps_with_timestamps(
+        &scraps_dir_path,
+        &exclude_dirs,
+        None::<GitCommandImpl>,
+    )?;
+    let scraps: Vec<_> = scraps_with_ts.into_iter().map(|(s, _)| s).collect();
+
+    let (scope, doc_title) = match (title, ctx) {
+        (Some(title), ctx) => {
+            let key = ScrapKey::new(&Title::from(title), &ctx.map(Ctx::from));
+            (ExportScope::Scrap(key), Some(title.to_string()))
+        }
+        (None, Some(ctx)) => (ExportScope::Ctx(Ctx::from(ctx)), Some(ctx.to_string())),
+        (None, None) => (
+            ExportScope::Wiki,
+            config.ssg.as_ref().map(|ssg| ssg.title.clone()),
+        ),
+    };
+
+    let usecase = ExportPandocUsecase::new();
+    let doc = usecase.execute(&scraps, &readme_text, &scope, doc_title.as_deref())?;
+    writeln!(writer, "{}", serde_json::to_string(&doc)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+    use serde_json::Value;
+
+    #[rstest]
+    fn run_exports_whole_wiki(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"[ssg]\nbase_url = \"http://localhost:1112/\"\ntitle = \"Handbook\"")
+            .add_scrap("README.md", b"Read [[guide]] first.")
+            .add_scrap("guide.md", b"# Guide\n\n#[[docs]]")
+            .add_scrap_with_context("Book", "intro.md", b"See [[guide]]");
+
+        let mut buf = Vec::new();
+        run(None, None, Some(project.project_root.as_path()), &mut buf).unwrap();
+
+        let doc: Value = serde_json::from_slice(&buf).unwrap();
+        assert_eq!(doc["pandoc-api-version"][0], 1);
+        assert_eq!(doc["meta"]["title"]["c"][0]["c"], "Handbook");
+        let anchors: Vec<&str> = doc["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|b| b["t"] == "Header" && b["c"][0] == 1)
+            .map(|b| b["c"][1][0].as_str().unwrap())
+            .collect();
+        assert_eq!(anchors, vec!["guide", "book/intro"]);
+    }
+
+    #[rstest]
+    fn run_exports_single_scrap_with_ctx(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("guide.md", b"# Guide")
+            .add_scrap_with_context("Book", "intro.md", b"See [[guide]]");
+
+        let mut buf = Vec::new();
+        run(
+            Some("intro"),
+            Some("Book"),
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let doc: Value = serde_json::from_slice(&buf).unwrap();
+        let blocks = doc["blocks"].as_array().unwrap();
+        assert_eq!(blocks.len(), 2);
+        // `guide` is outside the export, so the link stays a plain span.
+        assert_eq!(blocks[1]["c"][2]["t"], "Span");
+    }
+}
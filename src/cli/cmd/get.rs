@@ -6,6 +6,7 @@ use crate::cli::json::scrap::{CodeBlockJson, HeadingJson};
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::scrap::get::usecase::GetScrapUsecase;
 use scraps_libs::markdown::query::{code_blocks, heading_slug, headings, images, section};
 use scraps_libs::model::context::Ctx;
@@ -70,6 +71,7 @@ pub fn run(
     ctx: Option<&str>,
     heading: Option<&str>,
     json: Option<&str>,
+    no_cache: bool,
     project_path: Option<&Path>,
     writer: &mut impl Write,
 ) -> ScrapsResult<()> {
@@ -81,7 +83,8 @@ pub fn run(
         path_resolver.output_dir(&config),
     ];
 
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
     let target_title = Title::from(title);
     let target_ctx = ctx.map(Ctx::from);
 
@@ -161,6 +164,7 @@ mod tests {
             ctx,
             heading,
             json,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )?;
@@ -7,10 +7,14 @@ use comfy_table::{Cell, Table};
 use serde::{Deserialize, Serialize};
 
 use crate::cli::config::scrap_config::ScrapConfig;
+use crate::cli::display::traversal::DisplayTraversalTable;
+use crate::cli::json::scrap::{TraversalJson, TraversalResponse};
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::scrap::lookup_links::usecase::{LinkRefKind, LookupScrapLinksUsecase};
+use crate::usecase::scrap::traverse::usecase::{TraverseLinksUsecase, TraverseOptions};
 use scraps_libs::model::context::Ctx;
 use scraps_libs::model::title::Title;
 
@@ -47,7 +51,9 @@ struct LinksResponse {
 pub fn run(
     title: &str,
     ctx: Option<&str>,
+    traverse: Option<TraverseOptions>,
     json: bool,
+    no_cache: bool,
     project_path: Option<&Path>,
     writer: &mut impl Write,
 ) -> ScrapsResult<()> {
@@ -59,10 +65,33 @@ pub fn run(
         path_resolver.output_dir(&config),
     ];
 
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
     let target_title = Title::from(title);
     let target_ctx = ctx.map(Ctx::from);
 
+    if let Some(options) = traverse {
+        let nodes: Vec<TraversalJson> = TraverseLinksUsecase::new()
+            .execute(&scraps, &target_title, &target_ctx, &options)?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        if json {
+            let response = TraversalResponse {
+                count: nodes.len(),
+                results: nodes,
+            };
+            writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        } else if !nodes.is_empty() {
+            writeln!(
+                writer,
+                "{}",
+                DisplayTraversalTable::new(&nodes, options.direction)
+            )?;
+        }
+        return Ok(());
+    }
+
     let usecase = LookupScrapLinksUsecase::new();
     let results = usecase.execute(&scraps, &target_title, &target_ctx)?;
 
@@ -118,6 +147,7 @@ pub fn run(
 mod tests {
     use super::*;
     use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::scrap::traverse::usecase::TraverseDirection;
     use rstest::rstest;
 
     #[rstest]
@@ -132,6 +162,8 @@ mod tests {
         run(
             "rust",
             None,
+            None,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -155,7 +187,9 @@ mod tests {
         run(
             "rust",
             None,
+            None,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -186,7 +220,9 @@ mod tests {
         run(
             "rust",
             None,
+            None,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -211,7 +247,9 @@ mod tests {
         run(
             "Auth",
             Some("Backend"),
+            None,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -236,6 +274,8 @@ mod tests {
         let result = run(
             "Auth",
             None,
+            None,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -251,6 +291,8 @@ mod tests {
         let result = run(
             "nonexistent",
             None,
+            None,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -264,6 +306,8 @@ mod tests {
         let result = run(
             "rust",
             None,
+            None,
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -287,7 +331,9 @@ mod tests {
         run(
             "rust",
             None,
+            None,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -317,7 +363,9 @@ mod tests {
         run(
             "rust",
             None,
+            None,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -342,7 +390,9 @@ mod tests {
         run(
             "rust",
             None,
+            None,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -368,7 +418,9 @@ mod tests {
         run(
             "rust",
             None,
+            None,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -378,4 +430,81 @@ mod tests {
         assert!(output.contains("\"kind\":\"link\""));
         assert!(output.contains("\"kind\":\"embed\""));
     }
+
+    #[rstest]
+    fn run_json_traverses_to_depth(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("rust.md", b"[[cargo]] #[[lang]]")
+            .add_scrap("cargo.md", b"[[crates]] [[missing]]")
+            .add_scrap("crates.md", b"[[registry]]")
+            .add_scrap("registry.md", b"");
+
+        let mut buf = Vec::new();
+        run(
+            "rust",
+            None,
+            Some(TraverseOptions {
+                direction: TraverseDirection::Outbound,
+                depth: 2,
+                include_broken: true,
+                include_tags: true,
+            }),
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: TraversalResponse = serde_json::from_str(output.trim()).unwrap();
+        let reached: Vec<(&str, &str, usize, &str)> = response
+            .results
+            .iter()
+            .map(|r| {
+                let name = r.title.as_deref().or(r.tag.as_deref()).unwrap();
+                (r.kind.as_str(), name, r.distance, r.from.as_str())
+            })
+            .collect();
+        assert_eq!(
+            reached,
+            vec![
+                ("scrap", "cargo", 1, "rust"),
+                ("tag", "lang", 1, "rust"),
+                ("scrap", "crates", 2, "cargo"),
+                ("broken", "missing", 2, "cargo"),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn run_text_traverses_to_depth(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("rust.md", b"[[cargo]]")
+            .add_scrap("cargo.md", b"[[crates]]")
+            .add_scrap("crates.md", b"");
+
+        let mut buf = Vec::new();
+        run(
+            "rust",
+            None,
+            Some(TraverseOptions {
+                direction: TraverseDirection::Outbound,
+                depth: 2,
+                include_broken: false,
+                include_tags: false,
+            }),
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("crates"));
+        assert!(output.contains("link from cargo"));
+    }
 }
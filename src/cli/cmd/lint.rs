@@ -9,18 +9,24 @@ use crate::cli::config::scrap_config::ScrapConfig;
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::lint::rule::{LintRule, LintRuleName, LintWarning};
 use crate::usecase::lint::rules::stale_by_git::StaleByGitRule;
 use crate::usecase::lint::usecase::LintUsecase;
 
-pub fn run(project_path: Option<&Path>, rule_names: &[LintRuleName]) -> ScrapsResult<()> {
+pub fn run(
+    no_cache: bool,
+    project_path: Option<&Path>,
+    rule_names: &[LintRuleName],
+) -> ScrapsResult<()> {
     let path_resolver = PathResolver::new(project_path)?;
     let config = ScrapConfig::from_path(project_path)?;
     let scraps_dir_path = path_resolver.scraps_dir();
     let static_dir_path = path_resolver.static_dir();
     let output_dir_path = path_resolver.output_dir(&config);
     let exclude_dirs = vec![static_dir_path, output_dir_path];
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
 
     // CLI `--rule X` overrides everything; otherwise default rules plus
     // opt-in rules whose config section enables them.
@@ -106,7 +112,7 @@ mod tests {
             .add_scrap("a.md", b"[[b]]")
             .add_scrap("b.md", b"[[a]]");
 
-        let result = run(Some(project.project_root.as_path()), &[]);
+        let result = run(false, Some(project.project_root.as_path()), &[]);
         assert!(result.is_ok());
     }
 
@@ -116,13 +122,13 @@ mod tests {
             .add_config(b"")
             .add_scrap("lonely.md", b"no links here");
 
-        let result = run(Some(project.project_root.as_path()), &[]);
+        let result = run(false, Some(project.project_root.as_path()), &[]);
         assert!(result.is_ok());
     }
 
     #[rstest]
     fn run_fails_without_config(#[from(temp_scrap_project)] project: TempScrapProject) {
-        let result = run(Some(project.project_root.as_path()), &[]);
+        let result = run(false, Some(project.project_root.as_path()), &[]);
         assert!(result.is_err());
     }
 
@@ -133,6 +139,7 @@ mod tests {
             .add_scrap("lonely.md", b"no links here");
 
         let result = run(
+            false,
             Some(project.project_root.as_path()),
             &[LintRuleName::DeadEnd],
         );
@@ -143,7 +150,7 @@ mod tests {
     fn run_succeeds_with_empty_scraps(#[from(temp_scrap_project)] project: TempScrapProject) {
         project.add_config(b"");
 
-        let result = run(Some(project.project_root.as_path()), &[]);
+        let result = run(false, Some(project.project_root.as_path()), &[]);
         assert!(result.is_ok());
     }
 
@@ -158,7 +165,7 @@ mod tests {
 
         // Without --rule, presence of [lint.stale_by_git] enables the opt-in
         // rule; outside a git repo it gracefully skips.
-        let result = run(Some(project.project_root.as_path()), &[]);
+        let result = run(false, Some(project.project_root.as_path()), &[]);
         assert!(result.is_ok());
     }
 }
@@ -14,16 +14,18 @@ use tracing_subscriber::FmtSubscriber;
 
 pub async fn run(
     project_path: Option<&Path>,
+    no_cache: bool,
     http_addr: Option<&str>,
     allowed_hosts: Vec<String>,
 ) -> ScrapsResult<()> {
     init_tracing()?;
 
-    let (scraps_dir, exclude_dirs) = resolve_dirs(project_path)?;
+    let (scraps_dir, exclude_dirs, output_dir) = resolve_dirs(project_path)?;
+    let cache_dir = (!no_cache).then_some(output_dir);
 
     match http_addr {
-        Some(addr) => serve_http(addr, scraps_dir, exclude_dirs, allowed_hosts).await,
-        None => serve_stdio(scraps_dir, exclude_dirs).await,
+        Some(addr) => serve_http(addr, scraps_dir, exclude_dirs, cache_dir, allowed_hosts).await,
+        None => serve_stdio(scraps_dir, exclude_dirs, cache_dir).await,
     }
 }
 
@@ -40,25 +42,28 @@ fn init_tracing() -> ScrapsResult<()> {
 
 /// Resolve the wiki root. The root is the directory containing `.scraps.toml`
 /// (i.e. the project root). Config is loaded only to resolve the configured
-/// `output_dir` so it can be excluded from scrap traversal.
-fn resolve_dirs(project_path: Option<&Path>) -> ScrapsResult<(PathBuf, Vec<PathBuf>)> {
+/// `output_dir`, which is excluded from scrap traversal and holds the scrap
+/// cache.
+fn resolve_dirs(project_path: Option<&Path>) -> ScrapsResult<(PathBuf, Vec<PathBuf>, PathBuf)> {
     let path_resolver = PathResolver::new(project_path)
         .map_err(|e| McpError::ServiceError(format!("Failed to resolve paths: {e}")))?;
     let config = ScrapConfig::from_path(project_path)
         .map_err(|e| McpError::ServiceError(format!("Failed to load config: {e}")))?;
 
-    let exclude_dirs = vec![
-        path_resolver.static_dir(),
-        path_resolver.output_dir(&config),
-    ];
+    let output_dir = path_resolver.output_dir(&config);
+    let exclude_dirs = vec![path_resolver.static_dir(), output_dir.clone()];
 
-    Ok((path_resolver.scraps_dir(), exclude_dirs))
+    Ok((path_resolver.scraps_dir(), exclude_dirs, output_dir))
 }
 
-async fn serve_stdio(scraps_dir: PathBuf, exclude_dirs: Vec<PathBuf>) -> ScrapsResult<()> {
+async fn serve_stdio(
+    scraps_dir: PathBuf,
+    exclude_dirs: Vec<PathBuf>,
+    cache_dir: Option<PathBuf>,
+) -> ScrapsResult<()> {
     info!("Starting Scraps MCP server...");
 
-    let service = ScrapsServer::new(scraps_dir, exclude_dirs)
+    let service = ScrapsServer::new(scraps_dir, exclude_dirs, cache_dir)
         .serve((stdin(), stdout()))
         .await
         .inspect_err(|e| {
@@ -77,6 +82,7 @@ async fn serve_http(
     addr: &str,
     scraps_dir: PathBuf,
     exclude_dirs: Vec<PathBuf>,
+    cache_dir: Option<PathBuf>,
     allowed_hosts: Vec<String>,
 ) -> ScrapsResult<()> {
     let listener = TcpListener::bind(addr)
@@ -94,7 +100,7 @@ async fn serve_http(
         scraps_dir.display()
     );
 
-    let service = mcp::http::build_service(scraps_dir, exclude_dirs, allowed_hosts);
+    let service = mcp::http::build_service(scraps_dir, exclude_dirs, cache_dir, allowed_hosts);
     tokio::select! {
         result = mcp::http::serve(listener, service) => {
             result.map_err(|e| McpError::ServiceError(e.to_string()))?;
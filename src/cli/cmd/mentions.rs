@@ -0,0 +1,190 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context;
+use colored::Colorize;
+use comfy_table::presets::NOTHING;
+use comfy_table::{Cell, Table};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::config::scrap_config::ScrapConfig;
+use crate::cli::path_resolver::PathResolver;
+use crate::error::{ScrapsError, ScrapsResult};
+use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::usecase::lint::rule::scrap_relative_path;
+use crate::usecase::scrap::mentions::usecase::FindMentionsUsecase;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::title::Title;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MentionJson {
+    title: String,
+    ctx: Option<String>,
+    line: usize,
+    snippet: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct MentionsResponse {
+    results: Vec<MentionJson>,
+    count: usize,
+}
+
+pub fn run(
+    title: &str,
+    ctx: Option<&str>,
+    link: bool,
+    json: bool,
+    no_cache: bool,
+    project_path: Option<&Path>,
+    writer: &mut impl Write,
+) -> ScrapsResult<()> {
+    let path_resolver = PathResolver::new(project_path)?;
+    let config = ScrapConfig::from_path(project_path)?;
+    let scraps_dir_path = path_resolver.scraps_dir();
+    let exclude_dirs = vec![
+        path_resolver.static_dir(),
+        path_resolver.output_dir(&config),
+    ];
+
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
+    let target_title = Title::from(title);
+    let target_ctx = ctx.map(Ctx::from);
+
+    let usecase = FindMentionsUsecase::new();
+    let results = usecase.execute(&scraps, &target_title, &target_ctx)?;
+
+    if link {
+        for linked in usecase.link(&scraps, &target_title, &target_ctx)? {
+            let file_path = scraps_dir_path.join(scrap_relative_path(&linked.scrap));
+            std::fs::write(&file_path, &linked.md_text)
+                .context(ScrapsError::WriteScrap(file_path.clone()))?;
+        }
+    }
+
+    if json {
+        let items: Vec<MentionJson> = results
+            .into_iter()
+            .map(|r| MentionJson {
+                title: r.title.to_string(),
+                ctx: r.ctx.map(|c| c.to_string()),
+                line: r.line,
+                snippet: r.snippet,
+            })
+            .collect();
+        let response = MentionsResponse {
+            count: items.len(),
+            results: items,
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    } else {
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.load_style(NOTHING);
+        table.set_header(vec![
+            Cell::new("Title".bold()),
+            Cell::new("Context".bold()),
+            Cell::new("Line".bold()),
+            Cell::new("Snippet".bold()),
+        ]);
+
+        for r in &results {
+            table.add_row(vec![
+                Cell::new(r.title.to_string()),
+                Cell::new(r.ctx.as_ref().map(|c| c.to_string()).unwrap_or_default()),
+                Cell::new(r.line),
+                Cell::new(&r.snippet),
+            ]);
+        }
+        writeln!(writer, "{table}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+    use std::fs;
+
+    #[rstest]
+    fn run_text_outputs_mentions(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("cargo.md", b"# Cargo")
+            .add_scrap("rust.md", b"# Rust\n\nBuilt with cargo.");
+
+        let mut buf = Vec::new();
+        run(
+            "cargo",
+            None,
+            false,
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("rust"));
+        assert!(output.contains("Built with cargo."));
+    }
+
+    #[rstest]
+    fn run_json_outputs_mentions(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("cargo.md", b"# Cargo")
+            .add_scrap_with_context("Lang", "rust.md", b"[[cargo]] and cargo");
+
+        let mut buf = Vec::new();
+        run(
+            "cargo",
+            None,
+            false,
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: MentionsResponse = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(response.count, 1);
+        assert_eq!(response.results[0].title, "rust");
+        assert_eq!(response.results[0].ctx.as_deref(), Some("Lang"));
+        assert_eq!(response.results[0].line, 1);
+        assert_eq!(response.results[0].snippet, "[[cargo]] and cargo");
+    }
+
+    #[rstest]
+    fn run_link_rewrites_mentions(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("cargo.md", b"# Cargo")
+            .add_scrap("rust.md", b"Built with cargo and `cargo`.");
+
+        let mut buf = Vec::new();
+        run(
+            "cargo",
+            None,
+            true,
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let rewritten = fs::read_to_string(project.scrap_path("rust.md")).unwrap();
+        assert_eq!(rewritten, "Built with [[cargo]] and `cargo`.");
+    }
+}
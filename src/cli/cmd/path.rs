@@ -0,0 +1,254 @@
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::config::scrap_config::ScrapConfig;
+use crate::cli::path_resolver::PathResolver;
+use crate::error::ScrapsResult;
+use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::usecase::scrap::find_path::usecase::{
+    FindScrapPathUsecase, HopKind, PathHop, PathNode, PathOptions,
+};
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::title::Title;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PathHopJson {
+    /// `scrap` or `tag`.
+    kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ctx: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tag: Option<String>,
+    /// `link`, `backlink` or `tag`; absent on the starting scrap.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    via: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PathJson {
+    length: usize,
+    hops: Vec<PathHopJson>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PathResponse {
+    results: Vec<PathJson>,
+    count: usize,
+}
+
+fn via_label(via: HopKind) -> &'static str {
+    match via {
+        HopKind::Link => "link",
+        HopKind::Backlink => "backlink",
+        HopKind::Tag => "tag",
+    }
+}
+
+fn hop_json(hop: &PathHop) -> PathHopJson {
+    let via = hop.via.map(|v| via_label(v).to_string());
+    match &hop.node {
+        PathNode::Scrap(key) => PathHopJson {
+            kind: "scrap".to_string(),
+            title: Some(key.title().to_string()),
+            ctx: key.ctx().as_ref().map(|c| c.to_string()),
+            tag: None,
+            via,
+        },
+        PathNode::Tag(tag) => PathHopJson {
+            kind: "tag".to_string(),
+            title: None,
+            ctx: None,
+            tag: Some(tag.to_string()),
+            via,
+        },
+    }
+}
+
+fn path_text(path: &[PathHop]) -> String {
+    path.iter()
+        .map(|hop| {
+            let node = match &hop.node {
+                PathNode::Scrap(key) => key.to_string(),
+                PathNode::Tag(tag) => format!("#{tag}"),
+            };
+            match hop.via {
+                None => node,
+                Some(HopKind::Link) => format!(" → {node}"),
+                Some(HopKind::Backlink) => format!(" ← {node}"),
+                Some(HopKind::Tag) => format!(" — {node}"),
+            }
+        })
+        .collect()
+}
+
+pub fn run(
+    from: (&str, Option<&str>),
+    to: (&str, Option<&str>),
+    options: &PathOptions,
+    json: bool,
+    no_cache: bool,
+    project_path: Option<&Path>,
+    writer: &mut impl Write,
+) -> ScrapsResult<()> {
+    let path_resolver = PathResolver::new(project_path)?;
+    let config = ScrapConfig::from_path(project_path)?;
+    let scraps_dir_path = path_resolver.scraps_dir();
+    let exclude_dirs = vec![
+        path_resolver.static_dir(),
+        path_resolver.output_dir(&config),
+    ];
+
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
+    let from_title = Title::from(from.0);
+    let from_ctx = from.1.map(Ctx::from);
+    let to_title = Title::from(to.0);
+    let to_ctx = to.1.map(Ctx::from);
+
+    let usecase = FindScrapPathUsecase::new();
+    let paths = usecase.execute(
+        &scraps,
+        (&from_title, &from_ctx),
+        (&to_title, &to_ctx),
+        options,
+    )?;
+
+    if json {
+        let results: Vec<PathJson> = paths
+            .iter()
+            .map(|path| PathJson {
+                length: path.len() - 1,
+                hops: path.iter().map(hop_json).collect(),
+            })
+            .collect();
+        let response = PathResponse {
+            count: results.len(),
+            results,
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    } else {
+        for path in &paths {
+            writeln!(writer, "{}", path_text(path))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+
+    const OPTIONS: PathOptions = PathOptions {
+        undirected: false,
+        via_tags: false,
+        max_length: 6,
+    };
+
+    #[rstest]
+    fn run_text_outputs_path(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("rust.md", b"[[Tools/cargo]]")
+            .add_scrap_with_context("Tools", "cargo.md", b"[[crates]]")
+            .add_scrap("crates.md", b"");
+
+        let mut buf = Vec::new();
+        run(
+            ("rust", None),
+            ("crates", None),
+            &OPTIONS,
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        assert_eq!(output, "rust → Tools/cargo → crates\n");
+    }
+
+    #[rstest]
+    fn run_json_outputs_hops(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("a.md", b"#[[rust]]")
+            .add_scrap("b.md", b"#[[rust]]");
+
+        let mut buf = Vec::new();
+        run(
+            ("a", None),
+            ("b", None),
+            &PathOptions {
+                via_tags: true,
+                ..OPTIONS
+            },
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: PathResponse = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(response.count, 1);
+        let path = &response.results[0];
+        assert_eq!(path.length, 2);
+        assert_eq!(path.hops[0].title.as_deref(), Some("a"));
+        assert!(path.hops[0].via.is_none());
+        assert_eq!(path.hops[1].kind, "tag");
+        assert_eq!(path.hops[1].tag.as_deref(), Some("rust"));
+        assert_eq!(path.hops[2].via.as_deref(), Some("tag"));
+    }
+
+    #[rstest]
+    fn run_json_outputs_empty_when_unconnected(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"")
+            .add_scrap("a.md", b"")
+            .add_scrap("b.md", b"");
+
+        let mut buf = Vec::new();
+        run(
+            ("a", None),
+            ("b", None),
+            &OPTIONS,
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: PathResponse = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(response.count, 0);
+    }
+
+    #[rstest]
+    fn run_fails_for_unknown_scrap(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project.add_config(b"").add_scrap("a.md", b"");
+
+        let mut buf = Vec::new();
+        let result = run(
+            ("a", None),
+            ("missing", None),
+            &OPTIONS,
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use std::io::Write;
+use std::path::Path;
+
+use colored::Colorize;
+use comfy_table::presets::NOTHING;
+use comfy_table::{Cell, CellAlignment, Table};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::config::scrap_config::ScrapConfig;
+use crate::cli::path_resolver::PathResolver;
+use crate::error::{ScrapsError, ScrapsResult};
+use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::usecase::query::parser::Query;
+use crate::usecase::query::usecase::QueryScrapsUsecase;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct QueryResultJson {
+    title: String,
+    ctx: Option<String>,
+    linked_count: usize,
+    links_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct QueryResponse {
+    results: Vec<QueryResultJson>,
+    count: usize,
+}
+
+pub fn run(
+    expression: &str,
+    json: bool,
+    no_cache: bool,
+    project_path: Option<&Path>,
+    writer: &mut impl Write,
+) -> ScrapsResult<()> {
+    let query = Query::parse(expression).map_err(ScrapsError::from)?;
+
+    let path_resolver = PathResolver::new(project_path)?;
+    let config = ScrapConfig::from_path(project_path)?;
+    let scraps_dir_path = path_resolver.scraps_dir();
+    let exclude_dirs = vec![
+        path_resolver.static_dir(),
+        path_resolver.output_dir(&config),
+    ];
+
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
+
+    let usecase = QueryScrapsUsecase::new();
+    let results = usecase.execute(&scraps, &query)?;
+
+    if json {
+        let items: Vec<QueryResultJson> = results
+            .into_iter()
+            .map(|r| QueryResultJson {
+                title: r.title.to_string(),
+                ctx: r.ctx.map(|c| c.to_string()),
+                linked_count: r.linked_count,
+                links_count: r.links_count,
+            })
+            .collect();
+        let response = QueryResponse {
+            count: items.len(),
+            results: items,
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    } else {
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.load_style(NOTHING);
+        table.set_header(vec![
+            Cell::new("Title".bold()),
+            Cell::new("Context".bold()),
+            Cell::new("Linked".bold()),
+            Cell::new("Links".bold()),
+        ]);
+
+        for r in &results {
+            table.add_row(vec![
+                Cell::new(r.title.to_string()),
+                Cell::new(r.ctx.as_ref().map(|c| c.to_string()).unwrap_or_default()),
+                Cell::new(r.linked_count).set_alignment(CellAlignment::Right),
+                Cell::new(r.links_count).set_alignment(CellAlignment::Right),
+            ]);
+        }
+        writeln!(writer, "{table}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+
+    #[rstest]
+    fn run_json_outputs_sorted_matches(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("tokio.md", b"#[[rust/async]]")
+            .add_scrap("mio.md", b"#[[rust/async]] [[tokio]]")
+            .add_scrap("serde.md", b"#[[rust]] [[tokio]] [[mio]]");
+
+        let mut buf = Vec::new();
+        run(
+            "tag:rust/async sort:linked_count",
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: QueryResponse = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(response.count, 2);
+        assert_eq!(response.results[0].title, "tokio");
+        assert_eq!(response.results[0].linked_count, 2);
+        assert_eq!(response.results[1].title, "mio");
+        assert_eq!(response.results[1].links_count, 1);
+    }
+
+    #[rstest]
+    fn run_text_outputs_matches(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("todo.md", b"- [ ] write docs")
+            .add_scrap("done.md", b"- [x] ship");
+
+        let mut buf = Vec::new();
+        run(
+            "task:open",
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("todo"));
+        assert!(!output.contains("done"));
+    }
+
+    #[rstest]
+    fn run_fails_on_invalid_query(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project.add_config(b"");
+
+        let mut buf = Vec::new();
+        let result = run(
+            "colour:red",
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Query error: Unknown field 'colour'"
+        );
+    }
+}
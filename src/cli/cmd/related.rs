@@ -0,0 +1,184 @@
+use std::io::Write;
+use std::path::Path;
+
+use colored::Colorize;
+use comfy_table::presets::NOTHING;
+use comfy_table::{Cell, Table};
+use serde::{Deserialize, Serialize};
+
+use crate::cli::config::scrap_config::ScrapConfig;
+use crate::cli::path_resolver::PathResolver;
+use crate::error::ScrapsResult;
+use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::usecase::scrap::related::usecase::RelatedScrapsUsecase;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::title::Title;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RelatedScrapJson {
+    title: String,
+    ctx: Option<String>,
+    score: f64,
+    reason: String,
+    co_citations: usize,
+    shared_links: usize,
+    shared_tags: Vec<String>,
+    text_similarity: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RelatedResponse {
+    results: Vec<RelatedScrapJson>,
+    count: usize,
+}
+
+pub fn run(
+    title: &str,
+    ctx: Option<&str>,
+    num: usize,
+    json: bool,
+    no_cache: bool,
+    project_path: Option<&Path>,
+    writer: &mut impl Write,
+) -> ScrapsResult<()> {
+    let path_resolver = PathResolver::new(project_path)?;
+    let config = ScrapConfig::from_path(project_path)?;
+    let scraps_dir_path = path_resolver.scraps_dir();
+    let exclude_dirs = vec![
+        path_resolver.static_dir(),
+        path_resolver.output_dir(&config),
+    ];
+
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
+    let target_title = Title::from(title);
+    let target_ctx = ctx.map(Ctx::from);
+
+    let usecase = RelatedScrapsUsecase::new();
+    let results = usecase.execute(&scraps, &target_title, &target_ctx, num)?;
+
+    if json {
+        let items: Vec<RelatedScrapJson> = results
+            .into_iter()
+            .map(|r| RelatedScrapJson {
+                reason: r.reason(),
+                title: r.title.to_string(),
+                ctx: r.ctx.map(|c| c.to_string()),
+                score: r.score,
+                co_citations: r.co_citations,
+                shared_links: r.shared_links,
+                shared_tags: r.shared_tags.iter().map(|t| t.to_string()).collect(),
+                text_similarity: r.text_similarity,
+            })
+            .collect();
+        let response = RelatedResponse {
+            count: items.len(),
+            results: items,
+        };
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+    } else {
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.load_style(NOTHING);
+        table.set_header(vec![
+            Cell::new("Title".bold()),
+            Cell::new("Context".bold()),
+            Cell::new("Score".bold()),
+            Cell::new("Reason".bold()),
+        ]);
+
+        for r in &results {
+            table.add_row(vec![
+                Cell::new(r.title.to_string()),
+                Cell::new(r.ctx.as_ref().map(|c| c.to_string()).unwrap_or_default()),
+                Cell::new(format!("{:.2}", r.score)),
+                Cell::new(r.reason()),
+            ]);
+        }
+        writeln!(writer, "{table}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+
+    #[rstest]
+    fn run_text_outputs_reasons(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("tokio.md", b"[[futures]] [[mio]] #[[rust/async]]")
+            .add_scrap("async-std.md", b"[[futures]] [[mio]]")
+            .add_scrap("futures.md", b"")
+            .add_scrap("mio.md", b"");
+
+        let mut buf = Vec::new();
+        run(
+            "tokio",
+            None,
+            10,
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("async-std"));
+        assert!(output.contains("2 shared links"));
+    }
+
+    #[rstest]
+    fn run_json_respects_num(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("a.md", b"#[[rust/async]]")
+            .add_scrap("b.md", b"#[[rust/async]]")
+            .add_scrap("c.md", b"#[[rust]]");
+
+        let mut buf = Vec::new();
+        run(
+            "a",
+            None,
+            1,
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: RelatedResponse = serde_json::from_str(output.trim()).unwrap();
+        assert_eq!(response.count, 1);
+        let item = &response.results[0];
+        assert_eq!(item.title, "b");
+        assert_eq!(item.shared_tags, vec!["rust/async"]);
+        assert_eq!(item.reason, "tag rust/async");
+    }
+
+    #[rstest]
+    fn run_fails_for_unknown_scrap(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project.add_config(b"");
+
+        let mut buf = Vec::new();
+        let result = run(
+            "missing",
+            None,
+            10,
+            false,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        );
+        assert!(result.is_err());
+    }
+}
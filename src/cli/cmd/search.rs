@@ -3,28 +3,33 @@ use std::path::Path;
 
 use colored::Colorize;
 use comfy_table::presets::NOTHING;
-use comfy_table::{Cell, Table};
+use comfy_table::{Cell, CellAlignment, Table};
 use serde::{Deserialize, Serialize};
 
 use crate::cli::config::scrap_config::ScrapConfig;
-use crate::cli::json::scrap::ScrapKeyJson;
+use crate::cli::json::scrap::{SearchResultJson, SearchSnippetJson};
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
-use crate::usecase::search::usecase::SearchUsecase;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::usecase::search::usecase::{SearchEngineKind, SearchFilter, SearchUsecase};
 use scraps_libs::search::engine::SearchLogic;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct SearchResponse {
-    results: Vec<ScrapKeyJson>,
+    results: Vec<SearchResultJson>,
     count: usize,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     query: &str,
     num: usize,
     logic: SearchLogic,
+    engine: Option<SearchEngineKind>,
+    filter: SearchFilter,
     json: bool,
+    no_cache: bool,
     project_path: Option<&Path>,
     writer: &mut impl Write,
 ) -> ScrapsResult<()> {
@@ -36,50 +41,83 @@ pub fn run(
         path_resolver.output_dir(&config),
     ];
 
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
 
-    let usecase = SearchUsecase::new();
-    let results = usecase.execute(&scraps, query, num, logic)?;
+    let engine = engine.unwrap_or_else(|| config.search_engine());
+    let usecase = match (&cache, engine) {
+        (Some(cache), SearchEngineKind::Bm25) => {
+            SearchUsecase::new().with_body_tokens(cache.body_tokens(&scraps))
+        }
+        _ => SearchUsecase::new(),
+    };
+    let results = usecase.execute(&scraps, query, num, logic, engine, &filter)?;
 
-    let scrap_keys: Vec<ScrapKeyJson> = results
-        .into_iter()
-        .map(|r| ScrapKeyJson {
-            title: r.title.to_string(),
-            ctx: r.ctx.map(|c| c.to_string()),
-        })
-        .collect();
+    let results: Vec<SearchResultJson> = results.into_iter().map(Into::into).collect();
 
     if json {
-        let count = scrap_keys.len();
-        let response = SearchResponse {
-            results: scrap_keys,
-            count,
-        };
+        let count = results.len();
+        let response = SearchResponse { results, count };
         writeln!(writer, "{}", serde_json::to_string(&response)?)?;
     } else {
-        if scrap_keys.is_empty() {
+        if results.is_empty() {
             return Ok(());
         }
 
         let mut table = Table::new();
         table.load_style(NOTHING);
-        table.set_header(vec![Cell::new("Title".bold()), Cell::new("Context".bold())]);
+        table.set_header(vec![
+            Cell::new("Title".bold()),
+            Cell::new("Context".bold()),
+            Cell::new("Score".bold()),
+            Cell::new("Section".bold()),
+            Cell::new("Snippet".bold()),
+        ]);
 
-        for key in &scrap_keys {
-            table.add_row(vec![
-                Cell::new(&key.title),
-                Cell::new(key.ctx.as_deref().unwrap_or("")),
-            ]);
+        for result in &results {
+            let ctx = result.ctx.as_deref().unwrap_or("");
+            let score = format!("{:.2}", result.score);
+            if result.snippets.is_empty() {
+                table.add_row(vec![
+                    Cell::new(&result.title),
+                    Cell::new(ctx),
+                    Cell::new(&score).set_alignment(CellAlignment::Right),
+                ]);
+            }
+            for snippet in &result.snippets {
+                table.add_row(vec![
+                    Cell::new(&result.title),
+                    Cell::new(ctx),
+                    Cell::new(&score).set_alignment(CellAlignment::Right),
+                    Cell::new(snippet.heading.as_deref().unwrap_or("")),
+                    Cell::new(highlight(snippet)),
+                ]);
+            }
         }
         writeln!(writer, "{table}")?;
     }
     Ok(())
 }
 
+/// Snippet text with its matches in bold yellow.
+fn highlight(snippet: &SearchSnippetJson) -> String {
+    let mut out = String::new();
+    let mut cursor = 0;
+    for m in &snippet.matches {
+        out.push_str(&snippet.text[cursor..m.start]);
+        out.push_str(&snippet.text[m.start..m.end].yellow().bold().to_string());
+        cursor = m.end;
+    }
+    out.push_str(&snippet.text[cursor..]);
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cli::json::scrap::MatchRangeJson;
     use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::search::usecase::SearchScope;
     use rstest::rstest;
 
     #[rstest]
@@ -95,6 +133,9 @@ mod tests {
             "rust",
             100,
             SearchLogic::Or,
+            None,
+            SearchFilter::default(),
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
@@ -119,7 +160,10 @@ mod tests {
             "rust",
             100,
             SearchLogic::Or,
+            None,
+            SearchFilter::default(),
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -145,7 +189,10 @@ mod tests {
             "nonexistent-keyword-zzz",
             100,
             SearchLogic::Or,
+            None,
+            SearchFilter::default(),
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -170,7 +217,10 @@ mod tests {
             "rust python",
             100,
             SearchLogic::And,
+            None,
+            SearchFilter::default(),
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -197,7 +247,10 @@ mod tests {
             "rust",
             2,
             SearchLogic::Or,
+            None,
+            SearchFilter::default(),
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -216,10 +269,149 @@ mod tests {
             "rust",
             100,
             SearchLogic::Or,
+            None,
+            SearchFilter::default(),
+            false,
             false,
             Some(project.project_root.as_path()),
             &mut buf,
         );
         assert!(result.is_err());
     }
+
+    #[rstest]
+    fn run_bm25_engine_ranks_title_matches_first(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project
+            .add_config(b"")
+            .add_scrap("notes.md", b"Some notes mentioning ownership.")
+            .add_scrap("ownership.md", b"# Ownership\n\nRules of ownership.")
+            .add_scrap("python.md", b"# Python\n\nA different language.");
+
+        let mut buf = Vec::new();
+        run(
+            "ownership",
+            100,
+            SearchLogic::Or,
+            Some(SearchEngineKind::Bm25),
+            SearchFilter::default(),
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: SearchResponse = serde_json::from_str(output.trim()).unwrap();
+        let titles: Vec<&str> = response.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["ownership", "notes"]);
+    }
+
+    #[rstest]
+    fn run_uses_engine_from_config(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"[search]\nengine = \"bm25\"\n")
+            .add_scrap("a.md", b"The borrow checker rejects this.")
+            .add_scrap("b.md", b"A checker of the borrow.");
+
+        let mut buf = Vec::new();
+        run(
+            "\"borrow checker\"",
+            100,
+            SearchLogic::Or,
+            None,
+            SearchFilter::default(),
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: SearchResponse = serde_json::from_str(output.trim()).unwrap();
+        let titles: Vec<&str> = response.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["a"]);
+    }
+
+    #[rstest]
+    fn run_json_includes_score_and_snippets(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project.add_config(b"").add_scrap(
+            "ownership.md",
+            b"# Ownership\n\n## Moves\n\nValues move on assignment.",
+        );
+
+        let mut buf = Vec::new();
+        run(
+            "assignment",
+            100,
+            SearchLogic::Or,
+            Some(SearchEngineKind::Bm25),
+            SearchFilter::default(),
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: SearchResponse = serde_json::from_str(output.trim()).unwrap();
+        let result = &response.results[0];
+        assert!(result.score > 0.0);
+        let snippet = &result.snippets[0];
+        assert_eq!(snippet.line, 5);
+        assert_eq!(snippet.heading.as_deref(), Some("Moves"));
+        let m = &snippet.matches[0];
+        assert_eq!(&snippet.text[m.start..m.end], "assignment");
+    }
+
+    #[test]
+    fn highlight_wraps_each_match() {
+        let snippet = SearchSnippetJson {
+            text: "a rust b rust".to_string(),
+            line: 1,
+            heading: None,
+            matches: vec![
+                MatchRangeJson { start: 2, end: 6 },
+                MatchRangeJson { start: 9, end: 13 },
+            ],
+        };
+        let rust = "rust".yellow().bold().to_string();
+
+        assert_eq!(highlight(&snippet), format!("a {rust} b {rust}"));
+    }
+
+    #[rstest]
+    fn run_filters_by_ctx_and_scope(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap_with_context("journal", "2024-01-01.md", b"Tried tokio today.")
+            .add_scrap("tokio.md", b"# Tokio\n\nAn async runtime.");
+
+        let mut buf = Vec::new();
+        run(
+            "tokio",
+            100,
+            SearchLogic::Or,
+            None,
+            SearchFilter {
+                exclude_ctx: vec!["journal".into()],
+                scope: Some(SearchScope::Title),
+                ..SearchFilter::default()
+            },
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        let response: SearchResponse = serde_json::from_str(output.trim()).unwrap();
+        let titles: Vec<&str> = response.results.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, vec!["tokio"]);
+    }
 }
@@ -1,102 +1,367 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
 use std::time::Instant;
-use std::{net::SocketAddr, path::Path};
 
+use colored::Colorize;
 use scraps_libs::model::base_url::BaseUrl;
+use scraps_libs::model::scrap::Scrap;
 use url::Url;
 
 use crate::cli::display::serve::DisplayServeInfo;
 use crate::cli::path_resolver::PathResolver;
 use crate::cli::progress::ProgressImpl;
 use crate::error::ScrapsResult;
-use crate::input::file::read_scraps;
+use crate::input::file::{ogp_cache, read_assets, read_scraps, watch};
 use crate::output::build_renderer::BuildRendererImpl;
 use crate::usecase::progress::Progress;
 use crate::{
     cli::config::scrap_config::ScrapConfig,
     usecase::build::{
         model::{
-            color_scheme::ColorScheme, css::CssMetadata, html::HtmlMetadata, list_view_configs,
-            paging::Paging, sort::SortKey,
+            affected_pages::AffectedPages, asset::Assets, color_scheme::ColorScheme,
+            css::CssMetadata, feed::FeedConfigs, html::HtmlMetadata, list_view_configs,
+            list_view_configs::ListViewConfigs, ogp::OgpCache, paging::Paging, sort::SortKey,
         },
         usecase::BuildUsecase,
     },
-    usecase::serve::usecase::ServeUsecase,
+    usecase::serve::{live_reload::LiveReload, usecase::ServeUsecase},
 };
 use scraps_libs::git::GitCommandImpl;
 
-pub fn run(git: bool, project_path: Option<&Path>) -> ScrapsResult<()> {
-    // set local environment
-    let addr: SocketAddr = ([127, 0, 0, 1], 1112).into();
-    let base_url = BaseUrl::new(Url::parse(&format!("http://{addr}"))?.join("").unwrap()).unwrap();
-
-    // resolve paths
+pub fn run(
+    git: bool,
+    addr: IpAddr,
+    port: u16,
+    open: bool,
+    base_path: bool,
+    project_path: Option<&Path>,
+) -> ScrapsResult<()> {
     let path_resolver = PathResolver::new(project_path)?;
-    let config = ScrapConfig::from_path(project_path)?;
-    let ssg = config.require_ssg()?;
-    let scraps_dir_path = path_resolver.scraps_dir();
-    let static_dir_path = path_resolver.static_dir();
-    let output_dir_path = path_resolver.output_dir(&config);
-
-    // Input: read scraps (with git timestamps if --git is set) and README.
-    // The wiki root is the project root, so skip `static/` and the configured
-    // output directory at the top level.
-    let git_command = git.then(GitCommandImpl::new);
-    let exclude_dirs = vec![static_dir_path.clone(), output_dir_path.clone()];
-    let (scraps_with_ts, readme_text) =
-        read_scraps::to_all_scraps_with_timestamps(&scraps_dir_path, &exclude_dirs, git_command)?;
-
-    let renderer = BuildRendererImpl::new(&static_dir_path, &output_dir_path);
-    let build_usecase = BuildUsecase::new();
+    let settings = SiteSettings::load(&path_resolver)?;
 
-    let progress = ProgressImpl::init(Instant::now());
-    let title = &ssg.title;
-    let default_lang_code = scraps_libs::lang::LangCode::default();
-    let lang_code = ssg
-        .lang_code
-        .as_ref()
-        .map(|c| c.as_lang_code())
-        .unwrap_or(&default_lang_code);
-    let timezone = config.timezone.unwrap_or(chrono_tz::UTC);
-    let html_metadata = HtmlMetadata::new(lang_code, title, &ssg.description, &ssg.favicon);
-    let default_color_scheme = ColorScheme::OsSetting;
-    let css_metadata = CssMetadata::new(
-        ssg.color_scheme
-            .as_ref()
-            .map(|c| c.as_color_scheme())
-            .unwrap_or(&default_color_scheme),
-    );
-    let build_search_index = ssg.build_search_index.unwrap_or(true);
-    let default_sort_key = SortKey::CommittedDate;
-    let sort_key = ssg
-        .sort_key
-        .as_ref()
-        .map(|s| s.as_sort_key())
-        .unwrap_or(&default_sort_key);
-    let paging = match ssg.paginate_by {
-        None => Paging::Not,
-        Some(u) => Paging::By(u),
+    // Bind first so the site is built for the port actually in use.
+    let listener = ServeUsecase::bind(&SocketAddr::new(addr, port))?;
+    let local_addr = listener.local_addr()?;
+    if local_addr.port() != port {
+        println!(
+            "{}",
+            format!(
+                "Port {port} is in use, serving on {} instead",
+                local_addr.port()
+            )
+            .yellow()
+        );
+    }
+    let base_path = if base_path {
+        settings.base_path.clone()
+    } else {
+        "/".to_string()
+    };
+    let url_addr = if addr.is_unspecified() {
+        SocketAddr::new(Ipv4Addr::LOCALHOST.into(), local_addr.port())
+    } else {
+        local_addr
     };
-    let list_view_configs =
-        list_view_configs::ListViewConfigs::new(&build_search_index, sort_key, &paging);
-
-    let scrap_count = build_usecase.execute(
-        &scraps_with_ts,
-        &readme_text,
-        &progress,
-        &renderer,
-        &base_url,
-        timezone,
-        &html_metadata,
-        &css_metadata,
-        &list_view_configs,
-    )?;
+    let base_url = BaseUrl::new(Url::parse(&format!("http://{url_addr}{base_path}"))?)?;
+
+    let mut site = Site::new(&path_resolver, settings, base_url.clone(), git)?;
+
+    let progress = ProgressImpl::init(Instant::now());
+    let scrap_count = site.build(&progress)?;
     progress.end();
 
     // display serve info
-    let serve_info = DisplayServeInfo::new(title, &format!("http://{addr}"), scrap_count);
+    let serve_info = DisplayServeInfo::new(
+        &site.settings.title,
+        base_url.as_url().as_str(),
+        scrap_count,
+    );
     println!("{serve_info}");
+    if open {
+        open_browser(base_url.as_url().as_str());
+    }
+
+    // Rebuild on changes to the wiki, `static/` or the config, and reload
+    // open pages. The output directory and base path are fixed for the life
+    // of the server.
+    let output_dir_path = site.settings.output_dir_path.clone();
+    let live_reload = Arc::new(LiveReload::new());
+    let _watcher = {
+        let live_reload = live_reload.clone();
+        watch::watch(
+            &path_resolver.scraps_dir(),
+            std::slice::from_ref(&output_dir_path),
+            &[path_resolver.config_path()],
+            move |paths| match site.rebuild(&paths) {
+                Ok(true) => live_reload.reload(),
+                Ok(false) => {}
+                Err(e) => {
+                    let message = format!("{e:#}");
+                    eprintln!("{} {message}", "Build failed:".red().bold());
+                    live_reload.fail(&message);
+                }
+            },
+        )?
+    };
 
     // serve command
-    let serve_usecase = ServeUsecase::new(&output_dir_path);
-    serve_usecase.execute(&addr)
+    let serve_usecase = ServeUsecase::new(&output_dir_path)
+        .with_base_path(&base_path)
+        .with_live_reload(live_reload);
+    serve_usecase.execute(listener)
+}
+
+/// Open `url` with the platform's default handler. Failing to is not fatal.
+fn open_browser(url: &str) {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    if let Err(e) = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        eprintln!("{} {e}", "Failed to open a browser:".yellow());
+    }
+}
+
+/// Build inputs derived from `.scraps.toml`, reloaded when it changes.
+struct SiteSettings {
+    scraps_dir_path: PathBuf,
+    static_dir_path: PathBuf,
+    output_dir_path: PathBuf,
+    config_path: PathBuf,
+    /// Path of the configured base_url, e.g. `/wiki/`.
+    base_path: String,
+    title: String,
+    timezone: chrono_tz::Tz,
+    html_metadata: HtmlMetadata,
+    css_metadata: CssMetadata,
+    list_view_configs: ListViewConfigs,
+    feed_configs: Option<FeedConfigs>,
+}
+
+impl SiteSettings {
+    fn load(path_resolver: &PathResolver) -> ScrapsResult<SiteSettings> {
+        let config = ScrapConfig::from_path(Some(path_resolver.project_root()))?;
+        let ssg = config.require_ssg()?;
+
+        let title = &ssg.title;
+        let default_lang_code = scraps_libs::lang::LangCode::default();
+        let lang_code = ssg
+            .lang_code
+            .as_ref()
+            .map(|c| c.as_lang_code())
+            .unwrap_or(&default_lang_code);
+        let timezone = config.timezone.unwrap_or(chrono_tz::UTC);
+        let html_metadata = HtmlMetadata::new(lang_code, title, &ssg.description, &ssg.favicon);
+        let default_color_scheme = ColorScheme::OsSetting;
+        let css_metadata = CssMetadata::new(
+            ssg.color_scheme
+                .as_ref()
+                .map(|c| c.as_color_scheme())
+                .unwrap_or(&default_color_scheme),
+        );
+        let build_search_index = ssg.build_search_index.unwrap_or(true);
+        let build_graph = ssg.build_graph.unwrap_or(false);
+        let default_sort_key = SortKey::CommittedDate;
+        let sort_key = ssg
+            .sort_key
+            .as_ref()
+            .map(|s| s.as_sort_key())
+            .unwrap_or(&default_sort_key);
+        let paging = match ssg.paginate_by {
+            None => Paging::Not,
+            Some(u) => Paging::By(u),
+        };
+        let list_view_configs = list_view_configs::ListViewConfigs::new(
+            &build_search_index,
+            &build_graph,
+            sort_key,
+            &paging,
+        );
+
+        Ok(SiteSettings {
+            scraps_dir_path: path_resolver.scraps_dir(),
+            static_dir_path: path_resolver.static_dir(),
+            output_dir_path: path_resolver.output_dir(&config),
+            config_path: path_resolver.config_path(),
+            base_path: ssg.base_url().as_url().path().to_string(),
+            title: title.to_string(),
+            timezone,
+            html_metadata,
+            css_metadata,
+            list_view_configs,
+            feed_configs: ssg.feed_configs(),
+        })
+    }
+}
+
+/// The served site: its settings and the scraps of the last build, which a
+/// rebuild diffs against to find the pages to re-render.
+struct Site {
+    path_resolver: PathResolver,
+    base_url: BaseUrl,
+    git: bool,
+    settings: SiteSettings,
+    scraps_with_ts: Vec<(Scrap, Option<i64>)>,
+    readme_text: Option<String>,
+    assets: Assets,
+    /// Link cards are rendered from the cache as is; `scraps build` fetches
+    /// the pages of new autolinks.
+    ogp_cache: OgpCache,
+}
+
+impl Site {
+    fn new(
+        path_resolver: &PathResolver,
+        settings: SiteSettings,
+        base_url: BaseUrl,
+        git: bool,
+    ) -> ScrapsResult<Site> {
+        let (scraps_with_ts, readme_text) = Self::read(&settings, git)?;
+        let assets = Self::read_assets(&settings, &scraps_with_ts)?;
+        Ok(Site {
+            path_resolver: path_resolver.clone(),
+            base_url,
+            git,
+            settings,
+            scraps_with_ts,
+            readme_text,
+            assets,
+            ogp_cache: ogp_cache::load(path_resolver.project_root())?,
+        })
+    }
+
+    /// Input: read scraps (with git timestamps if --git is set) and README.
+    /// The wiki root is the project root, so skip `static/` and the
+    /// configured output directory at the top level.
+    #[allow(clippy::type_complexity)]
+    fn read(
+        settings: &SiteSettings,
+        git: bool,
+    ) -> ScrapsResult<(Vec<(Scrap, Option<i64>)>, Option<String>)> {
+        let git_command = git.then(GitCommandImpl::new);
+        read_scraps::to_all_scraps_with_timestamps(
+            &settings.scraps_dir_path,
+            &Self::exclude_dirs(settings),
+            git_command,
+        )
+    }
+
+    /// The files beside the scraps, copied next to their pages.
+    fn read_assets(
+        settings: &SiteSettings,
+        scraps_with_ts: &[(Scrap, Option<i64>)],
+    ) -> ScrapsResult<Assets> {
+        let assets =
+            read_assets::to_all_assets(&settings.scraps_dir_path, &Self::exclude_dirs(settings))?;
+        Ok(Assets::new(&assets, &Self::scraps(scraps_with_ts)))
+    }
+
+    fn exclude_dirs(settings: &SiteSettings) -> Vec<PathBuf> {
+        vec![
+            settings.static_dir_path.clone(),
+            settings.output_dir_path.clone(),
+        ]
+    }
+
+    fn renderer(&self) -> BuildRendererImpl {
+        BuildRendererImpl::new(
+            &self.settings.static_dir_path,
+            &self.settings.output_dir_path,
+        )
+    }
+
+    fn build<PG: Progress>(&self, progress: &PG) -> ScrapsResult<usize> {
+        let scrap_count = BuildUsecase::new().execute(
+            &self.scraps_with_ts,
+            &self.readme_text,
+            progress,
+            &self.renderer(),
+            &self.base_url,
+            self.settings.timezone,
+            &self.settings.html_metadata,
+            &self.settings.css_metadata,
+            &self.settings.list_view_configs,
+            &self.ogp_cache,
+            &self.assets,
+        )?;
+        self.build_feeds(progress)?;
+        Ok(scrap_count)
+    }
+
+    /// Feeds are ordered by commit date, so only `--git` serves them.
+    fn build_feeds<PG: Progress>(&self, progress: &PG) -> ScrapsResult<()> {
+        if let Some(feed_configs) = self.settings.feed_configs.as_ref().filter(|_| self.git) {
+            BuildUsecase::new().execute_feeds(
+                &self.scraps_with_ts,
+                progress,
+                &self.renderer(),
+                &self.base_url,
+                &self.settings.html_metadata,
+                feed_configs,
+                &self.assets,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Rebuild after `changed_paths` were modified. Templates, static files
+    /// and the config affect every page, so they trigger a full build;
+    /// other changes re-render the pages affected by the changed Markdown,
+    /// and every page once the set of files beside the scraps changes, then
+    /// copy those files again. Returns whether anything was rebuilt.
+    fn rebuild(&mut self, changed_paths: &[PathBuf]) -> ScrapsResult<bool> {
+        let full = changed_paths.iter().any(|p| {
+            p.starts_with(&self.settings.static_dir_path) || *p == self.settings.config_path
+        });
+
+        let progress = ProgressImpl::init(Instant::now());
+        if full {
+            self.settings = SiteSettings::load(&self.path_resolver)?;
+            self.ogp_cache = ogp_cache::load(self.path_resolver.project_root())?;
+            (self.scraps_with_ts, self.readme_text) = Self::read(&self.settings, self.git)?;
+            self.assets = Self::read_assets(&self.settings, &self.scraps_with_ts)?;
+            self.build(&progress)?;
+        } else {
+            let (scraps_with_ts, readme_text) = Self::read(&self.settings, self.git)?;
+            self.assets = Self::read_assets(&self.settings, &scraps_with_ts)?;
+            let affected = AffectedPages::between(
+                &Self::scraps(&self.scraps_with_ts),
+                &Self::scraps(&scraps_with_ts),
+            );
+            BuildUsecase::new().execute_affected(
+                &scraps_with_ts,
+                &readme_text,
+                &progress,
+                &self.renderer(),
+                &self.base_url,
+                self.settings.timezone,
+                &self.settings.html_metadata,
+                &self.settings.list_view_configs,
+                &self.ogp_cache,
+                &self.assets,
+                &affected,
+            )?;
+            self.scraps_with_ts = scraps_with_ts;
+            self.readme_text = readme_text;
+            self.build_feeds(&progress)?;
+        }
+        progress.end();
+        Ok(true)
+    }
+
+    fn scraps(scraps_with_ts: &[(Scrap, Option<i64>)]) -> Vec<Scrap> {
+        scraps_with_ts.iter().map(|(s, _)| s.clone()).collect()
+    }
 }
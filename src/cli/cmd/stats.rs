@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use colored::Colorize;
+use comfy_table::presets::NOTHING;
+use comfy_table::{Cell, Table};
+use itertools::Itertools;
+use scraps_libs::model::key::ScrapKey;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::config::scrap_config::ScrapConfig;
+use crate::cli::path_resolver::PathResolver;
+use crate::error::ScrapsResult;
+use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::usecase::stats::usecase::{StatsUsecase, WikiStats};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CtxCountJson {
+    ctx: Option<String>,
+    count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RankedScrapJson {
+    key: String,
+    score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TasksJson {
+    open: usize,
+    done: usize,
+    deferred: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StatsResponse {
+    scraps: usize,
+    scraps_per_ctx: Vec<CtxCountJson>,
+    tags: usize,
+    tag_depths: BTreeMap<usize, usize>,
+    links: usize,
+    broken_links: usize,
+    avg_in_degree: f64,
+    avg_out_degree: f64,
+    hubs: Vec<RankedScrapJson>,
+    authorities: Vec<RankedScrapJson>,
+    orphans: usize,
+    largest_component: usize,
+    tasks: TasksJson,
+    words: usize,
+}
+
+fn ranked(scores: &[(ScrapKey, f64)]) -> Vec<RankedScrapJson> {
+    scores
+        .iter()
+        .map(|(key, score)| RankedScrapJson {
+            key: key.to_string(),
+            score: *score,
+        })
+        .collect()
+}
+
+impl From<WikiStats> for StatsResponse {
+    fn from(stats: WikiStats) -> Self {
+        StatsResponse {
+            scraps: stats.scrap_count,
+            scraps_per_ctx: stats
+                .scraps_per_ctx
+                .iter()
+                .map(|(ctx, count)| CtxCountJson {
+                    ctx: ctx.as_ref().map(|c| c.to_string()),
+                    count: *count,
+                })
+                .collect(),
+            tags: stats.tag_count,
+            tag_depths: stats.tag_depths,
+            links: stats.total_links,
+            broken_links: stats.broken_links,
+            avg_in_degree: stats.avg_in_degree,
+            avg_out_degree: stats.avg_out_degree,
+            hubs: ranked(&stats.hubs),
+            authorities: ranked(&stats.authorities),
+            orphans: stats.orphans,
+            largest_component: stats.largest_component,
+            tasks: TasksJson {
+                open: stats.open_tasks,
+                done: stats.done_tasks,
+                deferred: stats.deferred_tasks,
+            },
+            words: stats.words,
+        }
+    }
+}
+
+pub fn run(
+    json: bool,
+    no_cache: bool,
+    project_path: Option<&Path>,
+    writer: &mut impl Write,
+) -> ScrapsResult<()> {
+    let path_resolver = PathResolver::new(project_path)?;
+    let config = ScrapConfig::from_path(project_path)?;
+    let scraps_dir_path = path_resolver.scraps_dir();
+    let exclude_dirs = vec![
+        path_resolver.static_dir(),
+        path_resolver.output_dir(&config),
+    ];
+
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
+
+    let usecase = StatsUsecase::new();
+    let stats = usecase.execute(&scraps)?;
+
+    if json {
+        let response = StatsResponse::from(stats);
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        return Ok(());
+    }
+
+    let keys = |ranking: &[(ScrapKey, f64)]| ranking.iter().map(|(key, _)| key).join(", ");
+    let rows = vec![
+        ("Scraps", stats.scrap_count.to_string()),
+        (
+            "Contexts",
+            stats
+                .scraps_per_ctx
+                .iter()
+                .map(|(ctx, count)| {
+                    let ctx = ctx.as_ref().map_or("(root)".to_string(), |c| c.to_string());
+                    format!("{ctx}: {count}")
+                })
+                .join(", "),
+        ),
+        ("Tags", stats.tag_count.to_string()),
+        (
+            "Tag depths",
+            stats
+                .tag_depths
+                .iter()
+                .map(|(depth, count)| format!("{depth}: {count}"))
+                .join(", "),
+        ),
+        ("Links", stats.total_links.to_string()),
+        ("Broken links", stats.broken_links.to_string()),
+        ("Avg in-degree", format!("{:.2}", stats.avg_in_degree)),
+        ("Avg out-degree", format!("{:.2}", stats.avg_out_degree)),
+        ("Hubs", keys(&stats.hubs)),
+        ("Authorities", keys(&stats.authorities)),
+        ("Orphans", stats.orphans.to_string()),
+        ("Largest component", stats.largest_component.to_string()),
+        (
+            "Tasks",
+            format!(
+                "{} open, {} done, {} deferred",
+                stats.open_tasks, stats.done_tasks, stats.deferred_tasks
+            ),
+        ),
+        ("Words", stats.words.to_string()),
+    ];
+
+    let mut table = Table::new();
+    table.load_style(NOTHING);
+    for (label, value) in rows {
+        table.add_row(vec![Cell::new(label.bold()), Cell::new(value)]);
+    }
+    writeln!(writer, "{table}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+
+    #[rstest]
+    fn run_text_outputs_report(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("a.md", b"[[b]] [[gone]]\n\n- [ ] todo\n")
+            .add_scrap_with_context("Book", "b.md", b"#[[rust]]");
+
+        let mut buf = Vec::new();
+        run(false, false, Some(project.project_root.as_path()), &mut buf).unwrap();
+
+        let output = String::from_utf8(buf).unwrap();
+        assert!(output.contains("Broken links"));
+        assert!(output.contains("(root): 1, Book: 1"));
+        assert!(output.contains("1 open, 0 done, 0 deferred"));
+    }
+
+    #[rstest]
+    fn run_json_outputs_metrics(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project
+            .add_config(b"")
+            .add_scrap("a.md", b"[[Book/b]] [[gone]]\n\n- [x] done\n")
+            .add_scrap_with_context("Book", "b.md", b"#[[lang/rust]]");
+
+        let mut buf = Vec::new();
+        run(true, false, Some(project.project_root.as_path()), &mut buf).unwrap();
+
+        let response: StatsResponse =
+            serde_json::from_str(String::from_utf8(buf).unwrap().trim()).unwrap();
+        assert_eq!(response.scraps, 2);
+        assert_eq!(response.links, 2);
+        assert_eq!(response.broken_links, 1);
+        assert_eq!(response.orphans, 1);
+        assert_eq!(response.largest_component, 2);
+        assert_eq!(response.tags, 2);
+        assert_eq!(response.tag_depths.get(&2), Some(&1));
+        assert_eq!(response.tasks.done, 1);
+        assert_eq!(response.hubs[0].key, "a");
+        assert_eq!(response.authorities[0].key, "Book/b");
+    }
+
+    #[rstest]
+    fn run_fails_without_config(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let mut buf = Vec::new();
+        let result = run(false, false, Some(project.project_root.as_path()), &mut buf);
+        assert!(result.is_err());
+    }
+}
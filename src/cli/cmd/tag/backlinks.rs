@@ -11,6 +11,7 @@ use crate::cli::json::scrap::ScrapKeyJson;
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::tag::lookup_backlinks::usecase::LookupTagBacklinksUsecase;
 
 #[derive(Debug, Serialize, serde::Deserialize)]
@@ -22,6 +23,7 @@ struct TagBacklinksResponse {
 pub fn run(
     tag: &str,
     json: bool,
+    no_cache: bool,
     project_path: Option<&Path>,
     writer: &mut impl Write,
 ) -> ScrapsResult<()> {
@@ -33,7 +35,8 @@ pub fn run(
         path_resolver.output_dir(&config),
     ];
 
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
     let usecase = LookupTagBacklinksUsecase::new();
     let tag_title = scraps_libs::model::title::Title::from(tag);
     let results = usecase.execute(&scraps, &tag_title)?;
@@ -87,7 +90,14 @@ mod tests {
             .add_scrap("b.md", b"#[[rust]] #[[cli]]");
 
         let mut buf = Vec::new();
-        run("rust", true, Some(project.project_root.as_path()), &mut buf).unwrap();
+        run(
+            "rust",
+            true,
+            false,
+            Some(project.project_root.as_path()),
+            &mut buf,
+        )
+        .unwrap();
 
         let output = String::from_utf8(buf).unwrap();
         let response: TagBacklinksResponse = serde_json::from_str(output.trim()).unwrap();
@@ -109,6 +119,7 @@ mod tests {
         run(
             "nonexistent",
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -131,6 +142,7 @@ mod tests {
         let result = run(
             "rust",
             false,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         );
@@ -148,6 +160,7 @@ mod tests {
         let result = run(
             "nonexistent",
             false,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         );
@@ -162,6 +175,7 @@ mod tests {
         let result = run(
             "rust",
             false,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         );
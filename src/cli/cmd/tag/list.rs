@@ -9,9 +9,15 @@ use crate::cli::json::tag::TagJson;
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::tag::list::usecase::ListTagUsecase;
 
-pub fn run(json: bool, project_path: Option<&Path>, writer: &mut impl Write) -> ScrapsResult<()> {
+pub fn run(
+    json: bool,
+    no_cache: bool,
+    project_path: Option<&Path>,
+    writer: &mut impl Write,
+) -> ScrapsResult<()> {
     let path_resolver = PathResolver::new(project_path)?;
     let config = ScrapConfig::from_path(project_path)?;
     let scraps_dir_path = path_resolver.scraps_dir();
@@ -20,7 +26,8 @@ pub fn run(json: bool, project_path: Option<&Path>, writer: &mut impl Write) ->
         path_resolver.output_dir(&config),
     ];
 
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
     let usecase = ListTagUsecase::new();
 
     let (tags, backlinks_map) = usecase.execute(&scraps)?;
@@ -70,7 +77,7 @@ mod tests {
             .add_scrap("b.md", b"#[[tag1]] #[[tag2]]");
 
         let mut buf = Vec::new();
-        let result = run(false, Some(project.project_root.as_path()), &mut buf);
+        let result = run(false, false, Some(project.project_root.as_path()), &mut buf);
         assert!(result.is_ok());
     }
 
@@ -79,7 +86,7 @@ mod tests {
         project.add_config(b"").add_scrap("a.md", b"#[[tag1]]");
 
         let mut buf = Vec::new();
-        let result = run(false, Some(project.project_root.as_path()), &mut buf);
+        let result = run(false, false, Some(project.project_root.as_path()), &mut buf);
         assert!(result.is_ok());
     }
 
@@ -88,14 +95,14 @@ mod tests {
         project.add_config(b"");
 
         let mut buf = Vec::new();
-        let result = run(false, Some(project.project_root.as_path()), &mut buf);
+        let result = run(false, false, Some(project.project_root.as_path()), &mut buf);
         assert!(result.is_ok());
     }
 
     #[rstest]
     fn run_fails_without_config(#[from(temp_scrap_project)] project: TempScrapProject) {
         let mut buf = Vec::new();
-        let result = run(false, Some(project.project_root.as_path()), &mut buf);
+        let result = run(false, false, Some(project.project_root.as_path()), &mut buf);
         assert!(result.is_err());
     }
 
@@ -107,7 +114,7 @@ mod tests {
             .add_scrap("b.md", b"#[[tag1]] #[[tag2]]");
 
         let mut buf = Vec::new();
-        run(true, Some(project.project_root.as_path()), &mut buf).unwrap();
+        run(true, false, Some(project.project_root.as_path()), &mut buf).unwrap();
 
         let output = String::from_utf8(buf).unwrap();
         let tags: Vec<TagJson> = serde_json::from_str(output.trim()).unwrap();
@@ -123,7 +130,7 @@ mod tests {
         project.add_config(b"");
 
         let mut buf = Vec::new();
-        run(true, Some(project.project_root.as_path()), &mut buf).unwrap();
+        run(true, false, Some(project.project_root.as_path()), &mut buf).unwrap();
 
         let output = String::from_utf8(buf).unwrap();
         let tags: Vec<TagJson> = serde_json::from_str(output.trim()).unwrap();
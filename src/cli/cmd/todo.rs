@@ -10,6 +10,7 @@ use crate::cli::config::scrap_config::ScrapConfig;
 use crate::cli::path_resolver::PathResolver;
 use crate::error::ScrapsResult;
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::todo::usecase::{StatusFilter, TodoUsecase};
 use scraps_libs::markdown::query::TaskStatus;
 
@@ -51,6 +52,7 @@ fn scrap_label(title: &str, ctx: Option<&str>) -> String {
 pub fn run(
     status: StatusFilter,
     json: bool,
+    no_cache: bool,
     project_path: Option<&Path>,
     writer: &mut impl Write,
 ) -> ScrapsResult<()> {
@@ -62,7 +64,8 @@ pub fn run(
         path_resolver.output_dir(&config),
     ];
 
-    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs)?;
+    let cache = (!no_cache).then(|| ScrapCache::load(&path_resolver.output_dir(&config)));
+    let scraps = read_scraps::to_all_scraps(&scraps_dir_path, &exclude_dirs, cache.as_ref())?;
 
     let usecase = TodoUsecase::new();
     let results = usecase.execute(&scraps, status)?;
@@ -134,6 +137,7 @@ mod tests {
         run(
             StatusFilter::Open,
             false,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -156,6 +160,7 @@ mod tests {
         run(
             StatusFilter::Open,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -183,6 +188,7 @@ mod tests {
         run(
             StatusFilter::Done,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -205,6 +211,7 @@ mod tests {
         run(
             StatusFilter::All,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -225,6 +232,7 @@ mod tests {
         run(
             StatusFilter::Open,
             true,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         )
@@ -242,6 +250,7 @@ mod tests {
         let result = run(
             StatusFilter::Open,
             false,
+            false,
             Some(project.project_root.as_path()),
             &mut buf,
         );
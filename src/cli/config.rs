@@ -2,4 +2,5 @@ pub mod base_url;
 pub mod color_scheme;
 pub mod lang;
 pub mod scrap_config;
+pub mod search_engine;
 pub mod sort_key;
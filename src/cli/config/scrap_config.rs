@@ -1,5 +1,7 @@
 use crate::cli::path_resolver::PathResolver;
 use crate::error::{anyhow::Context, CliError, ScrapsResult};
+use crate::usecase::build::model::feed::FeedConfigs;
+use crate::usecase::search::usecase::SearchEngineKind;
 use chrono_tz::Tz;
 use config::Config;
 use scraps_libs::model::base_url::BaseUrl;
@@ -9,7 +11,7 @@ use url::Url;
 
 use super::{
     base_url::BaseUrlConfig, color_scheme::ColorSchemeConfig, lang::LangCodeConfig,
-    sort_key::SortKeyConfig,
+    search_engine::SearchEngineConfig, sort_key::SortKeyConfig,
 };
 
 /// SSG-specific configuration (site generation settings)
@@ -21,9 +23,11 @@ pub struct SsgConfig {
     pub description: Option<String>,
     pub favicon: Option<Url>,
     pub build_search_index: Option<bool>,
+    pub build_graph: Option<bool>,
     pub sort_key: Option<SortKeyConfig>,
     pub paginate_by: Option<usize>,
     pub color_scheme: Option<ColorSchemeConfig>,
+    pub feed: Option<FeedConfig>,
 }
 
 impl SsgConfig {
@@ -31,6 +35,34 @@ impl SsgConfig {
     pub fn base_url(&self) -> BaseUrl {
         self.base_url.clone().into_base_url()
     }
+
+    /// Feed settings, with defaults when `[ssg.feed]` is absent. `None` when
+    /// feeds are disabled.
+    pub fn feed_configs(&self) -> Option<FeedConfigs> {
+        let default = FeedConfigs::default();
+        match &self.feed {
+            None => Some(default),
+            Some(feed) if !feed.enabled => None,
+            Some(feed) => Some(FeedConfigs {
+                limit: feed.limit.unwrap_or(default.limit),
+                full_content: feed.full_content.unwrap_or(default.full_content),
+                by_ctx: feed.by_ctx.unwrap_or(default.by_ctx),
+            }),
+        }
+    }
+}
+
+/// Configuration for the Atom feeds under `[ssg.feed]`.
+///
+/// Feeds are on by default and need commit timestamps, so they are written
+/// only by `--git` builds. `enabled = false` turns them off.
+#[derive(Debug, Deserialize)]
+pub struct FeedConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub limit: Option<usize>,
+    pub full_content: Option<bool>,
+    pub by_ctx: Option<bool>,
 }
 
 /// Lint-specific configuration. Each rule lives in its own nested table.
@@ -60,6 +92,12 @@ fn default_true() -> bool {
     true
 }
 
+/// Search-specific configuration for `scraps search`.
+#[derive(Debug, Deserialize, Default)]
+pub struct SearchConfig {
+    pub engine: Option<SearchEngineConfig>,
+}
+
 /// Main configuration struct
 #[derive(Debug, Deserialize)]
 pub struct ScrapConfig {
@@ -67,6 +105,7 @@ pub struct ScrapConfig {
     pub timezone: Option<Tz>,
     pub ssg: Option<SsgConfig>,
     pub lint: Option<LintConfig>,
+    pub search: Option<SearchConfig>,
 }
 
 impl ScrapConfig {
@@ -91,6 +130,15 @@ impl ScrapConfig {
             .ok_or_else(|| CliError::MissingSsgSection.into())
     }
 
+    /// Search engine from `[search]`, falling back to the default
+    pub fn search_engine(&self) -> SearchEngineKind {
+        self.search
+            .as_ref()
+            .and_then(|s| s.engine.as_ref())
+            .map(|e| *e.as_engine_kind())
+            .unwrap_or_default()
+    }
+
     /// Gets optional base_url if ssg section is present
     pub fn get_base_url(&self) -> Option<BaseUrl> {
         self.ssg.as_ref().map(|s| s.base_url())
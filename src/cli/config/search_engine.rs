@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+use crate::usecase::search::usecase::SearchEngineKind;
+
+#[derive(Deserialize)]
+#[serde(remote = "SearchEngineKind", rename_all = "snake_case")]
+pub enum SerdeSearchEngineKind {
+    Fuzzy,
+    Bm25,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct SearchEngineConfig(#[serde(with = "SerdeSearchEngineKind")] SearchEngineKind);
+
+impl SearchEngineConfig {
+    pub fn as_engine_kind(&self) -> &SearchEngineKind {
+        &self.0
+    }
+}
@@ -1,2 +1,3 @@
 pub mod serve;
 pub mod tag;
+pub mod traversal;
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use colored::Colorize;
+use comfy_table::{presets::NOTHING, Cell, CellAlignment, Table};
+
+use crate::cli::json::scrap::TraversalJson;
+use crate::usecase::scrap::traverse::usecase::TraverseDirection;
+
+pub struct DisplayTraversalTable<'a> {
+    nodes: &'a [TraversalJson],
+    direction: TraverseDirection,
+}
+
+impl<'a> DisplayTraversalTable<'a> {
+    pub fn new(nodes: &'a [TraversalJson], direction: TraverseDirection) -> Self {
+        Self { nodes, direction }
+    }
+}
+
+impl fmt::Display for DisplayTraversalTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.nodes.is_empty() {
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table.load_style(NOTHING);
+        table.set_header(vec![
+            Cell::new("Depth".bold()),
+            Cell::new("Kind".bold()),
+            Cell::new("Title".bold()),
+            Cell::new("Context".bold()),
+            Cell::new("Via".bold()),
+        ]);
+
+        // Outbound nodes are referenced by `from`; inbound ones reference it.
+        let preposition = match self.direction {
+            TraverseDirection::Outbound => "from",
+            TraverseDirection::Inbound => "to",
+        };
+        for node in self.nodes {
+            let name = match &node.tag {
+                Some(tag) => format!("#{tag}"),
+                None => node.title.clone().unwrap_or_default(),
+            };
+            table.add_row(vec![
+                Cell::new(node.distance).set_alignment(CellAlignment::Right),
+                Cell::new(&node.kind),
+                Cell::new(name),
+                Cell::new(node.ctx.as_deref().unwrap_or("")),
+                Cell::new(format!("{} {preposition} {}", node.via, node.from)),
+            ]);
+        }
+
+        write!(f, "{table}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(title: &str, via: &str, from: &str) -> TraversalJson {
+        TraversalJson {
+            kind: "scrap".to_string(),
+            title: Some(title.to_string()),
+            ctx: None,
+            tag: None,
+            distance: 1,
+            via: via.to_string(),
+            from: from.to_string(),
+        }
+    }
+
+    #[test]
+    fn display_traversal_table_outbound_names_the_referencing_scrap() {
+        let nodes = vec![node("cargo", "link", "rust")];
+        let output = DisplayTraversalTable::new(&nodes, TraverseDirection::Outbound).to_string();
+        assert!(output.contains("link from rust"));
+    }
+
+    #[test]
+    fn display_traversal_table_inbound_names_the_referenced_scrap() {
+        let nodes = vec![node("rust", "link", "cargo"), node("book", "embed", "rust")];
+        let output = DisplayTraversalTable::new(&nodes, TraverseDirection::Inbound).to_string();
+        assert!(output.contains("link to cargo"));
+        assert!(output.contains("embed to rust"));
+        assert!(!output.contains(" from "));
+    }
+}
@@ -1,3 +1,6 @@
+use crate::usecase::build::model::backlinks_map::BacklinkOccurrence;
+use crate::usecase::scrap::traverse::usecase::{TraverseEdgeKind, TraverseNode, TraverseResult};
+use crate::usecase::search::usecase::{SearchResult, SearchSnippet};
 use scraps_libs::markdown::query::{CodeBlock, Heading};
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +10,85 @@ pub struct ScrapKeyJson {
     pub ctx: Option<String>,
 }
 
+/// A linking scrap; `occurrences` is only present when context was requested.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BacklinkJson {
+    pub title: String,
+    pub ctx: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub occurrences: Option<Vec<BacklinkOccurrenceJson>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BacklinkOccurrenceJson {
+    pub line: usize,
+    pub context: String,
+    pub heading_path: Vec<String>,
+}
+
+impl From<BacklinkOccurrence> for BacklinkOccurrenceJson {
+    fn from(o: BacklinkOccurrence) -> Self {
+        Self {
+            line: o.line,
+            context: o.context,
+            heading_path: o.heading_path,
+        }
+    }
+}
+
+/// A search hit with its engine score and excerpts around the matches.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchResultJson {
+    pub title: String,
+    pub ctx: Option<String>,
+    pub score: f64,
+    pub snippets: Vec<SearchSnippetJson>,
+}
+
+impl From<SearchResult> for SearchResultJson {
+    fn from(r: SearchResult) -> Self {
+        Self {
+            title: r.title.to_string(),
+            ctx: r.ctx.map(|c| c.to_string()),
+            score: r.score,
+            snippets: r.snippets.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// `matches` are UTF-8 byte offsets into `text`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchSnippetJson {
+    pub text: String,
+    pub line: usize,
+    pub heading: Option<String>,
+    pub matches: Vec<MatchRangeJson>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MatchRangeJson {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl From<SearchSnippet> for SearchSnippetJson {
+    fn from(s: SearchSnippet) -> Self {
+        Self {
+            text: s.text,
+            line: s.line,
+            heading: s.heading,
+            matches: s
+                .matches
+                .into_iter()
+                .map(|m| MatchRangeJson {
+                    start: m.start,
+                    end: m.end,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct HeadingJson {
     pub level: u8,
@@ -43,3 +125,65 @@ impl From<CodeBlock> for CodeBlockJson {
         }
     }
 }
+
+/// A node reached by `--depth` traversal and the edge it was reached through.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TraversalJson {
+    /// `scrap`, `broken` or `tag`.
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ctx: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    pub distance: usize,
+    /// `link`, `embed` or `tag`.
+    pub via: String,
+    /// Scrap key, or `#tag`, the node was reached from.
+    pub from: String,
+}
+
+impl From<TraverseResult> for TraversalJson {
+    fn from(r: TraverseResult) -> Self {
+        let (kind, title, ctx, tag) = match r.node {
+            TraverseNode::Scrap(key) => (
+                "scrap",
+                Some(key.title().to_string()),
+                key.ctx().as_ref().map(|c| c.to_string()),
+                None,
+            ),
+            TraverseNode::Broken(key) => (
+                "broken",
+                Some(key.title().to_string()),
+                key.ctx().as_ref().map(|c| c.to_string()),
+                None,
+            ),
+            TraverseNode::Tag(tag) => ("tag", None, None, Some(tag.to_string())),
+        };
+        let via = match r.via.kind {
+            TraverseEdgeKind::Link => "link",
+            TraverseEdgeKind::Embed => "embed",
+            TraverseEdgeKind::Tag => "tag",
+        };
+        let from = match r.via.from {
+            TraverseNode::Scrap(key) | TraverseNode::Broken(key) => key.to_string(),
+            TraverseNode::Tag(tag) => format!("#{tag}"),
+        };
+        Self {
+            kind: kind.to_string(),
+            title,
+            ctx,
+            tag,
+            distance: r.distance,
+            via: via.to_string(),
+            from,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TraversalResponse {
+    pub results: Vec<TraversalJson>,
+    pub count: usize,
+}
@@ -6,6 +6,7 @@ use std::env;
 use std::path::{Path, PathBuf};
 
 /// Resolves project paths for scraps commands
+#[derive(Clone)]
 pub struct PathResolver {
     project_root: PathBuf,
 }
@@ -34,18 +34,28 @@ impl ProgressImpl {
     fn stage_message(stage: &Stage) -> &'static str {
         match stage {
             Stage::ReadScraps => "Reading Markdown files...",
+            Stage::FetchOgp => "Fetching link previews...",
+            Stage::ReuseHtml => "Checking for unchanged pages...",
             Stage::GenerateHtml => "Generating HTML files...",
             Stage::GenerateCss => "Generating CSS files...",
             Stage::GenerateJson => "Generating JSON files...",
+            Stage::GenerateFeed => "Generating Atom feeds...",
+            Stage::GenerateText => "Generating text files...",
+            Stage::CopyAssets => "Copying assets...",
         }
     }
 
     fn complete_message(stage: &Stage, count: &usize) -> String {
         match stage {
             Stage::ReadScraps => format!("Found {count} Scraps"),
+            Stage::FetchOgp => format!("Fetched {count} link previews"),
+            Stage::ReuseHtml => format!("Reused {count} unchanged pages"),
             Stage::GenerateHtml => format!("Generated {count} HTML files"),
             Stage::GenerateCss => format!("Generated {count} CSS files"),
             Stage::GenerateJson => format!("Generated {count} JSON files"),
+            Stage::GenerateFeed => format!("Generated {count} Atom feeds"),
+            Stage::GenerateText => format!("Generated {count} text files"),
+            Stage::CopyAssets => format!("Copied {count} assets"),
         }
     }
 }
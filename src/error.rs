@@ -20,11 +20,17 @@ pub enum ScrapsError {
     #[error("MCP error: {0}")]
     Mcp(#[from] McpError),
 
+    #[error("Query error: {0}")]
+    Query(#[from] QueryError),
+
     #[error("Failed to read scrap: {0}")]
     ReadScrap(PathBuf),
 
     #[error("Failed to read scraps")]
     ReadScraps,
+
+    #[error("Failed to write scrap: {0}")]
+    WriteScrap(PathBuf),
 }
 
 #[derive(Error, PartialEq, Debug)]
@@ -47,6 +53,12 @@ pub enum BuildError {
     #[error("Failed to write file: {0}")]
     WriteFailure(PathBuf),
 
+    #[error("Failed to remove file: {0}")]
+    RemoveFailure(PathBuf),
+
+    #[error("Failed to copy file: {0}")]
+    CopyFailure(PathBuf),
+
     #[error("Failed to render html")]
     RenderHtml,
 
@@ -55,12 +67,24 @@ pub enum BuildError {
 
     #[error("Failed to render json")]
     RenderJson,
+
+    #[error("Failed to render text")]
+    RenderText,
+
+    #[error("Failed to fetch OGP metadata: {0}")]
+    FetchOgp(url::Url),
+
+    #[error("Failed to read OGP cache: {0}")]
+    ReadOgpCache(PathBuf),
 }
 
 #[derive(Error, PartialEq, Debug)]
 pub enum ServeError {
     #[error("Failed to load file")]
     LoadFile,
+
+    #[error("Failed to watch files for changes")]
+    Watch,
 }
 
 #[derive(Error, PartialEq, Debug)]
@@ -78,6 +102,9 @@ pub enum CliError {
 
     #[error("Missing [ssg] section in .scraps.toml. This section is required for build and serve commands. See: https://boykush.github.io/scraps/scraps/configuration.reference.html")]
     MissingSsgSection,
+
+    #[error("--token-budget only applies to --target llms")]
+    TokenBudgetWithoutLlms,
 }
 
 #[derive(Error, PartialEq, Debug)]
@@ -94,3 +121,24 @@ pub enum McpError {
     #[error("Failed to bind MCP server to {0}")]
     Bind(String),
 }
+
+#[derive(Error, PartialEq, Debug)]
+pub enum QueryError {
+    #[error("Unexpected end of query")]
+    UnexpectedEnd,
+
+    #[error("Unexpected token '{0}'")]
+    UnexpectedToken(String),
+
+    #[error("Unterminated quote")]
+    UnterminatedQuote,
+
+    #[error("Unknown field '{0}'")]
+    UnknownField(String),
+
+    #[error("Invalid value '{value}' for {field}:")]
+    InvalidValue { field: String, value: String },
+
+    #[error("Only one sort: term is allowed")]
+    DuplicateSort,
+}
@@ -1 +1,5 @@
+pub mod ogp_cache;
+pub mod read_assets;
 pub mod read_scraps;
+pub(crate) mod scrap_cache;
+pub mod watch;
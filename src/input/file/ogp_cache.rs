@@ -0,0 +1,60 @@
+//! The committed `.scraps-ogp.json` at the project root, holding the Open
+//! Graph metadata of every autolink.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
+use crate::usecase::build::model::ogp::{OgpCache, OGP_CACHE_FILE_NAME};
+
+/// Read the cache, or an empty one when the file does not exist yet.
+pub fn load(project_root: &Path) -> ScrapsResult<OgpCache> {
+    let path = project_root.join(OGP_CACHE_FILE_NAME);
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).context(BuildError::ReadOgpCache(path)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(OgpCache::new()),
+        Err(e) => Err(e).context(BuildError::ReadOgpCache(path)),
+    }
+}
+
+/// Write the cache as pretty-printed JSON, so that it diffs well in review.
+pub fn save(project_root: &Path, cache: &OgpCache) -> ScrapsResult<()> {
+    let path = project_root.join(OGP_CACHE_FILE_NAME);
+    let mut json = serde_json::to_string_pretty(cache).context(BuildError::RenderJson)?;
+    json.push('\n');
+    fs::write(&path, json).context(BuildError::WriteFailure(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{simple_temp_dir, SimpleTempDir};
+    use crate::usecase::build::model::ogp::Ogp;
+    use rstest::rstest;
+    use url::Url;
+
+    #[rstest]
+    fn it_load_and_save(#[from(simple_temp_dir)] temp_dir: SimpleTempDir) {
+        let dir = temp_dir.path.clone();
+        assert_eq!(load(&dir).unwrap(), OgpCache::new());
+
+        let mut cache = OgpCache::new();
+        cache.insert(
+            Url::parse("https://example.com/").unwrap(),
+            Ogp {
+                title: Some("Example".to_string()),
+                ..Ogp::default()
+            },
+        );
+        save(&dir, &cache).unwrap();
+        assert_eq!(load(&dir).unwrap(), cache);
+
+        fs::write(dir.join(OGP_CACHE_FILE_NAME), "<<<<<<< HEAD").unwrap();
+        let err = load(&dir).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BuildError>(),
+            Some(&BuildError::ReadOgpCache(dir.join(OGP_CACHE_FILE_NAME)))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::path::{Path, PathBuf};
+
+use scraps_libs::model::file::AssetPath;
+
+use crate::error::ScrapsResult;
+use crate::usecase::build::model::asset::Asset;
+
+use super::read_scraps;
+
+/// Collect the non-markdown files under the wiki root, skipping dotfiles and
+/// `exclude_dirs` like [`read_scraps::to_scrap_paths`].
+pub(crate) fn to_all_assets(
+    scraps_dir_path: &Path,
+    exclude_dirs: &[PathBuf],
+) -> ScrapsResult<Vec<Asset>> {
+    let paths = read_scraps::to_file_paths(scraps_dir_path, exclude_dirs, &|path| {
+        !read_scraps::is_scrap_file(path)
+    })?;
+    let mut assets: Vec<Asset> = paths
+        .into_iter()
+        .filter_map(|source| {
+            let segments: Vec<String> = source
+                .strip_prefix(scraps_dir_path)
+                .ok()?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect();
+            let path = AssetPath::new(&segments)?;
+            Some(Asset { source, path })
+        })
+        .collect();
+    assets.sort_by(|a, b| a.source.cmp(&b.source));
+    Ok(assets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::TempScrapProject;
+
+    #[test]
+    fn it_to_all_assets() {
+        let project = TempScrapProject::new();
+        project
+            .add_scrap("intro.md", b"# Intro")
+            .add_asset("diagram.png", b"png")
+            .add_asset("Programming/Rust/ownership.svg", b"<svg/>")
+            .add_asset(".hidden.png", b"png")
+            .add_static_file("logo.png", b"png");
+        std::fs::write(project.output_dir.join("built.png"), b"png").unwrap();
+
+        let exclude = vec![project.static_dir.clone(), project.output_dir.clone()];
+        let assets = to_all_assets(&project.project_root, &exclude).unwrap();
+
+        let outputs: Vec<_> = assets.iter().map(|a| a.output_path()).collect();
+        assert_eq!(
+            outputs,
+            vec![
+                "scraps/programming/rust/ownership.svg".to_string(),
+                "scraps/diagram.png".to_string(),
+            ]
+        );
+        assert_eq!(
+            assets[0].source,
+            project.project_root.join("Programming/Rust/ownership.svg")
+        );
+    }
+}
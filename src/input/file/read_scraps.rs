@@ -8,6 +8,8 @@ use scraps_libs::model::{context::Ctx, scrap::Scrap};
 
 use crate::error::{ScrapsError, ScrapsResult};
 
+use super::scrap_cache::ScrapCache;
+
 /// Recursively walk `dir_path` collecting `*.md` files. Skips entries whose
 /// name starts with `.` (so `.git/`, `.scraps.toml`, etc. never enter the
 /// traversal) and any directory whose absolute path matches `exclude_dirs`
@@ -17,7 +19,21 @@ pub(crate) fn to_scrap_paths(
     scraps_dir_path: &Path,
     exclude_dirs: &[PathBuf],
 ) -> ScrapsResult<Vec<PathBuf>> {
-    let read_dir = fs::read_dir(scraps_dir_path).context(ScrapsError::ReadScraps)?;
+    to_file_paths(scraps_dir_path, exclude_dirs, &is_scrap_file)
+}
+
+pub(crate) fn is_scrap_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+}
+
+/// Walk `dir_path` like [`to_scrap_paths`], collecting the files `keep`
+/// accepts.
+pub(crate) fn to_file_paths(
+    dir_path: &Path,
+    exclude_dirs: &[PathBuf],
+    keep: &(dyn Fn(&Path) -> bool + Sync),
+) -> ScrapsResult<Vec<PathBuf>> {
+    let read_dir = fs::read_dir(dir_path).context(ScrapsError::ReadScraps)?;
 
     let paths = read_dir
         .map(|entry_res| {
@@ -29,15 +45,16 @@ pub(crate) fn to_scrap_paths(
             }
 
             match entry.file_type() {
-                Ok(file_type) if file_type.is_file() => match entry_path.extension() {
-                    Some(ext) if ext == "md" => Ok(vec![entry_path]),
-                    _ => Ok(vec![]),
-                },
+                Ok(file_type) if file_type.is_file() => Ok(if keep(&entry_path) {
+                    vec![entry_path]
+                } else {
+                    vec![]
+                }),
                 Ok(file_type) if file_type.is_dir() => {
                     if exclude_dirs.iter().any(|p| p == &entry_path) {
                         Ok(vec![])
                     } else {
-                        to_scrap_paths(&entry_path, &[])
+                        to_file_paths(&entry_path, &[], keep)
                     }
                 }
                 res => res
@@ -50,10 +67,12 @@ pub(crate) fn to_scrap_paths(
     Ok(paths.into_iter().flatten().collect::<Vec<PathBuf>>())
 }
 
-pub(crate) fn to_scrap_by_path(
+/// File stem and the (possibly nested) ctx of a scrap file under
+/// `scraps_dir_path`.
+fn scrap_location(
     scraps_dir_path: &Path,
     scrap_file_path: &Path,
-) -> ScrapsResult<Scrap> {
+) -> ScrapsResult<(String, Option<Ctx>)> {
     let file_prefix = scrap_file_path
         .file_stem()
         .ok_or(ScrapsError::ReadScrap(scrap_file_path.to_path_buf()))
@@ -78,21 +97,42 @@ pub(crate) fn to_scrap_by_path(
     } else {
         Some(Ctx::from(ctx_segments.join("/").as_str()))
     };
+    Ok((file_prefix.to_string(), ctx))
+}
+
+pub(crate) fn to_scrap_by_path(
+    scraps_dir_path: &Path,
+    scrap_file_path: &Path,
+) -> ScrapsResult<Scrap> {
+    let (file_prefix, ctx) = scrap_location(scraps_dir_path, scrap_file_path)?;
     let md_text = fs::read_to_string(scrap_file_path)
         .context(ScrapsError::ReadScrap(scrap_file_path.to_path_buf()))?;
-    let scrap = Scrap::new(file_prefix, &ctx, &md_text);
+    let scrap = Scrap::new(&file_prefix, &ctx, &md_text);
 
     Ok(scrap)
 }
 
+/// Read every scrap. With a `cache`, scraps whose file is unchanged since it
+/// was written are taken from it instead of being read and parsed.
 pub(crate) fn to_all_scraps(
     scraps_dir_path: &Path,
     exclude_dirs: &[PathBuf],
+    cache: Option<&ScrapCache>,
 ) -> ScrapsResult<Vec<Scrap>> {
-    let paths = to_scrap_paths(scraps_dir_path, exclude_dirs)?;
-    paths
+    to_scrap_paths(scraps_dir_path, exclude_dirs)?
         .iter()
-        .map(|path| to_scrap_by_path(scraps_dir_path, path))
+        .map(|path| match cache {
+            Some(cache) => {
+                let (file_prefix, ctx) = scrap_location(scraps_dir_path, path)?;
+                let metadata =
+                    fs::metadata(path).context(ScrapsError::ReadScrap(path.to_path_buf()))?;
+                match cache.scrap(&file_prefix, &ctx, &metadata) {
+                    Some(scrap) => Ok(scrap),
+                    None => to_scrap_by_path(scraps_dir_path, path),
+                }
+            }
+            None => to_scrap_by_path(scraps_dir_path, path),
+        })
         .collect()
 }
 
@@ -102,6 +142,7 @@ pub(crate) fn to_all_scraps(
 /// When `git_command` is `None`, no git subprocess is spawned and every scrap's
 /// `commited_ts` is returned as `None`. When `Some`, a `git not installed`
 /// failure is downgraded to `None` with a warning rather than an error.
+#[allow(clippy::type_complexity)]
 pub(crate) fn to_all_scraps_with_timestamps<
     GC: scraps_libs::git::GitCommand + Send + Sync + Copy,
 >(
@@ -157,6 +198,7 @@ pub(crate) fn to_all_scraps_with_timestamps<
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::input::file::scrap_cache;
     use crate::test_fixtures::TempScrapProject;
     use std::collections::HashSet;
 
@@ -188,7 +230,7 @@ mod tests {
         std::fs::write(project.project_root.join(".hidden.md"), b"# Hidden").unwrap();
 
         let exclude = vec![project.static_dir.clone(), project.output_dir.clone()];
-        let scraps = to_all_scraps(&project.project_root, &exclude).unwrap();
+        let scraps = to_all_scraps(&project.project_root, &exclude, None).unwrap();
         let titles = collect_titles(&scraps);
 
         assert_eq!(titles, HashSet::from(["intro".to_string()]));
@@ -201,7 +243,7 @@ mod tests {
         project.add_scrap_with_context("architecture", "overview.md", b"# Overview");
 
         let exclude = vec![project.static_dir.clone(), project.output_dir.clone()];
-        let scraps = to_all_scraps(&project.project_root, &exclude).unwrap();
+        let scraps = to_all_scraps(&project.project_root, &exclude, None).unwrap();
         let titles = collect_titles(&scraps);
 
         assert_eq!(
@@ -210,6 +252,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reads_unchanged_scraps_from_the_cache() {
+        let project = TempScrapProject::new();
+        project.add_scrap("note.md", b"[[a]]");
+        let path = project.project_root.join("note.md");
+        let exclude = vec![project.static_dir.clone(), project.output_dir.clone()];
+        let scraps = to_all_scraps(&project.project_root, &exclude, None).unwrap();
+        scrap_cache::save(&project.output_dir, &project.project_root, &scraps);
+
+        // Same size and mtime: the cache cannot tell the edit apart.
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        fs::write(&path, b"[[b]]").unwrap();
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        let cache = ScrapCache::load(&project.output_dir);
+        let cached = to_all_scraps(&project.project_root, &exclude, Some(&cache)).unwrap();
+        assert_eq!(cached, scraps);
+        let read = to_all_scraps(&project.project_root, &exclude, None).unwrap();
+        assert_eq!(read[0].md_text(), "[[b]]");
+    }
+
     #[test]
     fn readme_at_project_root_is_partitioned() {
         let project = TempScrapProject::new();
@@ -0,0 +1,292 @@
+//! Scraps parsed by `scraps build`, kept for the commands that only read.
+//!
+//! Read-only commands load every scrap on each invocation; reading and
+//! parsing the markdown dominates that cost on large wikis. Build writes each
+//! scrap's text with its links, tags, thumbnail, headings and BM25 body tokens
+//! to `.scraps-cache.json` in the output directory, next to the build
+//! manifest. Other commands only read it: an entry is reused while the file's
+//! mtime and size are unchanged, so a hit neither reads nor parses the file.
+//! The cache is best-effort: a missing, stale or unreadable cache falls back
+//! to parsing.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use scraps_libs::markdown::query::Heading;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::tag::Tag;
+use scraps_libs::model::title::Title;
+use scraps_libs::search::tokenizer::tokenize;
+
+use crate::usecase::search::usecase::bm25_body;
+
+pub(crate) const SCRAP_CACHE_FILE_NAME: &str = ".scraps-cache.json";
+
+/// Bumped whenever the parsed shape of a scrap changes.
+const CACHE_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "+2");
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheFile {
+    version: String,
+    /// Keyed by the scrap key, e.g. `Book/rust`.
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    mtime_ns: u128,
+    size: u64,
+    text: String,
+    links: Vec<LinkEntry>,
+    tags: Vec<String>,
+    thumbnail: Option<Url>,
+    headings: Vec<HeadingEntry>,
+    body_tokens: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct LinkEntry {
+    title: String,
+    ctx: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct HeadingEntry {
+    level: u8,
+    text: String,
+    line: usize,
+    parent: Option<String>,
+}
+
+impl CacheEntry {
+    fn new(scrap: &Scrap, metadata: &fs::Metadata) -> CacheEntry {
+        let (mtime_ns, size) = stamp(metadata);
+        CacheEntry {
+            mtime_ns,
+            size,
+            text: scrap.md_text().to_string(),
+            links: scrap
+                .links()
+                .iter()
+                .map(|k| LinkEntry {
+                    title: k.title().to_string(),
+                    ctx: k.ctx().as_ref().map(|c| c.to_string()),
+                })
+                .collect(),
+            tags: scrap.tags().iter().map(|t| t.to_string()).collect(),
+            thumbnail: scrap.thumbnail(),
+            headings: scrap
+                .headings()
+                .iter()
+                .map(|h| HeadingEntry {
+                    level: h.level,
+                    text: h.text.clone(),
+                    line: h.line,
+                    parent: h.parent.clone(),
+                })
+                .collect(),
+            body_tokens: tokenize(&bm25_body(scrap)),
+        }
+    }
+
+    fn to_scrap(&self, title: &str, ctx: &Option<Ctx>) -> Scrap {
+        Scrap::from_parts(
+            title,
+            ctx,
+            &self.text,
+            self.links
+                .iter()
+                .map(|l| {
+                    ScrapKey::new(
+                        &Title::from(l.title.as_str()),
+                        &l.ctx.as_deref().map(Ctx::from),
+                    )
+                })
+                .collect(),
+            self.tags.iter().map(|t| Tag::from(t.as_str())).collect(),
+            self.thumbnail.clone(),
+            self.headings
+                .iter()
+                .map(|h| Heading {
+                    level: h.level,
+                    text: h.text.clone(),
+                    line: h.line,
+                    parent: h.parent.clone(),
+                })
+                .collect(),
+        )
+    }
+}
+
+fn stamp(metadata: &fs::Metadata) -> (u128, u64) {
+    let mtime_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    (mtime_ns, metadata.len())
+}
+
+pub(crate) struct ScrapCache {
+    file: CacheFile,
+}
+
+impl ScrapCache {
+    /// Load the cache written to `output_dir_path`, starting empty when it is
+    /// missing, unreadable or written by another version.
+    pub(crate) fn load(output_dir_path: &Path) -> Self {
+        let file = fs::read(output_dir_path.join(SCRAP_CACHE_FILE_NAME))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<CacheFile>(&bytes).ok())
+            .filter(|file| file.version == CACHE_VERSION)
+            .unwrap_or_default();
+        ScrapCache { file }
+    }
+
+    /// The scrap `title` in `ctx`, when its file, described by `metadata`, is
+    /// unchanged since the cache was written.
+    pub(crate) fn scrap(
+        &self,
+        title: &str,
+        ctx: &Option<Ctx>,
+        metadata: &fs::Metadata,
+    ) -> Option<Scrap> {
+        let key = ScrapKey::new(&Title::from(title), ctx);
+        self.file
+            .entries
+            .get(&key.to_string())
+            .filter(|entry| (entry.mtime_ns, entry.size) == stamp(metadata))
+            .map(|entry| entry.to_scrap(title, ctx))
+    }
+
+    /// Cached tokens of [`bm25_body`] for those `scraps` whose text is still
+    /// the cached one.
+    pub(crate) fn body_tokens(&self, scraps: &[Scrap]) -> HashMap<ScrapKey, Vec<String>> {
+        scraps
+            .iter()
+            .filter_map(|scrap| {
+                let key = scrap.self_key();
+                let entry = self.file.entries.get(&key.to_string())?;
+                (entry.text == scrap.md_text()).then(|| (key, entry.body_tokens.clone()))
+            })
+            .collect()
+    }
+}
+
+/// Write the cache of `scraps`, read from `scraps_dir_path`, to
+/// `output_dir_path`. Failures are logged and otherwise ignored.
+pub(crate) fn save<'a>(
+    output_dir_path: &Path,
+    scraps_dir_path: &Path,
+    scraps: impl IntoIterator<Item = &'a Scrap>,
+) {
+    let entries = scraps
+        .into_iter()
+        .filter_map(|scrap| {
+            let metadata = fs::metadata(scrap_path(scraps_dir_path, scrap)).ok()?;
+            Some((
+                scrap.self_key().to_string(),
+                CacheEntry::new(scrap, &metadata),
+            ))
+        })
+        .collect();
+    let file = CacheFile {
+        version: CACHE_VERSION.to_string(),
+        entries,
+    };
+    if let Err(e) = write(output_dir_path, &file) {
+        tracing::warn!(
+            "failed to write scrap cache to {}: {e}",
+            output_dir_path.display()
+        );
+    }
+}
+
+/// Where `scrap` was read from: its ctx directories and title file stem.
+fn scrap_path(scraps_dir_path: &Path, scrap: &Scrap) -> std::path::PathBuf {
+    let mut path = scraps_dir_path.to_path_buf();
+    if let Some(ctx) = scrap.ctx() {
+        path.extend(ctx.segments());
+    }
+    path.join(format!("{}.md", scrap.title()))
+}
+
+fn write(output_dir_path: &Path, file: &CacheFile) -> std::io::Result<()> {
+    fs::create_dir_all(output_dir_path)?;
+    let path = output_dir_path.join(SCRAP_CACHE_FILE_NAME);
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_vec(file)?)?;
+    fs::rename(&tmp, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::TempScrapProject;
+
+    fn metadata(project: &TempScrapProject, file: &str) -> fs::Metadata {
+        fs::metadata(project.project_root.join(file)).unwrap()
+    }
+
+    #[test]
+    fn it_reuses_entries_until_the_file_changes() {
+        let project = TempScrapProject::new();
+        project.add_scrap("note.md", b"# Intro\n\n[[a]] #[[t]]");
+        let parsed = Scrap::new("note", &None, "# Intro\n\n[[a]] #[[t]]");
+
+        save(&project.output_dir, &project.project_root, [&parsed]);
+        assert!(project.output_dir.join(SCRAP_CACHE_FILE_NAME).exists());
+
+        let cache = ScrapCache::load(&project.output_dir);
+        let cached = cache.scrap("note", &None, &metadata(&project, "note.md"));
+        assert_eq!(cached, Some(parsed.clone()));
+        assert_eq!(
+            cache.body_tokens(&[parsed]),
+            HashMap::from([(
+                ScrapKey::from(Title::from("note")),
+                vec!["a".to_string(), "t".to_string()]
+            )])
+        );
+
+        fs::write(project.project_root.join("note.md"), b"[[b]] and more").unwrap();
+        assert_eq!(
+            cache.scrap("note", &None, &metadata(&project, "note.md")),
+            None
+        );
+        let changed = Scrap::new("note", &None, "[[b]] and more");
+        assert!(cache.body_tokens(&[changed]).is_empty());
+    }
+
+    #[test]
+    fn it_keys_entries_by_ctx_and_ignores_other_versions() {
+        let project = TempScrapProject::new();
+        project.add_scrap_with_context("Book", "note.md", b"[[a]]");
+        let scrap = Scrap::new("note", &Some("Book".into()), "[[a]]");
+
+        save(&project.output_dir, &project.project_root, [&scrap]);
+        let cache = ScrapCache::load(&project.output_dir);
+        let metadata = metadata(&project, "Book/note.md");
+        assert!(cache.scrap("note", &None, &metadata).is_none());
+        assert!(cache
+            .scrap("note", &Some("Book".into()), &metadata)
+            .is_some());
+
+        fs::write(
+            project.output_dir.join(SCRAP_CACHE_FILE_NAME),
+            br#"{"version":"0","entries":{"x":1}}"#,
+        )
+        .unwrap();
+        assert!(ScrapCache::load(&project.output_dir)
+            .file
+            .entries
+            .is_empty());
+    }
+}
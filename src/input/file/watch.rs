@@ -0,0 +1,148 @@
+//! Debounced file watching of a wiki for `scraps serve`.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::error::{anyhow::Context, ScrapsResult, ServeError};
+
+/// Quiet period before a burst of events, e.g. an editor's save, is reported.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Stops watching when dropped.
+pub struct WikiWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Watch `root` recursively and call `on_change` with the changed paths of
+/// each debounced burst, joined onto `root` as given. Paths under
+/// `exclude_dirs` and hidden entries are dropped, except the files listed in
+/// `hidden_files`, so the build output and caches written by a rebuild do not
+/// trigger another one. Reads are not changes, so a rebuild reading the wiki
+/// does not either.
+pub fn watch<F>(
+    root: &Path,
+    exclude_dirs: &[PathBuf],
+    hidden_files: &[PathBuf],
+    mut on_change: F,
+) -> ScrapsResult<WikiWatcher>
+where
+    F: FnMut(Vec<PathBuf>) + Send + 'static,
+{
+    // Events carry canonical paths on some platforms (e.g. /private/var on
+    // macOS), so compare against canonical roots.
+    let canonical = |p: &Path| p.canonicalize().unwrap_or_else(|_| p.to_path_buf());
+    let given_root = root.to_path_buf();
+    let root = canonical(root);
+    let exclude_dirs: Vec<PathBuf> = exclude_dirs.iter().map(|p| canonical(p)).collect();
+    let hidden_files: Vec<PathBuf> = hidden_files.iter().map(|p| canonical(p)).collect();
+
+    let (sender, receiver) = channel::<Event>();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+        match result {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                // Fails only once the debounce thread is gone.
+                let _ = sender.send(event);
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("file watcher error: {e}"),
+        }
+    })
+    .context(ServeError::Watch)?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .context(ServeError::Watch)?;
+
+    thread::spawn(move || {
+        while let Some(events) = next_burst(&receiver) {
+            let mut paths: Vec<PathBuf> = events
+                .into_iter()
+                .flat_map(|e| e.paths)
+                .filter(|p| is_watched(&root, &exclude_dirs, &hidden_files, p))
+                .filter_map(|p| Some(given_root.join(p.strip_prefix(&root).ok()?)))
+                .collect();
+            paths.sort();
+            paths.dedup();
+            if !paths.is_empty() {
+                on_change(paths);
+            }
+        }
+    });
+
+    Ok(WikiWatcher { _watcher: watcher })
+}
+
+/// Block for the next event, then gather the ones following it until
+/// `DEBOUNCE` passes without any. `None` once the watcher is dropped.
+fn next_burst(receiver: &Receiver<Event>) -> Option<Vec<Event>> {
+    let mut events = vec![receiver.recv().ok()?];
+    loop {
+        match receiver.recv_timeout(DEBOUNCE) {
+            Ok(event) => events.push(event),
+            Err(RecvTimeoutError::Timeout) => return Some(events),
+            Err(RecvTimeoutError::Disconnected) => return None,
+        }
+    }
+}
+
+fn is_watched(
+    root: &Path,
+    exclude_dirs: &[PathBuf],
+    hidden_files: &[PathBuf],
+    path: &Path,
+) -> bool {
+    if hidden_files.iter().any(|f| f == path) {
+        return true;
+    }
+    if exclude_dirs.iter().any(|d| path.starts_with(d)) {
+        return false;
+    }
+    match path.strip_prefix(root) {
+        Ok(relative) => !relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.')),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::scrap("/wiki/note.md", true)]
+    #[case::nested_static("/wiki/static/index.html", true)]
+    #[case::output("/wiki/_site/index.html", false)]
+    #[case::hidden_dir("/wiki/.git/index", false)]
+    #[case::hidden_file("/wiki/ctx/.note.md.swp", false)]
+    #[case::config("/wiki/.scraps.toml", true)]
+    #[case::outside("/elsewhere/note.md", false)]
+    fn it_filters_watched_paths(#[case] path: &str, #[case] expected: bool) {
+        let root = Path::new("/wiki");
+        let exclude = vec![PathBuf::from("/wiki/_site")];
+        let hidden = vec![PathBuf::from("/wiki/.scraps.toml")];
+
+        assert_eq!(
+            is_watched(root, &exclude, &hidden, Path::new(path)),
+            expected
+        );
+    }
+
+    #[test]
+    fn it_debounces_a_burst_of_events() {
+        let (sender, receiver) = channel();
+        for path in ["a.md", "b.md"] {
+            sender
+                .send(Event::new(EventKind::Any).add_path(PathBuf::from(path)))
+                .unwrap();
+        }
+        assert_eq!(next_burst(&receiver).unwrap().len(), 2);
+
+        drop(sender);
+        assert!(next_burst(&receiver).is_none());
+    }
+}
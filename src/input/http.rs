@@ -0,0 +1 @@
+pub mod ogp_fetcher;
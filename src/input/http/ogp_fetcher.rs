@@ -0,0 +1,117 @@
+use std::io::Read;
+use std::time::Duration;
+
+use ureq::Agent;
+use url::Url;
+
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
+use crate::usecase::build::ogp_fetcher::OgpFetcher;
+
+/// Open Graph tags sit in `<head>`, so larger pages are cut off here.
+const BODY_LIMIT: u64 = 1024 * 1024;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct OgpFetcherImpl {
+    agent: Agent,
+}
+
+impl Default for OgpFetcherImpl {
+    fn default() -> Self {
+        OgpFetcherImpl::new()
+    }
+}
+
+impl OgpFetcherImpl {
+    pub fn new() -> OgpFetcherImpl {
+        let agent = Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .user_agent(concat!("scraps/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .into();
+        OgpFetcherImpl { agent }
+    }
+}
+
+impl OgpFetcher for OgpFetcherImpl {
+    fn fetch_html(&self, url: &Url) -> ScrapsResult<String> {
+        let mut response = self
+            .agent
+            .get(url.as_str())
+            .header("Accept", "text/html,application/xhtml+xml")
+            .call()
+            .context(BuildError::FetchOgp(url.clone()))?;
+        let mut body = Vec::new();
+        response
+            .body_mut()
+            .as_reader()
+            .take(BODY_LIMIT)
+            .read_to_end(&mut body)
+            .context(BuildError::FetchOgp(url.clone()))?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    use super::*;
+
+    /// Serves `pages` by request path on a local port, answering 404 to
+    /// anything else, for as long as the test process runs. Returns the
+    /// server's base URL.
+    pub fn stub_server(pages: &[(&str, &str)]) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        let pages: Vec<(String, String)> = pages
+            .iter()
+            .map(|(path, html)| (path.to_string(), html.to_string()))
+            .collect();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&stream);
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                // Drain the headers before answering.
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    line.clear();
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let (status, body) = pages
+                    .iter()
+                    .find(|(page, _)| page == path)
+                    .map_or(("404 Not Found", ""), |(_, html)| ("200 OK", html.as_str()));
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+            }
+        });
+        base_url
+    }
+
+    #[test]
+    fn it_fetch_html() {
+        let html = r#"<head><meta property="og:title" content="Stub"></head>"#;
+        let base_url = stub_server(&[("/page", html)]);
+        let fetcher = OgpFetcherImpl::new();
+
+        let fetched = fetcher.fetch_html(&base_url.join("page").unwrap()).unwrap();
+        assert_eq!(fetched, html);
+
+        let missing = base_url.join("missing").unwrap();
+        let err = fetcher.fetch_html(&missing).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<BuildError>(),
+            Some(&BuildError::FetchOgp(missing))
+        );
+    }
+}
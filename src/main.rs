@@ -12,14 +12,45 @@ mod test_fixtures;
 
 use clap::Parser;
 use error::McpError;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::tag::Tag;
+use usecase::scrap::find_path::usecase::PathOptions;
+use usecase::scrap::traverse::usecase::{TraverseDirection, TraverseOptions};
+use usecase::search::usecase::SearchFilter;
 
 fn main() -> error::ScrapsResult<()> {
-    let cli::Cli { directory, command } = cli::Cli::parse();
+    let cli::Cli {
+        directory,
+        no_cache,
+        command,
+    } = cli::Cli::parse();
     let directory = directory.as_deref();
 
     match command {
         cli::SubCommands::Init => cli::cmd::init::run(directory),
-        cli::SubCommands::Build { verbose, git } => cli::cmd::build::run(verbose, git, directory),
+        cli::SubCommands::Build {
+            verbose,
+            git,
+            offline,
+            target,
+            token_budget,
+        } => cli::cmd::build::run(
+            verbose,
+            git,
+            offline,
+            target.into(),
+            token_budget,
+            no_cache,
+            directory,
+        ),
+        cli::SubCommands::Export { export_command } => match export_command {
+            cli::ExportSubCommands::Pandoc { title, ctx } => cli::cmd::export::pandoc::run(
+                title.as_deref(),
+                ctx.as_deref(),
+                directory,
+                &mut std::io::stdout(),
+            ),
+        },
         cli::SubCommands::Get {
             title,
             ctx,
@@ -30,24 +61,111 @@ fn main() -> error::ScrapsResult<()> {
             ctx.as_deref(),
             heading.as_deref(),
             json.as_deref(),
+            no_cache,
             directory,
             &mut std::io::stdout(),
         ),
         cli::SubCommands::Lint { rules } => {
             let rule_names: Vec<_> = rules.into_iter().map(Into::into).collect();
-            cli::cmd::lint::run(directory, &rule_names)
+            cli::cmd::lint::run(no_cache, directory, &rule_names)
         }
-        cli::SubCommands::Links { title, ctx, json } => cli::cmd::links::run(
+        cli::SubCommands::Links {
+            title,
+            ctx,
+            depth,
+            include_broken,
+            include_tags,
+            json,
+        } => cli::cmd::links::run(
+            &title,
+            ctx.as_deref(),
+            depth.map(|depth| TraverseOptions {
+                direction: TraverseDirection::Outbound,
+                depth,
+                include_broken,
+                include_tags,
+            }),
+            json,
+            no_cache,
+            directory,
+            &mut std::io::stdout(),
+        ),
+        cli::SubCommands::Backlinks {
+            title,
+            ctx,
+            context,
+            depth,
+            include_tags,
+            json,
+        } => cli::cmd::backlinks::run(
+            &title,
+            ctx.as_deref(),
+            depth.map(|depth| TraverseOptions {
+                direction: TraverseDirection::Inbound,
+                depth,
+                include_broken: false,
+                include_tags,
+            }),
+            context,
+            json,
+            no_cache,
+            directory,
+            &mut std::io::stdout(),
+        ),
+        cli::SubCommands::Related {
+            title,
+            ctx,
+            num,
+            json,
+        } => cli::cmd::related::run(
             &title,
             ctx.as_deref(),
+            num,
             json,
+            no_cache,
             directory,
             &mut std::io::stdout(),
         ),
-        cli::SubCommands::Backlinks { title, ctx, json } => cli::cmd::backlinks::run(
+        cli::SubCommands::Path {
+            from,
+            to,
+            from_ctx,
+            to_ctx,
+            undirected,
+            via_tags,
+            max_length,
+            json,
+        } => cli::cmd::path::run(
+            (&from, from_ctx.as_deref()),
+            (&to, to_ctx.as_deref()),
+            &PathOptions {
+                undirected,
+                via_tags,
+                max_length,
+            },
+            json,
+            no_cache,
+            directory,
+            &mut std::io::stdout(),
+        ),
+        cli::SubCommands::Mentions {
+            title,
+            ctx,
+            link,
+            json,
+        } => cli::cmd::mentions::run(
             &title,
             ctx.as_deref(),
+            link,
+            json,
+            no_cache,
+            directory,
+            &mut std::io::stdout(),
+        ),
+        cli::SubCommands::Query { expression, json } => cli::cmd::query::run(
+            &expression,
             json,
+            no_cache,
             directory,
             &mut std::io::stdout(),
         ),
@@ -55,26 +173,57 @@ fn main() -> error::ScrapsResult<()> {
             query,
             num,
             logic,
+            engine,
+            ctx,
+            exclude_ctx,
+            tag,
+            scope,
             json,
         } => cli::cmd::search::run(
             &query,
             num,
             logic.into(),
+            engine.map(Into::into),
+            SearchFilter {
+                ctx: ctx.as_deref().map(Ctx::from),
+                exclude_ctx: exclude_ctx.iter().map(|c| Ctx::from(c.as_str())).collect(),
+                tag: tag.map(Tag::from),
+                scope: scope.map(Into::into),
+                keys: None,
+            },
             json,
+            no_cache,
             directory,
             &mut std::io::stdout(),
         ),
-        cli::SubCommands::Serve { git } => cli::cmd::serve::run(git, directory),
+        cli::SubCommands::Serve {
+            git,
+            addr,
+            port,
+            open,
+            base_path,
+        } => cli::cmd::serve::run(git, addr, port, open, base_path, directory),
         cli::SubCommands::Tag { tag_command } => match tag_command {
             cli::TagSubCommands::List { json } => {
-                cli::cmd::tag::list::run(json, directory, &mut std::io::stdout())
-            }
-            cli::TagSubCommands::Backlinks { tag, json } => {
-                cli::cmd::tag::backlinks::run(&tag, json, directory, &mut std::io::stdout())
+                cli::cmd::tag::list::run(json, no_cache, directory, &mut std::io::stdout())
             }
+            cli::TagSubCommands::Backlinks { tag, json } => cli::cmd::tag::backlinks::run(
+                &tag,
+                json,
+                no_cache,
+                directory,
+                &mut std::io::stdout(),
+            ),
         },
-        cli::SubCommands::Todo { status, json } => {
-            cli::cmd::todo::run(status.into(), json, directory, &mut std::io::stdout())
+        cli::SubCommands::Todo { status, json } => cli::cmd::todo::run(
+            status.into(),
+            json,
+            no_cache,
+            directory,
+            &mut std::io::stdout(),
+        ),
+        cli::SubCommands::Stats { json } => {
+            cli::cmd::stats::run(json, no_cache, directory, &mut std::io::stdout())
         }
         cli::SubCommands::Mcp { mcp_command } => match mcp_command {
             cli::McpSubCommands::Serve { http, allowed_host } => {
@@ -82,6 +231,7 @@ fn main() -> error::ScrapsResult<()> {
                     .map_err(|e| McpError::RuntimeCreation(e.to_string()))?;
                 runtime.block_on(cli::cmd::mcp::serve::run(
                     directory,
+                    no_cache,
                     http.as_deref(),
                     allowed_host,
                 ))
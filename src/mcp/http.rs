@@ -25,6 +25,7 @@ type McpService = StreamableHttpService<ScrapsServer, NeverSessionManager>;
 pub fn build_service(
     scraps_dir: PathBuf,
     exclude_dirs: Vec<PathBuf>,
+    cache_dir: Option<PathBuf>,
     allowed_hosts: Vec<String>,
 ) -> McpService {
     let config = StreamableHttpServerConfig::default()
@@ -43,7 +44,13 @@ pub fn build_service(
     let config = config.with_allowed_hosts(allowed_hosts);
 
     StreamableHttpService::new(
-        move || Ok(ScrapsServer::new(scraps_dir.clone(), exclude_dirs.clone())),
+        move || {
+            Ok(ScrapsServer::new(
+                scraps_dir.clone(),
+                exclude_dirs.clone(),
+                cache_dir.clone(),
+            ))
+        },
         Arc::new(NeverSessionManager::default()),
         config,
     )
@@ -113,6 +120,7 @@ mod tests {
         let service = build_service(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
             allowed_hosts,
         );
 
@@ -166,7 +174,7 @@ mod tests {
         assert_eq!(status, StatusCode::OK);
         let response: serde_json::Value = serde_json::from_str(&body).unwrap();
         let tools = response["result"]["tools"].as_array().unwrap();
-        assert_eq!(tools.len(), 6);
+        assert_eq!(tools.len(), 7);
 
         server_handle.abort();
     }
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 
+use super::tools::find_scrap_path::{find_scrap_path, FindScrapPathRequest};
 use super::tools::get_scrap::{get_scrap, GetScrapRequest};
 use super::tools::list_tags::list_tags;
 use super::tools::lookup_scrap_backlinks::{lookup_scrap_backlinks, LookupScrapBacklinksRequest};
@@ -17,14 +18,21 @@ pub struct ScrapsServer {
     tool_router: ToolRouter<ScrapsServer>,
     scraps_dir: PathBuf,
     exclude_dirs: Vec<PathBuf>,
+    /// Output directory holding the scrap cache, unless `--no-cache`.
+    cache_dir: Option<PathBuf>,
 }
 
 impl ScrapsServer {
-    pub fn new(scraps_dir: PathBuf, exclude_dirs: Vec<PathBuf>) -> Self {
+    pub fn new(
+        scraps_dir: PathBuf,
+        exclude_dirs: Vec<PathBuf>,
+        cache_dir: Option<PathBuf>,
+    ) -> Self {
         Self {
             tool_router: Self::tool_router(),
             scraps_dir,
             exclude_dirs,
+            cache_dir,
         }
     }
 }
@@ -39,40 +47,68 @@ impl ScrapsServer {
         context: RequestContext<RoleServer>,
         parameters: Parameters<GetScrapRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        get_scrap(&self.scraps_dir, &self.exclude_dirs, context, parameters).await
+        get_scrap(
+            &self.scraps_dir,
+            &self.exclude_dirs,
+            self.cache_dir.as_deref(),
+            context,
+            parameters,
+        )
+        .await
     }
 
     #[tool(
-        description = "Search for scraps using fuzzy matching against title and body content. Space-separated keywords use OR logic by default (any keyword matches). Set logic to 'and' for all keywords to match. Returns matching scraps with titles and contexts. Use get_scrap to retrieve full content."
+        description = "Search for scraps using fuzzy matching against title and body content, or BM25 ranking with \"exact phrase\" support when engine is 'bm25'. Space-separated keywords use OR logic by default (any keyword matches). Set logic to 'and' for all keywords to match. Restrict candidates with ctx, exclude_ctx, tag (includes descendant tags), and 'in' (title, headings, body, or code). Set filter to a structured query such as 'tag:rust/async AND links-to:tokio AND NOT has:backlinks sort:linked_count' to narrow the results; query may then be empty. Returns matching scraps with titles, contexts, scores, and snippets around the matches (each with line, heading, and byte-offset match ranges). Use get_scrap to retrieve full content."
     )]
     async fn search_scraps(
         &self,
         context: RequestContext<RoleServer>,
         parameters: Parameters<SearchRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        search_scraps(&self.scraps_dir, &self.exclude_dirs, context, parameters).await
+        search_scraps(
+            &self.scraps_dir,
+            &self.exclude_dirs,
+            self.cache_dir.as_deref(),
+            context,
+            parameters,
+        )
+        .await
     }
 
     #[tool(
-        description = "Lookup outbound wiki links from a specific scrap. Returns all scraps that the specified scrap links to. Use get_scrap to retrieve full content."
+        description = "Lookup outbound wiki links from a specific scrap. Returns all scraps that the specified scrap links to; set depth to gather the transitive neighbourhood with distances, optionally including broken targets and tag hops. Use get_scrap to retrieve full content."
     )]
     async fn lookup_scrap_links(
         &self,
         context: RequestContext<RoleServer>,
         parameters: Parameters<LookupScrapLinksRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        lookup_scrap_links(&self.scraps_dir, &self.exclude_dirs, context, parameters).await
+        lookup_scrap_links(
+            &self.scraps_dir,
+            &self.exclude_dirs,
+            self.cache_dir.as_deref(),
+            context,
+            parameters,
+        )
+        .await
     }
 
     #[tool(
-        description = "Lookup inbound wiki links (backlinks) to a specific scrap. Returns all scraps that link to the specified scrap. Use get_scrap to retrieve full content."
+        description = "Lookup inbound wiki links (backlinks) to a specific scrap. Returns all scraps that link to the specified scrap; set context to also get each link's line, heading path and enclosing paragraph, or depth to follow backlinks transitively. Use get_scrap to retrieve full content."
     )]
     async fn lookup_scrap_backlinks(
         &self,
         context: RequestContext<RoleServer>,
         parameters: Parameters<LookupScrapBacklinksRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        lookup_scrap_backlinks(&self.scraps_dir, &self.exclude_dirs, context, parameters).await
+        lookup_scrap_backlinks(
+            &self.scraps_dir,
+            &self.exclude_dirs,
+            self.cache_dir.as_deref(),
+            context,
+            parameters,
+        )
+        .await
     }
 
     #[tool(
@@ -82,7 +118,13 @@ impl ScrapsServer {
         &self,
         context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, ErrorData> {
-        list_tags(&self.scraps_dir, &self.exclude_dirs, context).await
+        list_tags(
+            &self.scraps_dir,
+            &self.exclude_dirs,
+            self.cache_dir.as_deref(),
+            context,
+        )
+        .await
     }
 
     #[tool(
@@ -93,11 +135,36 @@ impl ScrapsServer {
         context: RequestContext<RoleServer>,
         parameters: Parameters<LookupTagBacklinksRequest>,
     ) -> Result<CallToolResult, ErrorData> {
-        lookup_tag_backlinks(&self.scraps_dir, &self.exclude_dirs, context, parameters).await
+        lookup_tag_backlinks(
+            &self.scraps_dir,
+            &self.exclude_dirs,
+            self.cache_dir.as_deref(),
+            context,
+            parameters,
+        )
+        .await
+    }
+
+    #[tool(
+        description = "Find the shortest wiki-link path(s) between two scraps, to explain how two notes connect. Set 'undirected' to also follow links backwards and 'via_tags' to allow hops through shared tags. Each hop reports how it was reached: link, backlink, or tag."
+    )]
+    async fn find_scrap_path(
+        &self,
+        context: RequestContext<RoleServer>,
+        parameters: Parameters<FindScrapPathRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        find_scrap_path(
+            &self.scraps_dir,
+            &self.exclude_dirs,
+            self.cache_dir.as_deref(),
+            context,
+            parameters,
+        )
+        .await
     }
 }
 
-#[tool_handler]
+#[tool_handler(router = self.tool_router)]
 impl ServerHandler for ScrapsServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo::new(ServerCapabilities::builder().enable_tools().build())
@@ -118,6 +185,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
         let info = server.get_info();
 
@@ -134,6 +202,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -144,7 +213,7 @@ mod tests {
 
         let tools = client.list_tools(Default::default()).await.unwrap();
 
-        assert_eq!(tools.tools.len(), 6);
+        assert_eq!(tools.tools.len(), 7);
 
         let tool_names: Vec<&str> = tools.tools.iter().map(|t| t.name.as_ref()).collect();
         assert!(tool_names.contains(&"get_scrap"));
@@ -153,6 +222,7 @@ mod tests {
         assert!(tool_names.contains(&"lookup_scrap_backlinks"));
         assert!(tool_names.contains(&"list_tags"));
         assert!(tool_names.contains(&"lookup_tag_backlinks"));
+        assert!(tool_names.contains(&"find_scrap_path"));
 
         client.cancel().await.unwrap();
         server_handle.abort();
@@ -166,6 +236,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -210,6 +281,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -249,6 +321,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -285,6 +358,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -315,6 +389,54 @@ mod tests {
         server_handle.abort();
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_call_lookup_scrap_links_with_depth(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project.add_scrap("source.md", b"# Source\n\n[[middle]]");
+        project.add_scrap("middle.md", b"# Middle\n\n[[target]] [[missing]]");
+        project.add_scrap("target.md", b"# Target");
+
+        let server = ScrapsServer::new(
+            project.scraps_dir.clone(),
+            vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
+        );
+
+        let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+        let server_handle = tokio::spawn(async move { server.serve(server_stream).await });
+
+        let client = ().serve(client_stream).await.unwrap();
+
+        let result = client
+            .call_tool(
+                CallToolRequestParams::new("lookup_scrap_links").with_arguments(
+                    serde_json::json!({"title": "source", "depth": 2, "include_broken": true})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error.unwrap_or(false));
+
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        assert_eq!(response["count"], 3);
+        assert_eq!(response["results"][1]["title"], "target");
+        assert_eq!(response["results"][1]["distance"], 2);
+        assert_eq!(response["results"][1]["from"], "middle");
+        assert_eq!(response["results"][2]["title"], "missing");
+        assert_eq!(response["results"][2]["kind"], "broken");
+
+        client.cancel().await.unwrap();
+        server_handle.abort();
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_call_lookup_scrap_backlinks(
@@ -326,6 +448,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -356,6 +479,51 @@ mod tests {
         server_handle.abort();
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_call_lookup_scrap_backlinks_with_context(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project.add_scrap("source.md", b"# Source\n\n- see [[target]]");
+        project.add_scrap("target.md", b"# Target\n\nTarget content");
+
+        let server = ScrapsServer::new(
+            project.scraps_dir.clone(),
+            vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
+        );
+
+        let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+        let server_handle = tokio::spawn(async move { server.serve(server_stream).await });
+
+        let client = ().serve(client_stream).await.unwrap();
+
+        let result = client
+            .call_tool(
+                CallToolRequestParams::new("lookup_scrap_backlinks").with_arguments(
+                    serde_json::json!({"title": "target", "context": true})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error.unwrap_or(false));
+
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        let occurrence = &response["results"][0]["occurrences"][0];
+        assert_eq!(occurrence["line"], 3);
+        assert_eq!(occurrence["context"], "- see [[target]]");
+        assert_eq!(occurrence["heading_path"], serde_json::json!(["Source"]));
+
+        client.cancel().await.unwrap();
+        server_handle.abort();
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_call_lookup_tag_backlinks(#[from(temp_scrap_project)] project: TempScrapProject) {
@@ -364,6 +532,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -398,6 +567,82 @@ mod tests {
         server_handle.abort();
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_call_search_scraps_with_filter(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project.add_scrap("tokio.md", b"# Tokio\n\n#[[rust/async]] runtime");
+        project.add_scrap("mio.md", b"# Mio\n\n#[[rust/async]] [[tokio]]");
+        project.add_scrap("flask.md", b"# Flask\n\n#[[python]] runtime server");
+        project.add_scrap("server.md", b"# Server\n\nserver");
+
+        let server = ScrapsServer::new(
+            project.scraps_dir.clone(),
+            vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
+        );
+
+        let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+        let server_handle = tokio::spawn(async move { server.serve(server_stream).await });
+
+        let client = ().serve(client_stream).await.unwrap();
+
+        let call = |arguments: serde_json::Value| {
+            CallToolRequestParams::new("search_scraps")
+                .with_arguments(arguments.as_object().unwrap().clone())
+        };
+
+        let result = client
+            .call_tool(call(serde_json::json!({
+                "query": "",
+                "filter": "tag:rust sort:linked_count"
+            })))
+            .await
+            .unwrap();
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        assert_eq!(response["count"], 2);
+        assert_eq!(response["results"][0]["title"], "tokio");
+
+        let result = client
+            .call_tool(call(serde_json::json!({
+                "query": "runtime",
+                "filter": "NOT tag:python"
+            })))
+            .await
+            .unwrap();
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        assert_eq!(response["count"], 1);
+        assert_eq!(response["results"][0]["title"], "tokio");
+
+        // The only filter match ranks below `num` other hits
+        let result = client
+            .call_tool(call(serde_json::json!({
+                "query": "server",
+                "num": 1,
+                "filter": "tag:python"
+            })))
+            .await
+            .unwrap();
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        assert_eq!(response["count"], 1);
+        assert_eq!(response["results"][0]["title"], "flask");
+
+        let result = client
+            .call_tool(call(
+                serde_json::json!({"query": "", "filter": "colour:red"}),
+            ))
+            .await;
+        assert!(result.is_err());
+
+        client.cancel().await.unwrap();
+        server_handle.abort();
+    }
+
     /// Test: search_scraps with AND logic (default) - all keywords must match
     #[rstest]
     #[tokio::test]
@@ -412,6 +657,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -466,6 +712,7 @@ mod tests {
         let server = ScrapsServer::new(
             project.scraps_dir.clone(),
             vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
         );
 
         let (client_stream, server_stream) = tokio::io::duplex(4096);
@@ -501,4 +748,138 @@ mod tests {
         client.cancel().await.unwrap();
         server_handle.abort();
     }
+
+    /// Test: search_scraps with the BM25 engine matches exact phrases
+    #[rstest]
+    #[tokio::test]
+    async fn test_call_search_scraps_bm25_engine(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project.add_scrap("a.md", b"The borrow checker rejects this.");
+        project.add_scrap("b.md", b"A checker of the borrow.");
+
+        let server = ScrapsServer::new(
+            project.scraps_dir.clone(),
+            vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
+        );
+
+        let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+        let server_handle = tokio::spawn(async move { server.serve(server_stream).await });
+
+        let client = ().serve(client_stream).await.unwrap();
+
+        let result = client
+            .call_tool(
+                CallToolRequestParams::new("search_scraps").with_arguments(
+                    serde_json::json!({"query": "\"borrow checker\"", "engine": "bm25"})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error.unwrap_or(false));
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        assert_eq!(response["count"], 1);
+        assert_eq!(response["results"][0]["title"], "a");
+        let snippet = &response["results"][0]["snippets"][0];
+        assert_eq!(snippet["text"], "The borrow checker rejects this.");
+        assert_eq!(snippet["matches"][0]["start"], 4);
+        assert_eq!(snippet["matches"][0]["end"], 18);
+
+        client.cancel().await.unwrap();
+        server_handle.abort();
+    }
+
+    /// Test: search_scraps restricts candidates by ctx and field
+    #[rstest]
+    #[tokio::test]
+    async fn test_call_search_scraps_with_scope_filters(
+        #[from(temp_scrap_project)] project: TempScrapProject,
+    ) {
+        project.add_scrap_with_context("journal", "2024-01-01.md", b"Tried tokio today.");
+        project.add_scrap("tokio.md", b"# Tokio\n\nAn async runtime.");
+        project.add_scrap("rust.md", b"Mentions tokio in the body.");
+
+        let server = ScrapsServer::new(
+            project.scraps_dir.clone(),
+            vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
+        );
+
+        let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+        let server_handle = tokio::spawn(async move { server.serve(server_stream).await });
+
+        let client = ().serve(client_stream).await.unwrap();
+
+        let result = client
+            .call_tool(
+                CallToolRequestParams::new("search_scraps").with_arguments(
+                    serde_json::json!({"query": "tokio", "exclude_ctx": ["journal"], "in": "title"})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error.unwrap_or(false));
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        assert_eq!(response["count"], 1);
+        assert_eq!(response["results"][0]["title"], "tokio");
+
+        client.cancel().await.unwrap();
+        server_handle.abort();
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_call_find_scrap_path(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project.add_scrap("source.md", b"# Source\n\n[[middle]]");
+        project.add_scrap("middle.md", b"# Middle\n\n[[target]]");
+        project.add_scrap("target.md", b"# Target");
+
+        let server = ScrapsServer::new(
+            project.scraps_dir.clone(),
+            vec![project.static_dir.clone(), project.output_dir.clone()],
+            None,
+        );
+
+        let (client_stream, server_stream) = tokio::io::duplex(4096);
+
+        let server_handle = tokio::spawn(async move { server.serve(server_stream).await });
+
+        let client = ().serve(client_stream).await.unwrap();
+
+        let result = client
+            .call_tool(
+                CallToolRequestParams::new("find_scrap_path").with_arguments(
+                    serde_json::json!({"from_title": "source", "to_title": "target"})
+                        .as_object()
+                        .unwrap()
+                        .clone(),
+                ),
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error.unwrap_or(false));
+
+        let content_text = result.content[0].as_text().unwrap();
+        let response: serde_json::Value = serde_json::from_str(&content_text.text).unwrap();
+        assert_eq!(response["count"], 1);
+        assert_eq!(response["results"][0]["length"], 2);
+        assert_eq!(response["results"][0]["hops"][1]["title"], "middle");
+
+        client.cancel().await.unwrap();
+        server_handle.abort();
+    }
 }
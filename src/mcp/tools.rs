@@ -1,3 +1,4 @@
+pub mod find_scrap_path;
 pub mod get_scrap;
 pub mod list_tags;
 pub mod lookup_scrap_backlinks;
@@ -0,0 +1,154 @@
+use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::usecase::scrap::find_path::usecase::{
+    FindScrapPathUsecase, HopKind, PathNode, PathOptions,
+};
+use rmcp::handler::server::wrapper::Parameters;
+use rmcp::model::ErrorCode;
+use rmcp::model::{CallToolResult, ContentBlock};
+use rmcp::schemars::JsonSchema;
+use rmcp::service::RequestContext;
+use rmcp::{ErrorData, RoleServer};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct FindScrapPathRequest {
+    /// Title of the scrap to start from
+    pub from_title: String,
+    /// Optional context of the starting scrap
+    pub from_ctx: Option<String>,
+    /// Title of the scrap to reach
+    pub to_title: String,
+    /// Optional context of the target scrap
+    pub to_ctx: Option<String>,
+    /// Also follow links backwards (default: false)
+    pub undirected: Option<bool>,
+    /// Allow hops through shared tags (default: false)
+    pub via_tags: Option<bool>,
+    /// Maximum number of hops; a tag hop counts as two (default: 6)
+    pub max_length: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PathHopJson {
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ctx: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PathJson {
+    pub length: usize,
+    pub hops: Vec<PathHopJson>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FindScrapPathResponse {
+    pub results: Vec<PathJson>,
+    pub count: usize,
+}
+
+pub async fn find_scrap_path(
+    scraps_dir: &Path,
+    exclude_dirs: &[std::path::PathBuf],
+    cache_dir: Option<&Path>,
+    _context: RequestContext<RoleServer>,
+    Parameters(request): Parameters<FindScrapPathRequest>,
+) -> Result<CallToolResult, ErrorData> {
+    // Load scraps from directory
+    let cache = cache_dir.map(ScrapCache::load);
+    let scraps =
+        read_scraps::to_all_scraps(scraps_dir, exclude_dirs, cache.as_ref()).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32003),
+                format!("Failed to load scraps: {e}"),
+                None,
+            )
+        })?;
+
+    let from_title = scraps_libs::model::title::Title::from(request.from_title.as_str());
+    let from_ctx = request
+        .from_ctx
+        .as_ref()
+        .map(|c| scraps_libs::model::context::Ctx::from(c.as_str()));
+    let to_title = scraps_libs::model::title::Title::from(request.to_title.as_str());
+    let to_ctx = request
+        .to_ctx
+        .as_ref()
+        .map(|c| scraps_libs::model::context::Ctx::from(c.as_str()));
+    let options = PathOptions {
+        undirected: request.undirected.unwrap_or(false),
+        via_tags: request.via_tags.unwrap_or(false),
+        max_length: request.max_length.unwrap_or(6),
+    };
+
+    let paths = FindScrapPathUsecase::new()
+        .execute(
+            &scraps,
+            (&from_title, &from_ctx),
+            (&to_title, &to_ctx),
+            &options,
+        )
+        .map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32006),
+                format!("Find scrap path failed: {e}"),
+                None,
+            )
+        })?;
+
+    // Convert results to structured response
+    let results: Vec<PathJson> = paths
+        .iter()
+        .map(|path| PathJson {
+            length: path.len() - 1,
+            hops: path
+                .iter()
+                .map(|hop| {
+                    let via = hop.via.map(|v| match v {
+                        HopKind::Link => "link",
+                        HopKind::Backlink => "backlink",
+                        HopKind::Tag => "tag",
+                    });
+                    match &hop.node {
+                        PathNode::Scrap(key) => PathHopJson {
+                            kind: "scrap",
+                            title: Some(key.title().to_string()),
+                            ctx: key.ctx().as_ref().map(|c| c.to_string()),
+                            tag: None,
+                            via,
+                        },
+                        PathNode::Tag(tag) => PathHopJson {
+                            kind: "tag",
+                            title: None,
+                            ctx: None,
+                            tag: Some(tag.to_string()),
+                            via,
+                        },
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    let count = results.len();
+    let response = FindScrapPathResponse { results, count };
+
+    Ok(CallToolResult::success(vec![ContentBlock::text(
+        serde_json::to_string(&response).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32007),
+                format!("JSON serialization failed: {e}"),
+                None,
+            )
+        })?,
+    )]))
+}
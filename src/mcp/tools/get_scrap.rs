@@ -1,4 +1,5 @@
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::mcp::json::scrap::{CodeBlockJson, HeadingJson};
 use crate::usecase::scrap::get::usecase::GetScrapUsecase;
 use rmcp::handler::server::wrapper::Parameters;
@@ -78,16 +79,19 @@ fn resolve_fields(spec: Option<&[String]>) -> Result<Vec<&'static str>, ErrorDat
 pub async fn get_scrap(
     scraps_dir: &Path,
     exclude_dirs: &[std::path::PathBuf],
+    cache_dir: Option<&Path>,
     _context: RequestContext<RoleServer>,
     Parameters(request): Parameters<GetScrapRequest>,
 ) -> Result<CallToolResult, ErrorData> {
-    let scraps = read_scraps::to_all_scraps(scraps_dir, exclude_dirs).map_err(|e| {
-        ErrorData::new(
-            ErrorCode(-32003),
-            format!("Failed to load scraps: {e}"),
-            None,
-        )
-    })?;
+    let cache = cache_dir.map(ScrapCache::load);
+    let scraps =
+        read_scraps::to_all_scraps(scraps_dir, exclude_dirs, cache.as_ref()).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32003),
+                format!("Failed to load scraps: {e}"),
+                None,
+            )
+        })?;
 
     let usecase = GetScrapUsecase::new();
 
@@ -1,4 +1,5 @@
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::usecase::tag::list::usecase::ListTagUsecase;
 use rmcp::model::ErrorCode;
 use rmcp::model::{CallToolResult, ContentBlock};
@@ -10,16 +11,19 @@ use std::path::Path;
 pub async fn list_tags(
     scraps_dir: &Path,
     exclude_dirs: &[std::path::PathBuf],
+    cache_dir: Option<&Path>,
     _context: RequestContext<RoleServer>,
 ) -> Result<CallToolResult, ErrorData> {
     // Load scraps from directory
-    let scraps = read_scraps::to_all_scraps(scraps_dir, exclude_dirs).map_err(|e| {
-        ErrorData::new(
-            ErrorCode(-32003),
-            format!("Failed to load scraps: {e}"),
-            None,
-        )
-    })?;
+    let cache = cache_dir.map(ScrapCache::load);
+    let scraps =
+        read_scraps::to_all_scraps(scraps_dir, exclude_dirs, cache.as_ref()).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32003),
+                format!("Failed to load scraps: {e}"),
+                None,
+            )
+        })?;
 
     // Create tag usecase
     let tag_usecase = ListTagUsecase::new();
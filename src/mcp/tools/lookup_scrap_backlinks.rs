@@ -1,6 +1,8 @@
 use crate::input::file::read_scraps;
-use crate::mcp::json::scrap::ScrapKeyJson;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::mcp::json::scrap::BacklinkJson;
 use crate::usecase::scrap::lookup_backlinks::usecase::LookupScrapBacklinksUsecase;
+use crate::usecase::scrap::traverse::usecase::{TraverseDirection, TraverseOptions};
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::ErrorCode;
 use rmcp::model::{CallToolResult, ContentBlock};
@@ -17,28 +19,38 @@ pub struct LookupScrapBacklinksRequest {
     pub title: String,
     /// Optional context if the scrap has one
     pub ctx: Option<String>,
+    /// Include each link's line, heading path and enclosing paragraph or list item
+    pub context: Option<bool>,
+    /// Follow backlinks breadth-first up to this many hops and report every
+    /// reached scrap with its distance and the edge it was reached through
+    pub depth: Option<usize>,
+    /// With depth, also hop through explicit tags
+    pub include_tags: Option<bool>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct LookupScrapBacklinksResponse {
-    pub results: Vec<ScrapKeyJson>,
+    pub results: Vec<BacklinkJson>,
     pub count: usize,
 }
 
 pub async fn lookup_scrap_backlinks(
     scraps_dir: &Path,
     exclude_dirs: &[std::path::PathBuf],
+    cache_dir: Option<&Path>,
     _context: RequestContext<RoleServer>,
     Parameters(request): Parameters<LookupScrapBacklinksRequest>,
 ) -> Result<CallToolResult, ErrorData> {
     // Load scraps from directory
-    let scraps = read_scraps::to_all_scraps(scraps_dir, exclude_dirs).map_err(|e| {
-        ErrorData::new(
-            ErrorCode(-32003),
-            format!("Failed to load scraps: {e}"),
-            None,
-        )
-    })?;
+    let cache = cache_dir.map(ScrapCache::load);
+    let scraps =
+        read_scraps::to_all_scraps(scraps_dir, exclude_dirs, cache.as_ref()).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32003),
+                format!("Failed to load scraps: {e}"),
+                None,
+            )
+        })?;
 
     // Create get scrap backlinks usecase
     let get_backlinks_usecase = LookupScrapBacklinksUsecase::new();
@@ -50,6 +62,16 @@ pub async fn lookup_scrap_backlinks(
         .as_ref()
         .map(|c| scraps_libs::model::context::Ctx::from(c.as_str()));
 
+    if let Some(depth) = request.depth {
+        let options = TraverseOptions {
+            direction: TraverseDirection::Inbound,
+            depth,
+            include_broken: false,
+            include_tags: request.include_tags.unwrap_or(false),
+        };
+        return super::lookup_scrap_links::traversal_result(&scraps, &title, &ctx, &options);
+    }
+
     let results = get_backlinks_usecase
         .execute(&scraps, &title, &ctx)
         .map_err(|e| {
@@ -61,11 +83,14 @@ pub async fn lookup_scrap_backlinks(
         })?;
 
     // Convert results to structured response
-    let scrap_jsons: Vec<ScrapKeyJson> = results
+    let with_context = request.context.unwrap_or(false);
+    let scrap_jsons: Vec<BacklinkJson> = results
         .into_iter()
-        .map(|result| ScrapKeyJson {
+        .map(|result| BacklinkJson {
             title: result.title.to_string(),
             ctx: result.ctx.map(|c| c.to_string()),
+            occurrences: with_context
+                .then(|| result.occurrences.into_iter().map(Into::into).collect()),
         })
         .collect();
 
@@ -1,11 +1,19 @@
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::mcp::json::scrap::{TraversalJson, TraversalResponse};
 use crate::usecase::scrap::lookup_links::usecase::{LinkRefKind, LookupScrapLinksUsecase};
+use crate::usecase::scrap::traverse::usecase::{
+    TraverseDirection, TraverseLinksUsecase, TraverseOptions,
+};
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::ErrorCode;
 use rmcp::model::{CallToolResult, ContentBlock};
 use rmcp::schemars::JsonSchema;
 use rmcp::service::RequestContext;
 use rmcp::{ErrorData, RoleServer};
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::title::Title;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -16,6 +24,13 @@ pub struct LookupScrapLinksRequest {
     pub title: String,
     /// Optional context if the scrap has one
     pub ctx: Option<String>,
+    /// Follow links breadth-first up to this many hops and report every
+    /// reached scrap with its distance and the edge it was reached through
+    pub depth: Option<usize>,
+    /// With depth, also report link targets that have no scrap
+    pub include_broken: Option<bool>,
+    /// With depth, also hop through explicit tags
+    pub include_tags: Option<bool>,
 }
 
 #[derive(Debug, Serialize, PartialEq, Eq, Clone, Copy)]
@@ -51,27 +66,37 @@ pub struct LookupScrapLinksResponse {
 pub async fn lookup_scrap_links(
     scraps_dir: &Path,
     exclude_dirs: &[std::path::PathBuf],
+    cache_dir: Option<&Path>,
     _context: RequestContext<RoleServer>,
     Parameters(request): Parameters<LookupScrapLinksRequest>,
 ) -> Result<CallToolResult, ErrorData> {
     // Load scraps from directory
-    let scraps = read_scraps::to_all_scraps(scraps_dir, exclude_dirs).map_err(|e| {
-        ErrorData::new(
-            ErrorCode(-32003),
-            format!("Failed to load scraps: {e}"),
-            None,
-        )
-    })?;
+    let cache = cache_dir.map(ScrapCache::load);
+    let scraps =
+        read_scraps::to_all_scraps(scraps_dir, exclude_dirs, cache.as_ref()).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32003),
+                format!("Failed to load scraps: {e}"),
+                None,
+            )
+        })?;
 
     // Create get scrap links usecase
     let get_links_usecase = LookupScrapLinksUsecase::new();
 
     // Execute get links
-    let title = scraps_libs::model::title::Title::from(request.title.as_str());
-    let ctx = request
-        .ctx
-        .as_ref()
-        .map(|c| scraps_libs::model::context::Ctx::from(c.as_str()));
+    let title = Title::from(request.title.as_str());
+    let ctx = request.ctx.as_ref().map(|c| Ctx::from(c.as_str()));
+
+    if let Some(depth) = request.depth {
+        let options = TraverseOptions {
+            direction: TraverseDirection::Outbound,
+            depth,
+            include_broken: request.include_broken.unwrap_or(false),
+            include_tags: request.include_tags.unwrap_or(false),
+        };
+        return traversal_result(&scraps, &title, &ctx, &options);
+    }
 
     let results = get_links_usecase
         .execute(&scraps, &title, &ctx)
@@ -110,3 +135,40 @@ pub async fn lookup_scrap_links(
         })?,
     )]))
 }
+
+/// Breadth-first neighbourhood of a scrap, shared by the link lookup tools
+/// when a `depth` is requested.
+pub(super) fn traversal_result(
+    scraps: &[Scrap],
+    title: &Title,
+    ctx: &Option<Ctx>,
+    options: &TraverseOptions,
+) -> Result<CallToolResult, ErrorData> {
+    let nodes: Vec<TraversalJson> = TraverseLinksUsecase::new()
+        .execute(scraps, title, ctx, options)
+        .map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32006),
+                format!("Link traversal failed: {e}"),
+                None,
+            )
+        })?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+    let response = TraversalResponse {
+        count: nodes.len(),
+        results: nodes,
+    };
+
+    Ok(CallToolResult::success(vec![ContentBlock::text(
+        serde_json::to_string(&response).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32007),
+                format!("JSON serialization failed: {e}"),
+                None,
+            )
+        })?,
+    )]))
+}
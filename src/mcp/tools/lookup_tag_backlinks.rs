@@ -1,4 +1,5 @@
 use crate::input::file::read_scraps;
+use crate::input::file::scrap_cache::ScrapCache;
 use crate::mcp::json::scrap::ScrapKeyJson;
 use crate::usecase::tag::lookup_backlinks::usecase::LookupTagBacklinksUsecase;
 use rmcp::handler::server::wrapper::Parameters;
@@ -26,17 +27,20 @@ pub struct LookupTagBacklinksResponse {
 pub async fn lookup_tag_backlinks(
     scraps_dir: &Path,
     exclude_dirs: &[std::path::PathBuf],
+    cache_dir: Option<&Path>,
     _context: RequestContext<RoleServer>,
     Parameters(request): Parameters<LookupTagBacklinksRequest>,
 ) -> Result<CallToolResult, ErrorData> {
     // Load scraps from directory
-    let scraps = read_scraps::to_all_scraps(scraps_dir, exclude_dirs).map_err(|e| {
-        ErrorData::new(
-            ErrorCode(-32003),
-            format!("Failed to load scraps: {e}"),
-            None,
-        )
-    })?;
+    let cache = cache_dir.map(ScrapCache::load);
+    let scraps =
+        read_scraps::to_all_scraps(scraps_dir, exclude_dirs, cache.as_ref()).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32003),
+                format!("Failed to load scraps: {e}"),
+                None,
+            )
+        })?;
 
     // Create tag backlinks usecase
     let lookup_usecase = LookupTagBacklinksUsecase::new();
@@ -1,13 +1,22 @@
 use crate::input::file::read_scraps;
-use crate::mcp::json::scrap::ScrapKeyJson;
-use crate::usecase::search::usecase::SearchUsecase;
+use crate::input::file::scrap_cache::ScrapCache;
+use crate::mcp::json::scrap::SearchResultJson;
+use crate::usecase::query::parser::Query;
+use crate::usecase::query::usecase::QueryScrapsUsecase;
+use crate::usecase::search::usecase::{
+    SearchEngineKind, SearchFilter, SearchResult, SearchUsecase,
+};
 use rmcp::handler::server::wrapper::Parameters;
 use rmcp::model::ErrorCode;
 use rmcp::model::{CallToolResult, ContentBlock};
 use rmcp::schemars::JsonSchema;
 use rmcp::service::RequestContext;
 use rmcp::{ErrorData, RoleServer};
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::tag::Tag;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
 /// Search logic for combining multiple keywords
@@ -30,54 +39,182 @@ impl From<SearchLogic> for scraps_libs::search::engine::SearchLogic {
     }
 }
 
+/// Ranking engine for the query
+#[derive(Debug, Clone, Copy, Default, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchEngine {
+    /// Fuzzy matching on title and body (default)
+    #[default]
+    Fuzzy,
+    /// BM25 ranking with title/heading boosts and "exact phrase" queries
+    Bm25,
+}
+
+impl From<SearchEngine> for SearchEngineKind {
+    fn from(engine: SearchEngine) -> Self {
+        match engine {
+            SearchEngine::Fuzzy => SearchEngineKind::Fuzzy,
+            SearchEngine::Bm25 => SearchEngineKind::Bm25,
+        }
+    }
+}
+
+/// Single field a scoped search looks in
+#[derive(Debug, Clone, Copy, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchScope {
+    Title,
+    Headings,
+    Body,
+    /// Fenced code block contents
+    Code,
+}
+
+impl From<SearchScope> for crate::usecase::search::usecase::SearchScope {
+    fn from(scope: SearchScope) -> Self {
+        match scope {
+            SearchScope::Title => Self::Title,
+            SearchScope::Headings => Self::Headings,
+            SearchScope::Body => Self::Body,
+            SearchScope::Code => Self::Code,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct SearchRequest {
-    /// Query string to search for
+    /// Query string to search for. May be empty when `filter` is given
     pub query: String,
     /// Maximum number of results to return (default: 100)
     pub num: Option<usize>,
     /// Search logic: "and" (default, all keywords must match) or "or" (any keyword matches)
     pub logic: Option<SearchLogic>,
+    /// Ranking engine: "fuzzy" (default) or "bm25"
+    pub engine: Option<SearchEngine>,
+    /// Only scraps in this context or a nested one
+    pub ctx: Option<String>,
+    /// Skip scraps in these contexts or nested ones
+    pub exclude_ctx: Option<Vec<String>>,
+    /// Only scraps with this tag or one of its descendants
+    pub tag: Option<String>,
+    /// Search only this field instead of title and body
+    #[serde(rename = "in")]
+    pub scope: Option<SearchScope>,
+    /// Structured query narrowing the results, in the `scraps query` language,
+    /// e.g. `tag:rust/async AND links-to:tokio AND NOT has:backlinks sort:linked_count`
+    pub filter: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct SearchResponse {
-    pub results: Vec<ScrapKeyJson>,
+    pub results: Vec<SearchResultJson>,
     pub count: usize,
 }
 
 pub async fn search_scraps(
     scraps_dir: &Path,
     exclude_dirs: &[std::path::PathBuf],
+    cache_dir: Option<&Path>,
     _context: RequestContext<RoleServer>,
     Parameters(request): Parameters<SearchRequest>,
 ) -> Result<CallToolResult, ErrorData> {
     // Load scraps from directory
-    let scraps = read_scraps::to_all_scraps(scraps_dir, exclude_dirs).map_err(|e| {
-        ErrorData::new(
-            ErrorCode(-32003),
-            format!("Failed to load scraps: {e}"),
-            None,
-        )
-    })?;
-
-    // Create search usecase
-    let search_usecase = SearchUsecase::new();
+    let cache = cache_dir.map(ScrapCache::load);
+    let scraps =
+        read_scraps::to_all_scraps(scraps_dir, exclude_dirs, cache.as_ref()).map_err(|e| {
+            ErrorData::new(
+                ErrorCode(-32003),
+                format!("Failed to load scraps: {e}"),
+                None,
+            )
+        })?;
 
     // Execute search
     let num = request.num.unwrap_or(100);
     let logic = request.logic.unwrap_or_default().into();
-    let results = search_usecase
-        .execute(&scraps, &request.query, num, logic)
-        .map_err(|e| ErrorData::new(ErrorCode(-32004), format!("Search failed: {e}"), None))?;
+    let engine = request.engine.unwrap_or_default().into();
+    let search_usecase = match (&cache, engine) {
+        (Some(cache), SearchEngineKind::Bm25) => {
+            SearchUsecase::new().with_body_tokens(cache.body_tokens(&scraps))
+        }
+        _ => SearchUsecase::new(),
+    };
+    // The structured filter narrows the candidates before they are ranked
+    let filter_query = request
+        .filter
+        .as_deref()
+        .map(Query::parse)
+        .transpose()
+        .map_err(|e| ErrorData::new(ErrorCode(-32006), format!("Invalid filter: {e}"), None))?;
+    let matched: Option<Vec<ScrapKey>> = filter_query
+        .as_ref()
+        .map(|query| {
+            QueryScrapsUsecase::new()
+                .execute(&scraps, query)
+                .map_err(|e| ErrorData::new(ErrorCode(-32006), format!("Query failed: {e}"), None))
+        })
+        .transpose()?
+        .map(|results| {
+            results
+                .into_iter()
+                .map(|r| ScrapKey::new(&r.title, &r.ctx))
+                .collect()
+        });
+    let filter = SearchFilter {
+        ctx: request.ctx.as_deref().map(Ctx::from),
+        exclude_ctx: request
+            .exclude_ctx
+            .iter()
+            .flatten()
+            .map(|c| Ctx::from(c.as_str()))
+            .collect(),
+        tag: request.tag.as_deref().map(Tag::from),
+        scope: request.scope.map(Into::into),
+        keys: matched.as_ref().map(|keys| keys.iter().cloned().collect()),
+    };
+    // An explicit `sort:` wins over relevance, so every hit is kept until
+    // the filter's order is applied
+    let sorted = filter_query.is_some_and(|query| query.sort.is_some());
+
+    let mut hits: HashMap<ScrapKey, SearchResult> = HashMap::new();
+    let keys: Vec<ScrapKey> = match matched {
+        Some(matched) if request.query.trim().is_empty() => matched.into_iter().take(num).collect(),
+        matched => {
+            let limit = if sorted { scraps.len() } else { num };
+            let results = search_usecase
+                .execute(&scraps, &request.query, limit, logic, engine, &filter)
+                .map_err(|e| {
+                    ErrorData::new(ErrorCode(-32004), format!("Search failed: {e}"), None)
+                })?;
+            let ranked: Vec<ScrapKey> = results
+                .iter()
+                .map(|r| ScrapKey::new(&r.title, &r.ctx))
+                .collect();
+            hits.extend(ranked.iter().cloned().zip(results));
+            match matched {
+                Some(matched) if sorted => matched
+                    .into_iter()
+                    .filter(|k| hits.contains_key(k))
+                    .take(num)
+                    .collect(),
+                _ => ranked,
+            }
+        }
+    };
 
-    // Convert results to structured response
-    let scrap_jsons: Vec<ScrapKeyJson> = results
-        .into_iter()
-        .map(|result| ScrapKeyJson {
-            title: result.title.to_string(),
-            ctx: result.ctx.map(|c| c.to_string()),
+    // Convert results to structured response; filter-only matches carry no
+    // score or snippets
+    let scrap_jsons: Vec<SearchResultJson> = keys
+        .iter()
+        .map(|key| match hits.remove(key) {
+            Some(hit) => hit.into(),
+            None => SearchResultJson {
+                title: key.title().to_string(),
+                ctx: key.ctx().as_ref().map(|c| c.to_string()),
+                score: 0.0,
+                snippets: Vec::new(),
+            },
         })
         .collect();
 
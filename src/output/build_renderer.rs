@@ -1,27 +1,44 @@
+use std::fs;
 use std::path::{Path, PathBuf};
 
 use chrono_tz::Tz;
 use scraps_libs::model::{base_url::BaseUrl, content::Content, scrap::Scrap, tag::Tag};
 
-use crate::error::ScrapsResult;
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
 use crate::service::search::render::SearchIndexRender;
 use crate::usecase::build::{
     css::render::CSSRender,
+    feed::render::FeedRender,
+    graph::render::GraphRender,
     html::{
-        index_render::IndexRender, scrap_render::ScrapRender, tag_render::TagRender,
-        tags_index_render::TagsIndexRender,
+        ctx_render::CtxRender, graph_render::GraphPageRender, index_render::IndexRender,
+        scrap_render::ScrapRender, tag_render::TagRender, tags_index_render::TagsIndexRender,
     },
+    ir::render::IrRender,
+    llms::render::LlmsRender,
     model::{
+        asset::{Asset, Assets},
         backlinks_map::BacklinksMap,
+        build_manifest::{BuildManifest, InputHash, BUILD_MANIFEST_FILE_NAME},
         css::CssMetadata,
+        ctx_page::CtxPage,
+        feed::Feeds,
+        graph::SiteGraph,
         html::HtmlMetadata,
+        ir::ScrapsIr,
         list_view_configs::ListViewConfigs,
+        llms_corpus::LlmsCorpus,
+        ogp::OgpCache,
         scrap_detail::{ScrapDetail, ScrapDetails},
+        sitemap::Sitemap,
     },
     renderer::{
-        CssRenderer, HtmlIndexRenderer, HtmlScrapRenderer, HtmlTagRenderer, HtmlTagsIndexRenderer,
-        SearchIndexJsonRenderer,
+        AssetCopier, AtomFeedRenderer, BuildManifestStore, CssRenderer, GraphJsonRenderer,
+        HtmlCtxRenderer, HtmlGraphRenderer, HtmlIndexRenderer, HtmlScrapRenderer, HtmlTagRenderer,
+        HtmlTagsIndexRenderer, IrJsonRenderer, LlmsTxtRenderer, SearchIndexJsonRenderer,
+        SitemapRenderer,
     },
+    sitemap::render::SitemapRender,
 };
 
 pub struct BuildRendererImpl {
@@ -68,6 +85,9 @@ impl HtmlScrapRenderer for BuildRendererImpl {
         html_metadata: &HtmlMetadata,
         scrap_detail: &ScrapDetail,
         backlinks_map: &BacklinksMap,
+        assets: &Assets,
+        ogp_cache: &OgpCache,
+        build_graph: bool,
     ) -> ScrapsResult<()> {
         let scrap_render = ScrapRender::new(&self.static_dir_path, &self.output_dir_path)?;
         scrap_render.run(
@@ -76,6 +96,9 @@ impl HtmlScrapRenderer for BuildRendererImpl {
             html_metadata,
             scrap_detail,
             backlinks_map,
+            assets,
+            ogp_cache,
+            build_graph,
         )
     }
 }
@@ -100,9 +123,34 @@ impl HtmlTagRenderer for BuildRendererImpl {
         html_metadata: &HtmlMetadata,
         tag: &Tag,
         backlinks_map: &BacklinksMap,
+        assets: &Assets,
     ) -> ScrapsResult<()> {
         let tag_render = TagRender::new(&self.static_dir_path, &self.output_dir_path)?;
-        tag_render.run(base_url, html_metadata, tag, backlinks_map)
+        tag_render.run(base_url, html_metadata, tag, backlinks_map, assets)
+    }
+}
+
+impl HtmlCtxRenderer for BuildRendererImpl {
+    fn render_ctx(
+        &self,
+        base_url: &BaseUrl,
+        html_metadata: &HtmlMetadata,
+        ctx_page: &CtxPage,
+        assets: &Assets,
+    ) -> ScrapsResult<()> {
+        let ctx_render = CtxRender::new(&self.static_dir_path, &self.output_dir_path)?;
+        ctx_render.run(base_url, html_metadata, ctx_page, assets)
+    }
+}
+
+impl HtmlGraphRenderer for BuildRendererImpl {
+    fn render_graph_page(
+        &self,
+        base_url: &BaseUrl,
+        html_metadata: &HtmlMetadata,
+    ) -> ScrapsResult<()> {
+        let graph_page_render = GraphPageRender::new(&self.static_dir_path, &self.output_dir_path)?;
+        graph_page_render.run(base_url, html_metadata)
     }
 }
 
@@ -120,3 +168,120 @@ impl SearchIndexJsonRenderer for BuildRendererImpl {
         search_index_render.run(base_url, scraps)
     }
 }
+
+impl GraphJsonRenderer for BuildRendererImpl {
+    fn render_graph(&self, graph: &SiteGraph) -> ScrapsResult<()> {
+        let graph_render = GraphRender::new(&self.output_dir_path)?;
+        graph_render.run(graph)
+    }
+}
+
+impl SitemapRenderer for BuildRendererImpl {
+    fn render_sitemap(&self, base_url: &BaseUrl, sitemap: &Sitemap) -> ScrapsResult<()> {
+        let sitemap_render = SitemapRender::new(&self.static_dir_path, &self.output_dir_path)?;
+        sitemap_render.run(base_url, sitemap)
+    }
+}
+
+impl AtomFeedRenderer for BuildRendererImpl {
+    fn render_feeds(&self, html_metadata: &HtmlMetadata, feeds: &Feeds) -> ScrapsResult<()> {
+        let feed_render = FeedRender::new(&self.output_dir_path)?;
+        feed_render.run(html_metadata, feeds)
+    }
+}
+
+impl LlmsTxtRenderer for BuildRendererImpl {
+    fn render_llms(&self, html_metadata: &HtmlMetadata, llms: &LlmsCorpus) -> ScrapsResult<()> {
+        let llms_render = LlmsRender::new(&self.output_dir_path)?;
+        llms_render.run(html_metadata, llms)
+    }
+}
+
+impl IrJsonRenderer for BuildRendererImpl {
+    fn render_ir(&self, ir: &ScrapsIr) -> ScrapsResult<()> {
+        let ir_render = IrRender::new(&self.output_dir_path)?;
+        ir_render.run(ir)
+    }
+}
+
+impl AssetCopier for BuildRendererImpl {
+    fn copy_asset(&self, asset: &Asset) -> ScrapsResult<bool> {
+        let path = self.output_dir_path.join(asset.output_path());
+        // A copy of the same size, written after the source last changed, is
+        // left alone so that `serve` rebuilds stay cheap.
+        let source_meta =
+            fs::metadata(&asset.source).context(BuildError::CopyFailure(asset.source.clone()))?;
+        let up_to_date = fs::metadata(&path).is_ok_and(|meta| {
+            meta.len() == source_meta.len()
+                && matches!(
+                    (meta.modified(), source_meta.modified()),
+                    (Ok(copied), Ok(source)) if copied >= source
+                )
+        });
+        if up_to_date {
+            return Ok(false);
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context(BuildError::CreateDir)?;
+        }
+        fs::copy(&asset.source, &path).context(BuildError::CopyFailure(path))?;
+        Ok(true)
+    }
+}
+
+impl BuildManifestStore for BuildRendererImpl {
+    fn load_manifest(&self) -> BuildManifest {
+        let path = self.output_dir_path.join(BUILD_MANIFEST_FILE_NAME);
+        let mut manifest = fs::read(&path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<BuildManifest>(&bytes).ok())
+            .filter(|manifest| manifest.is_current())
+            .unwrap_or_default();
+        // Outputs deleted by hand since have to be written again.
+        manifest.retain(|output| self.output_dir_path.join(output).is_file());
+        manifest
+    }
+
+    fn save_manifest(&self, manifest: &BuildManifest) -> ScrapsResult<()> {
+        let path = self.output_dir_path.join(BUILD_MANIFEST_FILE_NAME);
+        fs::create_dir_all(&self.output_dir_path).context(BuildError::CreateDir)?;
+        let bytes = serde_json::to_vec(manifest).context(BuildError::RenderJson)?;
+        fs::write(&path, bytes).context(BuildError::WriteFailure(path))
+    }
+
+    fn remove_output(&self, output: &str) -> ScrapsResult<()> {
+        let path = self.output_dir_path.join(output);
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context(BuildError::RemoveFailure(path)),
+        }
+        // Drop context directories left empty, e.g. `scraps/<ctx>/`.
+        let mut dir = path.parent();
+        while let Some(d) = dir.filter(|d| *d != self.output_dir_path) {
+            if fs::remove_dir(d).is_err() {
+                break;
+            }
+            dir = d.parent();
+        }
+        Ok(())
+    }
+
+    fn templates_hash(&self) -> String {
+        let mut templates: Vec<PathBuf> = fs::read_dir(&self.static_dir_path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.extension().is_some_and(|ext| ext == "html"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        templates.sort();
+        let mut hash = InputHash::new();
+        for template in templates {
+            hash.write(&template.to_string_lossy());
+            hash.write(&fs::read_to_string(&template).unwrap_or_default());
+        }
+        hash.finish()
+    }
+}
@@ -95,6 +95,21 @@ impl TempScrapProject {
         self
     }
 
+    /// Add a non-markdown file under the wiki root, copied into the output
+    /// next to the scrap pages
+    ///
+    /// # Arguments
+    /// * `filename` - Relative path from the project root (e.g., "diagram.png" or "Context/diagram.png")
+    /// * `content` - File content as bytes
+    pub fn add_asset(&self, filename: &str, content: &[u8]) -> &Self {
+        let path = self.project_root.join(filename);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("Failed to create parent dir");
+        }
+        fs::write(&path, content).expect("Failed to write asset file");
+        self
+    }
+
     /// Add config file to the project root
     ///
     /// # Arguments
@@ -1,9 +1,12 @@
 pub mod build;
+pub mod export;
 pub mod init;
 pub mod lint;
 pub mod progress;
+pub mod query;
 pub mod scrap;
 pub mod search;
 pub mod serve;
+pub mod stats;
 pub mod tag;
 pub mod todo;
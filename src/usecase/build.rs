@@ -1,5 +1,12 @@
 pub mod css;
+pub mod feed;
+pub mod graph;
 pub mod html;
+pub mod ir;
+pub mod llms;
 pub mod model;
+pub mod ogp_fetcher;
 pub mod renderer;
+pub mod sitemap;
 pub mod usecase;
+mod xml;
@@ -51,7 +51,6 @@ mod tests {
 
     use super::*;
     use std::fs;
-    use std::io::{BufWriter, Write};
 
     #[rstest]
     fn test_render_main(#[from(temp_scrap_project)] project: TempScrapProject) {
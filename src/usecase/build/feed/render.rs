@@ -0,0 +1,158 @@
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::{fs::File, path::PathBuf};
+
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
+use crate::usecase::build::model::{
+    feed::{Feed, Feeds},
+    html::HtmlMetadata,
+};
+use crate::usecase::build::xml;
+
+pub struct FeedRender {
+    output_dir_path: PathBuf,
+}
+
+impl FeedRender {
+    pub fn new(output_dir_path: &PathBuf) -> ScrapsResult<FeedRender> {
+        fs::create_dir_all(output_dir_path).context(BuildError::CreateDir)?;
+
+        Ok(FeedRender {
+            output_dir_path: output_dir_path.to_owned(),
+        })
+    }
+
+    pub fn run(&self, html_metadata: &HtmlMetadata, feeds: &Feeds) -> ScrapsResult<()> {
+        feeds
+            .iter()
+            .try_for_each(|feed| self.write(&feed.path, &Self::atom(html_metadata, feed)))
+    }
+
+    fn atom(html_metadata: &HtmlMetadata, feed: &Feed) -> String {
+        let title = match &feed.subtitle {
+            Some(subtitle) => format!("{} - {subtitle}", html_metadata.title()),
+            None => html_metadata.title(),
+        };
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n",
+            "<feed xmlns=\"http://www.w3.org/2005/Atom\">\n"
+        ));
+        out.push_str(&format!("  <title>{}</title>\n", xml::escape(&title)));
+        if let Some(description) = html_metadata.description() {
+            out.push_str(&format!(
+                "  <subtitle>{}</subtitle>\n",
+                xml::escape(&description)
+            ));
+        }
+        out.push_str(&format!(
+            "  <link href=\"{}\"/>\n",
+            xml::escape(feed.page_url.as_str())
+        ));
+        out.push_str(&format!(
+            "  <link rel=\"self\" href=\"{}\"/>\n",
+            xml::escape(feed.self_url.as_str())
+        ));
+        out.push_str(&format!(
+            "  <id>{}</id>\n",
+            xml::escape(feed.self_url.as_str())
+        ));
+        out.push_str(&format!(
+            "  <updated>{}</updated>\n",
+            xml::datetime(feed.updated().unwrap_or_default())
+        ));
+        out.push_str(&format!(
+            "  <author><name>{}</name></author>\n",
+            xml::escape(&html_metadata.title())
+        ));
+        for entry in &feed.entries {
+            out.push_str("  <entry>\n");
+            out.push_str(&format!(
+                "    <title>{}</title>\n",
+                xml::escape(&entry.title)
+            ));
+            out.push_str(&format!(
+                "    <link href=\"{}\"/>\n",
+                xml::escape(entry.url.as_str())
+            ));
+            out.push_str(&format!(
+                "    <id>{}</id>\n",
+                xml::escape(entry.url.as_str())
+            ));
+            out.push_str(&format!(
+                "    <updated>{}</updated>\n",
+                xml::datetime(entry.updated)
+            ));
+            out.push_str(&format!(
+                "    <content type=\"html\">{}</content>\n",
+                xml::escape(&entry.html)
+            ));
+            out.push_str("  </entry>\n");
+        }
+        out.push_str("</feed>\n");
+        out
+    }
+
+    fn write(&self, path: &str, text: &str) -> ScrapsResult<()> {
+        let file_path = &self.output_dir_path.join(path);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).context(BuildError::CreateDir)?;
+        }
+        let mut wtr = BufWriter::new(
+            File::create(file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        wtr.write_all(text.as_bytes())
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(file_path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::build::model::{
+        asset::Assets,
+        backlinks_map::BacklinksMap,
+        feed::{FeedConfigs, Feeds},
+    };
+    use rstest::rstest;
+    use scraps_libs::lang::LangCode;
+    use scraps_libs::model::{base_url::BaseUrl, scrap::Scrap};
+    use url::Url;
+
+    use super::*;
+
+    #[rstest]
+    fn it_writes_atom_feeds(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let base_url = BaseUrl::new(Url::parse("https://example.com/").unwrap()).unwrap();
+        let scraps_with_ts = vec![(
+            Scrap::new("Tips & Tricks", &None, "#[[rust]] Use `<T>`"),
+            Some(1_700_000_000),
+        )];
+        let scraps: Vec<Scrap> = scraps_with_ts.iter().map(|(s, _)| s.clone()).collect();
+        let feeds = Feeds::new(
+            &scraps_with_ts,
+            &base_url,
+            &BacklinksMap::new(&scraps),
+            &FeedConfigs::default(),
+            &Assets::default(),
+        );
+        let html_metadata = HtmlMetadata::new(&LangCode::default(), "Wiki", &None, &None);
+
+        FeedRender::new(&project.output_dir)
+            .unwrap()
+            .run(&html_metadata, &feeds)
+            .unwrap();
+
+        let site = fs::read_to_string(project.output_path("feed.xml")).unwrap();
+        assert!(site.contains("  <title>Wiki</title>\n"));
+        assert!(site.contains("  <link rel=\"self\" href=\"https://example.com/feed.xml\"/>\n"));
+        assert!(site.contains("  <updated>2023-11-14T22:13:20Z</updated>\n"));
+        assert!(site.contains("    <title>Tips &amp; Tricks</title>\n"));
+        assert!(site.contains("&lt;code&gt;&amp;lt;T&amp;gt;&lt;/code&gt;"));
+
+        let tag = fs::read_to_string(project.output_path("tags/rust.xml")).unwrap();
+        assert!(tag.contains("  <title>Wiki - #rust</title>\n"));
+        assert!(tag.contains("  <link href=\"https://example.com/tags/rust.html\"/>\n"));
+    }
+}
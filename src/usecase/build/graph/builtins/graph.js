@@ -0,0 +1,215 @@
+// Draws graph.json as a force-directed SVG, without any external library.
+// Every `[data-graph]` element shows the whole wiki, or with `data-key` the
+// scraps and tags one step away from that scrap.
+(() => {
+  'use strict';
+
+  const SVG_NS = 'http://www.w3.org/2000/svg';
+
+  const element = (name, attributes = {}) => {
+    const el = document.createElementNS(SVG_NS, name);
+    Object.entries(attributes).forEach(([key, value]) => el.setAttribute(key, value));
+    return el;
+  };
+
+  const neighbourhood = (graph, key) => {
+    const ids = new Set([key]);
+    graph.edges.forEach((edge) => {
+      if (edge.source === key) ids.add(edge.target);
+      if (edge.target === key) ids.add(edge.source);
+    });
+    return {
+      nodes: graph.nodes.filter((node) => ids.has(node.id)),
+      edges: graph.edges.filter((edge) => ids.has(edge.source) && ids.has(edge.target)),
+    };
+  };
+
+  // Fruchterman-Reingold, starting from a circle so that the same graph is
+  // always laid out the same way, then scaled to fit the view.
+  const layout = (nodes, edges, width, height) => {
+    const count = nodes.length;
+    const k = Math.sqrt((width * height) / count) * 0.8;
+    const index = new Map(nodes.map((node, i) => [node.id, i]));
+    const links = edges.map((edge) => [index.get(edge.source), index.get(edge.target)]);
+    const radius = Math.min(width, height) / 3;
+    const pos = nodes.map((_, i) => {
+      const angle = (2 * Math.PI * i) / count;
+      return { x: width / 2 + radius * Math.cos(angle), y: height / 2 + radius * Math.sin(angle) };
+    });
+    // Repulsion is quadratic in the node count, so large wikis settle in fewer steps.
+    const steps = Math.max(30, Math.min(300, Math.floor(3e6 / (count * count))));
+
+    for (let step = 0; step < steps; step++) {
+      const disp = pos.map(() => ({ x: 0, y: 0 }));
+      for (let i = 0; i < count; i++) {
+        for (let j = i + 1; j < count; j++) {
+          const dx = pos[i].x - pos[j].x;
+          const dy = pos[i].y - pos[j].y;
+          const dist = Math.max(Math.hypot(dx, dy), 0.01);
+          const force = (k * k) / dist / dist;
+          disp[i].x += dx * force;
+          disp[i].y += dy * force;
+          disp[j].x -= dx * force;
+          disp[j].y -= dy * force;
+        }
+      }
+      links.forEach(([a, b]) => {
+        const dx = pos[a].x - pos[b].x;
+        const dy = pos[a].y - pos[b].y;
+        const force = Math.max(Math.hypot(dx, dy), 0.01) / k;
+        disp[a].x -= dx * force;
+        disp[a].y -= dy * force;
+        disp[b].x += dx * force;
+        disp[b].y += dy * force;
+      });
+      const temperature = (width / 10) * (1 - step / steps);
+      pos.forEach((p, i) => {
+        // A little gravity keeps unconnected scraps near the middle.
+        const dx = disp[i].x + (width / 2 - p.x) * 0.05;
+        const dy = disp[i].y + (height / 2 - p.y) * 0.05;
+        const dist = Math.max(Math.hypot(dx, dy), 0.01);
+        const moved = Math.min(dist, temperature);
+        p.x += (dx / dist) * moved;
+        p.y += (dy / dist) * moved;
+      });
+    }
+    return fit(pos, width, height);
+  };
+
+  const fit = (pos, width, height) => {
+    const margin = 32;
+    const xs = pos.map((p) => p.x);
+    const ys = pos.map((p) => p.y);
+    const [minX, maxX, minY, maxY] = [Math.min(...xs), Math.max(...xs), Math.min(...ys), Math.max(...ys)];
+    const scale = Math.min(
+      (width - 2 * margin) / Math.max(maxX - minX, 1),
+      (height - 2 * margin) / Math.max(maxY - minY, 1),
+      1.5,
+    );
+    const offsetX = (width - (maxX - minX) * scale) / 2;
+    const offsetY = (height - (maxY - minY) * scale) / 2;
+    return pos.map((p) => ({ x: offsetX + (p.x - minX) * scale, y: offsetY + (p.y - minY) * scale }));
+  };
+
+  // Scraps of the same context share a colour.
+  const hue = (text) => [...text].reduce((h, c) => (h * 31 + c.codePointAt(0)) % 360, 0);
+
+  const label = (node) => (node.kind === 'tag' ? `#${node.title}` : node.title);
+
+  const zoomable = (svg, width, height) => {
+    let box = { x: 0, y: 0, w: width, h: height };
+    const apply = () => svg.setAttribute('viewBox', `${box.x} ${box.y} ${box.w} ${box.h}`);
+    svg.addEventListener('wheel', (event) => {
+      event.preventDefault();
+      const rect = svg.getBoundingClientRect();
+      const px = box.x + ((event.clientX - rect.left) / rect.width) * box.w;
+      const py = box.y + ((event.clientY - rect.top) / rect.height) * box.h;
+      const scale = event.deltaY > 0 ? 1.1 : 1 / 1.1;
+      box = {
+        x: px - (px - box.x) * scale,
+        y: py - (py - box.y) * scale,
+        w: box.w * scale,
+        h: box.h * scale,
+      };
+      apply();
+    }, { passive: false });
+
+    let drag = null;
+    svg.addEventListener('pointerdown', (event) => {
+      if (event.target.closest('a')) return;
+      drag = { x: event.clientX, y: event.clientY, box };
+      svg.setPointerCapture(event.pointerId);
+    });
+    svg.addEventListener('pointermove', (event) => {
+      if (!drag) return;
+      const rect = svg.getBoundingClientRect();
+      box = {
+        ...drag.box,
+        x: drag.box.x - ((event.clientX - drag.x) / rect.width) * drag.box.w,
+        y: drag.box.y - ((event.clientY - drag.y) / rect.height) * drag.box.h,
+      };
+      apply();
+    });
+    svg.addEventListener('pointerup', () => {
+      drag = null;
+    });
+  };
+
+  const draw = (root, graph, focus) => {
+    const width = root.clientWidth || 600;
+    const height = root.clientHeight || 400;
+    const pos = layout(graph.nodes, graph.edges, width, height);
+    const index = new Map(graph.nodes.map((node, i) => [node.id, i]));
+    const svg = element('svg', { viewBox: `0 0 ${width} ${height}`, role: 'img' });
+    const edgeLayer = element('g', { class: 'edges' });
+    const nodeLayer = element('g', { class: 'nodes' });
+
+    const lines = graph.edges.map((edge) => {
+      const a = pos[index.get(edge.source)];
+      const b = pos[index.get(edge.target)];
+      const line = element('line', { class: `edge ${edge.kind}`, x1: a.x, y1: a.y, x2: b.x, y2: b.y });
+      edgeLayer.appendChild(line);
+      return line;
+    });
+
+    const anchors = graph.nodes.map((node, i) => {
+      const { x, y } = pos[i];
+      const r = 4 + Math.sqrt(node.degree) * 2;
+      const anchor = element('a', {
+        href: root.dataset.baseUrl + node.url,
+        class: `node ${node.kind}${node.id === focus ? ' focus' : ''}`,
+      });
+      const circle = element('circle', { cx: x, cy: y, r });
+      if (node.ctx) circle.style.fill = `hsl(${hue(node.ctx)}, 55%, 55%)`;
+      const title = element('title');
+      title.textContent = node.ctx ? `${node.ctx}/${label(node)}` : label(node);
+      const text = element('text', { x, y: y - r - 4, 'text-anchor': 'middle' });
+      text.textContent = label(node);
+      anchor.append(title, circle, text);
+      nodeLayer.appendChild(anchor);
+      return anchor;
+    });
+
+    // Highlight a node and its neighbours while hovering it.
+    anchors.forEach((anchor, i) => {
+      const id = graph.nodes[i].id;
+      anchor.addEventListener('mouseenter', () => {
+        svg.classList.add('hovering');
+        anchor.classList.add('near');
+        graph.edges.forEach((edge, j) => {
+          if (edge.source !== id && edge.target !== id) return;
+          lines[j].classList.add('near');
+          anchors[index.get(edge.source)].classList.add('near');
+          anchors[index.get(edge.target)].classList.add('near');
+        });
+      });
+      anchor.addEventListener('mouseleave', () => {
+        svg.classList.remove('hovering');
+        svg.querySelectorAll('.near').forEach((el) => el.classList.remove('near'));
+      });
+    });
+
+    svg.append(edgeLayer, nodeLayer);
+    if (!focus) zoomable(svg, width, height);
+    root.appendChild(svg);
+  };
+
+  document.addEventListener('DOMContentLoaded', () => {
+    document.querySelectorAll('[data-graph]').forEach(async (root) => {
+      try {
+        const response = await fetch(root.dataset.graph);
+        const graph = await response.json();
+        const focus = root.dataset.key;
+        const shown = focus ? neighbourhood(graph, focus) : graph;
+        // A scrap without links has nothing to show.
+        if (shown.nodes.length < 2) {
+          root.hidden = true;
+          return;
+        }
+        draw(root, shown, focus);
+      } catch (error) {
+        console.error('Failed to load graph:', error);
+      }
+    });
+  });
+})();
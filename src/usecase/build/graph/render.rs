@@ -0,0 +1,71 @@
+use std::io::{BufWriter, Write};
+use std::{fs::File, path::PathBuf};
+
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
+use crate::usecase::build::model::graph::SiteGraph;
+
+const SCRIPT: &str = include_str!("builtins/graph.js");
+
+pub struct GraphRender {
+    output_dir_path: PathBuf,
+}
+
+impl GraphRender {
+    pub fn new(output_dir_path: &PathBuf) -> ScrapsResult<GraphRender> {
+        std::fs::create_dir_all(output_dir_path).context(BuildError::CreateDir)?;
+
+        Ok(GraphRender {
+            output_dir_path: output_dir_path.to_owned(),
+        })
+    }
+
+    /// Write `graph.json` and `graph.js`, the script drawing it, so graphs
+    /// work without a CDN.
+    pub fn run(&self, graph: &SiteGraph) -> ScrapsResult<()> {
+        let file_path = &self.output_dir_path.join("graph.json");
+        let mut wtr = BufWriter::new(
+            File::create(file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        serde_json::to_writer(&mut wtr, graph)
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+
+        let script_path = &self.output_dir_path.join("graph.js");
+        let mut wtr = BufWriter::new(
+            File::create(script_path).context(BuildError::WriteFailure(script_path.clone()))?,
+        );
+        wtr.write_all(SCRIPT.as_bytes())
+            .context(BuildError::WriteFailure(script_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(script_path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+    use scraps_libs::model::scrap::Scrap;
+    use serde_json::Value;
+    use std::fs;
+
+    use super::*;
+
+    #[rstest]
+    fn it_run(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let scraps = [Scrap::new("a", &None, "[[b]]"), Scrap::new("b", &None, "")];
+
+        let render = GraphRender::new(&project.output_dir).unwrap();
+        render.run(&SiteGraph::new(&scraps)).unwrap();
+
+        let json: Value =
+            serde_json::from_str(&fs::read_to_string(project.output_path("graph.json")).unwrap())
+                .unwrap();
+        assert_eq!(json["nodes"][0]["id"], "a");
+        assert_eq!(json["nodes"][0]["degree"], 1);
+        assert_eq!(json["edges"][0]["kind"], "link");
+        let script = fs::read_to_string(project.output_path("graph.js")).unwrap();
+        assert!(script.contains("data-graph"));
+    }
+}
@@ -1,4 +1,6 @@
 mod cdn_versions;
+pub mod ctx_render;
+pub mod graph_render;
 pub mod index_render;
 mod page_pointer;
 pub mod scrap_render;
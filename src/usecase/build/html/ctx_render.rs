@@ -0,0 +1,151 @@
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::{fs::File, path::PathBuf};
+
+use crate::error::BuildError;
+use crate::error::{anyhow::Context, ScrapsResult};
+use crate::usecase::build::model::asset::Assets;
+use crate::usecase::build::model::ctx_page::CtxPage;
+use crate::usecase::build::model::html::HtmlMetadata;
+use scraps_libs::model::base_url::BaseUrl;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::slugify;
+use tera::Tera;
+
+use crate::usecase::build::html::tera::ctx_tera;
+
+use super::serde::ctx::CtxTera;
+use super::serde::link_scraps::LinkScrapsTera;
+
+pub struct CtxRender {
+    tera: Tera,
+    output_dir_path: PathBuf,
+}
+
+impl CtxRender {
+    pub fn new(static_dir_path: &Path, output_dir_path: &Path) -> ScrapsResult<CtxRender> {
+        fs::create_dir_all(output_dir_path).context(BuildError::CreateDir)?;
+        // Built once here rather than per context: loading the glob re-parses
+        // every template, which dominates the build on large wikis.
+        let tera = ctx_tera::tera(static_dir_path.join("*.html").to_str().unwrap())?;
+
+        Ok(CtxRender {
+            tera,
+            output_dir_path: output_dir_path.to_owned(),
+        })
+    }
+
+    pub fn run(
+        &self,
+        base_url: &BaseUrl,
+        metadata: &HtmlMetadata,
+        ctx_page: &CtxPage,
+        assets: &Assets,
+    ) -> ScrapsResult<()> {
+        let mut context = ctx_tera::context(base_url, metadata);
+        context.insert(
+            "page_url",
+            &base_url.page_url(&ctx_page_path(&ctx_page.ctx)),
+        );
+        context.insert("ctx", &CtxTera::new(ctx_page));
+        context.insert(
+            "linked_scraps",
+            &LinkScrapsTera::new(&ctx_page.scraps, assets, base_url),
+        );
+
+        // The page is the directory index next to the scraps of the context,
+        // so `/scraps/<ctx>/` resolves to it.
+        let file_path = self.output_dir_path.join(ctx_output_path(&ctx_page.ctx));
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).context(BuildError::CreateDir)?;
+        }
+        // tera renders in many small writes, so buffer them into one file write.
+        let mut wtr = BufWriter::new(
+            File::create(&file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        self.tera
+            .render_to("__builtins/ctx.html", &context, &mut wtr)
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(file_path.clone()))
+    }
+}
+
+/// URL path of the context page relative to the site root.
+pub fn ctx_page_path(ctx: &Ctx) -> String {
+    let slug_path = ctx
+        .segments()
+        .iter()
+        .map(|s| slugify::by_dash(s))
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("scraps/{slug_path}/")
+}
+
+/// Path of the context page relative to the output directory.
+pub fn ctx_output_path(ctx: &Ctx) -> String {
+    format!("{}index.html", ctx_page_path(ctx))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::build::model::ctx_page::CtxPages;
+    use crate::usecase::build::model::scrap_detail::{ScrapDetail, ScrapDetails};
+    use rstest::rstest;
+    use scraps_libs::lang::LangCode;
+    use scraps_libs::model::scrap::Scrap;
+    use url::Url;
+
+    use super::*;
+
+    #[rstest]
+    fn it_run(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let metadata = HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+        let scraps = [
+            Scrap::new("borrowing", &Some("Programming/Rust".into()), "body"),
+            Scrap::new("README", &Some("Programming/Rust".into()), "About *Rust*"),
+        ];
+        let details: Vec<ScrapDetail> = scraps
+            .iter()
+            .map(|scrap| {
+                ScrapDetail::new(scrap, &None, &base_url, &HashMap::new(), &Assets::default())
+            })
+            .collect();
+        let ctx_pages = CtxPages::new(&ScrapDetails::new(&details));
+
+        let render = CtxRender::new(&project.static_dir, &project.output_dir).unwrap();
+        for ctx_page in ctx_pages.iter() {
+            render
+                .run(&base_url, &metadata, ctx_page, &Assets::default())
+                .unwrap();
+        }
+
+        let parent =
+            fs::read_to_string(project.output_path("scraps/programming/index.html")).unwrap();
+        assert!(parent
+            .contains("<a href=\"http://localhost:1112/scraps/programming/rust/\">Rust&#47;</a>"));
+
+        let page =
+            fs::read_to_string(project.output_path("scraps/programming/rust/index.html")).unwrap();
+        assert!(page.contains("<title>Programming/Rust - Scrap</title>"));
+        assert!(page.contains(
+            "<a href=\"http://localhost:1112/scraps/programming/\">Programming</a><span>&#47;</span>"
+        ));
+        assert!(page.contains("<p>About <em>Rust</em></p>"));
+        assert!(page.contains("http://localhost:1112/scraps/programming/rust/borrowing.html"));
+        assert!(!page.contains("scraps/programming/rust/readme.html"));
+    }
+
+    #[test]
+    fn it_places_the_page_as_the_directory_index() {
+        assert_eq!(
+            ctx_output_path(&Ctx::from("Programming/Rust Lang")),
+            "scraps/programming/rust-lang/index.html"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::{fs::File, path::PathBuf};
+
+use crate::error::BuildError;
+use crate::error::{anyhow::Context, ScrapsResult};
+use crate::usecase::build::model::html::HtmlMetadata;
+use scraps_libs::model::base_url::BaseUrl;
+
+use crate::usecase::build::html::tera::graph_tera;
+
+pub struct GraphPageRender {
+    static_dir_path: PathBuf,
+    output_dir_path: PathBuf,
+}
+
+impl GraphPageRender {
+    pub fn new(static_dir_path: &Path, output_dir_path: &Path) -> ScrapsResult<GraphPageRender> {
+        fs::create_dir_all(output_dir_path).context(BuildError::CreateDir)?;
+
+        Ok(GraphPageRender {
+            static_dir_path: static_dir_path.to_owned(),
+            output_dir_path: output_dir_path.to_owned(),
+        })
+    }
+
+    /// Write `graph.html`, which draws `graph.json` on load.
+    pub fn run(&self, base_url: &BaseUrl, metadata: &HtmlMetadata) -> ScrapsResult<()> {
+        let (tera, mut context) = graph_tera::base(
+            base_url,
+            metadata,
+            self.static_dir_path.join("*.html").to_str().unwrap(),
+        )?;
+        let template_name = if tera.get_template_names().any(|t| t == "graph.html") {
+            "graph.html"
+        } else {
+            "__builtins/graph.html"
+        };
+        context.insert("page_url", &base_url.page_url("graph.html"));
+        let file_path = &self.output_dir_path.join("graph.html");
+        // tera renders in many small writes, so buffer them into one file write.
+        let mut wtr = BufWriter::new(
+            File::create(file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        tera.render_to(template_name, &context, &mut wtr)
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(file_path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use rstest::rstest;
+    use scraps_libs::lang::LangCode;
+    use url::Url;
+
+    use super::*;
+
+    #[rstest]
+    fn it_run(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/wiki/").unwrap()).unwrap();
+        let metadata = HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+
+        let render = GraphPageRender::new(&project.static_dir, &project.output_dir).unwrap();
+        render.run(&base_url, &metadata).unwrap();
+
+        let html = fs::read_to_string(project.output_path("graph.html")).unwrap();
+        assert!(html.contains("data-graph=\"http://localhost:1112/wiki/graph.json\""));
+        assert!(
+            html.contains("<script src=\"http://localhost:1112/wiki/graph.js\" defer></script>")
+        );
+    }
+}
@@ -47,10 +47,10 @@ impl IndexRender {
         let scraps = &scrap_details.to_scraps();
         let sorted_scraps = IndexScrapsTera::new_with_sort(
             scrap_details,
-            &backlinks_map,
+            backlinks_map,
             &list_view_configs.sort_key,
         );
-        let stags = &TagsTera::new(&Tags::new(scraps), &backlinks_map);
+        let stags = &TagsTera::new(&Tags::new(scraps), backlinks_map);
         // setup tera
         let (tera, base_context) = {
             let (tera, mut context) = index_tera::base(
@@ -63,6 +63,7 @@ impl IndexRender {
                 &SortKeyTera::from(list_view_configs.sort_key.clone()),
             );
             context.insert("build_search_index", &list_view_configs.build_search_index);
+            context.insert("build_graph", &list_view_configs.build_graph);
             (tera, context)
         };
 
@@ -74,13 +75,14 @@ impl IndexRender {
         let index_scraps = chunks.first();
         if let Some(first_scraps) = index_scraps {
             let _span_generate_index = span!(Level::INFO, "generate_index").entered();
-            let (context, page_pointer) = Self::prepare_index_context(
+            let (mut context, page_pointer) = Self::prepare_index_context(
                 &base_context,
                 first_scraps,
                 stags,
                 total_pages,
                 readme_content,
             );
+            context.insert("page_url", &base_url.page_url(&page_pointer.current));
             self.render_html(&tera, &context, &page_pointer)?;
         }
 
@@ -92,13 +94,14 @@ impl IndexRender {
             .try_for_each(|(idx, page_scraps)| {
                 let _span_generate_paginated = span!(Level::INFO, "generate_paginated").entered();
                 let page_num = idx + 2;
-                let (context, page_pointer) = Self::prepare_paginated_context(
+                let (mut context, page_pointer) = Self::prepare_paginated_context(
                     &base_context,
                     page_scraps,
                     stags,
                     page_num,
                     total_pages,
                 );
+                context.insert("page_url", &base_url.page_url(&page_pointer.current));
                 self.render_html(&tera, &context, &page_pointer)?;
                 ScrapsResult::Ok(())
             })?;
@@ -172,6 +175,7 @@ mod tests {
     use url::Url;
 
     use super::*;
+    use crate::usecase::build::model::asset::Assets;
     use crate::usecase::build::model::backlinks_map::BacklinksMap;
     use crate::usecase::build::model::paging::Paging;
     use crate::usecase::build::model::scrap_detail::ScrapDetail;
@@ -195,18 +199,18 @@ mod tests {
             &Some(Url::parse("https://github.io/image.png").unwrap()),
         );
         let list_view_configs =
-            ListViewConfigs::new(&true, &SortKey::CommittedDate, &Paging::By(2));
+            ListViewConfigs::new(&true, &false, &SortKey::CommittedDate, &Paging::By(2));
 
         // scraps
         let scrap1 = Scrap::new("scrap1", &None, "# header1");
         let scrap2 = Scrap::new("scrap2", &None, "## header2");
         let scraps = [scrap1.clone(), scrap2.clone()];
-        let scrap_texts = scraps
+        let embeddable = scraps
             .iter()
-            .map(|scrap| (scrap.self_key(), scrap.md_text().to_string()))
+            .map(|scrap| (scrap.self_key(), scrap))
             .collect();
-        let sc1 = ScrapDetail::new(&scrap1, &Some(1), base_url, &scrap_texts);
-        let sc2 = ScrapDetail::new(&scrap2, &Some(0), base_url, &scrap_texts);
+        let sc1 = ScrapDetail::new(&scrap1, &Some(1), base_url, &embeddable, &Assets::default());
+        let sc2 = ScrapDetail::new(&scrap2, &Some(0), base_url, &embeddable, &Assets::default());
         let scrap_details = ScrapDetails::new(&vec![sc1.to_owned(), sc2.to_owned()]);
 
         let scraps = scrap_details.to_scraps();
@@ -247,7 +251,7 @@ mod tests {
             &Some(Url::parse("https://github.io/image.png").unwrap()),
         );
         let list_view_configs =
-            ListViewConfigs::new(&true, &SortKey::CommittedDate, &Paging::By(2));
+            ListViewConfigs::new(&true, &false, &SortKey::CommittedDate, &Paging::By(2));
 
         // scraps
         let scrap1 = Scrap::new("scrap1", &None, "# header1");
@@ -260,14 +264,14 @@ mod tests {
             scrap3.clone(),
             scrap4.clone(),
         ];
-        let scrap_texts = scraps
+        let embeddable = scraps
             .iter()
-            .map(|scrap| (scrap.self_key(), scrap.md_text().to_string()))
+            .map(|scrap| (scrap.self_key(), scrap))
             .collect();
-        let sc1 = ScrapDetail::new(&scrap1, &Some(3), base_url, &scrap_texts);
-        let sc2 = ScrapDetail::new(&scrap2, &Some(2), base_url, &scrap_texts);
-        let sc3 = ScrapDetail::new(&scrap3, &Some(1), base_url, &scrap_texts);
-        let sc4 = ScrapDetail::new(&scrap4, &Some(0), base_url, &scrap_texts);
+        let sc1 = ScrapDetail::new(&scrap1, &Some(3), base_url, &embeddable, &Assets::default());
+        let sc2 = ScrapDetail::new(&scrap2, &Some(2), base_url, &embeddable, &Assets::default());
+        let sc3 = ScrapDetail::new(&scrap3, &Some(1), base_url, &embeddable, &Assets::default());
+        let sc4 = ScrapDetail::new(&scrap4, &Some(0), base_url, &embeddable, &Assets::default());
         let scrap_details = ScrapDetails::new(&vec![
             sc1.to_owned(),
             sc2.to_owned(),
@@ -327,14 +331,14 @@ mod tests {
             &Some(Url::parse("https://github.io/image.png").unwrap()),
         );
         let list_view_configs =
-            ListViewConfigs::new(&true, &SortKey::CommittedDate, &Paging::By(10));
+            ListViewConfigs::new(&true, &false, &SortKey::CommittedDate, &Paging::By(10));
 
         let scrap1 = Scrap::new("scrap1", &None, "# header1");
-        let scrap_texts = [&scrap1]
+        let embeddable = [&scrap1]
             .iter()
-            .map(|scrap| (scrap.self_key(), scrap.md_text().to_string()))
+            .map(|scrap| (scrap.self_key(), *scrap))
             .collect();
-        let sc1 = ScrapDetail::new(&scrap1, &Some(0), base_url, &scrap_texts);
+        let sc1 = ScrapDetail::new(&scrap1, &Some(0), base_url, &embeddable, &Assets::default());
         let scrap_details = ScrapDetails::new(&vec![sc1]);
         let backlinks_map = BacklinksMap::new(&scrap_details.to_scraps());
 
@@ -5,12 +5,15 @@ use std::{fs::File, path::PathBuf};
 
 use crate::error::BuildError;
 use crate::error::{anyhow::Context, ScrapsResult};
+use crate::usecase::build::model::asset::Assets;
 use crate::usecase::build::model::backlinks_map::BacklinksMap;
 use crate::usecase::build::model::html::HtmlMetadata;
+use crate::usecase::build::model::ogp::OgpCache;
 use crate::usecase::build::model::scrap_detail::ScrapDetail;
 use chrono_tz::Tz;
 use scraps_libs::model::base_url::BaseUrl;
 use scraps_libs::model::file::ScrapFileStem;
+use scraps_libs::model::key::ScrapKey;
 use tera::Tera;
 
 use crate::usecase::build::html::tera::scrap_tera;
@@ -37,6 +40,7 @@ impl ScrapRender {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn run(
         &self,
         base_url: &BaseUrl,
@@ -44,17 +48,28 @@ impl ScrapRender {
         metadata: &HtmlMetadata,
         scrap_detail: &ScrapDetail,
         backlinks_map: &BacklinksMap,
+        assets: &Assets,
+        ogp_cache: &OgpCache,
+        build_graph: bool,
     ) -> ScrapsResult<()> {
         let mut context = scrap_tera::context(base_url, timezone, metadata);
+        context.insert("build_graph", &build_graph);
         let scrap = &scrap_detail.scrap();
+        context.insert(
+            "page_url",
+            &base_url.page_url(&scrap_output_path(&scrap.self_key())),
+        );
 
         // insert to context for linked list
-        context.insert("scrap", &ScrapDetailTera::from(scrap_detail.clone()));
+        context.insert(
+            "scrap",
+            &ScrapDetailTera::from(scrap_detail.clone()).with_ogp(ogp_cache),
+        );
 
         let linked_scraps = backlinks_map.get(&scrap.self_key());
         context.insert(
             "linked_scraps",
-            &LinkScrapsTera::new(&linked_scraps, base_url),
+            &LinkScrapsTera::backlinks(&linked_scraps, &scrap.self_key(), assets, base_url),
         );
 
         let file_path = &self
@@ -77,13 +92,21 @@ impl ScrapRender {
     }
 }
 
+/// Path of the scrap page relative to the output directory.
+pub fn scrap_output_path(key: &ScrapKey) -> String {
+    format!("scraps/{}.html", ScrapFileStem::from(key.clone()))
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs;
     use url::Url;
 
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
     use crate::usecase::build::model::backlinks_map::BacklinksMap;
     use crate::usecase::build::model::html::HtmlMetadata;
+    use crate::usecase::build::model::ogp::Ogp;
+    use rstest::rstest;
     use scraps_libs::lang::LangCode;
     use scraps_libs::model::base_url::BaseUrl;
     use scraps_libs::model::scrap::Scrap;
@@ -109,12 +132,16 @@ mod tests {
 
         // scraps
         let commited_ts1 = None;
-        let scrap1 = &Scrap::new("scrap 1", &None, "# header1");
+        let scrap1 = &Scrap::new(
+            "scrap 1",
+            &None,
+            "# header1\n\n<https://example.com/>\n\n<https://example.org/>",
+        );
         let scrap2 = &Scrap::new("scrap 2", &Some("Context".into()), "[[scrap1]]");
         let scraps = vec![scrap1.to_owned(), scrap2.to_owned()];
-        let scrap_texts = scraps
+        let embeddable = scraps
             .iter()
-            .map(|scrap| (scrap.self_key(), scrap.md_text().to_string()))
+            .map(|scrap| (scrap.self_key(), scrap))
             .collect();
         let backlinks_map = BacklinksMap::new(&scraps);
 
@@ -123,6 +150,16 @@ mod tests {
         // dot-suffix on the file stem.
         let scrap2_html_path = output_dir_path.join("scraps/context/scrap-2.html");
 
+        let mut ogp_cache = OgpCache::new();
+        ogp_cache.insert(
+            Url::parse("https://example.com/").unwrap(),
+            Ogp {
+                title: Some("Example".to_string()),
+                description: Some("An example page".to_string()),
+                image: Some(Url::parse("https://example.com/card.png").unwrap()),
+            },
+        );
+
         let render = ScrapRender::new(&static_dir_path, &output_dir_path).unwrap();
 
         render
@@ -130,25 +167,87 @@ mod tests {
                 base_url,
                 timezone,
                 &metadata,
-                &ScrapDetail::new(scrap1, &commited_ts1, base_url, &scrap_texts),
+                &ScrapDetail::new(
+                    scrap1,
+                    &commited_ts1,
+                    base_url,
+                    &embeddable,
+                    &Assets::default(),
+                ),
                 &backlinks_map,
+                &Assets::default(),
+                &ogp_cache,
+                false,
             )
             .unwrap();
 
         let result2 = fs::read_to_string(scrap1_html_path).unwrap();
-        assert!(!result2.is_empty());
+        assert!(result2.contains(r#"<div class="ogp-title">Example</div>"#));
+        assert!(result2.contains(r#"src="https://example.com/card.png""#));
+        assert!(result2.contains(r#"<a href="https://example.org/">https://example.org/</a>"#));
+        assert!(!result2.contains("corsproxy"));
 
         render
             .run(
                 base_url,
                 timezone,
                 &metadata,
-                &ScrapDetail::new(scrap2, &commited_ts1, base_url, &scrap_texts),
+                &ScrapDetail::new(
+                    scrap2,
+                    &commited_ts1,
+                    base_url,
+                    &embeddable,
+                    &Assets::default(),
+                ),
                 &backlinks_map,
+                &Assets::default(),
+                &OgpCache::new(),
+                true,
             )
             .unwrap();
 
+        let result2 = fs::read_to_string(output_dir_path.join("scraps/scrap-1.html")).unwrap();
+        assert!(!result2.contains("local-graph"));
         let result4 = fs::read_to_string(scrap2_html_path).unwrap();
-        assert!(!result4.is_empty());
+        assert!(result4.contains("data-key=\"Context/scrap 2\""));
+        assert!(result4.contains("<script src=\"http://localhost:1112/graph.js\" defer></script>"));
+    }
+
+    #[rstest]
+    fn it_run_renders_backlink_occurrences(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let base_url = &BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let metadata = HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+
+        let target = &Scrap::new("target", &None, "");
+        let source = &Scrap::new(
+            "source",
+            &None,
+            "# Notes\n\n## Usage\n\nRead [[target]] first.",
+        );
+        let scraps = vec![target.to_owned(), source.to_owned()];
+        let embeddable = scraps
+            .iter()
+            .map(|scrap| (scrap.self_key(), scrap))
+            .collect();
+        let backlinks_map = BacklinksMap::new(&scraps);
+
+        ScrapRender::new(&project.static_dir, &project.output_dir)
+            .unwrap()
+            .run(
+                base_url,
+                chrono_tz::UTC,
+                &metadata,
+                &ScrapDetail::new(target, &None, base_url, &embeddable, &Assets::default()),
+                &backlinks_map,
+                &Assets::default(),
+                &OgpCache::new(),
+                false,
+            )
+            .unwrap();
+
+        let result = fs::read_to_string(project.output_path("scraps/target.html")).unwrap();
+        assert!(result.contains(r#"<li class="occurrence">"#));
+        assert!(result.contains("Notes › Usage"));
+        assert!(result.contains("Read target first."));
     }
 }
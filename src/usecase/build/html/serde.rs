@@ -1,4 +1,5 @@
 pub mod content;
+pub mod ctx;
 pub mod index_scraps;
 pub mod link_scraps;
 pub mod scrap_detail;
@@ -1,9 +1,17 @@
+use std::collections::BTreeMap;
+
 use scraps_libs::model::content::{Content, ContentElement};
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 use url::Url;
 
-fn serialize_content_element<S>(element: &ContentElement, serializer: S) -> Result<S::Ok, S::Error>
+use crate::usecase::build::model::ogp::{Ogp, OgpCache};
+
+fn serialize_content_element<S>(
+    element: &ContentElement,
+    ogp: Option<&Ogp>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
@@ -18,22 +26,59 @@ where
             &ContentElementAutolink {
                 url: url.clone(),
                 host: url.host_str().map(|s| s.to_string()),
+                ogp,
             },
         ),
     }
 }
 
 #[derive(Serialize)]
-struct ContentElementAutolink {
+struct ContentElementAutolink<'a> {
     url: Url,
     host: Option<String>,
+    ogp: Option<&'a Ogp>,
 }
 
-#[derive(Serialize)]
-struct ContentElementTera(#[serde(serialize_with = "serialize_content_element")] ContentElement);
+struct ContentElementTera<'a> {
+    element: &'a ContentElement,
+    ogp: Option<&'a Ogp>,
+}
+
+impl Serialize for ContentElementTera<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_content_element(self.element, self.ogp, serializer)
+    }
+}
 
 #[derive(Clone, PartialEq, Debug)]
-pub struct ContentTera(Content);
+pub struct ContentTera {
+    content: Content,
+    /// Cards of the autolinks, by URL. Autolinks without one render as
+    /// plain links.
+    ogp: BTreeMap<Url, Ogp>,
+}
+
+impl ContentTera {
+    /// Attach the cached Open Graph metadata of the autolinks.
+    pub fn with_ogp(mut self, ogp_cache: &OgpCache) -> ContentTera {
+        self.ogp = self
+            .content
+            .elements()
+            .iter()
+            .filter_map(|element| match element {
+                ContentElement::Autolink(url) => ogp_cache
+                    .get(url)
+                    .filter(|ogp| !ogp.is_empty())
+                    .map(|ogp| (url.clone(), ogp.clone())),
+                ContentElement::Raw(_) => None,
+            })
+            .collect();
+        self
+    }
+}
 
 impl Serialize for ContentTera {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -42,10 +87,16 @@ impl Serialize for ContentTera {
     {
         let mut state = serializer.serialize_struct("Content", 1)?;
         let elements: Vec<ContentElementTera> = self
-            .0
+            .content
             .elements()
             .iter()
-            .map(|element| ContentElementTera(element.clone()))
+            .map(|element| ContentElementTera {
+                element,
+                ogp: match element {
+                    ContentElement::Autolink(url) => self.ogp.get(url),
+                    ContentElement::Raw(_) => None,
+                },
+            })
             .collect();
         state.serialize_field("elements", &elements)?;
         state.end()
@@ -54,6 +105,46 @@ impl Serialize for ContentTera {
 
 impl From<Content> for ContentTera {
     fn from(content: Content) -> Self {
-        ContentTera(content)
+        ContentTera {
+            content,
+            ogp: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_serializes_autolinks_with_ogp() {
+        let fetched = Url::parse("https://example.com/a").unwrap();
+        let empty = Url::parse("https://example.com/b").unwrap();
+        let content = Content::new(vec![
+            ContentElement::Raw("<p>".to_string()),
+            ContentElement::Autolink(fetched.clone()),
+            ContentElement::Autolink(empty.clone()),
+        ]);
+        let mut ogp_cache = OgpCache::new();
+        ogp_cache.insert(
+            fetched,
+            Ogp {
+                title: Some("A".to_string()),
+                ..Ogp::default()
+            },
+        );
+        ogp_cache.insert(empty, Ogp::default());
+
+        let json = serde_json::to_value(ContentTera::from(content).with_ogp(&ogp_cache)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "elements": [
+                    {"raw": "<p>"},
+                    {"autolink": {"url": "https://example.com/a", "host": "example.com", "ogp": {"title": "A"}}},
+                    {"autolink": {"url": "https://example.com/b", "host": "example.com", "ogp": null}},
+                ]
+            })
+        );
     }
 }
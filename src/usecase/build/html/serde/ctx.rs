@@ -0,0 +1,88 @@
+use scraps_libs::{model::context::Ctx, slugify};
+
+use crate::usecase::build::model::ctx_page::CtxPage;
+
+use super::content::ContentTera;
+
+/// A context linking to its page, as one segment of a breadcrumb or an entry
+/// of a child list.
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+pub struct CtxLinkTera {
+    /// The last segment, e.g. `Rust` for `Programming/Rust`.
+    name: String,
+    /// Slug-form path with each segment slugified, joined by `/`.
+    /// Used to build the context's URL: `/scraps/<slug>/`.
+    slug: String,
+}
+
+impl CtxLinkTera {
+    pub fn new(ctx: &Ctx) -> CtxLinkTera {
+        CtxLinkTera {
+            name: ctx.segments().last().cloned().unwrap_or_default(),
+            slug: ctx
+                .segments()
+                .iter()
+                .map(|s| slugify::by_dash(s))
+                .collect::<Vec<_>>()
+                .join("/"),
+        }
+    }
+
+    /// One link per segment of `ctx`, outermost first.
+    pub fn breadcrumbs(ctx: &Ctx) -> Vec<CtxLinkTera> {
+        ctx.prefixes().iter().map(CtxLinkTera::new).collect()
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct CtxTera {
+    /// Full context path for display, e.g. `Programming/Rust`.
+    title: String,
+    name: String,
+    /// Links to the enclosing contexts, outermost first.
+    breadcrumbs: Vec<CtxLinkTera>,
+    readme: Option<ContentTera>,
+    children: Vec<CtxLinkTera>,
+}
+
+impl CtxTera {
+    pub fn new(ctx_page: &CtxPage) -> CtxTera {
+        let ctx = &ctx_page.ctx;
+        CtxTera {
+            title: ctx.to_string(),
+            name: CtxLinkTera::new(ctx).name,
+            breadcrumbs: ctx
+                .parent()
+                .map(|parent| CtxLinkTera::breadcrumbs(&parent))
+                .unwrap_or_default(),
+            readme: ctx_page
+                .readme
+                .as_ref()
+                .map(|readme| readme.content().into()),
+            children: ctx_page.children.iter().map(CtxLinkTera::new).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_breadcrumbs_with_slugified_paths() {
+        let crumbs = CtxLinkTera::breadcrumbs(&Ctx::from("Programming/Rust Lang"));
+        assert_eq!(
+            crumbs,
+            vec![
+                CtxLinkTera {
+                    name: "Programming".to_string(),
+                    slug: "programming".to_string(),
+                },
+                CtxLinkTera {
+                    name: "Rust Lang".to_string(),
+                    slug: "programming/rust-lang".to_string(),
+                },
+            ]
+        );
+    }
+}
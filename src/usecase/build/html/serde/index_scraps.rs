@@ -75,6 +75,7 @@ mod tests {
     use scraps_libs::model::{base_url::BaseUrl, scrap::Scrap};
 
     use super::*;
+    use crate::usecase::build::model::asset::Assets;
 
     #[test]
     fn it_new_with_sort() {
@@ -89,15 +90,15 @@ mod tests {
             scrap3.clone(),
             scrap4.clone(),
         ];
-        let scrap_texts = scraps
+        let embeddable = scraps
             .iter()
-            .map(|scrap| (scrap.self_key(), scrap.md_text().to_string()))
+            .map(|scrap| (scrap.self_key(), scrap))
             .collect();
 
-        let sc1 = ScrapDetail::new(&scrap1, &None, base_url, &scrap_texts);
-        let sc2 = ScrapDetail::new(&scrap2, &Some(3), base_url, &scrap_texts);
-        let sc3 = ScrapDetail::new(&scrap3, &Some(2), base_url, &scrap_texts);
-        let sc4 = ScrapDetail::new(&scrap4, &Some(1), base_url, &scrap_texts);
+        let sc1 = ScrapDetail::new(&scrap1, &None, base_url, &embeddable, &Assets::default());
+        let sc2 = ScrapDetail::new(&scrap2, &Some(3), base_url, &embeddable, &Assets::default());
+        let sc3 = ScrapDetail::new(&scrap3, &Some(2), base_url, &embeddable, &Assets::default());
+        let sc4 = ScrapDetail::new(&scrap4, &Some(1), base_url, &embeddable, &Assets::default());
         let backlinks_map =
             BacklinksMap::new(&[sc1.scrap(), sc2.scrap(), sc3.scrap(), sc4.scrap()]);
 
@@ -2,10 +2,12 @@ use itertools::Itertools;
 use url::Url;
 
 use scraps_libs::{
-    html::{self, EmbedMode},
-    model::{base_url::BaseUrl, file::ScrapFileStem, scrap::Scrap},
+    html::EmbedMode,
+    model::{base_url::BaseUrl, file::ScrapFileStem, key::ScrapKey, scrap::Scrap},
 };
 
+use crate::usecase::build::model::{asset::Assets, backlinks_map::BacklinkOccurrence};
+
 #[derive(serde::Serialize, Clone, PartialEq, Debug)]
 struct SerializeLinkScrap {
     ctx: Option<String>,
@@ -13,11 +15,33 @@ struct SerializeLinkScrap {
     html_file_name: String,
     html_text: String,
     thumbnail: Option<Url>,
+    occurrences: Vec<SerializeOccurrence>,
+}
+
+#[derive(serde::Serialize, Clone, PartialEq, Debug)]
+struct SerializeOccurrence {
+    line: usize,
+    heading_path: Vec<String>,
+    /// The block without markdown syntax.
+    text: String,
+}
+
+impl SerializeOccurrence {
+    fn new(occurrence: BacklinkOccurrence, scrap: &Scrap) -> SerializeOccurrence {
+        SerializeOccurrence {
+            line: occurrence.line,
+            heading_path: occurrence.heading_path,
+            text: scrap.document().plain_text(occurrence.lines),
+        }
+    }
 }
 
 impl SerializeLinkScrap {
-    fn new(scrap: &Scrap, base_url: &BaseUrl) -> SerializeLinkScrap {
-        let content = html::to_content(scrap.md_text(), base_url, EmbedMode::Preserve);
+    fn new(scrap: &Scrap, assets: &Assets, base_url: &BaseUrl) -> SerializeLinkScrap {
+        let content =
+            scrap
+                .document()
+                .to_content(scrap.ctx(), assets.paths(), base_url, EmbedMode::Preserve);
         let html_file_name = format!("{}.html", ScrapFileStem::from(scrap.self_key().clone()));
         SerializeLinkScrap {
             ctx: scrap.ctx().as_ref().map(|c| c.to_string()),
@@ -25,6 +49,22 @@ impl SerializeLinkScrap {
             html_file_name,
             html_text: content.to_string(),
             thumbnail: scrap.thumbnail(),
+            occurrences: Vec::new(),
+        }
+    }
+
+    fn with_occurrences(
+        scrap: &Scrap,
+        target: &ScrapKey,
+        assets: &Assets,
+        base_url: &BaseUrl,
+    ) -> SerializeLinkScrap {
+        SerializeLinkScrap {
+            occurrences: BacklinkOccurrence::find(scrap, target)
+                .into_iter()
+                .map(|o| SerializeOccurrence::new(o, scrap))
+                .collect(),
+            ..Self::new(scrap, assets, base_url)
         }
     }
 }
@@ -33,10 +73,25 @@ impl SerializeLinkScrap {
 pub struct LinkScrapsTera(Vec<SerializeLinkScrap>);
 
 impl LinkScrapsTera {
-    pub fn new(scraps: &[Scrap], base_url: &BaseUrl) -> LinkScrapsTera {
+    pub fn new(scraps: &[Scrap], assets: &Assets, base_url: &BaseUrl) -> LinkScrapsTera {
+        let serialize_scraps = scraps
+            .iter()
+            .map(|s| SerializeLinkScrap::new(s, assets, base_url))
+            .collect_vec();
+
+        LinkScrapsTera(serialize_scraps)
+    }
+
+    /// Backlinks of `target`, each carrying where it links to the target.
+    pub fn backlinks(
+        scraps: &[Scrap],
+        target: &ScrapKey,
+        assets: &Assets,
+        base_url: &BaseUrl,
+    ) -> LinkScrapsTera {
         let serialize_scraps = scraps
             .iter()
-            .map(|s| SerializeLinkScrap::new(s, base_url))
+            .map(|s| SerializeLinkScrap::with_occurrences(s, target, assets, base_url))
             .collect_vec();
 
         LinkScrapsTera(serialize_scraps)
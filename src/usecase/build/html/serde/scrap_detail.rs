@@ -1,13 +1,17 @@
 use scraps_libs::model::file::ScrapFileStem;
 use url::Url;
 
-use crate::usecase::build::model::scrap_detail::ScrapDetail;
+use crate::usecase::build::model::{ogp::OgpCache, scrap_detail::ScrapDetail};
 
 use super::content::ContentTera;
+use super::ctx::CtxLinkTera;
 
 #[derive(serde::Serialize, Clone, PartialEq, Debug)]
 pub struct ScrapDetailTera {
+    key: String,
     ctx: Option<String>,
+    /// Links to the scrap's context and its ancestors, outermost first.
+    ctx_breadcrumbs: Vec<CtxLinkTera>,
     title: String,
     html_file_name: String,
     content: ContentTera,
@@ -15,6 +19,16 @@ pub struct ScrapDetailTera {
     commited_ts: Option<i64>,
 }
 
+impl ScrapDetailTera {
+    /// Render the autolinks of the content as cards.
+    pub fn with_ogp(self, ogp_cache: &OgpCache) -> ScrapDetailTera {
+        ScrapDetailTera {
+            content: self.content.with_ogp(ogp_cache),
+            ..self
+        }
+    }
+}
+
 impl From<ScrapDetail> for ScrapDetailTera {
     fn from(scrap_detail: ScrapDetail) -> Self {
         let scrap = scrap_detail.scrap();
@@ -22,7 +36,13 @@ impl From<ScrapDetail> for ScrapDetailTera {
         let content = scrap_detail.content();
         let html_file_name = format!("{}.html", ScrapFileStem::from(scrap.self_key()));
         ScrapDetailTera {
+            key: scrap.self_key().to_string(),
             ctx: scrap.ctx().as_ref().map(|ctx| ctx.to_string()),
+            ctx_breadcrumbs: scrap
+                .ctx()
+                .as_ref()
+                .map(CtxLinkTera::breadcrumbs)
+                .unwrap_or_default(),
             title: scrap.title().to_string(),
             html_file_name,
             content: content.into(),
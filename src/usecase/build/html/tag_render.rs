@@ -5,6 +5,7 @@ use std::{fs::File, path::PathBuf};
 
 use crate::error::BuildError;
 use crate::error::{anyhow::Context, ScrapsResult};
+use crate::usecase::build::model::asset::Assets;
 use crate::usecase::build::model::backlinks_map::BacklinksMap;
 use crate::usecase::build::model::html::HtmlMetadata;
 use scraps_libs::model::base_url::BaseUrl;
@@ -44,8 +45,10 @@ impl TagRender {
         metadata: &HtmlMetadata,
         tag: &Tag,
         backlinks_map: &BacklinksMap,
+        assets: &Assets,
     ) -> ScrapsResult<()> {
         let mut context = tag_tera::context(base_url, metadata);
+        context.insert("page_url", &base_url.page_url(&tag_output_path(tag)));
 
         // insert to context for linked list
         context.insert("tag", &TagTera::new(tag, backlinks_map));
@@ -53,7 +56,7 @@ impl TagRender {
         let linked_scraps = backlinks_map.get_tag(tag);
         context.insert(
             "linked_scraps",
-            &LinkScrapsTera::new(&linked_scraps, base_url),
+            &LinkScrapsTera::new(&linked_scraps, assets, base_url),
         );
 
         // Build the slug-based path: `tags/<slug-segment>/<...>.html`. Each
@@ -77,6 +80,11 @@ impl TagRender {
     }
 }
 
+/// Path of the tag page relative to the output directory.
+pub fn tag_output_path(tag: &Tag) -> String {
+    format!("tags/{}.html", tag_slug_path(tag))
+}
+
 fn tag_slug_path(tag: &Tag) -> String {
     tag.segments()
         .iter()
@@ -126,7 +134,13 @@ mod tests {
         let render = TagRender::new(&static_dir_path, &output_dir_path).unwrap();
 
         render
-            .run(&base_url, &metadata, &tag1, &backlinks_map)
+            .run(
+                &base_url,
+                &metadata,
+                &tag1,
+                &backlinks_map,
+                &Assets::default(),
+            )
             .unwrap();
 
         let result2 = fs::read_to_string(tag1_html_path).unwrap();
@@ -158,7 +172,13 @@ mod tests {
 
         let render = TagRender::new(&static_dir_path, &output_dir_path).unwrap();
         render
-            .run(&base_url, &metadata, &tag, &backlinks_map)
+            .run(
+                &base_url,
+                &metadata,
+                &tag,
+                &backlinks_map,
+                &Assets::default(),
+            )
             .unwrap();
 
         let body = fs::read_to_string(html_path).unwrap();
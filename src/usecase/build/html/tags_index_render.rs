@@ -60,6 +60,7 @@ impl TagsIndexRender {
             "__builtins/tags_index.html"
         };
         context.insert("tags", tags);
+        context.insert("page_url", &base_url.page_url("tags/"));
         let file_path = &self.output_tags_dir_path.join("index.html");
         // tera renders in many small writes, so buffer them into one file write.
         let mut wtr = BufWriter::new(
@@ -1,3 +1,5 @@
+pub mod ctx_tera;
+pub mod graph_tera;
 pub mod index_tera;
 pub mod scrap_tera;
 pub mod tag_tera;
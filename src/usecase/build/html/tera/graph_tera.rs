@@ -0,0 +1,48 @@
+use crate::error::BuildError;
+use crate::error::{anyhow::Context, ScrapsResult};
+use crate::usecase::build::html::cdn_versions::CDN_VERSIONS;
+use crate::usecase::build::model::html::HtmlMetadata;
+use once_cell::sync::Lazy;
+use scraps_libs::model::base_url::BaseUrl;
+use tera::Tera;
+
+static GRAPH_TERA: Lazy<Tera> = Lazy::new(|| {
+    let mut tera = Tera::default();
+    crate::service::tera_filters::register(&mut tera);
+    tera.add_raw_templates(vec![
+        (
+            "__builtins/base.html",
+            include_str!("../builtins/base.html"),
+        ),
+        (
+            "__builtins/macros.html",
+            include_str!("../builtins/macros.html"),
+        ),
+        (
+            "__builtins/graph.html",
+            include_str!("../builtins/graph.html"),
+        ),
+    ])
+    .unwrap();
+    tera
+});
+
+pub fn base(
+    base_url: &BaseUrl,
+    metadata: &HtmlMetadata,
+    template_dir: &str,
+) -> ScrapsResult<(Tera, tera::Context)> {
+    let mut tera = GRAPH_TERA.clone();
+    tera.load_from_glob(template_dir)
+        .context(BuildError::RenderHtml)?;
+
+    let mut context = tera::Context::new();
+    context.insert("base_url", &base_url.as_url());
+    context.insert("lang_code", &metadata.lang_code().to_string());
+    context.insert("title", &metadata.title());
+    context.insert("description", &metadata.description());
+    context.insert("favicon", &metadata.favicon());
+    context.insert("cdn", &CDN_VERSIONS);
+
+    Ok((tera, context))
+}
@@ -0,0 +1,75 @@
+use std::io::{BufWriter, Write};
+use std::{fs::File, path::PathBuf};
+
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
+use crate::usecase::build::model::ir::ScrapsIr;
+
+const SCHEMA: &str = include_str!("builtins/scraps.schema.json");
+
+pub struct IrRender {
+    output_dir_path: PathBuf,
+}
+
+impl IrRender {
+    pub fn new(output_dir_path: &PathBuf) -> ScrapsResult<IrRender> {
+        std::fs::create_dir_all(output_dir_path).context(BuildError::CreateDir)?;
+
+        Ok(IrRender {
+            output_dir_path: output_dir_path.to_owned(),
+        })
+    }
+
+    pub fn run(&self, ir: &ScrapsIr) -> ScrapsResult<()> {
+        let file_path = &self.output_dir_path.join("scraps.json");
+        let wtr = BufWriter::new(
+            File::create(file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        serde_json::to_writer(wtr, ir).context(BuildError::WriteFailure(file_path.clone()))?;
+
+        let schema_path = &self.output_dir_path.join("scraps.schema.json");
+        let mut wtr = BufWriter::new(
+            File::create(schema_path).context(BuildError::WriteFailure(schema_path.clone()))?,
+        );
+        wtr.write_all(SCHEMA.as_bytes())
+            .context(BuildError::WriteFailure(schema_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(schema_path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::build::model::{backlinks_map::BacklinksMap, ir::IR_SCHEMA_VERSION};
+    use rstest::rstest;
+    use scraps_libs::model::scrap::Scrap;
+    use serde_json::Value;
+    use std::fs;
+
+    use super::*;
+
+    #[rstest]
+    fn it_run(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let scraps_with_ts = vec![(Scrap::new("note", &None, "#[[ai]]"), None)];
+        let backlinks_map = BacklinksMap::new(&[scraps_with_ts[0].0.clone()]);
+        let ir = ScrapsIr::new(&scraps_with_ts, &backlinks_map);
+
+        let render = IrRender::new(&project.output_dir).unwrap();
+        render.run(&ir).unwrap();
+
+        let json: Value =
+            serde_json::from_str(&fs::read_to_string(project.output_path("scraps.json")).unwrap())
+                .unwrap();
+        assert_eq!(json["scraps"][0]["key"], "note");
+        assert_eq!(json["tags"][0]["name"], "ai");
+
+        let schema: Value = serde_json::from_str(
+            &fs::read_to_string(project.output_path("scraps.schema.json")).unwrap(),
+        )
+        .unwrap();
+        assert!(schema["$id"]
+            .as_str()
+            .unwrap()
+            .contains(&format!("scraps-{IR_SCHEMA_VERSION}")));
+    }
+}
@@ -0,0 +1,156 @@
+use std::io::{BufWriter, Write};
+use std::{fs::File, path::PathBuf};
+
+use itertools::Itertools;
+
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
+use crate::usecase::build::model::{
+    html::HtmlMetadata,
+    llms_corpus::{LlmsCorpus, LlmsEntry},
+};
+
+pub struct LlmsRender {
+    output_dir_path: PathBuf,
+}
+
+impl LlmsRender {
+    pub fn new(output_dir_path: &PathBuf) -> ScrapsResult<LlmsRender> {
+        std::fs::create_dir_all(output_dir_path).context(BuildError::CreateDir)?;
+
+        Ok(LlmsRender {
+            output_dir_path: output_dir_path.to_owned(),
+        })
+    }
+
+    pub fn run(&self, html_metadata: &HtmlMetadata, llms: &LlmsCorpus) -> ScrapsResult<()> {
+        self.write("llms.txt", &Self::index_text(html_metadata, llms))?;
+        self.write("llms-full.txt", &Self::full_text(html_metadata, llms))
+    }
+
+    fn index_text(html_metadata: &HtmlMetadata, llms: &LlmsCorpus) -> String {
+        let mut out = Self::header(html_metadata);
+        out.push_str("\nThe full corpus is available at llms-full.txt.\n");
+
+        // Root scraps first, then one section per context.
+        let sections = llms
+            .index
+            .iter()
+            .sorted_by_key(|entry| entry.key.ctx().as_ref().map(|c| c.to_string()))
+            .chunk_by(|entry| entry.key.ctx().as_ref().map(|c| c.to_string()));
+        for (ctx, entries) in &sections {
+            out.push_str(&format!("\n## {}\n\n", ctx.as_deref().unwrap_or("Scraps")));
+            for entry in entries {
+                out.push_str(&format!("- [{}]({})\n", entry.key.title(), entry.url));
+            }
+        }
+        out
+    }
+
+    fn full_text(html_metadata: &HtmlMetadata, llms: &LlmsCorpus) -> String {
+        let mut out = Self::header(html_metadata);
+        if let Some(readme) = &llms.readme {
+            out.push('\n');
+            out.push_str(readme.trim());
+            out.push('\n');
+        }
+        for entry in &llms.corpus {
+            out.push_str(&Self::full_entry(entry));
+        }
+        out
+    }
+
+    fn full_entry(entry: &LlmsEntry) -> String {
+        format!(
+            "\n---\n\n# {}\n\nSource: {}\n\n{}\n",
+            entry.key,
+            entry.url,
+            entry.text.trim()
+        )
+    }
+
+    fn header(html_metadata: &HtmlMetadata) -> String {
+        let mut out = format!("# {}\n", html_metadata.title());
+        if let Some(description) = html_metadata.description() {
+            out.push_str(&format!("\n> {description}\n"));
+        }
+        out
+    }
+
+    fn write(&self, file_name: &str, text: &str) -> ScrapsResult<()> {
+        let file_path = &self.output_dir_path.join(file_name);
+        let mut wtr = BufWriter::new(
+            File::create(file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        wtr.write_all(text.as_bytes())
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(file_path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::build::model::backlinks_map::BacklinksMap;
+    use rstest::rstest;
+    use scraps_libs::lang::LangCode;
+    use scraps_libs::model::{base_url::BaseUrl, scrap::Scrap};
+    use std::fs;
+    use url::Url;
+
+    use super::*;
+
+    #[rstest]
+    fn it_run(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let html_metadata = HtmlMetadata::new(
+            &LangCode::default(),
+            "Wiki",
+            &Some("My knowledge".to_string()),
+            &None,
+        );
+        let scraps = vec![
+            Scrap::new("note", &None, "see [[Book/DDD]] #[[ai]]"),
+            Scrap::new("DDD", &Some("Book".into()), "# Layers\n\nbody"),
+        ];
+        let backlinks_map = BacklinksMap::new(&scraps);
+        let llms = LlmsCorpus::new(
+            &scraps,
+            &Some("Welcome".to_string()),
+            &base_url,
+            &backlinks_map,
+            None,
+        );
+
+        let render = LlmsRender::new(&project.output_dir).unwrap();
+        render.run(&html_metadata, &llms).unwrap();
+
+        let index = fs::read_to_string(project.output_path("llms.txt")).unwrap();
+        assert_eq!(
+            index,
+            concat!(
+                "# Wiki\n\n> My knowledge\n",
+                "\nThe full corpus is available at llms-full.txt.\n",
+                "\n## Scraps\n\n",
+                "- [note](http://localhost:1112/scraps/note.html)\n",
+                "\n## Book\n\n",
+                "- [DDD](http://localhost:1112/scraps/book/ddd.html)\n",
+            )
+        );
+
+        let full = fs::read_to_string(project.output_path("llms-full.txt")).unwrap();
+        assert_eq!(
+            full,
+            concat!(
+                "# Wiki\n\n> My knowledge\n",
+                "\nWelcome\n",
+                "\n---\n\n# Book/DDD\n\n",
+                "Source: http://localhost:1112/scraps/book/ddd.html\n\n",
+                "# Layers\n\nbody\n",
+                "\n---\n\n# note\n\n",
+                "Source: http://localhost:1112/scraps/note.html\n\n",
+                "see DDD (→ Book/DDD) #ai\n",
+            )
+        );
+    }
+}
@@ -1,8 +1,19 @@
+pub mod affected_pages;
+pub mod asset;
 pub mod backlinks_map;
+pub mod build_manifest;
 pub mod color_scheme;
 pub mod css;
+pub mod ctx_page;
+pub mod feed;
+pub mod graph;
 pub mod html;
+pub mod ir;
 pub mod list_view_configs;
+pub mod llms_corpus;
+pub mod ogp;
 pub mod paging;
 pub mod scrap_detail;
+pub mod sitemap;
 pub mod sort;
+pub mod target;
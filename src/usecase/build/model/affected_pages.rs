@@ -0,0 +1,163 @@
+use std::collections::{HashMap, HashSet};
+
+use scraps_libs::model::{key::ScrapKey, scrap::Scrap, tag::Tag, tags::Tags};
+
+/// Pages whose HTML depends on a set of changed scraps, so a rebuild can skip
+/// the rest of the site. Index pages, the tags index and the search index
+/// list every scrap and are always regenerated on top of these.
+#[derive(Debug, PartialEq, Default)]
+pub struct AffectedPages {
+    pub scraps: HashSet<ScrapKey>,
+    pub tags: HashSet<Tag>,
+}
+
+impl AffectedPages {
+    /// Compare two snapshots of a wiki. A scrap is changed when it was added,
+    /// removed or its text differs. Affected are, among the scraps that still
+    /// exist:
+    ///
+    /// - the changed scraps themselves,
+    /// - scraps linking to a changed scrap, whose link may now resolve or not,
+    /// - scraps a changed scrap links to (before or after), whose backlinks
+    ///   changed,
+    /// - scraps embedding a changed scrap, transitively through embeds,
+    ///
+    /// plus the tag pages, with their ancestors, of the changed scraps.
+    pub fn between(before: &[Scrap], after: &[Scrap]) -> AffectedPages {
+        let before_by_key: HashMap<ScrapKey, &Scrap> =
+            before.iter().map(|s| (s.self_key(), s)).collect();
+        let after_by_key: HashMap<ScrapKey, &Scrap> =
+            after.iter().map(|s| (s.self_key(), s)).collect();
+
+        let changed: HashSet<ScrapKey> = before_by_key
+            .keys()
+            .chain(after_by_key.keys())
+            .filter(
+                |key| match (before_by_key.get(*key), after_by_key.get(*key)) {
+                    (Some(b), Some(a)) => b.md_text() != a.md_text(),
+                    _ => true,
+                },
+            )
+            .cloned()
+            .collect();
+        if changed.is_empty() {
+            return AffectedPages::default();
+        }
+
+        let changed_versions: Vec<Scrap> = changed
+            .iter()
+            .flat_map(|key| [before_by_key.get(key), after_by_key.get(key)])
+            .flatten()
+            .map(|s| (*s).clone())
+            .collect();
+
+        let mut scraps: HashSet<ScrapKey> = changed.clone();
+        scraps.extend(
+            changed_versions
+                .iter()
+                .flat_map(|s| s.links().iter().cloned()),
+        );
+        scraps.extend(
+            after
+                .iter()
+                .filter(|s| s.links().iter().any(|l| changed.contains(l)))
+                .map(|s| s.self_key()),
+        );
+
+        // Embeds are expanded at build time, so an embedding scrap renders
+        // stale content until it is rebuilt too.
+        let embedders: Vec<(ScrapKey, HashSet<ScrapKey>)> = after
+            .iter()
+            .map(|s| (s.self_key(), embedded_keys(s)))
+            .filter(|(_, embedded)| !embedded.is_empty())
+            .collect();
+        let mut frontier: Vec<ScrapKey> = changed.iter().cloned().collect();
+        let mut expanded: HashSet<ScrapKey> = HashSet::new();
+        while let Some(key) = frontier.pop() {
+            if !expanded.insert(key.clone()) {
+                continue;
+            }
+            for (embedder, embedded) in &embedders {
+                if embedded.contains(&key) {
+                    scraps.insert(embedder.clone());
+                    frontier.push(embedder.clone());
+                }
+            }
+        }
+
+        scraps.retain(|key| after_by_key.contains_key(key));
+
+        AffectedPages {
+            scraps,
+            tags: Tags::new(&changed_versions).into_iter().collect(),
+        }
+    }
+}
+
+fn embedded_keys(scrap: &Scrap) -> HashSet<ScrapKey> {
+    scrap
+        .document()
+        .embeds()
+        .cloned()
+        .map(|e| {
+            let mut path = e.ctx_path;
+            path.push(e.title);
+            ScrapKey::from_path_str(&path.join("/"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraps_libs::model::title::Title;
+
+    fn keys(titles: &[&str]) -> HashSet<ScrapKey> {
+        titles
+            .iter()
+            .map(|t| ScrapKey::from(Title::from(*t)))
+            .collect()
+    }
+
+    #[test]
+    fn it_is_empty_when_nothing_changed() {
+        let scraps = vec![Scrap::new("a", &None, "[[b]]"), Scrap::new("b", &None, "")];
+        let affected = AffectedPages::between(&scraps, &scraps);
+        assert_eq!(affected, AffectedPages::default());
+    }
+
+    #[test]
+    fn it_includes_linked_linking_and_embedding_scraps() {
+        let before = vec![
+            Scrap::new("a", &None, "[[b]] #[[lang/rust]]"),
+            Scrap::new("b", &None, ""),
+            Scrap::new("c", &None, "[[a]]"),
+            Scrap::new("d", &None, "![[a]]"),
+            Scrap::new("e", &None, "![[d]]"),
+            Scrap::new("f", &None, "unrelated"),
+        ];
+        let mut after = before.clone();
+        after[0] = Scrap::new("a", &None, "now links [[f]]");
+
+        let affected = AffectedPages::between(&before, &after);
+        assert_eq!(affected.scraps, keys(&["a", "b", "c", "d", "e", "f"]));
+        assert_eq!(
+            affected.tags,
+            HashSet::from([Tag::from("lang/rust"), Tag::from("lang")])
+        );
+    }
+
+    #[test]
+    fn it_skips_removed_scraps_but_rebuilds_their_neighbours() {
+        let before = vec![
+            Scrap::new("a", &None, "[[b]]"),
+            Scrap::new("b", &None, ""),
+            Scrap::new("c", &None, ""),
+        ];
+        let after = vec![Scrap::new("a", &None, "[[b]]"), Scrap::new("c", &None, "")];
+
+        let affected = AffectedPages::between(&before, &after);
+        assert_eq!(affected.scraps, keys(&["a"]));
+        assert!(affected.tags.is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use scraps_libs::model::{file::AssetPath, scrap::Scrap};
+
+use crate::usecase::build::html::{ctx_render::ctx_output_path, scrap_render::scrap_output_path};
+
+/// A non-markdown file under the wiki root, copied next to the scrap pages
+/// so that relative image and link URLs keep working.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub source: PathBuf,
+    pub path: AssetPath,
+}
+
+impl Asset {
+    /// Path of the copy relative to the output directory.
+    pub fn output_path(&self) -> String {
+        format!("scraps/{}", self.path)
+    }
+}
+
+/// The assets a build copies. An asset whose copy would overwrite the page of
+/// a scrap or context, or the copy of another asset, is left out with a
+/// warning.
+#[derive(Debug, Default)]
+pub struct Assets {
+    assets: Vec<Asset>,
+    paths: HashSet<AssetPath>,
+}
+
+impl Assets {
+    pub fn new(assets: &[Asset], scraps: &[Scrap]) -> Assets {
+        let mut owners: HashMap<String, String> = HashMap::new();
+        for scrap in scraps {
+            let key = scrap.self_key();
+            owners.insert(scrap_output_path(&key), format!("scrap {key}"));
+            if let Some(ctx) = scrap.ctx() {
+                for prefix in ctx.prefixes() {
+                    owners
+                        .entry(ctx_output_path(&prefix))
+                        .or_insert_with(|| format!("context {prefix}"));
+                }
+            }
+        }
+
+        let mut kept = Vec::new();
+        for asset in assets {
+            let output = asset.output_path();
+            if let Some(owner) = owners.get(&output) {
+                tracing::warn!(
+                    "skipped {}: its copy {output} would overwrite the page of {owner}",
+                    asset.source.display()
+                );
+                continue;
+            }
+            owners.insert(output, format!("asset {}", asset.source.display()));
+            kept.push(asset.clone());
+        }
+
+        Assets {
+            paths: kept.iter().map(|asset| asset.path.clone()).collect(),
+            assets: kept,
+        }
+    }
+
+    /// Paths of the assets, which `html::to_content` points relative URLs at.
+    pub fn paths(&self) -> &HashSet<AssetPath> {
+        &self.paths
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Asset> {
+        self.assets.iter()
+    }
+
+    pub fn as_slice(&self) -> &[Asset] {
+        &self.assets
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.assets.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(source: &str) -> Asset {
+        let segments: Vec<&str> = source.split('/').collect();
+        Asset {
+            source: PathBuf::from(source),
+            path: AssetPath::new(&segments).unwrap(),
+        }
+    }
+
+    #[test]
+    fn it_output_path() {
+        assert_eq!(
+            asset("Programming/Rust/ownership.png").output_path(),
+            "scraps/programming/rust/ownership.png"
+        );
+    }
+
+    #[test]
+    fn it_skips_assets_overwriting_pages_or_other_assets() {
+        let scraps = vec![
+            Scrap::new("note", &None, ""),
+            Scrap::new("rust", &Some("Programming".into()), ""),
+        ];
+        let assets = Assets::new(
+            &[
+                asset("note.html"),
+                asset("Programming/index.html"),
+                asset("Programming/rust.png"),
+                asset("programming/rust.png"),
+                asset("note.png"),
+            ],
+            &scraps,
+        );
+
+        let kept: Vec<_> = assets.iter().map(|a| a.source.clone()).collect();
+        assert_eq!(
+            kept,
+            vec![
+                PathBuf::from("Programming/rust.png"),
+                PathBuf::from("note.png")
+            ]
+        );
+        assert!(assets
+            .paths()
+            .contains(&AssetPath::new(&["note.png"]).unwrap()));
+        assert!(!assets
+            .paths()
+            .contains(&AssetPath::new(&["note.html"]).unwrap()));
+    }
+}
@@ -1,5 +1,8 @@
 use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
 
+use itertools::Itertools;
+use scraps_libs::markdown::query::WikiRef;
 use scraps_libs::model::{key::ScrapKey, scrap::Scrap, tag::Tag};
 
 /// Backlinks aggregated across a wiki. Two distinct namespaces are tracked:
@@ -65,6 +68,43 @@ impl BacklinksMap {
     }
 }
 
+/// A block of a linking scrap with a `[[wikilink]]` to the target, and the
+/// headings above it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct BacklinkOccurrence {
+    /// Line of the first link to the target in the block.
+    pub line: usize,
+    pub context: String,
+    /// Lines of the block `context` was taken from.
+    pub lines: RangeInclusive<usize>,
+    pub heading_path: Vec<String>,
+}
+
+impl BacklinkOccurrence {
+    /// Every block of `linking` linking to `target`, in source order. A block
+    /// linking to the target several times is listed once.
+    pub fn find(linking: &Scrap, target: &ScrapKey) -> Vec<BacklinkOccurrence> {
+        let doc = linking.document();
+        doc.wiki_refs_with_spans()
+            .iter()
+            .filter_map(|(r, span)| match r {
+                WikiRef::Link(link) if ScrapKey::from(link) == *target => Some(span.line),
+                _ => None,
+            })
+            .map(|line| {
+                let block = doc.block_context(line);
+                BacklinkOccurrence {
+                    line,
+                    context: block.text,
+                    lines: block.lines,
+                    heading_path: block.heading_path,
+                }
+            })
+            .dedup_by(|a, b| a.lines == b.lines)
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use scraps_libs::model::tag::Tag;
@@ -157,4 +197,35 @@ mod tests {
         let backlinks_map = BacklinksMap::new(&scraps);
         assert_eq!(backlinks_map.get_tag(&Tag::from("ai")), vec![scrap]);
     }
+
+    #[test]
+    fn it_finds_backlink_occurrences() {
+        let linking = Scrap::new(
+            "note",
+            &None,
+            "# Usage\n\n- see [[Context/scrap1|it]]\n- [[other]]\n\n## More\n\nAgain [[Context/scrap1]],\nand [[Context/scrap1]].",
+        );
+
+        let occurrences = BacklinkOccurrence::find(
+            &linking,
+            &ScrapKey::with_ctx(&"scrap1".into(), &"Context".into()),
+        );
+        assert_eq!(
+            occurrences,
+            vec![
+                BacklinkOccurrence {
+                    line: 3,
+                    context: "- see [[Context/scrap1|it]]".to_string(),
+                    lines: 3..=3,
+                    heading_path: vec!["Usage".to_string()],
+                },
+                BacklinkOccurrence {
+                    line: 8,
+                    context: "Again [[Context/scrap1]],\nand [[Context/scrap1]].".to_string(),
+                    lines: 8..=9,
+                    heading_path: vec!["Usage".to_string(), "More".to_string()],
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,334 @@
+use std::collections::BTreeMap;
+
+use chrono_tz::Tz;
+use scraps_libs::model::{base_url::BaseUrl, content::ContentElement, scrap::Scrap, tag::Tag};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    asset::{Asset, Assets},
+    backlinks_map::BacklinksMap,
+    ctx_page::CtxPage,
+    html::HtmlMetadata,
+    ogp::OgpCache,
+    scrap_detail::ScrapDetail,
+};
+
+/// File name of the manifest, written at the top of the output directory.
+pub const BUILD_MANIFEST_FILE_NAME: &str = ".scraps-manifest.json";
+
+/// Bumped whenever rendering changes in a way the input hashes miss.
+const MANIFEST_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "+5");
+
+/// Hash of the inputs of every page written by a build, keyed by the page's
+/// path relative to the output directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuildManifest {
+    version: String,
+    outputs: BTreeMap<String, String>,
+}
+
+impl Default for BuildManifest {
+    fn default() -> Self {
+        BuildManifest::new()
+    }
+}
+
+impl BuildManifest {
+    pub fn new() -> BuildManifest {
+        BuildManifest {
+            version: MANIFEST_VERSION.to_string(),
+            outputs: BTreeMap::new(),
+        }
+    }
+
+    /// Whether the manifest was written by this version of scraps.
+    pub fn is_current(&self) -> bool {
+        self.version == MANIFEST_VERSION
+    }
+
+    pub fn insert(&mut self, output: &str, input_hash: &str) {
+        self.outputs
+            .insert(output.to_string(), input_hash.to_string());
+    }
+
+    /// Whether `output` was last written from inputs hashing to `input_hash`.
+    pub fn is_unchanged(&self, output: &str, input_hash: &str) -> bool {
+        self.is_current() && self.outputs.get(output).is_some_and(|h| h == input_hash)
+    }
+
+    /// Outputs recorded here that `current` no longer produces.
+    pub fn stale_outputs<'a>(&'a self, current: &BuildManifest) -> Vec<&'a str> {
+        self.outputs
+            .keys()
+            .filter(|output| !current.outputs.contains_key(*output))
+            .map(|output| output.as_str())
+            .collect()
+    }
+
+    pub fn retain<F: FnMut(&str) -> bool>(&mut self, mut f: F) {
+        self.outputs.retain(|output, _| f(output));
+    }
+}
+
+/// 64-bit FNV-1a over length-prefixed parts. Unlike `DefaultHasher` its
+/// output is stable across Rust releases, so manifests survive a rebuild of
+/// scraps itself.
+#[derive(Debug, Clone)]
+pub struct InputHash(u64);
+
+impl Default for InputHash {
+    fn default() -> Self {
+        InputHash::new()
+    }
+}
+
+impl InputHash {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub fn new() -> InputHash {
+        InputHash(Self::OFFSET)
+    }
+
+    pub fn write(&mut self, part: &str) -> &mut InputHash {
+        // The length prefix keeps ("ab", "c") apart from ("a", "bc").
+        for byte in (part.len() as u64)
+            .to_le_bytes()
+            .iter()
+            .chain(part.as_bytes())
+        {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+        self
+    }
+
+    pub fn finish(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// Hash of the inputs shared by every page: the templates and the config.
+pub fn site_hash(
+    templates_hash: &str,
+    base_url: &BaseUrl,
+    timezone: Tz,
+    html_metadata: &HtmlMetadata,
+    build_graph: bool,
+    assets: &Assets,
+) -> String {
+    let mut asset_paths: Vec<String> = assets.paths().iter().map(|p| p.to_string()).collect();
+    asset_paths.sort();
+    let mut hash = InputHash::new();
+    hash.write(templates_hash)
+        .write(base_url.as_url().as_str())
+        .write(timezone.name())
+        .write(&html_metadata.lang_code().to_string())
+        .write(&html_metadata.title())
+        .write(&html_metadata.description().unwrap_or_default())
+        .write(
+            html_metadata
+                .favicon()
+                .as_ref()
+                .map_or("", |favicon| favicon.as_str()),
+        )
+        .write(&build_graph.to_string());
+    // Relative URLs point at an asset only while it exists.
+    for asset_path in asset_paths {
+        hash.write(&asset_path);
+    }
+    hash.finish()
+}
+
+/// Hash recorded for a copied asset. Copies are refreshed by comparing file
+/// times, so only the source is recorded, to remove the copy once it is gone.
+pub fn asset_hash(asset: &Asset) -> String {
+    InputHash::new()
+        .write(&asset.source.to_string_lossy())
+        .finish()
+}
+
+/// Hash of the inputs of a scrap page: its text with the embedded scraps
+/// expanded, its commit timestamp, the scraps linking to it and the cards of
+/// its autolinks.
+pub fn scrap_page_hash(
+    site_hash: &str,
+    scrap_detail: &ScrapDetail,
+    backlinks_map: &BacklinksMap,
+    ogp_cache: &OgpCache,
+) -> String {
+    let scrap = scrap_detail.scrap();
+    let mut hash = InputHash::new();
+    hash.write(site_hash)
+        .write(&scrap.self_key().to_string())
+        .write(scrap.md_text())
+        .write(&scrap_detail.content().to_string())
+        .write(
+            &scrap_detail
+                .commited_ts()
+                .map(|ts| ts.to_string())
+                .unwrap_or_default(),
+        );
+    write_scraps(&mut hash, &backlinks_map.get(&scrap.self_key()));
+    for element in scrap_detail.content().elements() {
+        if let ContentElement::Autolink(url) = element {
+            let ogp = ogp_cache.get(url).cloned().unwrap_or_default();
+            hash.write(ogp.title.as_deref().unwrap_or_default())
+                .write(ogp.description.as_deref().unwrap_or_default())
+                .write(ogp.image.as_ref().map_or("", |image| image.as_str()));
+        }
+    }
+    hash.finish()
+}
+
+/// Hash of the inputs of a tag page: the scraps listed under the tag.
+pub fn tag_page_hash(site_hash: &str, tag: &Tag, backlinks_map: &BacklinksMap) -> String {
+    let mut hash = InputHash::new();
+    hash.write(site_hash).write(&tag.to_string());
+    write_scraps(&mut hash, &backlinks_map.get_tag(tag));
+    hash.finish()
+}
+
+/// Hash of the inputs of a context page: its README, child contexts and
+/// scraps.
+pub fn ctx_page_hash(site_hash: &str, ctx_page: &CtxPage) -> String {
+    let mut hash = InputHash::new();
+    hash.write(site_hash)
+        .write(&ctx_page.ctx.to_string())
+        .write(
+            &ctx_page
+                .readme
+                .as_ref()
+                .map(|readme| readme.content().to_string())
+                .unwrap_or_default(),
+        )
+        .write(&ctx_page.children.len().to_string());
+    for child in &ctx_page.children {
+        hash.write(&child.to_string());
+    }
+    write_scraps(&mut hash, &ctx_page.scraps);
+    hash.finish()
+}
+
+/// Writes linking scraps in key order, independent of the read order.
+fn write_scraps(hash: &mut InputHash, scraps: &[Scrap]) {
+    let mut scraps: Vec<(String, &str)> = scraps
+        .iter()
+        .map(|scrap| (scrap.self_key().to_string(), scrap.md_text()))
+        .collect();
+    scraps.sort();
+    for (key, md_text) in scraps {
+        hash.write(&key).write(md_text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usecase::build::model::ogp::Ogp;
+    use std::collections::HashMap;
+    use url::Url;
+
+    fn detail(scrap: &Scrap, ts: Option<i64>) -> ScrapDetail {
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        ScrapDetail::new(scrap, &ts, &base_url, &HashMap::new(), &Assets::default())
+    }
+
+    #[test]
+    fn it_hashes_parts_stably() {
+        let hash = InputHash::new().write("a").write("bc").finish();
+        assert_eq!(hash, InputHash::new().write("a").write("bc").finish());
+        assert_ne!(hash, InputHash::new().write("ab").write("c").finish());
+        assert_eq!(hash.len(), 16);
+    }
+
+    #[test]
+    fn it_compares_with_a_previous_build() {
+        let mut previous = BuildManifest::new();
+        previous.insert("scraps/a.html", "1");
+        previous.insert("scraps/removed.html", "2");
+
+        let mut current = BuildManifest::new();
+        current.insert("scraps/a.html", "1");
+
+        assert!(previous.is_unchanged("scraps/a.html", "1"));
+        assert!(!previous.is_unchanged("scraps/a.html", "3"));
+        assert!(!previous.is_unchanged("scraps/new.html", "1"));
+        assert_eq!(
+            previous.stale_outputs(&current),
+            vec!["scraps/removed.html"]
+        );
+
+        let outdated = BuildManifest {
+            version: "0.0.0".to_string(),
+            ..previous
+        };
+        assert!(!outdated.is_unchanged("scraps/a.html", "1"));
+    }
+
+    #[test]
+    fn it_changes_the_scrap_page_hash_with_its_inputs() {
+        let target = Scrap::new("target", &None, "#[[tag]]");
+        let linking = Scrap::new("linking", &None, "[[target]]");
+        let edited = Scrap::new("linking", &None, "see [[target]]");
+        let other = Scrap::new("other", &None, "#[[tag]]");
+
+        let backlinks = BacklinksMap::new(&[target.clone(), linking.clone()]);
+        let ogp_cache = OgpCache::new();
+        let hash = scrap_page_hash("site", &detail(&target, Some(0)), &backlinks, &ogp_cache);
+
+        // Same inputs in another order.
+        let reordered = BacklinksMap::new(&[linking.clone(), target.clone()]);
+        assert_eq!(
+            hash,
+            scrap_page_hash("site", &detail(&target, Some(0)), &reordered, &ogp_cache)
+        );
+        // The commit timestamp, the site and the backlinks all count.
+        assert_ne!(
+            hash,
+            scrap_page_hash("site", &detail(&target, Some(1)), &backlinks, &ogp_cache)
+        );
+        assert_ne!(
+            hash,
+            scrap_page_hash(
+                "other site",
+                &detail(&target, Some(0)),
+                &backlinks,
+                &ogp_cache
+            )
+        );
+        let edited_backlinks = BacklinksMap::new(&[target.clone(), edited]);
+        assert_ne!(
+            hash,
+            scrap_page_hash(
+                "site",
+                &detail(&target, Some(0)),
+                &edited_backlinks,
+                &ogp_cache
+            )
+        );
+
+        // So do the cards of its autolinks.
+        let url = Url::parse("https://example.com/").unwrap();
+        let linked = Scrap::new("linked", &None, "<https://example.com/>");
+        let mut fetched = OgpCache::new();
+        fetched.insert(
+            url,
+            Ogp {
+                title: Some("Example".to_string()),
+                ..Ogp::default()
+            },
+        );
+        assert_ne!(
+            scrap_page_hash("site", &detail(&linked, None), &backlinks, &ogp_cache),
+            scrap_page_hash("site", &detail(&linked, None), &backlinks, &fetched)
+        );
+
+        let tag = Tag::from("tag");
+        let tagged = BacklinksMap::new(&[target.clone(), other]);
+        assert_ne!(
+            tag_page_hash("site", &tag, &backlinks),
+            tag_page_hash("site", &tag, &tagged)
+        );
+    }
+}
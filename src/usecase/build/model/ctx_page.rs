@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+
+use scraps_libs::model::{context::Ctx, scrap::Scrap};
+
+use crate::usecase::build::html::{ctx_render::ctx_output_path, scrap_render::scrap_output_path};
+
+use super::scrap_detail::{ScrapDetail, ScrapDetails};
+
+/// Title of the scrap shown as the intro of its context's page.
+const README_TITLE: &str = "README";
+
+/// The landing page of a context directory.
+#[derive(Clone)]
+pub struct CtxPage {
+    pub ctx: Ctx,
+    /// `README.md` of the directory, rendered above the listings.
+    pub readme: Option<ScrapDetail>,
+    /// Contexts nested directly in this one.
+    pub children: Vec<Ctx>,
+    /// Scraps directly in this context, README excluded, by title.
+    pub scraps: Vec<Scrap>,
+}
+
+/// A page for every context and every ancestor of one, so each segment of a
+/// breadcrumb leads somewhere.
+pub struct CtxPages(Vec<CtxPage>);
+
+impl CtxPages {
+    pub fn new(scrap_details: &ScrapDetails) -> CtxPages {
+        let mut pages: BTreeMap<Ctx, CtxPage> = BTreeMap::new();
+        for scrap_detail in scrap_details.to_vec() {
+            let scrap = scrap_detail.scrap();
+            let Some(ctx) = scrap.ctx().clone() else {
+                continue;
+            };
+            for prefix in ctx.prefixes() {
+                pages.entry(prefix.clone()).or_insert_with(|| CtxPage {
+                    ctx: prefix,
+                    readme: None,
+                    children: Vec::new(),
+                    scraps: Vec::new(),
+                });
+            }
+            let page = pages.get_mut(&ctx).unwrap();
+            if scrap.title().to_string() == README_TITLE {
+                page.readme = Some(scrap_detail);
+            } else {
+                page.scraps.push(scrap);
+            }
+        }
+
+        let ctxs: Vec<Ctx> = pages.keys().cloned().collect();
+        for ctx in ctxs {
+            if let Some(parent) = ctx.parent() {
+                pages.get_mut(&parent).unwrap().children.push(ctx);
+            }
+        }
+
+        let pages = pages
+            .into_values()
+            .map(|mut page| {
+                page.scraps
+                    .sort_by_key(|scrap| scrap.title().to_string().to_lowercase());
+                page
+            })
+            // A scrap titled "index" already owns the page's path.
+            .filter(|page| {
+                let output = ctx_output_path(&page.ctx);
+                !page
+                    .scraps
+                    .iter()
+                    .any(|scrap| scrap_output_path(&scrap.self_key()) == output)
+            })
+            .collect();
+        CtxPages(pages)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, CtxPage> {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use scraps_libs::model::base_url::BaseUrl;
+    use url::Url;
+
+    use super::*;
+    use crate::usecase::build::model::asset::Assets;
+
+    fn ctx_pages(scraps: &[Scrap]) -> CtxPages {
+        let base_url = BaseUrl::new(Url::parse("https://example.com/").unwrap()).unwrap();
+        let details: Vec<ScrapDetail> = scraps
+            .iter()
+            .map(|scrap| {
+                ScrapDetail::new(scrap, &None, &base_url, &HashMap::new(), &Assets::default())
+            })
+            .collect();
+        CtxPages::new(&ScrapDetails::new(&details))
+    }
+
+    #[test]
+    fn it_lists_child_contexts_and_scraps() {
+        let pages = ctx_pages(&[
+            Scrap::new("ownership", &Some("Programming/Rust".into()), ""),
+            Scrap::new("Borrowing", &Some("Programming/Rust".into()), ""),
+            Scrap::new("README", &Some("Programming/Rust".into()), "# Rust"),
+            Scrap::new("goroutine", &Some("Programming/Go".into()), ""),
+            Scrap::new("root", &None, ""),
+        ]);
+
+        let ctxs: Vec<String> = pages.iter().map(|p| p.ctx.to_string()).collect();
+        assert_eq!(
+            ctxs,
+            vec!["Programming", "Programming/Go", "Programming/Rust"]
+        );
+
+        let programming = pages.iter().next().unwrap();
+        let children: Vec<String> = programming.children.iter().map(|c| c.to_string()).collect();
+        assert_eq!(children, vec!["Programming/Go", "Programming/Rust"]);
+        assert!(programming.scraps.is_empty());
+        assert!(programming.readme.is_none());
+
+        let rust = pages.iter().nth(2).unwrap();
+        let titles: Vec<String> = rust.scraps.iter().map(|s| s.title().to_string()).collect();
+        assert_eq!(titles, vec!["Borrowing", "ownership"]);
+        assert_eq!(
+            rust.readme.as_ref().unwrap().content().to_string(),
+            "<h1>Rust</h1>\n"
+        );
+    }
+
+    #[test]
+    fn it_leaves_the_page_to_a_scrap_titled_index() {
+        let pages = ctx_pages(&[Scrap::new("index", &Some("Notes".into()), "")]);
+        assert_eq!(pages.iter().count(), 0);
+    }
+}
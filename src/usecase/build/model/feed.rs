@@ -0,0 +1,305 @@
+use std::collections::{BTreeMap, HashMap};
+
+use scraps_libs::{
+    html::EmbedMode,
+    model::{base_url::BaseUrl, context::Ctx, key::ScrapKey, scrap::Scrap, tags::Tags},
+};
+use url::Url;
+
+use crate::usecase::build::html::{
+    ctx_render::ctx_page_path, scrap_render::scrap_output_path, tag_render::tag_output_path,
+};
+
+use super::{asset::Assets, backlinks_map::BacklinksMap};
+
+/// Settings of `[ssg.feed]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedConfigs {
+    /// Entries per feed.
+    pub limit: usize,
+    /// Whether entries carry the whole scrap rather than its first paragraph.
+    pub full_content: bool,
+    /// Whether each context gets a feed of its own as well.
+    pub by_ctx: bool,
+}
+
+impl Default for FeedConfigs {
+    fn default() -> Self {
+        FeedConfigs {
+            limit: 20,
+            full_content: false,
+            by_ctx: false,
+        }
+    }
+}
+
+/// An Atom feed of the most recently committed scraps among a set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feed {
+    /// Output path relative to the output directory.
+    pub path: String,
+    /// Appended to the site title, e.g. the tag of a tag feed.
+    pub subtitle: Option<String>,
+    /// The page the feed follows.
+    pub page_url: Url,
+    pub self_url: Url,
+    pub entries: Vec<FeedEntry>,
+}
+
+impl Feed {
+    /// Commit time of the newest entry.
+    pub fn updated(&self) -> Option<i64> {
+        self.entries.iter().map(|entry| entry.updated).max()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEntry {
+    pub title: String,
+    pub url: Url,
+    pub updated: i64,
+    /// Either the first paragraph or the whole scrap, as HTML.
+    pub html: String,
+}
+
+/// Site-wide, per-tag and optionally per-context feeds. Only scraps with a
+/// commit timestamp take part, so feeds are empty without `--git`.
+#[derive(Debug, PartialEq)]
+pub struct Feeds(Vec<Feed>);
+
+impl Feeds {
+    pub fn new(
+        scraps_with_ts: &[(Scrap, Option<i64>)],
+        base_url: &BaseUrl,
+        backlinks_map: &BacklinksMap,
+        configs: &FeedConfigs,
+        assets: &Assets,
+    ) -> Feeds {
+        let commited_ts: HashMap<ScrapKey, i64> = scraps_with_ts
+            .iter()
+            .filter_map(|(scrap, ts)| Some((scrap.self_key(), (*ts)?)))
+            .collect();
+        let embeddable: HashMap<ScrapKey, &Scrap> = scraps_with_ts
+            .iter()
+            .map(|(scrap, _)| (scrap.self_key(), scrap))
+            .collect();
+        let scraps: Vec<Scrap> = scraps_with_ts
+            .iter()
+            .map(|(scrap, _)| scrap.clone())
+            .collect();
+
+        let feed = |path: String, subtitle: Option<String>, page_url: Url, members: &[Scrap]| {
+            let mut members: Vec<(&Scrap, i64)> = members
+                .iter()
+                .filter_map(|scrap| Some((scrap, *commited_ts.get(&scrap.self_key())?)))
+                .collect();
+            members.sort_by(|(a, a_ts), (b, b_ts)| {
+                b_ts.cmp(a_ts)
+                    .then_with(|| a.self_key().to_string().cmp(&b.self_key().to_string()))
+            });
+            let entries: Vec<FeedEntry> = members
+                .into_iter()
+                .take(configs.limit)
+                .map(|(scrap, updated)| {
+                    FeedEntry::new(
+                        scrap,
+                        updated,
+                        base_url,
+                        &embeddable,
+                        assets,
+                        configs.full_content,
+                    )
+                })
+                .collect();
+            (!entries.is_empty()).then(|| Feed {
+                self_url: base_url.page_url(&path),
+                path,
+                subtitle,
+                page_url,
+                entries,
+            })
+        };
+
+        let mut feeds: Vec<Feed> = Vec::new();
+        feeds.extend(feed(
+            "feed.xml".to_string(),
+            None,
+            base_url.page_url(""),
+            &scraps,
+        ));
+
+        for tag in Tags::new(&scraps).iter() {
+            let page_path = tag_output_path(tag);
+            feeds.extend(feed(
+                format!("{}.xml", page_path.trim_end_matches(".html")),
+                Some(format!("#{tag}")),
+                base_url.page_url(&page_path),
+                &backlinks_map.get_tag(tag),
+            ));
+        }
+
+        if configs.by_ctx {
+            // A context's feed also follows its nested contexts.
+            let mut by_ctx: BTreeMap<String, (Ctx, Vec<Scrap>)> = BTreeMap::new();
+            for scrap in &scraps {
+                let Some(ctx) = scrap.ctx() else { continue };
+                for ancestor in ctx.prefixes() {
+                    by_ctx
+                        .entry(ancestor.to_string())
+                        .or_insert_with(|| (ancestor, Vec::new()))
+                        .1
+                        .push(scrap.clone());
+                }
+            }
+            for (ctx, members) in by_ctx.values() {
+                let page_path = ctx_page_path(ctx);
+                feeds.extend(feed(
+                    format!("{page_path}feed.xml"),
+                    Some(ctx.to_string()),
+                    base_url.page_url(&page_path),
+                    members,
+                ));
+            }
+        }
+
+        Feeds(feeds)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Feed> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl FeedEntry {
+    fn new(
+        scrap: &Scrap,
+        updated: i64,
+        base_url: &BaseUrl,
+        embeddable: &HashMap<ScrapKey, &Scrap>,
+        assets: &Assets,
+        full_content: bool,
+    ) -> FeedEntry {
+        let content = scrap.document().to_content(
+            scrap.ctx(),
+            assets.paths(),
+            base_url,
+            EmbedMode::Expand(embeddable),
+        );
+        let html = content.to_string();
+        let html = if full_content {
+            html
+        } else {
+            first_paragraph(&html).unwrap_or(html)
+        };
+        FeedEntry {
+            title: scrap.title().to_string(),
+            url: base_url.page_url(&scrap_output_path(&scrap.self_key())),
+            updated,
+            html,
+        }
+    }
+}
+
+/// The first `<p>` element; paragraphs never nest, so the first closing tag
+/// ends it.
+fn first_paragraph(html: &str) -> Option<String> {
+    let start = html.find("<p>")?;
+    let end = start + html[start..].find("</p>")? + "</p>".len();
+    Some(html[start..end].to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feeds(scraps_with_ts: &[(Scrap, Option<i64>)], configs: &FeedConfigs) -> Feeds {
+        let base_url = BaseUrl::new(Url::parse("https://example.com/").unwrap()).unwrap();
+        let scraps: Vec<Scrap> = scraps_with_ts.iter().map(|(s, _)| s.clone()).collect();
+        Feeds::new(
+            scraps_with_ts,
+            &base_url,
+            &BacklinksMap::new(&scraps),
+            configs,
+            &Assets::default(),
+        )
+    }
+
+    #[test]
+    fn it_lists_the_latest_commits_first() {
+        let scraps_with_ts = vec![
+            (Scrap::new("old", &None, "#[[rust]]\n\nold body"), Some(10)),
+            (
+                Scrap::new("new", &None, "# New\n\nfirst\n\nsecond"),
+                Some(30),
+            ),
+            (Scrap::new("mid", &None, "#[[rust]] mid"), Some(20)),
+            (Scrap::new("untracked", &None, "no commit yet"), None),
+        ];
+        let configs = FeedConfigs {
+            limit: 2,
+            ..FeedConfigs::default()
+        };
+
+        let feeds = feeds(&scraps_with_ts, &configs);
+        let site = feeds.iter().next().unwrap();
+        assert_eq!(site.path, "feed.xml");
+        assert_eq!(site.self_url.as_str(), "https://example.com/feed.xml");
+        assert_eq!(site.updated(), Some(30));
+        let titles: Vec<&str> = site.entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["new", "mid"]);
+        assert_eq!(site.entries[0].html, "<p>first</p>");
+        assert_eq!(
+            site.entries[0].url.as_str(),
+            "https://example.com/scraps/new.html"
+        );
+
+        let tag = feeds.iter().nth(1).unwrap();
+        assert_eq!(tag.path, "tags/rust.xml");
+        assert_eq!(tag.page_url.as_str(), "https://example.com/tags/rust.html");
+        let titles: Vec<&str> = tag.entries.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, vec!["mid", "old"]);
+        assert_eq!(feeds.len(), 2);
+    }
+
+    #[test]
+    fn it_builds_context_feeds_with_full_content() {
+        let scraps_with_ts = vec![
+            (
+                Scrap::new("a", &Some(Ctx::from("Lang/Rust")), "one\n\ntwo"),
+                Some(10),
+            ),
+            (Scrap::new("b", &Some(Ctx::from("Lang")), "three"), Some(20)),
+        ];
+        let configs = FeedConfigs {
+            full_content: true,
+            by_ctx: true,
+            ..FeedConfigs::default()
+        };
+
+        let feeds = feeds(&scraps_with_ts, &configs);
+        let paths: Vec<&str> = feeds.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "feed.xml",
+                "scraps/lang/feed.xml",
+                "scraps/lang/rust/feed.xml"
+            ]
+        );
+        let lang = feeds.iter().nth(1).unwrap();
+        assert_eq!(lang.entries.len(), 2);
+        assert_eq!(lang.subtitle.as_deref(), Some("Lang"));
+        let rust = feeds.iter().nth(2).unwrap();
+        assert_eq!(rust.entries[0].html, "<p>one</p>\n<p>two</p>\n");
+    }
+
+    #[test]
+    fn it_has_no_feeds_without_commit_timestamps() {
+        let scraps_with_ts = vec![(Scrap::new("a", &None, "#[[t]]"), None)];
+        assert_eq!(feeds(&scraps_with_ts, &FeedConfigs::default()).len(), 0);
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use itertools::Itertools;
+use scraps_libs::model::{key::ScrapKey, scrap::Scrap, tag::Tag, tags::Tags};
+use serde::Serialize;
+
+use crate::usecase::build::html::{scrap_render::scrap_output_path, tag_render::tag_output_path};
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphNodeKind {
+    Scrap,
+    Tag,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct GraphNode {
+    /// Scrap key, or the tag path prefixed with `#` for tags.
+    pub id: String,
+    pub kind: GraphNodeKind,
+    pub title: String,
+    pub ctx: Option<String>,
+    pub tags: Vec<String>,
+    /// Page of the node, relative to the site root.
+    pub url: String,
+    /// Number of edges touching the node.
+    pub degree: usize,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphEdgeKind {
+    Link,
+    Embed,
+    Tag,
+}
+
+/// A reference from `source` to `target`. Tag edges run from a scrap to its
+/// tag, and from a nested tag to its parent.
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub kind: GraphEdgeKind,
+}
+
+/// The link graph of the site, written as `graph.json` for the graph page
+/// and the local graph of each scrap page. Links and embeds to missing
+/// scraps are left out.
+#[derive(Debug, Serialize)]
+pub struct SiteGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl SiteGraph {
+    pub fn new(scraps: &[Scrap]) -> SiteGraph {
+        let keys: HashSet<ScrapKey> = scraps.iter().map(|scrap| scrap.self_key()).collect();
+
+        let mut edges: BTreeSet<GraphEdge> = BTreeSet::new();
+        for scrap in scraps {
+            let source = scrap.self_key();
+            let embeds = scrap.document().embeds().cloned().map(|embed| {
+                let mut path = embed.ctx_path;
+                path.push(embed.title);
+                (
+                    ScrapKey::from_path_str(&path.join("/")),
+                    GraphEdgeKind::Embed,
+                )
+            });
+            let links = scrap
+                .links()
+                .iter()
+                .map(|key| (key.clone(), GraphEdgeKind::Link));
+            for (target, kind) in links.chain(embeds) {
+                if target != source && keys.contains(&target) {
+                    edges.insert(GraphEdge {
+                        source: source.to_string(),
+                        target: target.to_string(),
+                        kind,
+                    });
+                }
+            }
+            for tag in scrap.tags() {
+                edges.insert(GraphEdge {
+                    source: source.to_string(),
+                    target: tag_id(tag),
+                    kind: GraphEdgeKind::Tag,
+                });
+            }
+        }
+
+        let tags = Tags::new(scraps);
+        for tag in tags.iter() {
+            let segments = tag.segments();
+            if segments.len() > 1 {
+                let parent = Tag::from(segments[..segments.len() - 1].join("/").as_str());
+                edges.insert(GraphEdge {
+                    source: tag_id(tag),
+                    target: tag_id(&parent),
+                    kind: GraphEdgeKind::Tag,
+                });
+            }
+        }
+
+        let mut degrees: HashMap<&str, usize> = HashMap::new();
+        for edge in &edges {
+            *degrees.entry(&edge.source).or_default() += 1;
+            *degrees.entry(&edge.target).or_default() += 1;
+        }
+        let degree = |id: &str| degrees.get(id).copied().unwrap_or_default();
+
+        let scrap_nodes = scraps
+            .iter()
+            .sorted_by_key(|scrap| scrap.self_key().to_string())
+            .map(|scrap| {
+                let key = scrap.self_key();
+                GraphNode {
+                    degree: degree(&key.to_string()),
+                    id: key.to_string(),
+                    kind: GraphNodeKind::Scrap,
+                    title: scrap.title().to_string(),
+                    ctx: scrap.ctx().as_ref().map(|ctx| ctx.to_string()),
+                    tags: scrap.tags().iter().map(|tag| tag.to_string()).collect(),
+                    url: scrap_output_path(&key),
+                }
+            });
+        let tag_nodes = tags.iter().map(|tag| {
+            let id = tag_id(tag);
+            GraphNode {
+                degree: degree(&id),
+                id,
+                kind: GraphNodeKind::Tag,
+                title: tag.to_string(),
+                ctx: None,
+                tags: Vec::new(),
+                url: tag_output_path(tag),
+            }
+        });
+        let nodes = scrap_nodes.chain(tag_nodes).collect();
+
+        SiteGraph {
+            nodes,
+            edges: edges.into_iter().collect(),
+        }
+    }
+}
+
+fn tag_id(tag: &Tag) -> String {
+    format!("#{tag}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraps_libs::model::context::Ctx;
+
+    #[test]
+    fn it_collects_links_embeds_and_tags() {
+        let scraps = [
+            Scrap::new(
+                "a",
+                &None,
+                "[[b]] [[b]] ![[Book/c]] [[missing]] [[a]] #[[ai/ml]]",
+            ),
+            Scrap::new("b", &None, "#[[ai]]"),
+            Scrap::new("c", &Some(Ctx::from("Book")), ""),
+        ];
+
+        let graph = SiteGraph::new(&scraps);
+        let edges: Vec<(&str, &str, GraphEdgeKind)> = graph
+            .edges
+            .iter()
+            .map(|e| (e.source.as_str(), e.target.as_str(), e.kind))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("#ai/ml", "#ai", GraphEdgeKind::Tag),
+                ("a", "#ai/ml", GraphEdgeKind::Tag),
+                ("a", "Book/c", GraphEdgeKind::Embed),
+                ("a", "b", GraphEdgeKind::Link),
+                ("b", "#ai", GraphEdgeKind::Tag),
+            ]
+        );
+
+        let nodes: Vec<(&str, GraphNodeKind, usize, &str)> = graph
+            .nodes
+            .iter()
+            .map(|n| (n.id.as_str(), n.kind, n.degree, n.url.as_str()))
+            .collect();
+        assert_eq!(
+            nodes,
+            vec![
+                ("Book/c", GraphNodeKind::Scrap, 1, "scraps/book/c.html"),
+                ("a", GraphNodeKind::Scrap, 3, "scraps/a.html"),
+                ("b", GraphNodeKind::Scrap, 2, "scraps/b.html"),
+                ("#ai", GraphNodeKind::Tag, 2, "tags/ai.html"),
+                ("#ai/ml", GraphNodeKind::Tag, 2, "tags/ai/ml.html"),
+            ]
+        );
+        assert_eq!(graph.nodes[0].ctx.as_deref(), Some("Book"));
+        assert_eq!(graph.nodes[1].tags, vec!["ai/ml"]);
+    }
+}
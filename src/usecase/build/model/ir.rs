@@ -0,0 +1,311 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use scraps_libs::markdown::query::{TaskStatus, WikiRef};
+use scraps_libs::model::{file::ScrapFileStem, key::ScrapKey, scrap::Scrap, tags::Tags};
+use serde::Serialize;
+
+use super::backlinks_map::BacklinksMap;
+
+/// Version of the `scraps.json` shape, bumped on breaking changes. Its JSON
+/// Schema ships next to it as `scraps.schema.json`.
+pub const IR_SCHEMA_VERSION: &str = "1.0.0";
+
+/// The typed graph of a whole wiki, emitted by `scraps build --target json`.
+#[derive(Debug, Serialize)]
+pub struct ScrapsIr {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub scraps: Vec<IrScrap>,
+    pub tags: Vec<IrTag>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrScrap {
+    pub key: String,
+    pub title: String,
+    pub ctx: Option<String>,
+    pub path: String,
+    pub html_path: String,
+    pub headings: Vec<IrHeading>,
+    pub code_blocks: Vec<IrCodeBlock>,
+    pub tags: Vec<String>,
+    pub refs: Vec<IrRef>,
+    pub backlinks: Vec<String>,
+    pub tasks: Vec<IrTask>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<IrGit>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrHeading {
+    pub level: u8,
+    pub text: String,
+    pub line: usize,
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrCodeBlock {
+    pub lang: Option<String>,
+    pub content: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IrRefKind {
+    Link,
+    Embed,
+    Tag,
+}
+
+/// One outbound `[[]]`-family occurrence. `target` is a scrap key for links
+/// and embeds, and a tag path for tags.
+#[derive(Debug, Serialize)]
+pub struct IrRef {
+    pub kind: IrRefKind,
+    pub target: String,
+    pub resolved: bool,
+    pub heading: Option<String>,
+    pub alias: Option<String>,
+    pub span: IrSpan,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrSpan {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrTask {
+    pub status: &'static str,
+    pub text: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrGit {
+    pub commited_ts: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IrTag {
+    pub name: String,
+    pub depth: usize,
+    pub backlinks: Vec<String>,
+}
+
+impl ScrapsIr {
+    pub fn new(scraps_with_ts: &[(Scrap, Option<i64>)], backlinks_map: &BacklinksMap) -> ScrapsIr {
+        let keys: HashSet<ScrapKey> = scraps_with_ts
+            .iter()
+            .map(|(scrap, _)| scrap.self_key())
+            .collect();
+        let scraps = scraps_with_ts
+            .iter()
+            .sorted_by_key(|(scrap, _)| scrap.self_key().to_string())
+            .map(|(scrap, commited_ts)| IrScrap::new(scrap, commited_ts, &keys, backlinks_map))
+            .collect();
+        let all_scraps = scraps_with_ts.iter().map(|(s, _)| s.clone()).collect_vec();
+        let tags = Tags::new(&all_scraps)
+            .into_iter()
+            .map(|tag| IrTag {
+                name: tag.to_string(),
+                depth: tag.depth(),
+                backlinks: sorted_keys(&backlinks_map.get_tag(&tag)),
+            })
+            .collect();
+
+        ScrapsIr {
+            schema: "scraps.schema.json",
+            version: IR_SCHEMA_VERSION,
+            scraps,
+            tags,
+        }
+    }
+}
+
+impl IrScrap {
+    fn new(
+        scrap: &Scrap,
+        commited_ts: &Option<i64>,
+        keys: &HashSet<ScrapKey>,
+        backlinks_map: &BacklinksMap,
+    ) -> IrScrap {
+        let key = scrap.self_key();
+        let doc = scrap.document();
+        let refs = doc
+            .wiki_refs_with_spans()
+            .iter()
+            .map(|(wref, span)| {
+                let span = IrSpan {
+                    line: span.line,
+                    start: span.start,
+                    end: span.end,
+                };
+                match wref {
+                    WikiRef::Link(r) => {
+                        let target = ScrapKey::from(r);
+                        IrRef {
+                            kind: IrRefKind::Link,
+                            resolved: keys.contains(&target),
+                            target: target.to_string(),
+                            heading: r.heading.clone(),
+                            alias: r.alias.clone(),
+                            span,
+                        }
+                    }
+                    WikiRef::Embed(r) => {
+                        let mut path = r.ctx_path.clone();
+                        path.push(r.title.clone());
+                        let target = ScrapKey::from_path_str(&path.join("/"));
+                        IrRef {
+                            kind: IrRefKind::Embed,
+                            resolved: keys.contains(&target),
+                            target: target.to_string(),
+                            heading: r.heading.clone(),
+                            alias: None,
+                            span,
+                        }
+                    }
+                    WikiRef::Tag(t) => IrRef {
+                        kind: IrRefKind::Tag,
+                        target: t.path.join("/"),
+                        resolved: true,
+                        heading: None,
+                        alias: None,
+                        span,
+                    },
+                }
+            })
+            .collect();
+
+        IrScrap {
+            title: scrap.title().to_string(),
+            ctx: scrap.ctx().as_ref().map(|c| c.to_string()),
+            path: format!("{key}.md"),
+            html_path: format!("scraps/{}.html", ScrapFileStem::from(key.clone())),
+            headings: doc
+                .headings()
+                .iter()
+                .map(|h| IrHeading {
+                    level: h.level,
+                    text: h.text.clone(),
+                    line: h.line,
+                    parent: h.parent.clone(),
+                })
+                .collect(),
+            code_blocks: doc
+                .code_blocks()
+                .iter()
+                .map(|c| IrCodeBlock {
+                    lang: c.lang.clone(),
+                    content: c.content.clone(),
+                    line: c.line,
+                })
+                .collect(),
+            tags: scrap.tags().iter().map(|t| t.to_string()).collect(),
+            refs,
+            backlinks: sorted_keys(&backlinks_map.get(&key)),
+            tasks: doc
+                .task_items()
+                .iter()
+                .map(|t| IrTask {
+                    status: match t.status {
+                        TaskStatus::Open => "open",
+                        TaskStatus::Done => "done",
+                        TaskStatus::Deferred => "deferred",
+                    },
+                    text: t.text.clone(),
+                    line: t.line,
+                })
+                .collect(),
+            git: commited_ts.map(|commited_ts| IrGit { commited_ts }),
+            key: key.to_string(),
+        }
+    }
+}
+
+fn sorted_keys(scraps: &[Scrap]) -> Vec<String> {
+    scraps
+        .iter()
+        .map(|s| s.self_key().to_string())
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_new() {
+        let scraps_with_ts = vec![
+            (
+                Scrap::new(
+                    "note",
+                    &None,
+                    "# Note\n\n[[Book/DDD#Layers|layers]] ![[gone]] #[[ai/ml]]\n\n- [ ] todo\n\n```rust\nfn main() {}\n```\n",
+                ),
+                Some(42),
+            ),
+            (Scrap::new("DDD", &Some("Book".into()), "back to [[note]]"), None),
+        ];
+        let scraps = scraps_with_ts.iter().map(|(s, _)| s.clone()).collect_vec();
+        let backlinks_map = BacklinksMap::new(&scraps);
+
+        let ir = serde_json::to_value(ScrapsIr::new(&scraps_with_ts, &backlinks_map)).unwrap();
+
+        assert_eq!(ir["$schema"], "scraps.schema.json");
+        assert_eq!(ir["version"], IR_SCHEMA_VERSION);
+        let ddd = &ir["scraps"][0];
+        assert_eq!(ddd["key"], "Book/DDD");
+        assert_eq!(ddd["path"], "Book/DDD.md");
+        assert_eq!(ddd["html_path"], "scraps/book/ddd.html");
+        assert_eq!(ddd["backlinks"], json!(["note"]));
+        assert!(ddd.get("git").is_none());
+
+        let note = &ir["scraps"][1];
+        assert_eq!(note["git"], json!({ "commited_ts": 42 }));
+        assert_eq!(note["headings"][0]["text"], "Note");
+        assert_eq!(note["code_blocks"][0]["lang"], "rust");
+        assert_eq!(note["tags"], json!(["ai/ml"]));
+        assert_eq!(
+            note["tasks"],
+            json!([{ "status": "open", "text": "todo", "line": 5 }])
+        );
+        assert_eq!(
+            note["refs"],
+            json!([
+                {
+                    "kind": "link", "target": "Book/DDD", "resolved": true,
+                    "heading": "Layers", "alias": "layers",
+                    "span": { "line": 3, "start": 8, "end": 34 },
+                },
+                {
+                    "kind": "embed", "target": "gone", "resolved": false,
+                    "heading": null, "alias": null,
+                    "span": { "line": 3, "start": 35, "end": 44 },
+                },
+                {
+                    "kind": "tag", "target": "ai/ml", "resolved": true,
+                    "heading": null, "alias": null,
+                    "span": { "line": 3, "start": 45, "end": 55 },
+                },
+            ])
+        );
+        assert_eq!(
+            ir["tags"],
+            json!([
+                { "name": "ai", "depth": 1, "backlinks": ["note"] },
+                { "name": "ai/ml", "depth": 2, "backlinks": ["note"] },
+            ])
+        );
+    }
+}
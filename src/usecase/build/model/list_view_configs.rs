@@ -2,14 +2,22 @@ use super::{paging::Paging, sort::SortKey};
 
 pub struct ListViewConfigs {
     pub build_search_index: bool,
+    /// Whether to write the link graph and show it on scrap pages.
+    pub build_graph: bool,
     pub sort_key: SortKey,
     pub paging: Paging,
 }
 
 impl ListViewConfigs {
-    pub fn new(build_search_index: &bool, sort_key: &SortKey, paging: &Paging) -> ListViewConfigs {
+    pub fn new(
+        build_search_index: &bool,
+        build_graph: &bool,
+        sort_key: &SortKey,
+        paging: &Paging,
+    ) -> ListViewConfigs {
         ListViewConfigs {
             build_search_index: *build_search_index,
+            build_graph: *build_graph,
             sort_key: sort_key.clone(),
             paging: paging.clone(),
         }
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use scraps_libs::{
+    html::EmbedMode,
+    model::{base_url::BaseUrl, file::ScrapFileStem, key::ScrapKey, scrap::Scrap},
+    readable,
+};
+
+use super::backlinks_map::BacklinksMap;
+
+/// One scrap rendered as readable markdown for the packed corpus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmsEntry {
+    pub key: ScrapKey,
+    pub url: String,
+    pub text: String,
+    pub linked_count: usize,
+}
+
+impl LlmsEntry {
+    /// Rough token estimate (about four bytes per token). Cheap, dependency
+    /// free, and conservative enough for CJK text where a character is three
+    /// bytes.
+    pub fn tokens(&self) -> usize {
+        estimate_tokens(&self.key.to_string()) + estimate_tokens(&self.text)
+    }
+}
+
+/// The `llms.txt` / `llms-full.txt` payload. `index` lists every scrap, while
+/// `corpus` holds the subset that fits in the token budget, most-linked first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LlmsCorpus {
+    pub readme: Option<String>,
+    pub index: Vec<LlmsEntry>,
+    pub corpus: Vec<LlmsEntry>,
+}
+
+impl LlmsCorpus {
+    pub fn new(
+        scraps: &[Scrap],
+        readme_text: &Option<String>,
+        base_url: &BaseUrl,
+        backlinks_map: &BacklinksMap,
+        token_budget: Option<usize>,
+    ) -> LlmsCorpus {
+        let embeddable: HashMap<_, _> = scraps
+            .iter()
+            .map(|scrap| (scrap.self_key(), scrap))
+            .collect();
+
+        let index = scraps
+            .iter()
+            .map(|scrap| {
+                let key = scrap.self_key();
+                LlmsEntry {
+                    url: format!(
+                        "{}scraps/{}.html",
+                        base_url.as_url(),
+                        ScrapFileStem::from(key.clone())
+                    ),
+                    text: readable::to_markdown(scrap.md_text(), EmbedMode::Expand(&embeddable)),
+                    linked_count: backlinks_map.get(&key).len(),
+                    key,
+                }
+            })
+            .sorted_by(|a, b| {
+                b.linked_count
+                    .cmp(&a.linked_count)
+                    .then_with(|| a.key.cmp(&b.key))
+            })
+            .collect_vec();
+
+        let readme = readme_text
+            .as_ref()
+            .map(|text| readable::to_markdown(text, EmbedMode::Expand(&embeddable)));
+
+        let mut remaining = token_budget.unwrap_or(usize::MAX);
+        let readme = readme.filter(|text| {
+            let tokens = estimate_tokens(text);
+            if tokens > remaining {
+                return false;
+            }
+            remaining -= tokens;
+            true
+        });
+
+        // Greedy fill: a scrap that does not fit is skipped so smaller,
+        // less-linked scraps can still use the rest of the budget.
+        let corpus = index
+            .iter()
+            .filter(|entry| {
+                let tokens = entry.tokens();
+                if tokens > remaining {
+                    return false;
+                }
+                remaining -= tokens;
+                true
+            })
+            .cloned()
+            .collect_vec();
+
+        LlmsCorpus {
+            readme,
+            index,
+            corpus,
+        }
+    }
+}
+
+fn estimate_tokens(text: &str) -> usize {
+    text.len().div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use url::Url;
+
+    fn base_url() -> BaseUrl {
+        BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn it_orders_by_linked_count() {
+        let scraps = vec![
+            Scrap::new("leaf", &None, "[[hub]]"),
+            Scrap::new("hub", &Some("Book".into()), "hub body"),
+            Scrap::new("other", &None, "[[Book/hub]] and [[leaf]]"),
+        ];
+        let backlinks_map = BacklinksMap::new(&scraps);
+
+        let llms = LlmsCorpus::new(&scraps, &None, &base_url(), &backlinks_map, None);
+
+        let keys = llms.corpus.iter().map(|e| e.key.to_string()).collect_vec();
+        assert_eq!(keys, vec!["Book/hub", "leaf", "other"]);
+        assert_eq!(llms.index, llms.corpus);
+        assert_eq!(
+            llms.corpus[0].url,
+            "http://localhost:1112/scraps/book/hub.html"
+        );
+        assert_eq!(llms.corpus[2].text, "hub (→ Book/hub) and leaf");
+    }
+
+    #[test]
+    fn it_expands_embeds() {
+        let scraps = vec![
+            Scrap::new("source", &None, "![[target]]"),
+            Scrap::new("target", &None, "embedded body"),
+        ];
+        let backlinks_map = BacklinksMap::new(&scraps);
+
+        let llms = LlmsCorpus::new(&scraps, &None, &base_url(), &backlinks_map, None);
+
+        let source = llms
+            .corpus
+            .iter()
+            .find(|e| e.key.to_string() == "source")
+            .unwrap();
+        assert_eq!(source.text, "embedded body");
+    }
+
+    #[test]
+    fn it_fills_token_budget_with_most_linked_first() {
+        let scraps = vec![
+            Scrap::new("hub", &None, &"x".repeat(40)),
+            Scrap::new("big", &None, &format!("[[hub]] {}", "y".repeat(400))),
+            Scrap::new("small", &None, "[[hub]]"),
+        ];
+        let backlinks_map = BacklinksMap::new(&scraps);
+
+        let llms = LlmsCorpus::new(
+            &scraps,
+            &Some("# README".to_string()),
+            &base_url(),
+            &backlinks_map,
+            Some(30),
+        );
+
+        let keys = llms.corpus.iter().map(|e| e.key.to_string()).collect_vec();
+        assert_eq!(keys, vec!["hub", "small"]);
+        assert_eq!(llms.readme, Some("# README".to_string()));
+        assert_eq!(llms.index.len(), 3);
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Open Graph metadata of a linked page, rendered as the card of an
+/// autolink.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ogp {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<Url>,
+}
+
+impl Ogp {
+    /// Read the `og:` meta tags of `html`, falling back to the `twitter:`
+    /// ones, `<title>` and the `description` meta tag. Relative image URLs
+    /// are resolved against `page_url`.
+    pub fn from_html(html: &str, page_url: &Url) -> Ogp {
+        // Lowercasing ASCII keeps byte offsets, so `lower` indexes `html`.
+        let lower = html.to_ascii_lowercase();
+        let head_end = lower.find("</head").unwrap_or(lower.len());
+
+        let mut metas: BTreeMap<String, String> = BTreeMap::new();
+        let mut from = 0;
+        while let Some(start) = lower
+            .get(from..head_end)
+            .and_then(|head| head.find("<meta"))
+            .map(|i| from + i)
+        {
+            let end = lower[start..].find('>').map_or(lower.len(), |i| start + i);
+            let attrs = attributes(&html[start + "<meta".len()..end]);
+            let key = attrs.get("property").or_else(|| attrs.get("name"));
+            if let (Some(key), Some(content)) = (key, attrs.get("content")) {
+                metas
+                    .entry(key.to_ascii_lowercase())
+                    .or_insert_with(|| content.clone());
+            }
+            from = end;
+        }
+        let meta = |keys: &[&str]| {
+            keys.iter()
+                .filter_map(|key| metas.get(*key))
+                .map(|value| normalize(value))
+                .find(|value| !value.is_empty())
+        };
+
+        let title = meta(&["og:title", "twitter:title"]).or_else(|| {
+            let start = lower[..head_end].find("<title")?;
+            let start = start + lower[start..].find('>')? + 1;
+            let end = start + lower[start..].find("</title")?;
+            Some(normalize(&decode_entities(&html[start..end]))).filter(|t| !t.is_empty())
+        });
+        let description = meta(&["og:description", "twitter:description", "description"]);
+        let image = meta(&["og:image", "og:image:url", "twitter:image"])
+            .and_then(|image| page_url.join(&image).ok())
+            .filter(|image| matches!(image.scheme(), "http" | "https"));
+
+        Ogp {
+            title,
+            description,
+            image,
+        }
+    }
+
+    /// Whether the page had nothing to show on a card.
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image.is_none()
+    }
+}
+
+/// Attributes of a tag, with names lowercased and entities decoded.
+fn attributes(tag: &str) -> BTreeMap<String, String> {
+    let mut attrs = BTreeMap::new();
+    let mut rest = tag.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c == '/' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remainder) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let inner = &after[1..];
+                        let end = inner.find(quote).unwrap_or(inner.len());
+                        (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remainder;
+                decode_entities(value)
+            }
+            None => {
+                // Skip stray characters such as the `/` of `<meta ... />`.
+                if name.is_empty() {
+                    rest = rest.get(1..).unwrap_or("");
+                }
+                String::new()
+            }
+        };
+        if !name.is_empty() {
+            attrs.entry(name).or_insert(value);
+        }
+        rest = rest.trim_start();
+    }
+    attrs
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let entity = rest[1..]
+            .find(';')
+            .filter(|end| *end <= 10)
+            .map(|end| &rest[1..end + 1]);
+        let ch = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        });
+        match (entity, ch) {
+            (Some(entity), Some(ch)) => {
+                decoded.push(ch);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Collapse runs of whitespace, as a browser does when showing the text.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// File name of the cache, written at the project root and meant to be
+/// committed so that builds without network access render the same cards.
+pub const OGP_CACHE_FILE_NAME: &str = ".scraps-ogp.json";
+
+/// Open Graph metadata of every autolink fetched so far, keyed by URL.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct OgpCache(BTreeMap<Url, Ogp>);
+
+impl OgpCache {
+    pub fn new() -> OgpCache {
+        OgpCache(BTreeMap::new())
+    }
+
+    pub fn get(&self, url: &Url) -> Option<&Ogp> {
+        self.0.get(url)
+    }
+
+    pub fn contains(&self, url: &Url) -> bool {
+        self.0.contains_key(url)
+    }
+
+    pub fn insert(&mut self, url: Url, ogp: Ogp) {
+        self.0.insert(url, ogp);
+    }
+
+    /// Drop the entries of URLs no longer linked from any scrap.
+    pub fn retain(&mut self, urls: &BTreeSet<Url>) {
+        self.0.retain(|url, _| urls.contains(url));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn page_url() -> Url {
+        Url::parse("https://example.com/posts/1").unwrap()
+    }
+
+    #[test]
+    fn it_from_html_og_tags() {
+        let html = r#"<!DOCTYPE html>
+<html><head>
+  <meta charset="utf-8">
+  <META Property="og:title" content="Tom &amp; Jerry&#39;s &#x1F600;" />
+  <meta property='og:description' content='A  short
+    description'>
+  <meta property="og:image" content="/images/card.png">
+  <title>Ignored</title>
+</head><body><meta property="og:title" content="Body"></body></html>"#;
+
+        assert_eq!(
+            Ogp::from_html(html, &page_url()),
+            Ogp {
+                title: Some("Tom & Jerry's 😀".to_string()),
+                description: Some("A short description".to_string()),
+                image: Some(Url::parse("https://example.com/images/card.png").unwrap()),
+            }
+        );
+    }
+
+    #[rstest]
+    #[case::title_tag(
+        "<head><title>\n  Page &lt;1&gt;\n</title><meta name=description content=plain></head>",
+        Some("Page <1>"),
+        Some("plain")
+    )]
+    #[case::twitter(
+        r#"<meta name="twitter:title" content="Tweet"><meta name="twitter:description" content="Card">"#,
+        Some("Tweet"),
+        Some("Card")
+    )]
+    #[case::empty_og_title(
+        r#"<meta property="og:title" content=" "><title>Fallback</title>"#,
+        Some("Fallback"),
+        None
+    )]
+    #[case::nothing("<html><body>no head</body></html>", None, None)]
+    fn it_from_html_fallbacks(
+        #[case] html: &str,
+        #[case] title: Option<&str>,
+        #[case] description: Option<&str>,
+    ) {
+        let ogp = Ogp::from_html(html, &page_url());
+        assert_eq!(ogp.title.as_deref(), title);
+        assert_eq!(ogp.description.as_deref(), description);
+        assert_eq!(ogp.is_empty(), title.is_none() && description.is_none());
+    }
+
+    #[rstest]
+    #[case::absolute("https://cdn.example.net/a.png", Some("https://cdn.example.net/a.png"))]
+    #[case::protocol_relative("//cdn.example.net/a.png", Some("https://cdn.example.net/a.png"))]
+    #[case::relative("a.png", Some("https://example.com/posts/a.png"))]
+    #[case::data("data:image/png;base64,AAAA", None)]
+    fn it_from_html_image(#[case] image: &str, #[case] expected: Option<&str>) {
+        let html = format!(r#"<meta property="og:image" content="{image}">"#);
+        assert_eq!(
+            Ogp::from_html(&html, &page_url()).image,
+            expected.map(|url| Url::parse(url).unwrap())
+        );
+    }
+
+    #[test]
+    fn it_cache_round_trip() {
+        let url = Url::parse("https://example.com/").unwrap();
+        let mut cache = OgpCache::new();
+        cache.insert(
+            url.clone(),
+            Ogp {
+                title: Some("Example".to_string()),
+                ..Ogp::default()
+            },
+        );
+        cache.insert(
+            Url::parse("https://gone.example.com/").unwrap(),
+            Ogp::default(),
+        );
+        cache.retain(&BTreeSet::from([url.clone()]));
+
+        let json = serde_json::to_string(&cache).unwrap();
+        assert_eq!(json, r#"{"https://example.com/":{"title":"Example"}}"#);
+        assert_eq!(serde_json::from_str::<OgpCache>(&json).unwrap(), cache);
+    }
+}
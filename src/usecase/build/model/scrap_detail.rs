@@ -3,10 +3,12 @@ use itertools::Itertools;
 use std::collections::HashMap;
 
 use scraps_libs::{
-    html::{self, EmbedMode},
+    html::EmbedMode,
     model::{base_url::BaseUrl, content::Content, key::ScrapKey, scrap::Scrap},
 };
 
+use super::asset::Assets;
+
 #[derive(Clone)]
 pub struct ScrapDetail {
     v: Scrap,
@@ -19,9 +21,15 @@ impl ScrapDetail {
         scrap: &Scrap,
         commited_ts: &Option<i64>,
         base_url: &BaseUrl,
-        scrap_texts: &HashMap<ScrapKey, String>,
+        embeddable: &HashMap<ScrapKey, &Scrap>,
+        assets: &Assets,
     ) -> ScrapDetail {
-        let content = html::to_content(scrap.md_text(), base_url, EmbedMode::Expand(scrap_texts));
+        let content = scrap.document().to_content(
+            scrap.ctx(),
+            assets.paths(),
+            base_url,
+            EmbedMode::Expand(embeddable),
+        );
         ScrapDetail {
             v: scrap.to_owned(),
             content,
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use scraps_libs::model::{base_url::BaseUrl, key::ScrapKey, scrap::Scrap, tags::Tags};
+use url::Url;
+
+use crate::usecase::build::html::{
+    ctx_render::ctx_page_path, scrap_render::scrap_output_path, tag_render::tag_output_path,
+};
+
+use super::{backlinks_map::BacklinksMap, ctx_page::CtxPages, scrap_detail::ScrapDetails};
+
+/// A page listed in `sitemap.xml`, with the commit timestamp of its latest
+/// content when known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapUrl {
+    pub loc: Url,
+    pub lastmod: Option<i64>,
+}
+
+/// Every index, scrap, context and tag page of the site.
+#[derive(Debug, PartialEq)]
+pub struct Sitemap {
+    pub urls: Vec<SitemapUrl>,
+}
+
+impl Sitemap {
+    pub fn new(
+        base_url: &BaseUrl,
+        scrap_details: &ScrapDetails,
+        backlinks_map: &BacklinksMap,
+        ctx_pages: &CtxPages,
+        index_page_count: usize,
+    ) -> Sitemap {
+        let commited_ts: HashMap<ScrapKey, i64> = scrap_details
+            .to_vec()
+            .iter()
+            .filter_map(|d| Some((d.scrap().self_key(), d.commited_ts()?)))
+            .collect();
+        let latest = |scraps: &[Scrap]| {
+            scraps
+                .iter()
+                .filter_map(|scrap| commited_ts.get(&scrap.self_key()).copied())
+                .max()
+        };
+        let scraps = scrap_details.to_scraps();
+        let site_lastmod = latest(&scraps);
+
+        // Index pages: `./` then `2.html`, `3.html`, ... when paginated.
+        let mut urls: Vec<SitemapUrl> = (1..=index_page_count.max(1))
+            .map(|page| SitemapUrl {
+                loc: match page {
+                    1 => base_url.page_url(""),
+                    n => base_url.page_url(&format!("{n}.html")),
+                },
+                lastmod: site_lastmod,
+            })
+            .collect();
+
+        urls.extend(scraps.iter().map(|scrap| SitemapUrl {
+            loc: base_url.page_url(&scrap_output_path(&scrap.self_key())),
+            lastmod: commited_ts.get(&scrap.self_key()).copied(),
+        }));
+
+        urls.extend(ctx_pages.iter().map(|page| {
+            let readme = page.readme.iter().map(|readme| readme.scrap());
+            let listed: Vec<Scrap> = page.scraps.iter().cloned().chain(readme).collect();
+            SitemapUrl {
+                loc: base_url.page_url(&ctx_page_path(&page.ctx)),
+                lastmod: latest(&listed),
+            }
+        }));
+
+        urls.push(SitemapUrl {
+            loc: base_url.page_url("tags/"),
+            lastmod: site_lastmod,
+        });
+        let tags = Tags::new(&scraps);
+        urls.extend(tags.iter().map(|tag| SitemapUrl {
+            loc: base_url.page_url(&tag_output_path(tag)),
+            lastmod: latest(&backlinks_map.get_tag(tag)),
+        }));
+
+        Sitemap { urls }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usecase::build::model::asset::Assets;
+    use crate::usecase::build::model::scrap_detail::ScrapDetail;
+
+    #[test]
+    fn it_lists_every_page_with_its_lastmod() {
+        let base_url = BaseUrl::new(Url::parse("https://example.com/wiki/").unwrap()).unwrap();
+        let scraps = [
+            (Scrap::new("a", &None, "#[[tag]]"), Some(10)),
+            (Scrap::new("b", &Some("ctx".into()), "#[[tag]]"), Some(20)),
+            (Scrap::new("c", &None, ""), None),
+        ];
+        let details: Vec<ScrapDetail> = scraps
+            .iter()
+            .map(|(scrap, ts)| {
+                ScrapDetail::new(scrap, ts, &base_url, &HashMap::new(), &Assets::default())
+            })
+            .collect();
+        let scrap_details = ScrapDetails::new(&details);
+        let backlinks_map = BacklinksMap::new(&scrap_details.to_scraps());
+        let ctx_pages = CtxPages::new(&scrap_details);
+
+        let sitemap = Sitemap::new(&base_url, &scrap_details, &backlinks_map, &ctx_pages, 2);
+        let urls: Vec<(&str, Option<i64>)> = sitemap
+            .urls
+            .iter()
+            .map(|url| (url.loc.as_str(), url.lastmod))
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                ("https://example.com/wiki/", Some(20)),
+                ("https://example.com/wiki/2.html", Some(20)),
+                ("https://example.com/wiki/scraps/a.html", Some(10)),
+                ("https://example.com/wiki/scraps/ctx/b.html", Some(20)),
+                ("https://example.com/wiki/scraps/c.html", None),
+                ("https://example.com/wiki/scraps/ctx/", Some(20)),
+                ("https://example.com/wiki/tags/", Some(20)),
+                ("https://example.com/wiki/tags/tag.html", Some(20)),
+            ]
+        );
+    }
+}
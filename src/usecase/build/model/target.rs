@@ -0,0 +1,10 @@
+/// Emit target selected by `scraps build --target`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildTarget {
+    /// Static site: HTML pages, CSS, and the search index.
+    Html,
+    /// `llms.txt` index and `llms-full.txt` packed corpus.
+    Llms,
+    /// `scraps.json` typed graph and its JSON Schema.
+    Json,
+}
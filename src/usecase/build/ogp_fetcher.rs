@@ -0,0 +1,9 @@
+use url::Url;
+
+use crate::error::ScrapsResult;
+
+/// Fetches the pages behind autolinks to read their Open Graph metadata.
+pub trait OgpFetcher: Sync {
+    /// HTML of the page at `url`.
+    fn fetch_html(&self, url: &Url) -> ScrapsResult<String>;
+}
@@ -3,11 +3,20 @@ use scraps_libs::model::{base_url::BaseUrl, content::Content, scrap::Scrap, tag:
 
 use crate::error::ScrapsResult;
 use crate::usecase::build::model::{
+    asset::{Asset, Assets},
     backlinks_map::BacklinksMap,
+    build_manifest::BuildManifest,
     css::CssMetadata,
+    ctx_page::CtxPage,
+    feed::Feeds,
+    graph::SiteGraph,
     html::HtmlMetadata,
+    ir::ScrapsIr,
     list_view_configs::ListViewConfigs,
+    llms_corpus::LlmsCorpus,
+    ogp::OgpCache,
     scrap_detail::{ScrapDetail, ScrapDetails},
+    sitemap::Sitemap,
 };
 
 pub trait HtmlIndexRenderer {
@@ -23,6 +32,7 @@ pub trait HtmlIndexRenderer {
 }
 
 pub trait HtmlScrapRenderer {
+    #[allow(clippy::too_many_arguments)]
     fn render_scrap(
         &self,
         base_url: &BaseUrl,
@@ -30,6 +40,9 @@ pub trait HtmlScrapRenderer {
         html_metadata: &HtmlMetadata,
         scrap_detail: &ScrapDetail,
         backlinks_map: &BacklinksMap,
+        assets: &Assets,
+        ogp_cache: &OgpCache,
+        build_graph: bool,
     ) -> ScrapsResult<()>;
 }
 
@@ -50,6 +63,25 @@ pub trait HtmlTagRenderer {
         html_metadata: &HtmlMetadata,
         tag: &Tag,
         backlinks_map: &BacklinksMap,
+        assets: &Assets,
+    ) -> ScrapsResult<()>;
+}
+
+pub trait HtmlCtxRenderer {
+    fn render_ctx(
+        &self,
+        base_url: &BaseUrl,
+        html_metadata: &HtmlMetadata,
+        ctx_page: &CtxPage,
+        assets: &Assets,
+    ) -> ScrapsResult<()>;
+}
+
+pub trait HtmlGraphRenderer {
+    fn render_graph_page(
+        &self,
+        base_url: &BaseUrl,
+        html_metadata: &HtmlMetadata,
     ) -> ScrapsResult<()>;
 }
 
@@ -61,13 +93,59 @@ pub trait SearchIndexJsonRenderer {
     fn render_search_index(&self, base_url: &BaseUrl, scraps: &[Scrap]) -> ScrapsResult<()>;
 }
 
+pub trait GraphJsonRenderer {
+    fn render_graph(&self, graph: &SiteGraph) -> ScrapsResult<()>;
+}
+
+pub trait SitemapRenderer {
+    fn render_sitemap(&self, base_url: &BaseUrl, sitemap: &Sitemap) -> ScrapsResult<()>;
+}
+
+pub trait AtomFeedRenderer {
+    fn render_feeds(&self, html_metadata: &HtmlMetadata, feeds: &Feeds) -> ScrapsResult<()>;
+}
+
+pub trait LlmsTxtRenderer {
+    fn render_llms(&self, html_metadata: &HtmlMetadata, llms: &LlmsCorpus) -> ScrapsResult<()>;
+}
+
+pub trait IrJsonRenderer {
+    fn render_ir(&self, ir: &ScrapsIr) -> ScrapsResult<()>;
+}
+
+pub trait AssetCopier {
+    /// Copy `asset` into the output directory. Returns whether it was
+    /// written, `false` when the copy is already up to date.
+    fn copy_asset(&self, asset: &Asset) -> ScrapsResult<bool>;
+}
+
+pub trait BuildManifestStore {
+    /// Manifest of the previous build, empty when there is none.
+    fn load_manifest(&self) -> BuildManifest;
+    fn save_manifest(&self, manifest: &BuildManifest) -> ScrapsResult<()>;
+    /// Delete an output of a previous build, given relative to the output
+    /// directory.
+    fn remove_output(&self, output: &str) -> ScrapsResult<()>;
+    /// Hash of the user templates, which every page is rendered with.
+    fn templates_hash(&self) -> String;
+}
+
 pub trait BuildRenderer:
     HtmlIndexRenderer
     + HtmlScrapRenderer
     + HtmlTagsIndexRenderer
     + HtmlTagRenderer
+    + HtmlCtxRenderer
+    + HtmlGraphRenderer
     + CssRenderer
     + SearchIndexJsonRenderer
+    + GraphJsonRenderer
+    + SitemapRenderer
+    + AtomFeedRenderer
+    + LlmsTxtRenderer
+    + IrJsonRenderer
+    + AssetCopier
+    + BuildManifestStore
     + Sync
 {
 }
@@ -77,8 +155,17 @@ impl<T> BuildRenderer for T where
         + HtmlScrapRenderer
         + HtmlTagsIndexRenderer
         + HtmlTagRenderer
+        + HtmlCtxRenderer
+        + HtmlGraphRenderer
         + CssRenderer
         + SearchIndexJsonRenderer
+        + GraphJsonRenderer
+        + SitemapRenderer
+        + AtomFeedRenderer
+        + LlmsTxtRenderer
+        + IrJsonRenderer
+        + AssetCopier
+        + BuildManifestStore
         + Sync
 {
 }
@@ -86,12 +173,34 @@ impl<T> BuildRenderer for T where
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
 
-    pub struct BuildRendererTest();
+    /// Keeps the manifest in memory, counts the scrap pages written and
+    /// records the assets copied and the outputs removed.
+    #[derive(Default)]
+    pub struct BuildRendererTest {
+        manifest: Mutex<BuildManifest>,
+        rendered_scraps: AtomicUsize,
+        copied_assets: Mutex<Vec<String>>,
+        removed_outputs: Mutex<Vec<String>>,
+    }
 
     impl BuildRendererTest {
         pub fn new() -> Self {
-            Self()
+            Self::default()
+        }
+
+        pub fn rendered_scraps(&self) -> usize {
+            self.rendered_scraps.load(Ordering::Relaxed)
+        }
+
+        pub fn copied_assets(&self) -> Vec<String> {
+            self.copied_assets.lock().unwrap().clone()
+        }
+
+        pub fn removed_outputs(&self) -> Vec<String> {
+            self.removed_outputs.lock().unwrap().clone()
         }
     }
 
@@ -117,7 +226,11 @@ pub mod tests {
             _html_metadata: &HtmlMetadata,
             _scrap_detail: &ScrapDetail,
             _backlinks_map: &BacklinksMap,
+            _assets: &Assets,
+            _ogp_cache: &OgpCache,
+            _build_graph: bool,
         ) -> ScrapsResult<()> {
+            self.rendered_scraps.fetch_add(1, Ordering::Relaxed);
             Ok(())
         }
     }
@@ -141,6 +254,29 @@ pub mod tests {
             _html_metadata: &HtmlMetadata,
             _tag: &Tag,
             _backlinks_map: &BacklinksMap,
+            _assets: &Assets,
+        ) -> ScrapsResult<()> {
+            Ok(())
+        }
+    }
+
+    impl HtmlCtxRenderer for BuildRendererTest {
+        fn render_ctx(
+            &self,
+            _base_url: &BaseUrl,
+            _html_metadata: &HtmlMetadata,
+            _ctx_page: &CtxPage,
+            _assets: &Assets,
+        ) -> ScrapsResult<()> {
+            Ok(())
+        }
+    }
+
+    impl HtmlGraphRenderer for BuildRendererTest {
+        fn render_graph_page(
+            &self,
+            _base_url: &BaseUrl,
+            _html_metadata: &HtmlMetadata,
         ) -> ScrapsResult<()> {
             Ok(())
         }
@@ -157,4 +293,68 @@ pub mod tests {
             Ok(())
         }
     }
+
+    impl GraphJsonRenderer for BuildRendererTest {
+        fn render_graph(&self, _graph: &SiteGraph) -> ScrapsResult<()> {
+            Ok(())
+        }
+    }
+
+    impl SitemapRenderer for BuildRendererTest {
+        fn render_sitemap(&self, _base_url: &BaseUrl, _sitemap: &Sitemap) -> ScrapsResult<()> {
+            Ok(())
+        }
+    }
+
+    impl AtomFeedRenderer for BuildRendererTest {
+        fn render_feeds(&self, _html_metadata: &HtmlMetadata, _feeds: &Feeds) -> ScrapsResult<()> {
+            Ok(())
+        }
+    }
+
+    impl LlmsTxtRenderer for BuildRendererTest {
+        fn render_llms(
+            &self,
+            _html_metadata: &HtmlMetadata,
+            _llms: &LlmsCorpus,
+        ) -> ScrapsResult<()> {
+            Ok(())
+        }
+    }
+
+    impl IrJsonRenderer for BuildRendererTest {
+        fn render_ir(&self, _ir: &ScrapsIr) -> ScrapsResult<()> {
+            Ok(())
+        }
+    }
+
+    impl AssetCopier for BuildRendererTest {
+        fn copy_asset(&self, asset: &Asset) -> ScrapsResult<bool> {
+            self.copied_assets.lock().unwrap().push(asset.output_path());
+            Ok(true)
+        }
+    }
+
+    impl BuildManifestStore for BuildRendererTest {
+        fn load_manifest(&self) -> BuildManifest {
+            self.manifest.lock().unwrap().clone()
+        }
+
+        fn save_manifest(&self, manifest: &BuildManifest) -> ScrapsResult<()> {
+            *self.manifest.lock().unwrap() = manifest.clone();
+            Ok(())
+        }
+
+        fn remove_output(&self, output: &str) -> ScrapsResult<()> {
+            self.removed_outputs
+                .lock()
+                .unwrap()
+                .push(output.to_string());
+            Ok(())
+        }
+
+        fn templates_hash(&self) -> String {
+            String::new()
+        }
+    }
 }
@@ -0,0 +1,172 @@
+use std::io::{BufWriter, Write};
+use std::{fs::File, path::PathBuf};
+
+use once_cell::sync::Lazy;
+use scraps_libs::model::base_url::BaseUrl;
+use tera::Tera;
+
+use crate::error::{anyhow::Context, BuildError, ScrapsResult};
+use crate::usecase::build::model::sitemap::Sitemap;
+use crate::usecase::build::xml;
+
+static ROBOTS_TERA: Lazy<Tera> = Lazy::new(|| {
+    let mut tera = Tera::default();
+    tera.add_raw_template("__builtins/robots.txt", include_str!("builtins/robots.txt"))
+        .unwrap();
+    tera
+});
+
+pub struct SitemapRender {
+    static_dir_path: PathBuf,
+    output_dir_path: PathBuf,
+}
+
+impl SitemapRender {
+    pub fn new(
+        static_dir_path: &PathBuf,
+        output_dir_path: &PathBuf,
+    ) -> ScrapsResult<SitemapRender> {
+        std::fs::create_dir_all(output_dir_path).context(BuildError::CreateDir)?;
+
+        Ok(SitemapRender {
+            static_dir_path: static_dir_path.to_owned(),
+            output_dir_path: output_dir_path.to_owned(),
+        })
+    }
+
+    /// Write `sitemap.xml` and `robots.txt`, which points crawlers at it.
+    /// A `robots.txt` in the static directory replaces the builtin one.
+    pub fn run(&self, base_url: &BaseUrl, sitemap: &Sitemap) -> ScrapsResult<()> {
+        self.write("sitemap.xml", &Self::sitemap_xml(sitemap))?;
+        self.render_robots(base_url)
+    }
+
+    fn sitemap_xml(sitemap: &Sitemap) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n"
+        ));
+        for url in &sitemap.urls {
+            out.push_str("  <url>\n");
+            out.push_str(&format!(
+                "    <loc>{}</loc>\n",
+                xml::escape(url.loc.as_str())
+            ));
+            if let Some(lastmod) = url.lastmod {
+                out.push_str(&format!(
+                    "    <lastmod>{}</lastmod>\n",
+                    xml::datetime(lastmod)
+                ));
+            }
+            out.push_str("  </url>\n");
+        }
+        out.push_str("</urlset>\n");
+        out
+    }
+
+    fn render_robots(&self, base_url: &BaseUrl) -> ScrapsResult<()> {
+        let mut tera = ROBOTS_TERA.clone();
+        tera.load_from_glob(self.static_dir_path.join("*.txt").to_str().unwrap())
+            .context(BuildError::RenderText)?;
+        let template_name = if tera.get_template_names().any(|t| t == "robots.txt") {
+            "robots.txt"
+        } else {
+            "__builtins/robots.txt"
+        };
+        let mut context = tera::Context::new();
+        context.insert("base_url", &base_url.as_url());
+        context.insert("sitemap_url", &base_url.page_url("sitemap.xml"));
+
+        let file_path = &self.output_dir_path.join("robots.txt");
+        let mut wtr = BufWriter::new(
+            File::create(file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        tera.render_to(template_name, &context, &mut wtr)
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(file_path.clone()))
+    }
+
+    fn write(&self, file_name: &str, text: &str) -> ScrapsResult<()> {
+        let file_path = &self.output_dir_path.join(file_name);
+        let mut wtr = BufWriter::new(
+            File::create(file_path).context(BuildError::WriteFailure(file_path.clone()))?,
+        );
+        wtr.write_all(text.as_bytes())
+            .context(BuildError::WriteFailure(file_path.clone()))?;
+        wtr.flush()
+            .context(BuildError::WriteFailure(file_path.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_fixtures::{temp_scrap_project, TempScrapProject};
+    use crate::usecase::build::model::sitemap::SitemapUrl;
+    use rstest::rstest;
+    use std::fs;
+    use url::Url;
+
+    use super::*;
+
+    fn base_url() -> BaseUrl {
+        BaseUrl::new(Url::parse("https://example.com/wiki/").unwrap()).unwrap()
+    }
+
+    #[rstest]
+    fn it_writes_sitemap_and_default_robots(#[from(temp_scrap_project)] project: TempScrapProject) {
+        let sitemap = Sitemap {
+            urls: vec![
+                SitemapUrl {
+                    loc: Url::parse("https://example.com/wiki/").unwrap(),
+                    lastmod: Some(1_700_000_000),
+                },
+                SitemapUrl {
+                    loc: Url::parse("https://example.com/wiki/scraps/a.html?x=1&y=2").unwrap(),
+                    lastmod: None,
+                },
+            ],
+        };
+
+        SitemapRender::new(&project.static_dir, &project.output_dir)
+            .unwrap()
+            .run(&base_url(), &sitemap)
+            .unwrap();
+
+        let xml = fs::read_to_string(project.output_path("sitemap.xml")).unwrap();
+        assert!(xml.contains(concat!(
+            "  <url>\n",
+            "    <loc>https://example.com/wiki/</loc>\n",
+            "    <lastmod>2023-11-14T22:13:20Z</lastmod>\n",
+            "  </url>\n"
+        )));
+        assert!(xml.contains(concat!(
+            "  <url>\n",
+            "    <loc>https://example.com/wiki/scraps/a.html?x=1&amp;y=2</loc>\n",
+            "  </url>\n"
+        )));
+
+        let robots = fs::read_to_string(project.output_path("robots.txt")).unwrap();
+        assert!(robots.contains("User-agent: *"));
+        assert!(robots.contains("Sitemap: https://example.com/wiki/sitemap.xml"));
+    }
+
+    #[rstest]
+    fn it_renders_robots_from_static(#[from(temp_scrap_project)] project: TempScrapProject) {
+        project.add_static_file(
+            "robots.txt",
+            b"User-agent: *\nDisallow: /drafts/\nSitemap: {{ sitemap_url }}\n",
+        );
+
+        SitemapRender::new(&project.static_dir, &project.output_dir)
+            .unwrap()
+            .run(&base_url(), &Sitemap { urls: vec![] })
+            .unwrap();
+
+        let robots = fs::read_to_string(project.output_path("robots.txt")).unwrap();
+        assert_eq!(
+            robots,
+            "User-agent: *\nDisallow: /drafts/\nSitemap: https://example.com/wiki/sitemap.xml\n"
+        );
+    }
+}
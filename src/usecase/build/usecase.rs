@@ -1,3 +1,4 @@
+use super::ogp_fetcher::OgpFetcher;
 use super::renderer::BuildRenderer;
 use crate::error::ScrapsResult;
 use crate::usecase::progress::{Progress, Stage};
@@ -6,18 +7,31 @@ use rayon::iter::IntoParallelIterator;
 use rayon::prelude::*;
 use scraps_libs::{
     html::{self, EmbedMode},
-    model::{base_url::BaseUrl, scrap::Scrap, tags::Tags},
+    model::{base_url::BaseUrl, scrap::Scrap, tag::Tag, tags::Tags},
 };
 use tracing::{span, Level};
 
+use super::html::{
+    ctx_render::ctx_output_path, scrap_render::scrap_output_path, tag_render::tag_output_path,
+};
 use super::model::{
+    affected_pages::AffectedPages,
+    asset::Assets,
     backlinks_map::BacklinksMap,
+    build_manifest::{self, BuildManifest},
     css::CssMetadata,
+    ctx_page::{CtxPage, CtxPages},
+    feed::{FeedConfigs, Feeds},
+    graph::SiteGraph,
     html::HtmlMetadata,
+    ir::ScrapsIr,
     list_view_configs::ListViewConfigs,
+    llms_corpus::LlmsCorpus,
+    ogp::{Ogp, OgpCache},
     scrap_detail::{ScrapDetail, ScrapDetails},
+    sitemap::Sitemap,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 pub struct BuildUsecase;
 
@@ -26,6 +40,52 @@ impl BuildUsecase {
         BuildUsecase
     }
 
+    /// Fetch the Open Graph metadata of autolinks missing from `cache`, and
+    /// drop the entries of URLs no longer linked. Without a `fetcher` the
+    /// cache is left as is. Pages that fail to load are warned about and
+    /// tried again on the next build. Returns the number of pages fetched.
+    pub fn execute_ogp<PG: Progress, OF: OgpFetcher>(
+        &self,
+        scraps_with_ts: &[(Scrap, Option<i64>)],
+        progress: &PG,
+        fetcher: Option<&OF>,
+        cache: &mut OgpCache,
+    ) -> ScrapsResult<usize> {
+        let Some(fetcher) = fetcher else {
+            return Ok(0);
+        };
+        let urls: BTreeSet<_> = scraps_with_ts
+            .par_iter()
+            .flat_map_iter(|(scrap, _)| html::autolinks(scrap.md_text()))
+            .collect();
+        cache.retain(&urls);
+        let missing: Vec<_> = urls.iter().filter(|url| !cache.contains(url)).collect();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+
+        progress.start_stage(&Stage::FetchOgp);
+        let span_fetch_ogp = span!(Level::INFO, "fetch_ogp").entered();
+        let fetched: Vec<_> = missing
+            .into_par_iter()
+            .filter_map(|url| match fetcher.fetch_html(url) {
+                Ok(html) => Some((url.clone(), Ogp::from_html(&html, url))),
+                Err(e) => {
+                    tracing::warn!("{e:#}");
+                    None
+                }
+            })
+            .collect();
+        let fetched_count = fetched.len();
+        for (url, ogp) in fetched {
+            cache.insert(url, ogp);
+        }
+        span_fetch_ogp.exit();
+        progress.complete_stage(&Stage::FetchOgp, &fetched_count);
+        Ok(fetched_count)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn execute<PG: Progress, BR: BuildRenderer>(
         &self,
         scraps_with_ts: &[(Scrap, Option<i64>)],
@@ -37,25 +97,104 @@ impl BuildUsecase {
         html_metadata: &HtmlMetadata,
         css_metadata: &CssMetadata,
         list_view_configs: &ListViewConfigs,
+        ogp_cache: &OgpCache,
+        assets: &Assets,
+    ) -> ScrapsResult<usize> {
+        let (scrap_count, _) = self.render_site(
+            scraps_with_ts,
+            readme_text,
+            progress,
+            renderer,
+            base_url,
+            timezone,
+            html_metadata,
+            Some(css_metadata),
+            list_view_configs,
+            ogp_cache,
+            assets,
+            None,
+        )?;
+        Ok(scrap_count)
+    }
+
+    /// Re-render only the scrap and tag pages in `affected`, plus the pages
+    /// listing every scrap, after an incremental change. CSS is left as is.
+    /// Returns the number of HTML files written.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_affected<PG: Progress, BR: BuildRenderer>(
+        &self,
+        scraps_with_ts: &[(Scrap, Option<i64>)],
+        readme_text: &Option<String>,
+        progress: &PG,
+        renderer: &BR,
+        base_url: &BaseUrl,
+        timezone: Tz,
+        html_metadata: &HtmlMetadata,
+        list_view_configs: &ListViewConfigs,
+        ogp_cache: &OgpCache,
+        assets: &Assets,
+        affected: &AffectedPages,
     ) -> ScrapsResult<usize> {
+        let (_, html_count) = self.render_site(
+            scraps_with_ts,
+            readme_text,
+            progress,
+            renderer,
+            base_url,
+            timezone,
+            html_metadata,
+            None,
+            list_view_configs,
+            ogp_cache,
+            assets,
+            Some(affected),
+        )?;
+        Ok(html_count)
+    }
+
+    /// Render the site, restricted to `affected` scrap and tag pages when
+    /// given, and copy `assets` next to the scrap pages. Returns the scrap
+    /// count and the number of HTML files written.
+    #[allow(clippy::too_many_arguments)]
+    fn render_site<PG: Progress, BR: BuildRenderer>(
+        &self,
+        scraps_with_ts: &[(Scrap, Option<i64>)],
+        readme_text: &Option<String>,
+        progress: &PG,
+        renderer: &BR,
+        base_url: &BaseUrl,
+        timezone: Tz,
+        html_metadata: &HtmlMetadata,
+        css_metadata: Option<&CssMetadata>,
+        list_view_configs: &ListViewConfigs,
+        ogp_cache: &OgpCache,
+        assets: &Assets,
+        affected: Option<&AffectedPages>,
+    ) -> ScrapsResult<(usize, usize)> {
         progress.start_stage(&Stage::ReadScraps);
         let span_read_scraps = span!(Level::INFO, "read_scraps").entered();
 
-        let scrap_texts: HashMap<_, _> = scraps_with_ts
+        let embeddable: HashMap<_, _> = scraps_with_ts
             .iter()
-            .map(|(scrap, _)| (scrap.self_key(), scrap.md_text().to_string()))
+            .map(|(scrap, _)| (scrap.self_key(), scrap))
             .collect();
 
         // Process README content
-        let readme_content = readme_text
-            .as_ref()
-            .map(|text| html::to_content(text, base_url, EmbedMode::Expand(&scrap_texts)));
+        let readme_content = readme_text.as_ref().map(|text| {
+            html::to_content(
+                text,
+                &None,
+                assets.paths(),
+                base_url,
+                EmbedMode::Expand(&embeddable),
+            )
+        });
 
         // Build ScrapDetails from pre-loaded data
         let scrap_details = scraps_with_ts
             .into_par_iter()
             .map(|(scrap, commited_ts)| {
-                ScrapDetail::new(scrap, commited_ts, base_url, &scrap_texts)
+                ScrapDetail::new(scrap, commited_ts, base_url, &embeddable, assets)
             })
             .collect::<Vec<ScrapDetail>>();
         let scrap_details = ScrapDetails::new(&scrap_details);
@@ -65,6 +204,72 @@ impl BuildUsecase {
         span_read_scraps.exit();
         progress.complete_stage(&Stage::ReadScraps, &scrap_details.len());
 
+        // Compare the inputs of each scrap and tag page with the manifest of
+        // the previous build; pages in `affected` are rendered regardless.
+        progress.start_stage(&Stage::ReuseHtml);
+        let span_check_manifest = span!(Level::INFO, "check_manifest").entered();
+        let previous_manifest = renderer.load_manifest();
+        let site_hash = build_manifest::site_hash(
+            &renderer.templates_hash(),
+            base_url,
+            timezone,
+            html_metadata,
+            list_view_configs.build_graph,
+            assets,
+        );
+        let mut manifest = BuildManifest::new();
+        let mut reused_count = 0;
+
+        let mut rendered_details = Vec::new();
+        for scrap_detail in scrap_details.to_vec() {
+            let key = scrap_detail.scrap().self_key();
+            let output = scrap_output_path(&key);
+            let input_hash = build_manifest::scrap_page_hash(
+                &site_hash,
+                &scrap_detail,
+                &backlinks_map,
+                ogp_cache,
+            );
+            if affected.is_some_and(|a| a.scraps.contains(&key))
+                || !previous_manifest.is_unchanged(&output, &input_hash)
+            {
+                rendered_details.push(scrap_detail);
+            } else {
+                reused_count += 1;
+            }
+            manifest.insert(&output, &input_hash);
+        }
+
+        let tags = Tags::new(&scraps);
+        let mut rendered_tags: Vec<&Tag> = Vec::new();
+        for tag in tags.iter() {
+            let output = tag_output_path(tag);
+            let input_hash = build_manifest::tag_page_hash(&site_hash, tag, &backlinks_map);
+            if affected.is_some_and(|a| a.tags.contains(tag))
+                || !previous_manifest.is_unchanged(&output, &input_hash)
+            {
+                rendered_tags.push(tag);
+            } else {
+                reused_count += 1;
+            }
+            manifest.insert(&output, &input_hash);
+        }
+
+        let ctx_pages = CtxPages::new(&scrap_details);
+        let mut rendered_ctx_pages: Vec<&CtxPage> = Vec::new();
+        for ctx_page in ctx_pages.iter() {
+            let output = ctx_output_path(&ctx_page.ctx);
+            let input_hash = build_manifest::ctx_page_hash(&site_hash, ctx_page);
+            if !previous_manifest.is_unchanged(&output, &input_hash) {
+                rendered_ctx_pages.push(ctx_page);
+            } else {
+                reused_count += 1;
+            }
+            manifest.insert(&output, &input_hash);
+        }
+        span_check_manifest.exit();
+        progress.complete_stage(&Stage::ReuseHtml, &reused_count);
+
         // generate html
         progress.start_stage(&Stage::GenerateHtml);
 
@@ -82,19 +287,19 @@ impl BuildUsecase {
 
         // generate html scraps
         let span_generate_html_scraps = span!(Level::INFO, "generate_html_scraps").entered();
-        scrap_details
-            .to_vec()
-            .into_par_iter()
-            .try_for_each(|scrap_detail| {
-                let _span_generate_html_scrap = span!(Level::INFO, "generate_html_scrap").entered();
-                renderer.render_scrap(
-                    base_url,
-                    timezone,
-                    html_metadata,
-                    &scrap_detail,
-                    &backlinks_map,
-                )
-            })?;
+        rendered_details.par_iter().try_for_each(|scrap_detail| {
+            let _span_generate_html_scrap = span!(Level::INFO, "generate_html_scrap").entered();
+            renderer.render_scrap(
+                base_url,
+                timezone,
+                html_metadata,
+                scrap_detail,
+                &backlinks_map,
+                assets,
+                ogp_cache,
+                list_view_configs.build_graph,
+            )
+        })?;
         span_generate_html_scraps.exit();
 
         // generate html tags index
@@ -105,35 +310,180 @@ impl BuildUsecase {
 
         // generate html tags
         let span_generate_html_tags = span!(Level::INFO, "generate_html_tags").entered();
-        let tags = Tags::new(&scraps);
-        tags.iter().par_bridge().try_for_each(|tag| {
+        rendered_tags.par_iter().try_for_each(|tag| {
             let _span_render_tag = span!(Level::INFO, "generate_html_tag").entered();
-            renderer.render_tag(base_url, html_metadata, tag, &backlinks_map)
+            renderer.render_tag(base_url, html_metadata, tag, &backlinks_map, assets)
         })?;
         span_generate_html_tags.exit();
 
-        progress.complete_stage(&Stage::GenerateHtml, &{
-            index_page_count + scrap_details.len() + 1 + // tags index
-                tags.len()
-        });
+        // generate html ctx pages
+        let span_generate_html_ctxs = span!(Level::INFO, "generate_html_ctxs").entered();
+        rendered_ctx_pages.par_iter().try_for_each(|ctx_page| {
+            let _span_render_ctx = span!(Level::INFO, "generate_html_ctx").entered();
+            renderer.render_ctx(base_url, html_metadata, ctx_page, assets)
+        })?;
+        span_generate_html_ctxs.exit();
+
+        // generate the graph page
+        if list_view_configs.build_graph {
+            let _span_generate_html_graph = span!(Level::INFO, "generate_html_graph").entered();
+            renderer.render_graph_page(base_url, html_metadata)?;
+        }
+
+        // remove pages of scraps, tags and contexts and the assets that are
+        // gone, before copying assets onto paths that pages may have left
+        for asset in assets.iter() {
+            manifest.insert(&asset.output_path(), &build_manifest::asset_hash(asset));
+        }
+        for output in previous_manifest.stale_outputs(&manifest) {
+            renderer.remove_output(output)?;
+        }
+
+        let html_count = index_page_count + rendered_details.len() + 1 + // tags index
+            rendered_tags.len() + rendered_ctx_pages.len() + usize::from(list_view_configs.build_graph);
+        progress.complete_stage(&Stage::GenerateHtml, &html_count);
+
+        // copy assets, skipping copies that are already up to date
+        if !assets.is_empty() {
+            progress.start_stage(&Stage::CopyAssets);
+            let span_copy_assets = span!(Level::INFO, "copy_assets").entered();
+            let copied = assets
+                .as_slice()
+                .par_iter()
+                .map(|asset| renderer.copy_asset(asset))
+                .collect::<ScrapsResult<Vec<bool>>>()?;
+            let copied_count = copied.into_iter().filter(|copied| *copied).count();
+            span_copy_assets.exit();
+            progress.complete_stage(&Stage::CopyAssets, &copied_count);
+        }
+
+        // record this build
+        renderer.save_manifest(&manifest)?;
 
         // generate css
-        progress.start_stage(&Stage::GenerateCss);
-        let span_generate_css = span!(Level::INFO, "generate_css").entered();
-        renderer.render_css(css_metadata)?;
-        span_generate_css.exit();
-        progress.complete_stage(&Stage::GenerateCss, &1);
-
-        // generate search index json when build_search_index is true
-        if list_view_configs.build_search_index {
+        if let Some(css_metadata) = css_metadata {
+            progress.start_stage(&Stage::GenerateCss);
+            let span_generate_css = span!(Level::INFO, "generate_css").entered();
+            renderer.render_css(css_metadata)?;
+            span_generate_css.exit();
+            progress.complete_stage(&Stage::GenerateCss, &1);
+        }
+
+        // generate search index json and graph json when enabled
+        if list_view_configs.build_search_index || list_view_configs.build_graph {
             progress.start_stage(&Stage::GenerateJson);
-            let _span_generate_json_search_index =
-                span!(Level::INFO, "generate_json_search_index").entered();
-            renderer.render_search_index(base_url, &scraps)?;
-            progress.complete_stage(&Stage::GenerateJson, &1);
+            if list_view_configs.build_search_index {
+                let _span_generate_json_search_index =
+                    span!(Level::INFO, "generate_json_search_index").entered();
+                renderer.render_search_index(base_url, &scraps)?;
+            }
+            if list_view_configs.build_graph {
+                let _span_generate_json_graph = span!(Level::INFO, "generate_json_graph").entered();
+                renderer.render_graph(&SiteGraph::new(&scraps))?;
+            }
+            let json_count = usize::from(list_view_configs.build_search_index)
+                + usize::from(list_view_configs.build_graph);
+            progress.complete_stage(&Stage::GenerateJson, &json_count);
         }
 
-        Ok(scraps.len())
+        // generate sitemap.xml and robots.txt
+        progress.start_stage(&Stage::GenerateText);
+        let span_generate_sitemap = span!(Level::INFO, "generate_sitemap").entered();
+        let sitemap = Sitemap::new(
+            base_url,
+            &scrap_details,
+            &backlinks_map,
+            &ctx_pages,
+            index_page_count,
+        );
+        renderer.render_sitemap(base_url, &sitemap)?;
+        span_generate_sitemap.exit();
+        progress.complete_stage(&Stage::GenerateText, &2);
+
+        Ok((scraps.len(), html_count))
+    }
+
+    /// Emit the Atom feeds of recently committed scraps. Returns the number
+    /// of feeds written, none when no scrap has a commit timestamp.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_feeds<PG: Progress, BR: BuildRenderer>(
+        &self,
+        scraps_with_ts: &[(Scrap, Option<i64>)],
+        progress: &PG,
+        renderer: &BR,
+        base_url: &BaseUrl,
+        html_metadata: &HtmlMetadata,
+        feed_configs: &FeedConfigs,
+        assets: &Assets,
+    ) -> ScrapsResult<usize> {
+        progress.start_stage(&Stage::GenerateFeed);
+        let span_generate_feeds = span!(Level::INFO, "generate_feeds").entered();
+        let scraps: Vec<_> = scraps_with_ts.iter().map(|(s, _)| s.clone()).collect();
+        let backlinks_map = BacklinksMap::new(&scraps);
+        let feeds = Feeds::new(
+            scraps_with_ts,
+            base_url,
+            &backlinks_map,
+            feed_configs,
+            assets,
+        );
+        renderer.render_feeds(html_metadata, &feeds)?;
+        span_generate_feeds.exit();
+        progress.complete_stage(&Stage::GenerateFeed, &feeds.len());
+
+        Ok(feeds.len())
+    }
+
+    /// Emit the `llms.txt` index and the `llms-full.txt` packed corpus.
+    /// Returns the number of scraps that made it into the corpus.
+    #[allow(clippy::too_many_arguments)]
+    pub fn execute_llms<PG: Progress, BR: BuildRenderer>(
+        &self,
+        scraps: &[Scrap],
+        readme_text: &Option<String>,
+        progress: &PG,
+        renderer: &BR,
+        base_url: &BaseUrl,
+        html_metadata: &HtmlMetadata,
+        token_budget: Option<usize>,
+    ) -> ScrapsResult<usize> {
+        progress.start_stage(&Stage::ReadScraps);
+        let span_read_scraps = span!(Level::INFO, "read_scraps").entered();
+        let backlinks_map = BacklinksMap::new(scraps);
+        let llms = LlmsCorpus::new(scraps, readme_text, base_url, &backlinks_map, token_budget);
+        span_read_scraps.exit();
+        progress.complete_stage(&Stage::ReadScraps, &scraps.len());
+
+        progress.start_stage(&Stage::GenerateText);
+        let span_generate_llms = span!(Level::INFO, "generate_llms").entered();
+        renderer.render_llms(html_metadata, &llms)?;
+        span_generate_llms.exit();
+        progress.complete_stage(&Stage::GenerateText, &2);
+
+        Ok(llms.corpus.len())
+    }
+
+    pub fn execute_json<PG: Progress, BR: BuildRenderer>(
+        &self,
+        scraps_with_ts: &[(Scrap, Option<i64>)],
+        progress: &PG,
+        renderer: &BR,
+    ) -> ScrapsResult<usize> {
+        progress.start_stage(&Stage::ReadScraps);
+        let span_read_scraps = span!(Level::INFO, "read_scraps").entered();
+        let scraps: Vec<_> = scraps_with_ts.iter().map(|(s, _)| s.clone()).collect();
+        let backlinks_map = BacklinksMap::new(&scraps);
+        let ir = ScrapsIr::new(scraps_with_ts, &backlinks_map);
+        span_read_scraps.exit();
+        progress.complete_stage(&Stage::ReadScraps, &scraps.len());
+
+        progress.start_stage(&Stage::GenerateJson);
+        let span_generate_ir = span!(Level::INFO, "generate_ir").entered();
+        renderer.render_ir(&ir)?;
+        span_generate_ir.exit();
+        progress.complete_stage(&Stage::GenerateJson, &2);
+
+        Ok(ir.scraps.len())
     }
 }
 
@@ -144,14 +494,17 @@ mod tests {
     use crate::usecase::progress::tests::ProgressTest;
 
     use super::*;
+    use crate::usecase::build::model::asset::Asset;
     use scraps_libs::lang::LangCode;
+    use scraps_libs::model::file::AssetPath;
+    use std::collections::HashSet;
     use url::Url;
 
     #[test]
     fn it_run() {
         let scraps_with_ts = vec![
             (
-                Scrap::new("test1", &None, &concat!("# header1\n", "## header2\n")),
+                Scrap::new("test1", &None, concat!("# header1\n", "## header2\n")),
                 Some(0i64),
             ),
             (Scrap::new("test2", &None, "[[test1]]\n"), Some(0i64)),
@@ -170,7 +523,8 @@ mod tests {
             &Some(Url::parse("https://github.io/image.png").unwrap()),
         );
         let css_metadata = &CssMetadata::new(&ColorScheme::OsSetting);
-        let list_view_configs = ListViewConfigs::new(&true, &SortKey::LinkedCount, &Paging::Not);
+        let list_view_configs =
+            ListViewConfigs::new(&true, &false, &SortKey::LinkedCount, &Paging::Not);
 
         let usecase = BuildUsecase::new();
         let result = usecase
@@ -184,6 +538,8 @@ mod tests {
                 html_metadata,
                 css_metadata,
                 &list_view_configs,
+                &OgpCache::new(),
+                &Assets::default(),
             )
             .unwrap();
         assert_eq!(result, 2);
@@ -193,7 +549,7 @@ mod tests {
     fn it_run_when_build_search_index_is_false() {
         let scraps_with_ts = vec![
             (
-                Scrap::new("test1", &None, &concat!("# header1\n", "## header2\n")),
+                Scrap::new("test1", &None, concat!("# header1\n", "## header2\n")),
                 Some(0i64),
             ),
             (Scrap::new("test2", &None, "[[test1]]\n"), Some(0i64)),
@@ -210,7 +566,8 @@ mod tests {
             &Some(Url::parse("https://github.io/image.png").unwrap()),
         );
         let css_metadata = &CssMetadata::new(&ColorScheme::OsSetting);
-        let list_view_configs = ListViewConfigs::new(&false, &SortKey::LinkedCount, &Paging::Not);
+        let list_view_configs =
+            ListViewConfigs::new(&false, &false, &SortKey::LinkedCount, &Paging::Not);
 
         let usecase = BuildUsecase::new();
         let result = usecase
@@ -224,8 +581,306 @@ mod tests {
                 html_metadata,
                 css_metadata,
                 &list_view_configs,
+                &OgpCache::new(),
+                &Assets::default(),
+            )
+            .unwrap();
+        assert_eq!(result, 2);
+    }
+
+    #[test]
+    fn it_run_affected_pages_only() {
+        let scraps_with_ts = vec![
+            (Scrap::new("test1", &None, "#[[tag1]]\n"), Some(0i64)),
+            (Scrap::new("test2", &None, "[[test1]]\n"), Some(0i64)),
+            (Scrap::new("test3", &None, "#[[tag2]]\n"), Some(0i64)),
+        ];
+
+        let progress = ProgressTest::new();
+        let renderer = BuildRendererTest::new();
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let html_metadata = &HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+        let list_view_configs =
+            ListViewConfigs::new(&true, &false, &SortKey::LinkedCount, &Paging::Not);
+        let affected = AffectedPages {
+            scraps: HashSet::from([Scrap::new("test2", &None, "").self_key()]),
+            tags: HashSet::from([Tag::from("tag1")]),
+        };
+
+        let usecase = BuildUsecase::new();
+        // Pages missing from the manifest are rendered too, so build once.
+        usecase
+            .execute(
+                &scraps_with_ts,
+                &None,
+                &progress,
+                &renderer,
+                &base_url,
+                chrono_tz::UTC,
+                html_metadata,
+                &CssMetadata::new(&ColorScheme::OsSetting),
+                &list_view_configs,
+                &OgpCache::new(),
+                &Assets::default(),
+            )
+            .unwrap();
+        let result = usecase
+            .execute_affected(
+                &scraps_with_ts,
+                &None,
+                &progress,
+                &renderer,
+                &base_url,
+                chrono_tz::UTC,
+                html_metadata,
+                &list_view_configs,
+                &OgpCache::new(),
+                &Assets::default(),
+                &affected,
+            )
+            .unwrap();
+        // index, test2, tags index and tag1
+        assert_eq!(result, 4);
+    }
+
+    #[test]
+    fn it_reuses_pages_with_unchanged_inputs() {
+        let progress = ProgressTest::new();
+        let renderer = BuildRendererTest::new();
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let html_metadata = &HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+        let css_metadata = &CssMetadata::new(&ColorScheme::OsSetting);
+        let list_view_configs =
+            ListViewConfigs::new(&true, &false, &SortKey::LinkedCount, &Paging::Not);
+        let build = |scraps_with_ts: &[(Scrap, Option<i64>)]| {
+            BuildUsecase::new()
+                .execute(
+                    scraps_with_ts,
+                    &None,
+                    &progress,
+                    &renderer,
+                    &base_url,
+                    chrono_tz::UTC,
+                    html_metadata,
+                    css_metadata,
+                    &list_view_configs,
+                    &OgpCache::new(),
+                    &Assets::default(),
+                )
+                .unwrap();
+        };
+
+        let scraps_with_ts = vec![
+            (Scrap::new("test1", &None, "# header1\n"), Some(0i64)),
+            (Scrap::new("test2", &None, "[[test1]]\n"), Some(0i64)),
+            (Scrap::new("test3", &None, "# header3\n"), Some(0i64)),
+        ];
+        build(&scraps_with_ts);
+        assert_eq!(renderer.rendered_scraps(), 3);
+
+        build(&scraps_with_ts);
+        assert_eq!(renderer.rendered_scraps(), 3);
+
+        // test2 changed, and test1 lists it as a backlink.
+        let edited = vec![
+            scraps_with_ts[0].clone(),
+            (Scrap::new("test2", &None, "see [[test1]]\n"), Some(1i64)),
+            scraps_with_ts[2].clone(),
+        ];
+        build(&edited);
+        assert_eq!(renderer.rendered_scraps(), 5);
+    }
+
+    #[test]
+    fn it_run_feeds() {
+        let scraps_with_ts = vec![
+            (Scrap::new("test1", &None, "#[[tag1]]\n"), Some(10i64)),
+            (Scrap::new("test2", &None, "[[test1]]\n"), Some(20i64)),
+            (Scrap::new("test3", &None, "#[[tag2]]\n"), None),
+        ];
+
+        let progress = ProgressTest::new();
+        let renderer = BuildRendererTest::new();
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let html_metadata = &HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+
+        let result = BuildUsecase::new()
+            .execute_feeds(
+                &scraps_with_ts,
+                &progress,
+                &renderer,
+                &base_url,
+                html_metadata,
+                &FeedConfigs::default(),
+                &Assets::default(),
             )
             .unwrap();
+        // site-wide and tag1; tag2 has no committed scrap
+        assert_eq!(result, 2);
+    }
+
+    /// Answers with a page titled after the URL's path, failing on `/down`.
+    struct OgpFetcherTest;
+    impl OgpFetcher for OgpFetcherTest {
+        fn fetch_html(&self, url: &Url) -> ScrapsResult<String> {
+            match url.path() {
+                "/down" => Err(crate::error::BuildError::FetchOgp(url.clone()).into()),
+                path => Ok(format!("<title>{path}</title>")),
+            }
+        }
+    }
+
+    #[test]
+    fn it_run_ogp() {
+        let scraps_with_ts = vec![
+            (
+                Scrap::new("test1", &None, "<https://example.com/a>\n"),
+                None,
+            ),
+            (
+                Scrap::new(
+                    "test2",
+                    &None,
+                    "<https://example.com/cached>\n\n<https://example.com/down>\n",
+                ),
+                None,
+            ),
+        ];
+        let cached = Url::parse("https://example.com/cached").unwrap();
+        let unlinked = Url::parse("https://example.com/unlinked").unwrap();
+        let mut cache = OgpCache::new();
+        cache.insert(cached.clone(), Ogp::default());
+        cache.insert(unlinked.clone(), Ogp::default());
+        let progress = ProgressTest::new();
+
+        // Offline, the cache is left as is.
+        let offline = BuildUsecase::new()
+            .execute_ogp(
+                &scraps_with_ts,
+                &progress,
+                None::<&OgpFetcherTest>,
+                &mut cache,
+            )
+            .unwrap();
+        assert_eq!(offline, 0);
+        assert!(cache.contains(&unlinked));
+
+        let fetched = BuildUsecase::new()
+            .execute_ogp(
+                &scraps_with_ts,
+                &progress,
+                Some(&OgpFetcherTest),
+                &mut cache,
+            )
+            .unwrap();
+        assert_eq!(fetched, 1);
+        assert_eq!(
+            cache
+                .get(&Url::parse("https://example.com/a").unwrap())
+                .and_then(|ogp| ogp.title.as_deref()),
+            Some("/a")
+        );
+        assert_eq!(cache.get(&cached), Some(&Ogp::default()));
+        assert!(!cache.contains(&unlinked));
+        assert!(!cache.contains(&Url::parse("https://example.com/down").unwrap()));
+    }
+
+    #[test]
+    fn it_copies_assets_and_removes_deleted_ones() {
+        let progress = ProgressTest::new();
+        let renderer = BuildRendererTest::new();
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let html_metadata = &HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+        let list_view_configs =
+            ListViewConfigs::new(&false, &false, &SortKey::LinkedCount, &Paging::Not);
+        let scraps_with_ts = vec![(Scrap::new("note", &None, "![](diagram.png)\n"), None)];
+        let asset = |source: &str| Asset {
+            source: std::path::PathBuf::from(source),
+            path: AssetPath::new(&source.split('/').collect::<Vec<_>>()).unwrap(),
+        };
+        let build = |assets: &[Asset]| {
+            BuildUsecase::new()
+                .execute(
+                    &scraps_with_ts,
+                    &None,
+                    &progress,
+                    &renderer,
+                    &base_url,
+                    chrono_tz::UTC,
+                    html_metadata,
+                    &CssMetadata::new(&ColorScheme::OsSetting),
+                    &list_view_configs,
+                    &OgpCache::new(),
+                    &Assets::new(assets, &[scraps_with_ts[0].0.clone()]),
+                )
+                .unwrap();
+        };
+
+        build(&[
+            asset("diagram.png"),
+            asset("Programming/ownership.svg"),
+            asset("note.html"),
+        ]);
+        let mut copied_assets = renderer.copied_assets();
+        copied_assets.sort();
+        assert_eq!(
+            copied_assets,
+            vec![
+                "scraps/diagram.png".to_string(),
+                "scraps/programming/ownership.svg".to_string(),
+            ]
+        );
+        assert!(renderer.removed_outputs().is_empty());
+        // Adding an asset re-renders the pages that may point at it.
+        assert_eq!(renderer.rendered_scraps(), 1);
+
+        build(&[asset("diagram.png")]);
+        assert_eq!(
+            renderer.removed_outputs(),
+            vec!["scraps/programming/ownership.svg".to_string()]
+        );
+        assert_eq!(renderer.rendered_scraps(), 2);
+    }
+
+    #[test]
+    fn it_run_llms() {
+        let scraps = vec![
+            Scrap::new("test1", &None, concat!("# header1\n", "## header2\n")),
+            Scrap::new("test2", &None, "[[test1]]\n"),
+        ];
+
+        let progress = ProgressTest::new();
+        let renderer = BuildRendererTest::new();
+        let base_url = BaseUrl::new(Url::parse("http://localhost:1112/").unwrap()).unwrap();
+        let html_metadata = &HtmlMetadata::new(&LangCode::default(), "Scrap", &None, &None);
+
+        let usecase = BuildUsecase::new();
+        let result = usecase
+            .execute_llms(
+                &scraps,
+                &None,
+                &progress,
+                &renderer,
+                &base_url,
+                html_metadata,
+                Some(0),
+            )
+            .unwrap();
+        assert_eq!(result, 0);
+    }
+
+    #[test]
+    fn it_run_json() {
+        let scraps_with_ts = vec![
+            (Scrap::new("test1", &None, "# header1\n"), Some(0i64)),
+            (Scrap::new("test2", &None, "[[test1]]\n"), None),
+        ];
+
+        let progress = ProgressTest::new();
+        let renderer = BuildRendererTest::new();
+        let result = BuildUsecase::new()
+            .execute_json(&scraps_with_ts, &progress, &renderer)
+            .unwrap();
         assert_eq!(result, 2);
     }
 }
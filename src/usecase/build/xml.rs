@@ -0,0 +1,38 @@
+//! Helpers shared by the XML outputs: the sitemap and the Atom feeds.
+
+use chrono::{DateTime, SecondsFormat};
+
+/// Escape text for an XML element or a quoted attribute value.
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// A unix timestamp as an RFC 3339 date-time in UTC, as both sitemaps and
+/// Atom expect.
+pub fn datetime(ts: i64) -> String {
+    DateTime::from_timestamp(ts, 0)
+        .unwrap_or_default()
+        .to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_escapes_markup() {
+        assert_eq!(
+            escape(r#"<a href="?x=1&y='2'">"#),
+            "&lt;a href=&quot;?x=1&amp;y=&apos;2&apos;&quot;&gt;"
+        );
+    }
+
+    #[test]
+    fn it_formats_datetimes_in_utc() {
+        assert_eq!(datetime(1_700_000_000), "2023-11-14T22:13:20Z");
+    }
+}
@@ -0,0 +1,194 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use scraps_libs::markdown::query::wikilinks;
+use scraps_libs::model::{context::Ctx, key::ScrapKey, scrap::Scrap};
+use scraps_libs::pandoc::{self, PandocWriter};
+use serde_json::Value;
+
+use crate::error::ScrapsResult;
+
+/// Which part of the wiki an export covers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportScope {
+    /// A single scrap.
+    Scrap(ScrapKey),
+    /// Every scrap whose ctx is the given ctx or nested under it.
+    Ctx(Ctx),
+    /// The whole wiki, README first.
+    Wiki,
+}
+
+pub struct ExportPandocUsecase;
+
+impl ExportPandocUsecase {
+    pub fn new() -> ExportPandocUsecase {
+        ExportPandocUsecase
+    }
+
+    pub fn execute(
+        &self,
+        scraps: &[Scrap],
+        readme_text: &Option<String>,
+        scope: &ExportScope,
+        title: Option<&str>,
+    ) -> ScrapsResult<Value> {
+        let scrap_texts: HashMap<ScrapKey, String> = scraps
+            .iter()
+            .map(|scrap| (scrap.self_key(), scrap.md_text().to_string()))
+            .collect();
+
+        let (readme, ordered) = match scope {
+            ExportScope::Scrap(key) => {
+                let scrap = scraps
+                    .iter()
+                    .find(|scrap| &scrap.self_key() == key)
+                    .ok_or_else(|| anyhow::anyhow!("Scrap not found: {}", key))?;
+                (None, vec![scrap])
+            }
+            ExportScope::Ctx(ctx) => {
+                let ordered = scraps
+                    .iter()
+                    .filter(|scrap| {
+                        scrap
+                            .ctx()
+                            .as_ref()
+                            .is_some_and(|c| c.segments().starts_with(ctx.segments()))
+                    })
+                    .sorted_by_key(|scrap| reading_key(scrap))
+                    .collect_vec();
+                if ordered.is_empty() {
+                    anyhow::bail!("No scraps found in ctx: {}", ctx);
+                }
+                (None, ordered)
+            }
+            ExportScope::Wiki => (
+                readme_text.as_deref(),
+                Self::reading_order(scraps, readme_text),
+            ),
+        };
+
+        let exported: HashSet<ScrapKey> = ordered.iter().map(|scrap| scrap.self_key()).collect();
+        let writer = PandocWriter::new(&scrap_texts, &exported);
+        let mut blocks = readme
+            .map(|text| writer.blocks(text, Some("readme"), 0))
+            .unwrap_or_default();
+        for scrap in ordered {
+            blocks.extend(writer.scrap_blocks(&scrap.self_key(), scrap.md_text()));
+        }
+
+        Ok(pandoc::document(title, blocks))
+    }
+
+    /// Scraps the README links to, in the order it links them, followed by
+    /// the rest ordered by ctx and title.
+    fn reading_order<'a>(scraps: &'a [Scrap], readme_text: &Option<String>) -> Vec<&'a Scrap> {
+        let by_key: HashMap<ScrapKey, &Scrap> = scraps
+            .iter()
+            .map(|scrap| (scrap.self_key(), scrap))
+            .collect();
+        let linked = readme_text
+            .as_deref()
+            .map(wikilinks)
+            .unwrap_or_default()
+            .iter()
+            .map(ScrapKey::from)
+            .unique()
+            .filter_map(|key| by_key.get(&key).copied())
+            .collect_vec();
+        let linked_keys: HashSet<ScrapKey> = linked.iter().map(|scrap| scrap.self_key()).collect();
+        let rest = scraps
+            .iter()
+            .filter(|scrap| !linked_keys.contains(&scrap.self_key()))
+            .sorted_by_key(|scrap| reading_key(scrap));
+        linked.into_iter().chain(rest).collect()
+    }
+}
+
+/// Root scraps first, then contexts depth-first, titles alphabetical within.
+fn reading_key(scrap: &Scrap) -> (Vec<String>, String) {
+    let ctx = scrap
+        .ctx()
+        .as_ref()
+        .map(|c| c.segments().to_vec())
+        .unwrap_or_default();
+    (ctx, scrap.title().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use scraps_libs::model::title::Title;
+
+    fn scrap_anchors(doc: &Value) -> Vec<String> {
+        doc["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|b| b["t"] == "Header" && b["c"][1][1][0] == "scrap")
+            .map(|b| b["c"][1][0].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    fn scraps() -> Vec<Scrap> {
+        vec![
+            Scrap::new("alpha", &None, "[[Book/DDD]]"),
+            Scrap::new("DDD", &Some("Book".into()), "# DDD"),
+            Scrap::new("Rust", &Some("Book/Lang".into()), "![[alpha]]"),
+            Scrap::new("zeta", &None, "zeta body"),
+        ]
+    }
+
+    #[test]
+    fn it_exports_single_scrap() {
+        let usecase = ExportPandocUsecase::new();
+        let key = ScrapKey::with_ctx(&Title::from("DDD"), &Ctx::from("Book"));
+
+        let doc = usecase
+            .execute(&scraps(), &None, &ExportScope::Scrap(key), Some("DDD"))
+            .unwrap();
+
+        assert_eq!(scrap_anchors(&doc), vec!["book/ddd"]);
+        assert_eq!(doc["meta"]["title"]["c"][0]["c"], "DDD");
+    }
+
+    #[test]
+    fn it_errors_on_missing_scrap() {
+        let usecase = ExportPandocUsecase::new();
+        let key = ScrapKey::from(Title::from("missing"));
+
+        let result = usecase.execute(&scraps(), &None, &ExportScope::Scrap(key), None);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_exports_ctx_subtree() {
+        let usecase = ExportPandocUsecase::new();
+
+        let doc = usecase
+            .execute(&scraps(), &None, &ExportScope::Ctx(Ctx::from("Book")), None)
+            .unwrap();
+
+        assert_eq!(scrap_anchors(&doc), vec!["book/ddd", "book/lang/rust"]);
+    }
+
+    #[test]
+    fn it_exports_wiki_in_readme_first_order() {
+        let usecase = ExportPandocUsecase::new();
+        let readme = Some("# Welcome\n\nStart at [[zeta]] then [[Book/DDD]].".to_string());
+
+        let doc = usecase
+            .execute(&scraps(), &readme, &ExportScope::Wiki, None)
+            .unwrap();
+
+        let blocks = doc["blocks"].as_array().unwrap();
+        assert_eq!(blocks[0]["c"][1][0], "readme--welcome");
+        assert_eq!(
+            scrap_anchors(&doc),
+            vec!["zeta", "book/ddd", "alpha", "book/lang/rust"]
+        );
+        // README links point at in-document anchors.
+        assert_eq!(blocks[1]["c"][4]["c"][2][0], "#zeta");
+    }
+}
@@ -40,6 +40,7 @@ impl LintRuleName {
     }
 
     /// All rules including opt-in ones. Used by `scraps lint --all`.
+    #[allow(dead_code)]
     pub fn all_rules() -> Vec<LintRuleName> {
         let mut rules = Self::default_rules();
         rules.push(Self::StaleByGit);
@@ -1,6 +1,5 @@
 use std::collections::HashMap;
 
-use scraps_libs::markdown::query::wikilinks;
 use scraps_libs::model::{key::ScrapKey, scrap::Scrap, tags::Tags};
 
 use crate::usecase::build::model::backlinks_map::BacklinksMap;
@@ -22,10 +21,8 @@ impl LintRule for OverlinkingRule {
         scraps
             .iter()
             .flat_map(|scrap| {
-                let all_links: Vec<ScrapKey> = wikilinks(scrap.md_text())
-                    .iter()
-                    .map(ScrapKey::from)
-                    .collect();
+                let all_links: Vec<ScrapKey> =
+                    scrap.document().wikilinks().map(ScrapKey::from).collect();
                 let mut counts: HashMap<ScrapKey, usize> = HashMap::new();
                 for link in &all_links {
                     *counts.entry(link.clone()).or_insert(0) += 1;
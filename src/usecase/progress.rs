@@ -1,9 +1,14 @@
 #[derive(Debug)]
 pub enum Stage {
     ReadScraps,
+    FetchOgp,
+    ReuseHtml,
     GenerateHtml,
     GenerateCss,
     GenerateJson,
+    GenerateFeed,
+    GenerateText,
+    CopyAssets,
 }
 
 pub trait Progress {
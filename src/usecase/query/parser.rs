@@ -0,0 +1,407 @@
+//! Parser for the wiki query language used by `scraps query`.
+//!
+//! ```text
+//! tag:rust/async AND links-to:"tokio" AND NOT has:backlinks sort:linked_count
+//! ```
+//!
+//! Terms are `field:value` pairs or bare words, which match title and body
+//! text. Terms are combined with `AND` (also implied by juxtaposition), `OR`
+//! and `NOT`, and grouped with parentheses. `AND` binds tighter than `OR`.
+//! A single `sort:` term orders the results and may appear anywhere at the
+//! top level.
+
+use scraps_libs::markdown::query::TaskStatus;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::tag::Tag;
+
+use crate::error::QueryError;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    /// `None` matches every scrap.
+    pub expr: Option<Expr>,
+    pub sort: Option<SortKey>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Predicate(Predicate),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Predicate {
+    /// Tagged with the tag or one of its descendants.
+    Tag(Tag),
+    LinksTo(ScrapKey),
+    LinkedFrom(ScrapKey),
+    /// In the context or one of its nested contexts.
+    Ctx(Ctx),
+    /// Case-insensitive substring of the title.
+    Title(String),
+    /// Case-insensitive substring of the title or body.
+    Text(String),
+    Has(HasField),
+    Task(TaskStatus),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HasField {
+    Backlinks,
+    Links,
+    Tags,
+    Tasks,
+    Ctx,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// Most backlinks first.
+    LinkedCount,
+    /// Most outbound links first.
+    LinksCount,
+    /// Alphabetical by title.
+    Title,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term {
+        field: Option<String>,
+        value: String,
+    },
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Query, QueryError> {
+        let (tokens, sort) = extract_sort(tokenize(input)?)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = if parser.tokens.is_empty() {
+            None
+        } else {
+            let expr = parser.parse_or()?;
+            if let Some(token) = parser.peek() {
+                return Err(QueryError::UnexpectedToken(token.to_string()));
+            }
+            Some(expr)
+        };
+        Ok(Query { expr, sort })
+    }
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::LParen => write!(f, "("),
+            Token::RParen => write!(f, ")"),
+            Token::And => write!(f, "AND"),
+            Token::Or => write!(f, "OR"),
+            Token::Not => write!(f, "NOT"),
+            Token::Term {
+                field: Some(field),
+                value,
+            } => write!(f, "{field}:{value}"),
+            Token::Term { field: None, value } => write!(f, "{value}"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '(' || c == ')' {
+            chars.next();
+            tokens.push(if c == '(' {
+                Token::LParen
+            } else {
+                Token::RParen
+            });
+            continue;
+        }
+        if c == '"' {
+            chars.next();
+            tokens.push(Token::Term {
+                field: None,
+                value: read_quoted(&mut chars)?,
+            });
+            continue;
+        }
+
+        let mut word = String::new();
+        let mut field = None;
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == '(' || c == ')' {
+                break;
+            }
+            chars.next();
+            if c == ':' && field.is_none() {
+                field = Some(std::mem::take(&mut word));
+                if chars.peek() == Some(&'"') {
+                    chars.next();
+                    word = read_quoted(&mut chars)?;
+                    break;
+                }
+            } else {
+                word.push(c);
+            }
+        }
+        tokens.push(match (field, word.as_str()) {
+            (None, "AND") => Token::And,
+            (None, "OR") => Token::Or,
+            (None, "NOT") => Token::Not,
+            (field, _) => Token::Term { field, value: word },
+        });
+    }
+    Ok(tokens)
+}
+
+fn read_quoted(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, QueryError> {
+    let mut value = String::new();
+    for c in chars.by_ref() {
+        if c == '"' {
+            return Ok(value);
+        }
+        value.push(c);
+    }
+    Err(QueryError::UnterminatedQuote)
+}
+
+/// Pull the `sort:` term out of the token stream, along with the `AND` that
+/// joined it to its neighbour.
+fn extract_sort(mut tokens: Vec<Token>) -> Result<(Vec<Token>, Option<SortKey>), QueryError> {
+    let mut sort = None;
+    while let Some(i) = tokens
+        .iter()
+        .position(|t| matches!(t, Token::Term { field: Some(f), .. } if f == "sort"))
+    {
+        let Token::Term { value, .. } = tokens.remove(i) else {
+            unreachable!()
+        };
+        if sort.is_some() {
+            return Err(QueryError::DuplicateSort);
+        }
+        sort = Some(match value.as_str() {
+            "linked_count" => SortKey::LinkedCount,
+            "links_count" => SortKey::LinksCount,
+            "title" => SortKey::Title,
+            _ => {
+                return Err(QueryError::InvalidValue {
+                    field: "sort".to_string(),
+                    value,
+                })
+            }
+        });
+        if i > 0 && tokens.get(i - 1) == Some(&Token::And) {
+            tokens.remove(i - 1);
+        } else if tokens.get(i) == Some(&Token::And) {
+            tokens.remove(i);
+        }
+    }
+    Ok((tokens, sort))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.next();
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, QueryError> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.next();
+                }
+                Some(Token::Not | Token::LParen | Token::Term { .. }) => {}
+                _ => return Ok(expr),
+            }
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, QueryError> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::LParen) => {
+                let expr = self.parse_or()?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    Some(token) => Err(QueryError::UnexpectedToken(token.to_string())),
+                    None => Err(QueryError::UnexpectedEnd),
+                }
+            }
+            Some(Token::Term { field, value }) => {
+                Ok(Expr::Predicate(predicate(field.as_deref(), value)?))
+            }
+            Some(token) => Err(QueryError::UnexpectedToken(token.to_string())),
+            None => Err(QueryError::UnexpectedEnd),
+        }
+    }
+}
+
+fn predicate(field: Option<&str>, value: String) -> Result<Predicate, QueryError> {
+    let invalid = |field: &str, value: String| QueryError::InvalidValue {
+        field: field.to_string(),
+        value,
+    };
+    if value.trim().is_empty() {
+        return Err(invalid(field.unwrap_or("text"), value));
+    }
+    match field {
+        None | Some("text") => Ok(Predicate::Text(value.to_lowercase())),
+        Some("title") => Ok(Predicate::Title(value.to_lowercase())),
+        Some("tag") => Ok(Predicate::Tag(Tag::from(value.as_str()))),
+        Some("links-to") => Ok(Predicate::LinksTo(ScrapKey::from_path_str(&value))),
+        Some("linked-from") => Ok(Predicate::LinkedFrom(ScrapKey::from_path_str(&value))),
+        Some("ctx") => Ok(Predicate::Ctx(Ctx::from(value.as_str()))),
+        Some("has") => match value.as_str() {
+            "backlinks" => Ok(Predicate::Has(HasField::Backlinks)),
+            "links" => Ok(Predicate::Has(HasField::Links)),
+            "tags" => Ok(Predicate::Has(HasField::Tags)),
+            "tasks" => Ok(Predicate::Has(HasField::Tasks)),
+            "ctx" => Ok(Predicate::Has(HasField::Ctx)),
+            _ => Err(invalid("has", value)),
+        },
+        Some("task") => match value.as_str() {
+            "open" => Ok(Predicate::Task(TaskStatus::Open)),
+            "done" => Ok(Predicate::Task(TaskStatus::Done)),
+            "deferred" => Ok(Predicate::Task(TaskStatus::Deferred)),
+            _ => Err(invalid("task", value)),
+        },
+        Some(other) => Err(QueryError::UnknownField(other.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pred(p: Predicate) -> Expr {
+        Expr::Predicate(p)
+    }
+
+    #[test]
+    fn it_parses_and_chain_with_sort() {
+        let query = Query::parse(
+            r#"tag:rust/async AND links-to:"tokio" AND NOT has:backlinks AND task:open AND sort:linked_count"#,
+        )
+        .unwrap();
+
+        assert_eq!(query.sort, Some(SortKey::LinkedCount));
+        assert_eq!(
+            query.expr,
+            Some(Expr::And(
+                Box::new(Expr::And(
+                    Box::new(Expr::And(
+                        Box::new(pred(Predicate::Tag(Tag::from("rust/async")))),
+                        Box::new(pred(Predicate::LinksTo(ScrapKey::from_path_str("tokio")))),
+                    )),
+                    Box::new(Expr::Not(Box::new(pred(Predicate::Has(
+                        HasField::Backlinks
+                    ))))),
+                )),
+                Box::new(pred(Predicate::Task(TaskStatus::Open))),
+            ))
+        );
+    }
+
+    #[test]
+    fn it_binds_and_tighter_than_or() {
+        let query = Query::parse("ctx:a OR ctx:b title:x").unwrap();
+        assert_eq!(
+            query.expr,
+            Some(Expr::Or(
+                Box::new(pred(Predicate::Ctx(Ctx::from("a")))),
+                Box::new(Expr::And(
+                    Box::new(pred(Predicate::Ctx(Ctx::from("b")))),
+                    Box::new(pred(Predicate::Title("x".to_string()))),
+                )),
+            ))
+        );
+    }
+
+    #[test]
+    fn it_parses_groups_and_bare_words() {
+        let query = Query::parse(r#"(ctx:a OR "Async Rust") NOT tag:x"#).unwrap();
+        assert_eq!(
+            query.expr,
+            Some(Expr::And(
+                Box::new(Expr::Or(
+                    Box::new(pred(Predicate::Ctx(Ctx::from("a")))),
+                    Box::new(pred(Predicate::Text("async rust".to_string()))),
+                )),
+                Box::new(Expr::Not(Box::new(pred(Predicate::Tag(Tag::from("x")))))),
+            ))
+        );
+    }
+
+    #[test]
+    fn it_matches_everything_with_only_sort() {
+        let query = Query::parse("sort:title").unwrap();
+        assert_eq!(query.expr, None);
+        assert_eq!(query.sort, Some(SortKey::Title));
+    }
+
+    #[test]
+    fn it_reports_errors() {
+        assert_eq!(
+            Query::parse("colour:red"),
+            Err(QueryError::UnknownField("colour".to_string()))
+        );
+        assert_eq!(
+            Query::parse("has:friends"),
+            Err(QueryError::InvalidValue {
+                field: "has".to_string(),
+                value: "friends".to_string()
+            })
+        );
+        assert_eq!(Query::parse("tag:a AND"), Err(QueryError::UnexpectedEnd));
+        assert_eq!(Query::parse("(tag:a"), Err(QueryError::UnexpectedEnd));
+        assert_eq!(
+            Query::parse("tag:a )"),
+            Err(QueryError::UnexpectedToken(")".to_string()))
+        );
+        assert_eq!(
+            Query::parse(r#"title:"open"#),
+            Err(QueryError::UnterminatedQuote)
+        );
+        assert_eq!(
+            Query::parse("sort:title sort:title"),
+            Err(QueryError::DuplicateSort)
+        );
+    }
+}
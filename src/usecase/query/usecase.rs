@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use crate::error::ScrapsResult;
+use crate::usecase::build::model::backlinks_map::BacklinksMap;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::title::Title;
+
+use super::parser::{Expr, HasField, Predicate, Query, SortKey};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryResult {
+    pub title: Title,
+    pub ctx: Option<Ctx>,
+    /// Number of scraps linking here.
+    pub linked_count: usize,
+    /// Number of distinct scraps linked from here.
+    pub links_count: usize,
+}
+
+pub struct QueryScrapsUsecase;
+
+impl QueryScrapsUsecase {
+    pub fn new() -> QueryScrapsUsecase {
+        QueryScrapsUsecase
+    }
+
+    /// Scraps matching the query, in its `sort:` order or by key otherwise.
+    pub fn execute(&self, scraps: &[Scrap], query: &Query) -> ScrapsResult<Vec<QueryResult>> {
+        let backlinks_map = BacklinksMap::new(scraps);
+        let evaluator = Evaluator {
+            backlinks_map: &backlinks_map,
+        };
+
+        let mut matched: Vec<(ScrapKey, QueryResult)> = scraps
+            .iter()
+            .filter(|scrap| {
+                query
+                    .expr
+                    .as_ref()
+                    .is_none_or(|expr| evaluator.eval(expr, scrap))
+            })
+            .map(|scrap| {
+                let key = scrap.self_key();
+                let links_count = scrap.links().iter().collect::<HashSet<_>>().len();
+                let result = QueryResult {
+                    title: scrap.title().clone(),
+                    ctx: scrap.ctx().clone(),
+                    linked_count: backlinks_map.get(&key).len(),
+                    links_count,
+                };
+                (key, result)
+            })
+            .collect();
+
+        matched.sort_by(|(a_key, a), (b_key, b)| {
+            let primary = match query.sort {
+                Some(SortKey::LinkedCount) => b.linked_count.cmp(&a.linked_count),
+                Some(SortKey::LinksCount) => b.links_count.cmp(&a.links_count),
+                Some(SortKey::Title) => a.title.to_string().cmp(&b.title.to_string()),
+                None => std::cmp::Ordering::Equal,
+            };
+            primary.then_with(|| a_key.cmp(b_key))
+        });
+
+        Ok(matched.into_iter().map(|(_, result)| result).collect())
+    }
+}
+
+struct Evaluator<'a> {
+    backlinks_map: &'a BacklinksMap,
+}
+
+impl Evaluator<'_> {
+    fn eval(&self, expr: &Expr, scrap: &Scrap) -> bool {
+        match expr {
+            Expr::And(a, b) => self.eval(a, scrap) && self.eval(b, scrap),
+            Expr::Or(a, b) => self.eval(a, scrap) || self.eval(b, scrap),
+            Expr::Not(a) => !self.eval(a, scrap),
+            Expr::Predicate(p) => self.matches(p, scrap),
+        }
+    }
+
+    fn matches(&self, predicate: &Predicate, scrap: &Scrap) -> bool {
+        match predicate {
+            Predicate::Tag(tag) => scrap
+                .tags()
+                .iter()
+                .any(|t| t == tag || t.ancestors().contains(tag)),
+            Predicate::LinksTo(key) => scrap.links().contains(key),
+            Predicate::LinkedFrom(key) => self
+                .backlinks_map
+                .get(&scrap.self_key())
+                .iter()
+                .any(|s| s.self_key() == *key),
+            Predicate::Ctx(ctx) => scrap
+                .ctx()
+                .as_ref()
+                .is_some_and(|c| c.segments().starts_with(ctx.segments())),
+            Predicate::Title(needle) => scrap.title().to_string().to_lowercase().contains(needle),
+            Predicate::Text(needle) => {
+                scrap.title().to_string().to_lowercase().contains(needle)
+                    || scrap.md_text().to_lowercase().contains(needle)
+            }
+            Predicate::Has(HasField::Backlinks) => {
+                !self.backlinks_map.get(&scrap.self_key()).is_empty()
+            }
+            Predicate::Has(HasField::Links) => !scrap.links().is_empty(),
+            Predicate::Has(HasField::Tags) => !scrap.tags().is_empty(),
+            Predicate::Has(HasField::Tasks) => !scrap.document().task_items().is_empty(),
+            Predicate::Has(HasField::Ctx) => scrap.ctx().is_some(),
+            Predicate::Task(status) => scrap
+                .document()
+                .task_items()
+                .iter()
+                .any(|item| item.status == *status),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(scraps: &[Scrap], input: &str) -> Vec<String> {
+        QueryScrapsUsecase::new()
+            .execute(scraps, &Query::parse(input).unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|r| ScrapKey::new(&r.title, &r.ctx).to_string())
+            .collect()
+    }
+
+    fn wiki() -> Vec<Scrap> {
+        vec![
+            Scrap::new("tokio", &None, "Async runtime."),
+            Scrap::new(
+                "runtime",
+                &Some("programming".into()),
+                "#[[rust/async]] uses [[tokio]]\n\n- [ ] benchmark",
+            ),
+            Scrap::new(
+                "actors",
+                &Some("programming/rust".into()),
+                "#[[rust/async]] [[tokio]] [[programming/runtime]]\n\n- [x] done",
+            ),
+            Scrap::new("garden", &None, "#[[hobby]] tomatoes"),
+        ]
+    }
+
+    #[test]
+    fn it_evaluates_the_example_query() {
+        let scraps = wiki();
+        assert_eq!(
+            query(
+                &scraps,
+                r#"tag:rust/async AND links-to:"tokio" AND ctx:programming AND NOT has:backlinks AND task:done"#
+            ),
+            vec!["programming/rust/actors"]
+        );
+    }
+
+    #[test]
+    fn it_matches_tag_descendants_and_nested_ctx() {
+        let scraps = wiki();
+        assert_eq!(
+            query(&scraps, "tag:rust"),
+            vec!["programming/rust/actors", "programming/runtime"]
+        );
+        assert_eq!(
+            query(&scraps, "ctx:programming/rust"),
+            vec!["programming/rust/actors"]
+        );
+    }
+
+    #[test]
+    fn it_matches_text_and_links() {
+        let scraps = wiki();
+        assert_eq!(query(&scraps, "TOMATOES"), vec!["garden"]);
+        assert_eq!(
+            query(&scraps, "linked-from:programming/rust/actors"),
+            vec!["programming/runtime", "tokio"]
+        );
+        assert_eq!(
+            query(&scraps, "task:open OR has:tags NOT has:links"),
+            vec!["garden", "programming/runtime"]
+        );
+    }
+
+    #[test]
+    fn it_sorts_by_linked_count() {
+        let scraps = wiki();
+        assert_eq!(
+            query(&scraps, "sort:linked_count"),
+            vec![
+                "tokio",
+                "programming/runtime",
+                "programming/rust/actors",
+                "garden"
+            ]
+        );
+    }
+}
@@ -1,3 +1,7 @@
+pub mod find_path;
 pub mod get;
 pub mod lookup_backlinks;
 pub mod lookup_links;
+pub mod mentions;
+pub mod related;
+pub mod traverse;
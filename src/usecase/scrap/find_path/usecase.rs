@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::error::ScrapsResult;
+use crate::usecase::build::model::backlinks_map::BacklinksMap;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::tag::Tag;
+use scraps_libs::model::title::Title;
+
+/// Upper bound on the number of equally short paths returned. Dense wikis
+/// can have combinatorially many; a handful is enough to justify a link.
+const MAX_PATHS: usize = 10;
+
+/// A node on a path: a scrap, or a tag when tag hops are allowed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PathNode {
+    Scrap(ScrapKey),
+    Tag(Tag),
+}
+
+/// How a hop reached its node from the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HopKind {
+    /// The previous scrap links to this one.
+    Link,
+    /// This scrap links to the previous one (undirected mode only).
+    Backlink,
+    /// Between a scrap and a tag it declares (tag hops only).
+    Tag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathHop {
+    pub node: PathNode,
+    /// `None` for the starting scrap.
+    pub via: Option<HopKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathOptions {
+    /// Also walk links backwards.
+    pub undirected: bool,
+    /// Allow `scrap -> #tag -> scrap` hops through shared explicit tags.
+    pub via_tags: bool,
+    /// Maximum number of hops; a tag detour counts as two.
+    pub max_length: usize,
+}
+
+pub struct FindScrapPathUsecase;
+
+impl FindScrapPathUsecase {
+    pub fn new() -> FindScrapPathUsecase {
+        FindScrapPathUsecase
+    }
+
+    /// Every shortest path from one scrap to another over resolved links,
+    /// up to `MAX_PATHS`. Empty when the scraps are not connected within
+    /// `max_length` hops.
+    pub fn execute(
+        &self,
+        scraps: &[Scrap],
+        from: (&Title, &Option<Ctx>),
+        to: (&Title, &Option<Ctx>),
+        options: &PathOptions,
+    ) -> ScrapsResult<Vec<Vec<PathHop>>> {
+        let keys: HashSet<ScrapKey> = scraps.iter().map(|s| s.self_key()).collect();
+        let from_key = Self::existing_key(&keys, from)?;
+        let to_key = Self::existing_key(&keys, to)?;
+
+        let graph = Graph::new(scraps, &keys, options);
+        Ok(graph.shortest_paths(
+            PathNode::Scrap(from_key),
+            PathNode::Scrap(to_key),
+            options.max_length,
+        ))
+    }
+
+    fn existing_key(
+        keys: &HashSet<ScrapKey>,
+        (title, ctx): (&Title, &Option<Ctx>),
+    ) -> ScrapsResult<ScrapKey> {
+        let key = ScrapKey::new(title, ctx);
+        if keys.contains(&key) {
+            Ok(key)
+        } else {
+            Err(anyhow::anyhow!(
+                "Scrap not found: title='{}', ctx='{:?}'",
+                title,
+                ctx
+            ))
+        }
+    }
+}
+
+struct Graph {
+    adjacency: HashMap<PathNode, Vec<(PathNode, HopKind)>>,
+}
+
+impl Graph {
+    fn new(scraps: &[Scrap], keys: &HashSet<ScrapKey>, options: &PathOptions) -> Graph {
+        let mut adjacency: HashMap<PathNode, Vec<(PathNode, HopKind)>> = HashMap::new();
+        let backlinks_map = BacklinksMap::new(scraps);
+
+        for scrap in scraps {
+            let node = PathNode::Scrap(scrap.self_key());
+            let edges = adjacency.entry(node).or_default();
+            for link in scrap.links().iter().filter(|l| keys.contains(l)) {
+                edges.push((PathNode::Scrap(link.clone()), HopKind::Link));
+            }
+            if options.undirected {
+                for backlink in backlinks_map.get(&scrap.self_key()) {
+                    edges.push((PathNode::Scrap(backlink.self_key()), HopKind::Backlink));
+                }
+            }
+            if options.via_tags {
+                for tag in scrap.tags() {
+                    edges.push((PathNode::Tag(tag.clone()), HopKind::Tag));
+                }
+            }
+        }
+        if options.via_tags {
+            for scrap in scraps {
+                for tag in scrap.tags() {
+                    adjacency
+                        .entry(PathNode::Tag(tag.clone()))
+                        .or_default()
+                        .push((PathNode::Scrap(scrap.self_key()), HopKind::Tag));
+                }
+            }
+        }
+
+        // Stable neighbour order keeps path enumeration reproducible.
+        for edges in adjacency.values_mut() {
+            edges.sort_by(|a, b| a.0.cmp(&b.0));
+            edges.dedup_by(|a, b| a.0 == b.0);
+        }
+        Graph { adjacency }
+    }
+
+    fn shortest_paths(&self, from: PathNode, to: PathNode, max_length: usize) -> Vec<Vec<PathHop>> {
+        // Breadth-first search recording every predecessor at the previous
+        // level, so all equally short paths can be rebuilt afterwards.
+        let mut dist: HashMap<PathNode, usize> = HashMap::from([(from.clone(), 0)]);
+        let mut preds: HashMap<PathNode, Vec<(PathNode, HopKind)>> = HashMap::new();
+        let mut queue = VecDeque::from([from.clone()]);
+        let mut found: Option<usize> = (from == to).then_some(0);
+
+        while let Some(node) = queue.pop_front() {
+            let d = dist[&node];
+            if d >= max_length || found.is_some_and(|f| d >= f) {
+                continue;
+            }
+            for (next, kind) in self.adjacency.get(&node).into_iter().flatten() {
+                match dist.get(next) {
+                    None => {
+                        dist.insert(next.clone(), d + 1);
+                        preds
+                            .entry(next.clone())
+                            .or_default()
+                            .push((node.clone(), *kind));
+                        if *next == to {
+                            found = Some(d + 1);
+                        }
+                        queue.push_back(next.clone());
+                    }
+                    Some(&dn) if dn == d + 1 => preds
+                        .entry(next.clone())
+                        .or_default()
+                        .push((node.clone(), *kind)),
+                    Some(_) => {}
+                }
+            }
+        }
+
+        if found.is_none() {
+            return Vec::new();
+        }
+        let mut paths = Vec::new();
+        let mut suffix = vec![];
+        Self::unwind(&preds, &from, to, &mut suffix, &mut paths);
+        paths
+    }
+
+    /// Walk predecessors back from `node` to `from`, emitting each complete
+    /// path. `suffix` holds the hops after `node`, nearest last.
+    fn unwind(
+        preds: &HashMap<PathNode, Vec<(PathNode, HopKind)>>,
+        from: &PathNode,
+        node: PathNode,
+        suffix: &mut Vec<PathHop>,
+        paths: &mut Vec<Vec<PathHop>>,
+    ) {
+        if paths.len() >= MAX_PATHS {
+            return;
+        }
+        if node == *from {
+            let mut path = vec![PathHop { node, via: None }];
+            path.extend(suffix.iter().rev().cloned());
+            paths.push(path);
+            return;
+        }
+        for (pred, kind) in preds.get(&node).into_iter().flatten() {
+            suffix.push(PathHop {
+                node: node.clone(),
+                via: Some(*kind),
+            });
+            Self::unwind(preds, from, pred.clone(), suffix, paths);
+            suffix.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(undirected: bool, via_tags: bool, max_length: usize) -> PathOptions {
+        PathOptions {
+            undirected,
+            via_tags,
+            max_length,
+        }
+    }
+
+    fn render(path: &[PathHop]) -> String {
+        path.iter()
+            .map(|hop| match &hop.node {
+                PathNode::Scrap(key) => key.to_string(),
+                PathNode::Tag(tag) => format!("#{tag}"),
+            })
+            .collect::<Vec<_>>()
+            .join(" > ")
+    }
+
+    fn find(scraps: &[Scrap], from: &str, to: &str, options: PathOptions) -> Vec<String> {
+        FindScrapPathUsecase::new()
+            .execute(scraps, (&from.into(), &None), (&to.into(), &None), &options)
+            .unwrap()
+            .iter()
+            .map(|p| render(p))
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_all_shortest_directed_paths() {
+        let scraps = vec![
+            Scrap::new("a", &None, "[[b]] [[c]]"),
+            Scrap::new("b", &None, "[[d]]"),
+            Scrap::new("c", &None, "[[d]]"),
+            Scrap::new("d", &None, "[[e]]"),
+            Scrap::new("e", &None, ""),
+        ];
+
+        let paths = find(&scraps, "a", "e", options(false, false, 6));
+
+        assert_eq!(paths, vec!["a > b > d > e", "a > c > d > e"]);
+        assert!(find(&scraps, "e", "a", options(false, false, 6)).is_empty());
+    }
+
+    #[test]
+    fn it_walks_backlinks_when_undirected() {
+        let scraps = vec![
+            Scrap::new("a", &None, "[[hub]]"),
+            Scrap::new("b", &None, "[[hub]]"),
+            Scrap::new("hub", &None, ""),
+        ];
+
+        let paths = FindScrapPathUsecase::new()
+            .execute(
+                &scraps,
+                (&"a".into(), &None),
+                (&"b".into(), &None),
+                &options(true, false, 6),
+            )
+            .unwrap();
+
+        assert_eq!(paths.len(), 1);
+        let via: Vec<_> = paths[0].iter().map(|h| h.via).collect();
+        assert_eq!(
+            via,
+            vec![None, Some(HopKind::Link), Some(HopKind::Backlink)]
+        );
+    }
+
+    #[test]
+    fn it_hops_through_tags() {
+        let scraps = vec![
+            Scrap::new("a", &None, "#[[rust]]"),
+            Scrap::new("b", &None, "#[[rust]]"),
+        ];
+
+        assert!(find(&scraps, "a", "b", options(false, false, 6)).is_empty());
+        assert_eq!(
+            find(&scraps, "a", "b", options(false, true, 6)),
+            vec!["a > #rust > b"]
+        );
+    }
+
+    #[test]
+    fn it_respects_max_length() {
+        let scraps = vec![
+            Scrap::new("a", &None, "[[b]]"),
+            Scrap::new("b", &None, "[[c]]"),
+            Scrap::new("c", &None, ""),
+        ];
+
+        assert!(find(&scraps, "a", "c", options(false, false, 1)).is_empty());
+        assert_eq!(
+            find(&scraps, "a", "c", options(false, false, 2)),
+            vec!["a > b > c"]
+        );
+        assert_eq!(find(&scraps, "a", "a", options(false, false, 0)), vec!["a"]);
+    }
+
+    #[test]
+    fn it_errors_on_unknown_scrap() {
+        let scraps = vec![Scrap::new("a", &None, "")];
+
+        let result = FindScrapPathUsecase::new().execute(
+            &scraps,
+            (&"a".into(), &None),
+            (&"missing".into(), &None),
+            &options(false, false, 6),
+        );
+
+        assert!(result.is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::error::ScrapsResult;
-use crate::usecase::build::model::backlinks_map::BacklinksMap;
+use crate::usecase::build::model::backlinks_map::{BacklinkOccurrence, BacklinksMap};
 use scraps_libs::model::context::Ctx;
 use scraps_libs::model::key::ScrapKey;
 use scraps_libs::model::scrap::Scrap;
@@ -11,6 +11,8 @@ pub struct LookupScrapBacklinksResult {
     pub title: Title,
     pub ctx: Option<Ctx>,
     pub md_text: String,
+    /// Each link to the target inside this scrap, in source order.
+    pub occurrences: Vec<BacklinkOccurrence>,
 }
 
 pub struct LookupScrapBacklinksUsecase;
@@ -57,6 +59,7 @@ impl LookupScrapBacklinksUsecase {
                     title,
                     ctx,
                     md_text: linking_scrap.md_text().to_string(),
+                    occurrences: BacklinkOccurrence::find(&linking_scrap, &target_key),
                 }
             })
             .collect();
@@ -132,6 +135,31 @@ mod tests {
         assert_eq!(results[0].title.to_string(), "scrap1");
     }
 
+    #[test]
+    fn test_lookup_scrap_backlinks_occurrences() {
+        let scraps = vec![
+            Scrap::new(
+                "scrap1",
+                &None,
+                "# Scrap 1\n\n## Notes\n\n- first [[target_scrap]]\n- second [[target_scrap]]",
+            ),
+            Scrap::new("target_scrap", &None, ""),
+        ];
+
+        let usecase = LookupScrapBacklinksUsecase::new();
+
+        let results = usecase
+            .execute(&scraps, &Title::from("target_scrap"), &None)
+            .expect("Should succeed");
+
+        let occurrences = &results[0].occurrences;
+        assert_eq!(occurrences.len(), 2);
+        assert_eq!(occurrences[0].line, 5);
+        assert_eq!(occurrences[0].context, "- first [[target_scrap]]");
+        assert_eq!(occurrences[0].heading_path, vec!["Scrap 1", "Notes"]);
+        assert_eq!(occurrences[1].line, 6);
+    }
+
     #[test]
     fn test_lookup_scrap_backlinks_not_found() {
         let scraps = vec![Scrap::new("scrap1", &None, "# Scrap 1\n\nContent.")];
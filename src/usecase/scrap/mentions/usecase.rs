@@ -0,0 +1,249 @@
+use crate::error::ScrapsResult;
+use scraps_libs::markdown::query::{mentions, table_cell_ranges, SourceSpan};
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::title::Title;
+
+/// Characters of context kept on each side of a mention in its snippet.
+const SNIPPET_RADIUS: usize = 40;
+
+/// One unlinked, plain-text mention of the target title in another scrap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MentionResult {
+    pub title: Title,
+    pub ctx: Option<Ctx>,
+    pub line: usize,
+    pub snippet: String,
+    pub span: SourceSpan,
+}
+
+/// A scrap body with its mentions rewritten into wikilinks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkedScrap {
+    pub scrap: Scrap,
+    pub md_text: String,
+    pub count: usize,
+}
+
+pub struct FindMentionsUsecase;
+
+impl FindMentionsUsecase {
+    pub fn new() -> FindMentionsUsecase {
+        FindMentionsUsecase
+    }
+
+    /// Unlinked mentions of the target's title across the other scraps, in
+    /// scrap key and then source order.
+    pub fn execute(
+        &self,
+        scraps: &[Scrap],
+        title: &Title,
+        ctx: &Option<Ctx>,
+    ) -> ScrapsResult<Vec<MentionResult>> {
+        let target_key = Self::existing_key(scraps, title, ctx)?;
+        let mut results: Vec<MentionResult> = Self::mentioning(scraps, &target_key)
+            .flat_map(|(scrap, spans)| {
+                let text = scrap.md_text();
+                spans.into_iter().map(move |span| MentionResult {
+                    title: scrap.title().clone(),
+                    ctx: scrap.ctx().clone(),
+                    line: span.line,
+                    snippet: snippet(text, &span),
+                    span,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| {
+            ScrapKey::new(&a.title, &a.ctx)
+                .cmp(&ScrapKey::new(&b.title, &b.ctx))
+                .then_with(|| a.span.start.cmp(&b.span.start))
+        });
+        Ok(results)
+    }
+
+    /// Rewrite every unlinked mention into a `[[wikilink]]` to the target.
+    /// The mention text is kept as the alias when it differs from the key;
+    /// inside a table cell its pipe is escaped so the row stays intact.
+    pub fn link(
+        &self,
+        scraps: &[Scrap],
+        title: &Title,
+        ctx: &Option<Ctx>,
+    ) -> ScrapsResult<Vec<LinkedScrap>> {
+        let target_key = Self::existing_key(scraps, title, ctx)?;
+        let target = target_key.to_string();
+
+        let linked = Self::mentioning(scraps, &target_key)
+            .map(|(scrap, spans)| {
+                let text = scrap.md_text();
+                let cells = table_cell_ranges(text);
+                let mut md_text = String::with_capacity(text.len());
+                let mut cursor = 0;
+                for span in &spans {
+                    let mention = &text[span.start..span.end];
+                    md_text.push_str(&text[cursor..span.start]);
+                    let in_cell = cells
+                        .iter()
+                        .any(|&(start, end)| start <= span.start && span.end <= end);
+                    let pipe = if in_cell { "\\|" } else { "|" };
+                    if mention == target {
+                        md_text.push_str(&format!("[[{target}]]"));
+                    } else {
+                        md_text.push_str(&format!("[[{target}{pipe}{mention}]]"));
+                    }
+                    cursor = span.end;
+                }
+                md_text.push_str(&text[cursor..]);
+                LinkedScrap {
+                    scrap: scrap.clone(),
+                    md_text,
+                    count: spans.len(),
+                }
+            })
+            .collect();
+        Ok(linked)
+    }
+
+    fn mentioning<'a>(
+        scraps: &'a [Scrap],
+        target_key: &'a ScrapKey,
+    ) -> impl Iterator<Item = (&'a Scrap, Vec<SourceSpan>)> + 'a {
+        let phrase = target_key.title().to_string();
+        scraps
+            .iter()
+            .filter(move |scrap| scrap.self_key() != *target_key)
+            .map(move |scrap| (scrap, mentions(scrap.md_text(), &phrase)))
+            .filter(|(_, spans)| !spans.is_empty())
+    }
+
+    fn existing_key(scraps: &[Scrap], title: &Title, ctx: &Option<Ctx>) -> ScrapsResult<ScrapKey> {
+        let key = ScrapKey::new(title, ctx);
+        if scraps.iter().any(|scrap| scrap.self_key() == key) {
+            Ok(key)
+        } else {
+            Err(anyhow::anyhow!(
+                "Scrap not found: title='{}', ctx='{:?}'",
+                title,
+                ctx
+            ))
+        }
+    }
+}
+
+/// The mention's line, clipped to `SNIPPET_RADIUS` characters on each side.
+fn snippet(text: &str, span: &SourceSpan) -> String {
+    let line_start = text[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = text[span.end..]
+        .find('\n')
+        .map_or(text.len(), |i| span.end + i);
+    let before: Vec<char> = text[line_start..span.start].chars().collect();
+    let after: Vec<char> = text[span.end..line_end].chars().collect();
+
+    let mut out = String::new();
+    if before.len() > SNIPPET_RADIUS {
+        out.push('…');
+        out.extend(&before[before.len() - SNIPPET_RADIUS..]);
+    } else {
+        out.extend(&before);
+    }
+    out.push_str(&text[span.start..span.end]);
+    if after.len() > SNIPPET_RADIUS {
+        out.extend(&after[..SNIPPET_RADIUS]);
+        out.push('…');
+    } else {
+        out.extend(&after);
+    }
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_unlinked_mentions() {
+        let scraps = vec![
+            Scrap::new("Rust", &None, "Rust mentions itself"),
+            Scrap::new("b", &None, "I like [[Rust]].\n\nrust is fast"),
+            Scrap::new("a", &None, "`Rust` in code, Rustacean, and Rust."),
+        ];
+
+        let results = FindMentionsUsecase::new()
+            .execute(&scraps, &"Rust".into(), &None)
+            .unwrap();
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title.to_string(), "a");
+        assert_eq!(results[0].line, 1);
+        assert_eq!(results[0].snippet, "`Rust` in code, Rustacean, and Rust.");
+        assert_eq!(results[1].title.to_string(), "b");
+        assert_eq!(results[1].line, 3);
+        assert_eq!(results[1].snippet, "rust is fast");
+    }
+
+    #[test]
+    fn it_clips_long_snippets() {
+        let text = format!("{} Rust {}", "x".repeat(60), "y".repeat(60));
+        let scraps = vec![
+            Scrap::new("Rust", &None, ""),
+            Scrap::new("long", &None, &text),
+        ];
+
+        let results = FindMentionsUsecase::new()
+            .execute(&scraps, &"Rust".into(), &None)
+            .unwrap();
+
+        assert_eq!(
+            results[0].snippet,
+            format!("…{} Rust {}…", "x".repeat(39), "y".repeat(39))
+        );
+    }
+
+    #[test]
+    fn it_links_mentions() {
+        let scraps = vec![
+            Scrap::new("DDD", &Some("Book".into()), ""),
+            Scrap::new("note", &None, "Read ddd, then DDD."),
+        ];
+
+        let linked = FindMentionsUsecase::new()
+            .link(&scraps, &"DDD".into(), &Some("Book".into()))
+            .unwrap();
+
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].count, 2);
+        assert_eq!(
+            linked[0].md_text,
+            "Read [[Book/DDD|ddd]], then [[Book/DDD|DDD]]."
+        );
+    }
+
+    #[test]
+    fn it_escapes_alias_pipes_in_tables() {
+        let scraps = vec![
+            Scrap::new("DDD", &Some("Book".into()), ""),
+            Scrap::new("note", &None, "| book | ddd |\n|---|---|\n| ddd | x |\n"),
+        ];
+
+        let linked = FindMentionsUsecase::new()
+            .link(&scraps, &"DDD".into(), &Some("Book".into()))
+            .unwrap();
+
+        assert_eq!(
+            linked[0].md_text,
+            "| book | [[Book/DDD\\|ddd]] |\n|---|---|\n| [[Book/DDD\\|ddd]] | x |\n"
+        );
+        let relinked = Scrap::new("note", &None, &linked[0].md_text);
+        assert_eq!(
+            relinked.links(),
+            &[ScrapKey::new(&"DDD".into(), &Some("Book".into()))]
+        );
+    }
+
+    #[test]
+    fn it_errors_on_unknown_scrap() {
+        let result = FindMentionsUsecase::new().execute(&[], &"missing".into(), &None);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use scraps_libs::search::tokenizer;
+
+use crate::error::ScrapsResult;
+use crate::usecase::build::model::backlinks_map::BacklinksMap;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::tag::Tag;
+use scraps_libs::model::title::Title;
+
+/// Blend weights. Graph signals count shared neighbours, so a single shared
+/// link weighs as much as a fully similar body.
+const CO_CITATION_WEIGHT: f64 = 1.0;
+const COUPLING_WEIGHT: f64 = 1.0;
+const TAG_WEIGHT: f64 = 0.5;
+const TEXT_WEIGHT: f64 = 1.0;
+
+/// One suggested scrap with the signals that ranked it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedResult {
+    pub title: Title,
+    pub ctx: Option<Ctx>,
+    pub score: f64,
+    /// Scraps linking to both the target and this scrap.
+    pub co_citations: usize,
+    /// Outbound links this scrap shares with the target.
+    pub shared_links: usize,
+    /// Most specific tags shared with the target, ancestors folded away.
+    pub shared_tags: Vec<Tag>,
+    /// TF-IDF cosine similarity of the bodies, in `0.0..=1.0`.
+    pub text_similarity: f64,
+}
+
+impl RelatedResult {
+    /// Human-readable reason, e.g. `3 shared links, tag rust/async`.
+    pub fn reason(&self) -> String {
+        let mut parts = Vec::new();
+        if self.shared_links > 0 {
+            parts.push(plural(self.shared_links, "shared link"));
+        }
+        if self.co_citations > 0 {
+            parts.push(plural(self.co_citations, "co-citation"));
+        }
+        if !self.shared_tags.is_empty() {
+            let label = if self.shared_tags.len() == 1 {
+                "tag"
+            } else {
+                "tags"
+            };
+            parts.push(format!("{label} {}", self.shared_tags.iter().join(", ")));
+        }
+        if self.text_similarity > 0.0 {
+            parts.push(format!("similar text {:.2}", self.text_similarity));
+        }
+        parts.join(", ")
+    }
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+pub struct RelatedScrapsUsecase;
+
+impl RelatedScrapsUsecase {
+    pub fn new() -> RelatedScrapsUsecase {
+        RelatedScrapsUsecase
+    }
+
+    /// Rank scraps related to the target that it is not yet linked with, in
+    /// either direction. Returns at most `num` results, best first.
+    pub fn execute(
+        &self,
+        scraps: &[Scrap],
+        title: &Title,
+        ctx: &Option<Ctx>,
+        num: usize,
+    ) -> ScrapsResult<Vec<RelatedResult>> {
+        let target_key = ScrapKey::new(title, ctx);
+        let target_index = scraps
+            .iter()
+            .position(|scrap| scrap.self_key() == target_key)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Scrap not found: title='{}', ctx='{:?}'", title, ctx)
+            })?;
+        let target = &scraps[target_index];
+
+        let backlinks_map = BacklinksMap::new(scraps);
+        let keys: HashSet<ScrapKey> = scraps.iter().map(|s| s.self_key()).collect();
+        // Broken links point nowhere, so sharing one says nothing.
+        let target_links: HashSet<&ScrapKey> =
+            target.links().iter().filter(|l| keys.contains(l)).collect();
+        let target_citers: HashSet<ScrapKey> = backlinks_map
+            .get(&target_key)
+            .iter()
+            .map(|s| s.self_key())
+            .collect();
+        let target_tags = tag_closure(target.tags());
+        let vectors = tfidf_vectors(scraps);
+        let target_vector = &vectors[target_index];
+
+        let results = scraps
+            .iter()
+            .zip(&vectors)
+            .filter(|(scrap, _)| {
+                let key = scrap.self_key();
+                key != target_key && !target_links.contains(&key) && !target_citers.contains(&key)
+            })
+            .map(|(scrap, vector)| {
+                let key = scrap.self_key();
+                let co_citations = backlinks_map
+                    .get(&key)
+                    .iter()
+                    .filter(|s| target_citers.contains(&s.self_key()))
+                    .map(|s| s.self_key())
+                    .unique()
+                    .count();
+                let shared_links = scrap
+                    .links()
+                    .iter()
+                    .filter(|l| target_links.contains(l))
+                    .count();
+                let shared: Vec<Tag> = tag_closure(scrap.tags())
+                    .intersection(&target_tags)
+                    .cloned()
+                    .collect();
+                let tag_score: usize = shared.iter().map(|t| t.depth()).sum();
+                let text_similarity = cosine(target_vector, vector);
+
+                let score = CO_CITATION_WEIGHT * co_citations as f64
+                    + COUPLING_WEIGHT * shared_links as f64
+                    + TAG_WEIGHT * tag_score as f64
+                    + TEXT_WEIGHT * text_similarity;
+
+                RelatedResult {
+                    title: scrap.title().clone(),
+                    ctx: scrap.ctx().clone(),
+                    score,
+                    co_citations,
+                    shared_links,
+                    shared_tags: most_specific(&shared),
+                    text_similarity,
+                }
+            })
+            .filter(|r| r.score > 0.0)
+            .sorted_by(|a, b| {
+                b.score.total_cmp(&a.score).then_with(|| {
+                    ScrapKey::new(&a.title, &a.ctx).cmp(&ScrapKey::new(&b.title, &b.ctx))
+                })
+            })
+            .take(num)
+            .collect();
+
+        Ok(results)
+    }
+}
+
+/// Explicit tags plus their ancestors, so `rust/async` and `rust/tokio`
+/// still share `rust`.
+fn tag_closure(tags: &[Tag]) -> HashSet<Tag> {
+    tags.iter()
+        .flat_map(|t| std::iter::once(t.clone()).chain(t.ancestors()))
+        .collect()
+}
+
+/// Drop tags that are ancestors of another tag in the list.
+fn most_specific(tags: &[Tag]) -> Vec<Tag> {
+    let ancestors: HashSet<Tag> = tags.iter().flat_map(|t| t.ancestors()).collect();
+    tags.iter()
+        .filter(|t| !ancestors.contains(t))
+        .cloned()
+        .sorted()
+        .collect()
+}
+
+/// Body prose without `[[]]`-family syntax; links and tags are scored by the
+/// graph signals already.
+fn prose(scrap: &Scrap) -> String {
+    let doc = scrap.document();
+    let text = doc.text();
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for (_, span) in doc.wiki_refs_with_spans() {
+        out.push_str(&text[cursor..span.start]);
+        out.push(' ');
+        cursor = span.end;
+    }
+    out.push_str(&text[cursor..]);
+    out
+}
+
+/// Search tokens, so CJK prose is split into bigrams too. Single letters
+/// and digits are dropped, lone CJK characters kept.
+fn tokenize(text: &str) -> impl Iterator<Item = String> {
+    tokenizer::tokenize(text)
+        .into_iter()
+        .filter(|w| w.chars().count() > 1 || tokenizer::is_cjk_unigram(w))
+}
+
+/// TF-IDF vector of each scrap's prose, in the order of `scraps`, with
+/// inverse document frequencies over the whole wiki.
+fn tfidf_vectors(scraps: &[Scrap]) -> Vec<HashMap<String, f64>> {
+    let term_counts: Vec<HashMap<String, f64>> = scraps
+        .iter()
+        .map(|scrap| {
+            let mut tf: HashMap<String, f64> = HashMap::new();
+            for term in tokenize(&prose(scrap)) {
+                *tf.entry(term).or_default() += 1.0;
+            }
+            tf
+        })
+        .collect();
+
+    let mut df: HashMap<&str, usize> = HashMap::new();
+    for term in term_counts.iter().flat_map(|tf| tf.keys()) {
+        *df.entry(term).or_default() += 1;
+    }
+    let n = scraps.len() as f64;
+    let idf: HashMap<&str, f64> = df
+        .into_iter()
+        .map(|(term, df)| (term, (n / df as f64).ln()))
+        .collect();
+
+    term_counts
+        .iter()
+        .map(|tf| {
+            tf.iter()
+                .map(|(term, count)| (term.clone(), count * idf[term.as_str()]))
+                .filter(|(_, w)| *w > 0.0)
+                .collect()
+        })
+        .collect()
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(term, w)| b.get(term).map(|v| w * v))
+        .sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|w| w * w).sum::<f64>().sqrt();
+    let denom = norm(a) * norm(b);
+    if denom == 0.0 {
+        0.0
+    } else {
+        dot / denom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn related(scraps: &[Scrap], title: &str) -> Vec<RelatedResult> {
+        RelatedScrapsUsecase::new()
+            .execute(scraps, &title.into(), &None, 10)
+            .unwrap()
+    }
+
+    #[test]
+    fn it_ranks_by_shared_links_and_co_citation() {
+        let scraps = vec![
+            Scrap::new("target", &None, "[[x]] [[y]]"),
+            Scrap::new("coupled", &None, "[[x]] [[y]]"),
+            Scrap::new("cited", &None, ""),
+            Scrap::new("citer", &None, "[[target]] [[cited]]"),
+            Scrap::new("x", &None, ""),
+            Scrap::new("y", &None, ""),
+            Scrap::new("unrelated", &None, ""),
+        ];
+
+        let results = related(&scraps, "target");
+
+        let titles: Vec<String> = results.iter().map(|r| r.title.to_string()).collect();
+        // x, y (linked) and citer (links to target) are excluded.
+        assert_eq!(titles, vec!["coupled", "cited"]);
+        assert_eq!(results[0].shared_links, 2);
+        assert_eq!(results[0].reason(), "2 shared links");
+        assert_eq!(results[1].co_citations, 1);
+        assert_eq!(results[1].reason(), "1 co-citation");
+    }
+
+    #[test]
+    fn it_weights_deeper_shared_tags_higher() {
+        let scraps = vec![
+            Scrap::new("target", &None, "#[[rust/async]]"),
+            Scrap::new("deep", &None, "#[[rust/async]]"),
+            Scrap::new("shallow", &None, "#[[rust/tokio]]"),
+        ];
+
+        let results = related(&scraps, "target");
+
+        assert_eq!(results[0].title.to_string(), "deep");
+        assert_eq!(results[0].shared_tags, vec![Tag::from("rust/async")]);
+        assert_eq!(results[0].reason(), "tag rust/async");
+        assert_eq!(results[1].title.to_string(), "shallow");
+        assert_eq!(results[1].reason(), "tag rust");
+    }
+
+    #[test]
+    fn it_scores_text_similarity() {
+        let scraps = vec![
+            Scrap::new("target", &None, "ownership borrowing lifetimes"),
+            Scrap::new("similar", &None, "borrowing and lifetimes explained"),
+            Scrap::new("other", &None, "gardening tomatoes"),
+        ];
+
+        let results = related(&scraps, "target");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title.to_string(), "similar");
+        assert!(results[0].text_similarity > 0.0);
+        assert!(results[0].reason().starts_with("similar text 0."));
+    }
+
+    #[test]
+    fn it_scores_cjk_text_similarity() {
+        let scraps = vec![
+            Scrap::new("target", &None, "所有権と借用規則について"),
+            Scrap::new("similar", &None, "借用規則の解説"),
+            Scrap::new("other", &None, "家庭菜園でトマトを育てる"),
+        ];
+
+        let results = related(&scraps, "target");
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title.to_string(), "similar");
+        assert!(results[0].text_similarity > 0.0);
+    }
+
+    #[test]
+    fn it_ignores_shared_broken_links() {
+        let scraps = vec![
+            Scrap::new("target", &None, "[[x]] [[missing]]"),
+            Scrap::new("coupled", &None, "[[x]] [[missing]]"),
+            Scrap::new("broken", &None, "[[missing]]"),
+            Scrap::new("x", &None, ""),
+        ];
+
+        let results = related(&scraps, "target");
+
+        let titles: Vec<String> = results.iter().map(|r| r.title.to_string()).collect();
+        assert_eq!(titles, vec!["coupled"]);
+        assert_eq!(results[0].shared_links, 1);
+    }
+
+    #[test]
+    fn it_errors_on_unknown_scrap() {
+        let result = RelatedScrapsUsecase::new().execute(&[], &"missing".into(), &None, 10);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::error::ScrapsResult;
+use scraps_libs::markdown::query::WikiRef;
+use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
+use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::tag::Tag;
+use scraps_libs::model::title::Title;
+
+/// Which way links are followed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraverseDirection {
+    /// From a scrap to the scraps it references.
+    Outbound,
+    /// From a scrap to the scraps referencing it.
+    Inbound,
+}
+
+/// A node reached by the traversal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TraverseNode {
+    Scrap(ScrapKey),
+    /// A link target with no scrap behind it. Never expanded further.
+    Broken(ScrapKey),
+    Tag(Tag),
+}
+
+/// The kind of reference an edge stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TraverseEdgeKind {
+    /// Plain wikilink `[[...]]`.
+    Link,
+    /// Inline embed `![[...]]`.
+    Embed,
+    /// Between a scrap and an explicit tag it declares.
+    Tag,
+}
+
+/// The edge a node was first reached through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraverseEdge {
+    pub from: TraverseNode,
+    pub kind: TraverseEdgeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraverseResult {
+    pub node: TraverseNode,
+    /// Hops from the starting scrap; a tag detour counts as two.
+    pub distance: usize,
+    pub via: TraverseEdge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraverseOptions {
+    pub direction: TraverseDirection,
+    /// Maximum distance from the starting scrap.
+    pub depth: usize,
+    /// Report unresolved link targets. Only outbound links can be broken.
+    pub include_broken: bool,
+    /// Allow `scrap -> #tag -> scrap` hops through explicit tags.
+    pub include_tags: bool,
+}
+
+pub struct TraverseLinksUsecase;
+
+impl TraverseLinksUsecase {
+    pub fn new() -> TraverseLinksUsecase {
+        TraverseLinksUsecase
+    }
+
+    /// Breadth-first neighbourhood of a scrap up to `options.depth` hops.
+    /// Each node is reported once, at its shortest distance, in order of
+    /// distance.
+    pub fn execute(
+        &self,
+        scraps: &[Scrap],
+        title: &Title,
+        ctx: &Option<Ctx>,
+        options: &TraverseOptions,
+    ) -> ScrapsResult<Vec<TraverseResult>> {
+        let start_key = ScrapKey::new(title, ctx);
+        if !scraps.iter().any(|scrap| scrap.self_key() == start_key) {
+            return Err(anyhow::anyhow!(
+                "Scrap not found: title='{}', ctx='{:?}'",
+                title,
+                ctx
+            ));
+        }
+
+        let graph = Graph::new(scraps, options);
+        let start = TraverseNode::Scrap(start_key);
+        let mut seen: HashSet<TraverseNode> = HashSet::from([start.clone()]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        let mut results = Vec::new();
+
+        while let Some((node, distance)) = queue.pop_front() {
+            if distance >= options.depth {
+                continue;
+            }
+            for (next, kind) in graph.neighbours(&node) {
+                if !seen.insert(next.clone()) {
+                    continue;
+                }
+                if !matches!(next, TraverseNode::Broken(_)) {
+                    queue.push_back((next.clone(), distance + 1));
+                }
+                results.push(TraverseResult {
+                    node: next.clone(),
+                    distance: distance + 1,
+                    via: TraverseEdge {
+                        from: node.clone(),
+                        kind: *kind,
+                    },
+                });
+            }
+        }
+
+        Ok(results)
+    }
+}
+
+struct Graph {
+    adjacency: HashMap<TraverseNode, Vec<(TraverseNode, TraverseEdgeKind)>>,
+}
+
+impl Graph {
+    fn new(scraps: &[Scrap], options: &TraverseOptions) -> Graph {
+        let keys: HashSet<ScrapKey> = scraps.iter().map(|s| s.self_key()).collect();
+        let mut adjacency: HashMap<TraverseNode, Vec<(TraverseNode, TraverseEdgeKind)>> =
+            HashMap::new();
+
+        for scrap in scraps {
+            let source = scrap.self_key();
+            for (target, kind) in references(scrap) {
+                let resolved = keys.contains(&target);
+                match (options.direction, resolved) {
+                    (TraverseDirection::Outbound, true) => adjacency
+                        .entry(TraverseNode::Scrap(source.clone()))
+                        .or_default()
+                        .push((TraverseNode::Scrap(target), kind)),
+                    (TraverseDirection::Outbound, false) if options.include_broken => adjacency
+                        .entry(TraverseNode::Scrap(source.clone()))
+                        .or_default()
+                        .push((TraverseNode::Broken(target), kind)),
+                    (TraverseDirection::Inbound, true) => adjacency
+                        .entry(TraverseNode::Scrap(target))
+                        .or_default()
+                        .push((TraverseNode::Scrap(source.clone()), kind)),
+                    _ => {}
+                }
+            }
+            if options.include_tags {
+                for tag in scrap.tags() {
+                    adjacency
+                        .entry(TraverseNode::Scrap(source.clone()))
+                        .or_default()
+                        .push((TraverseNode::Tag(tag.clone()), TraverseEdgeKind::Tag));
+                    adjacency
+                        .entry(TraverseNode::Tag(tag.clone()))
+                        .or_default()
+                        .push((TraverseNode::Scrap(source.clone()), TraverseEdgeKind::Tag));
+                }
+            }
+        }
+
+        // Stable neighbour order keeps the traversal reproducible; a link
+        // wins over an embed of the same target.
+        for edges in adjacency.values_mut() {
+            edges.sort();
+            edges.dedup_by(|a, b| a.0 == b.0);
+        }
+        Graph { adjacency }
+    }
+
+    fn neighbours(&self, node: &TraverseNode) -> &[(TraverseNode, TraverseEdgeKind)] {
+        self.adjacency.get(node).map_or(&[], Vec::as_slice)
+    }
+}
+
+/// Link and embed targets of a scrap, unresolved ones included.
+fn references(scrap: &Scrap) -> impl Iterator<Item = (ScrapKey, TraverseEdgeKind)> + '_ {
+    scrap
+        .document()
+        .wiki_refs()
+        .cloned()
+        .filter_map(|wref| match wref {
+            WikiRef::Link(r) => Some((ScrapKey::from(&r), TraverseEdgeKind::Link)),
+            WikiRef::Embed(r) => {
+                let mut path = r.ctx_path;
+                path.push(r.title);
+                Some((
+                    ScrapKey::from_path_str(&path.join("/")),
+                    TraverseEdgeKind::Embed,
+                ))
+            }
+            WikiRef::Tag(_) => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(direction: TraverseDirection, depth: usize) -> TraverseOptions {
+        TraverseOptions {
+            direction,
+            depth,
+            include_broken: false,
+            include_tags: false,
+        }
+    }
+
+    fn scrap_node(title: &str) -> TraverseNode {
+        TraverseNode::Scrap(Title::from(title).into())
+    }
+
+    fn traverse(scraps: &[Scrap], options: &TraverseOptions) -> Vec<TraverseResult> {
+        TraverseLinksUsecase::new()
+            .execute(scraps, &"a".into(), &None, options)
+            .unwrap()
+    }
+
+    #[test]
+    fn it_walks_outbound_links_up_to_depth() {
+        let scraps = vec![
+            Scrap::new("a", &None, "[[b]] ![[c]]"),
+            Scrap::new("b", &None, "[[c]] [[d]]"),
+            Scrap::new("c", &None, ""),
+            Scrap::new("d", &None, "[[e]]"),
+            Scrap::new("e", &None, ""),
+        ];
+
+        let results = traverse(&scraps, &options(TraverseDirection::Outbound, 2));
+
+        assert_eq!(
+            results,
+            vec![
+                TraverseResult {
+                    node: scrap_node("b"),
+                    distance: 1,
+                    via: TraverseEdge {
+                        from: scrap_node("a"),
+                        kind: TraverseEdgeKind::Link,
+                    },
+                },
+                TraverseResult {
+                    node: scrap_node("c"),
+                    distance: 1,
+                    via: TraverseEdge {
+                        from: scrap_node("a"),
+                        kind: TraverseEdgeKind::Embed,
+                    },
+                },
+                TraverseResult {
+                    node: scrap_node("d"),
+                    distance: 2,
+                    via: TraverseEdge {
+                        from: scrap_node("b"),
+                        kind: TraverseEdgeKind::Link,
+                    },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_walks_inbound_links() {
+        let scraps = vec![
+            Scrap::new("a", &None, ""),
+            Scrap::new("b", &None, "[[a]]"),
+            Scrap::new("c", &None, "[[b]]"),
+            Scrap::new("d", &None, "[[c]]"),
+        ];
+
+        let results = traverse(&scraps, &options(TraverseDirection::Inbound, 2));
+
+        let reached: Vec<(TraverseNode, usize)> =
+            results.into_iter().map(|r| (r.node, r.distance)).collect();
+        assert_eq!(reached, vec![(scrap_node("b"), 1), (scrap_node("c"), 2)]);
+    }
+
+    #[test]
+    fn it_includes_broken_targets_without_expanding_them() {
+        let scraps = vec![Scrap::new("a", &None, "[[missing]]")];
+
+        let without = traverse(&scraps, &options(TraverseDirection::Outbound, 3));
+        assert!(without.is_empty());
+
+        let with = traverse(
+            &scraps,
+            &TraverseOptions {
+                include_broken: true,
+                ..options(TraverseDirection::Outbound, 3)
+            },
+        );
+        assert_eq!(with.len(), 1);
+        assert_eq!(
+            with[0].node,
+            TraverseNode::Broken(Title::from("missing").into())
+        );
+    }
+
+    #[test]
+    fn it_hops_through_tags() {
+        let scraps = vec![
+            Scrap::new("a", &None, "#[[rust]]"),
+            Scrap::new("b", &None, "#[[rust]]"),
+        ];
+
+        let results = traverse(
+            &scraps,
+            &TraverseOptions {
+                include_tags: true,
+                ..options(TraverseDirection::Outbound, 2)
+            },
+        );
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].node, TraverseNode::Tag(Tag::from("rust")));
+        assert_eq!(results[1].node, scrap_node("b"));
+        assert_eq!(results[1].distance, 2);
+        assert_eq!(results[1].via.kind, TraverseEdgeKind::Tag);
+    }
+
+    #[test]
+    fn it_errors_on_unknown_scrap() {
+        let result = TraverseLinksUsecase::new().execute(
+            &[],
+            &"missing".into(),
+            &None,
+            &options(TraverseDirection::Outbound, 1),
+        );
+        assert!(result.is_err());
+    }
+}
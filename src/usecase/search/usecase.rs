@@ -1,24 +1,69 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
 
 use crate::error::ScrapsResult;
 use scraps_libs::model::context::Ctx;
+use scraps_libs::model::key::ScrapKey;
 use scraps_libs::model::scrap::Scrap;
+use scraps_libs::model::tag::Tag;
 use scraps_libs::model::title::Title;
+use scraps_libs::search::bm25_engine::Bm25SearchEngine;
 use scraps_libs::search::engine::{SearchEngine, SearchLogic};
 use scraps_libs::search::fuzzy_engine::FuzzySearchEngine;
+use scraps_libs::search::result::SearchItem;
+use scraps_libs::search::snippet::{snippets, Snippet};
+
+/// Excerpts returned per result.
+const SNIPPETS_PER_RESULT: usize = 2;
+
+/// Ranking strategy used by [`SearchUsecase`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchEngineKind {
+    /// Fuzzy matching on title and body (default).
+    #[default]
+    Fuzzy,
+    /// BM25 over an inverted index, with title and heading boosts and
+    /// `"exact phrase"` queries.
+    Bm25,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SearchResult {
     pub title: Title,
     pub ctx: Option<Ctx>,
     pub md_text: String,
+    /// Engine score; only comparable within one engine.
+    pub score: f64,
+    /// Excerpts around the best-matching regions, best first.
+    pub snippets: Vec<SearchSnippet>,
 }
 
-pub struct SearchUsecase;
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchSnippet {
+    pub text: String,
+    /// Byte ranges of the query matches within `text`.
+    pub matches: Vec<Range<usize>>,
+    /// 1-based line of the first match in the scrap.
+    pub line: usize,
+    /// Innermost heading the match sits under.
+    pub heading: Option<String>,
+}
+
+pub struct SearchUsecase {
+    /// Tokens of [`bm25_body`] already known per scrap, such as from the
+    /// scrap cache; other scraps are tokenized while indexing.
+    body_tokens: HashMap<ScrapKey, Vec<String>>,
+}
 
 impl SearchUsecase {
     pub fn new() -> SearchUsecase {
-        SearchUsecase
+        SearchUsecase {
+            body_tokens: HashMap::new(),
+        }
+    }
+
+    pub fn with_body_tokens(self, body_tokens: HashMap<ScrapKey, Vec<String>>) -> SearchUsecase {
+        SearchUsecase { body_tokens }
     }
 
     pub fn execute(
@@ -27,34 +72,40 @@ impl SearchUsecase {
         query: &str,
         num: usize,
         logic: SearchLogic,
+        engine: SearchEngineKind,
+        filter: &SearchFilter,
     ) -> ScrapsResult<Vec<SearchResult>> {
-        // Create title-to-scrap mapping for efficient lookup
+        // Narrow the candidates before ranking
+        let scraps: Vec<&Scrap> = scraps.iter().filter(|s| filter.accepts(s)).collect();
+
+        // Create key-to-scrap mapping for efficient lookup
         let scrap_map: HashMap<String, &Scrap> = scraps
             .iter()
-            .map(|scrap| (scrap.self_key().to_string(), scrap))
+            .map(|scrap| (scrap.self_key().to_string(), *scrap))
             .collect();
 
-        // Create search items in memory (title + body for search)
-        let lib_items: Vec<scraps_libs::search::result::SearchItem> = scraps
+        // Create search items in memory from the fields in scope
+        let lib_items: Vec<SearchItem> = scraps
             .iter()
-            .map(|scrap| {
-                scraps_libs::search::result::SearchItem::new(
-                    &scrap.self_key().to_string(),
-                    scrap.md_text(),
-                )
-            })
+            .map(|scrap| self.search_item(scrap, engine, filter.scope))
             .collect();
 
         // Perform search
-        let engine = FuzzySearchEngine::new();
-        let search_results = engine.search(&lib_items, query, num, logic);
+        let search_results = match engine {
+            SearchEngineKind::Fuzzy => {
+                FuzzySearchEngine::new().search_scored(&lib_items, query, num, logic)
+            }
+            SearchEngineKind::Bm25 => {
+                Bm25SearchEngine::new().search_scored(&lib_items, query, num, logic)
+            }
+        };
 
         // Convert to final results using HashMap lookup
         let results: Vec<SearchResult> = search_results
             .into_iter()
-            .filter_map(|result| {
-                // Find the corresponding scrap by title using HashMap
-                scrap_map.get(&result.title).map(|scrap| {
+            .filter_map(|(result, score)| {
+                // Find the corresponding scrap by key using HashMap
+                scrap_map.get(&result.id).map(|scrap| {
                     let scrap_key = &scrap.self_key();
                     let title: Title = scrap_key.into();
                     let ctx: Option<Ctx> = scrap_key.into();
@@ -63,6 +114,8 @@ impl SearchUsecase {
                         title,
                         ctx,
                         md_text: scrap.md_text().to_string(),
+                        score,
+                        snippets: search_snippets(scrap, query, filter.scope),
                     }
                 })
             })
@@ -70,11 +123,172 @@ impl SearchUsecase {
 
         Ok(results)
     }
+
+    fn search_item(
+        &self,
+        scrap: &Scrap,
+        engine: SearchEngineKind,
+        scope: Option<SearchScope>,
+    ) -> SearchItem {
+        let key = scrap.self_key();
+        let id = key.to_string();
+        let md_text = scrap.md_text();
+        let heading_texts =
+            || -> Vec<String> { scrap.headings().iter().map(|h| h.text.clone()).collect() };
+        match scope {
+            None => match engine {
+                SearchEngineKind::Fuzzy => SearchItem::new(&id, md_text),
+                SearchEngineKind::Bm25 => {
+                    let item =
+                        SearchItem::new(&id, &bm25_body(scrap)).with_headings(heading_texts());
+                    match self.body_tokens.get(&key) {
+                        Some(tokens) => item.with_body_tokens(tokens.clone()),
+                        None => item,
+                    }
+                }
+            },
+            Some(SearchScope::Title) => {
+                SearchItem::new(&scrap.title().to_string(), "").with_id(&id)
+            }
+            Some(scope) => {
+                let (text, _) = scoped_text(scrap, scope);
+                SearchItem::new("", &text).with_id(&id)
+            }
+        }
+    }
+}
+
+/// Candidate restrictions applied before ranking.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchFilter {
+    /// Only scraps in this context or a nested one.
+    pub ctx: Option<Ctx>,
+    /// Skip scraps in these contexts or nested ones.
+    pub exclude_ctx: Vec<Ctx>,
+    /// Only scraps tagged with this tag or one of its descendants.
+    pub tag: Option<Tag>,
+    /// Search a single field instead of title and body.
+    pub scope: Option<SearchScope>,
+    /// Only scraps with one of these keys, such as the matches of a query.
+    pub keys: Option<HashSet<ScrapKey>>,
+}
+
+/// Field a scoped search looks in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScope {
+    Title,
+    Headings,
+    Body,
+    /// Fenced code block contents.
+    Code,
+}
+
+impl SearchFilter {
+    fn accepts(&self, scrap: &Scrap) -> bool {
+        let in_ctx = |ctx: &Ctx| {
+            scrap
+                .ctx()
+                .as_ref()
+                .is_some_and(|c| c.segments().starts_with(ctx.segments()))
+        };
+        self.keys
+            .as_ref()
+            .is_none_or(|keys| keys.contains(&scrap.self_key()))
+            && self.ctx.as_ref().is_none_or(in_ctx)
+            && !self.exclude_ctx.iter().any(in_ctx)
+            && self.tag.as_ref().is_none_or(|tag| {
+                scrap
+                    .tags()
+                    .iter()
+                    .any(|t| t == tag || t.ancestors().contains(tag))
+            })
+    }
+}
+
+/// The body BM25 indexes: the text without its heading lines, as headings
+/// get their own boosted field rather than being counted twice.
+pub fn bm25_body(scrap: &Scrap) -> String {
+    let heading_lines: HashSet<usize> = scrap.headings().iter().map(|h| h.line).collect();
+    scrap
+        .md_text()
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| !heading_lines.contains(&(i + 1)))
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The text a search limited to `scope` looks in, with the scrap line each
+/// of its lines comes from. A title is not on any line of the body.
+fn scoped_text(scrap: &Scrap, scope: SearchScope) -> (String, Vec<usize>) {
+    match scope {
+        SearchScope::Title => (scrap.title().to_string(), Vec::new()),
+        SearchScope::Headings => (
+            scrap
+                .headings()
+                .iter()
+                .map(|h| h.text.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            scrap.headings().iter().map(|h| h.line).collect(),
+        ),
+        SearchScope::Body => (
+            scrap.md_text().to_string(),
+            (1..=scrap.md_text().lines().count()).collect(),
+        ),
+        SearchScope::Code => {
+            let blocks = scrap.document().code_blocks();
+            let lines = blocks
+                .iter()
+                .flat_map(|b| (b.line + 1..).take(b.content.lines().count()))
+                .collect();
+            // Each block's content ends with a newline already.
+            let code: String = blocks.iter().map(|b| b.content.as_str()).collect();
+            (code.trim_end().to_string(), lines)
+        }
+    }
+}
+
+/// Excerpts of the text that was searched. A title search has none, as the
+/// result's title already shows the match.
+fn search_snippets(scrap: &Scrap, query: &str, scope: Option<SearchScope>) -> Vec<SearchSnippet> {
+    let found = match scope {
+        None => snippets(scrap.md_text(), query, SNIPPETS_PER_RESULT),
+        Some(scope) => {
+            let (text, lines) = scoped_text(scrap, scope);
+            snippets(&text, query, SNIPPETS_PER_RESULT)
+                .into_iter()
+                .filter_map(|snippet| {
+                    let line = *lines.get(snippet.line.checked_sub(1)?)?;
+                    Some(Snippet { line, ..snippet })
+                })
+                .collect()
+        }
+    };
+    if found.is_empty() {
+        return Vec::new();
+    }
+    let headings = scrap.headings();
+    found
+        .into_iter()
+        .map(|snippet| SearchSnippet {
+            heading: headings
+                .iter()
+                .rev()
+                .find(|h| h.line <= snippet.line)
+                .map(|h| h.text.clone()),
+            text: snippet.text,
+            matches: snippet.matches,
+            line: snippet.line,
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use scraps_libs::model::key::ScrapKey;
 
     #[test]
     fn it_run() {
@@ -90,7 +304,14 @@ mod tests {
         let usecase = SearchUsecase::new();
 
         let results = usecase
-            .execute(&scraps, "test", 100, SearchLogic::And)
+            .execute(
+                &scraps,
+                "test",
+                100,
+                SearchLogic::And,
+                SearchEngineKind::Fuzzy,
+                &SearchFilter::default(),
+            )
             .unwrap();
 
         // Should find documents containing "test"
@@ -119,7 +340,14 @@ mod tests {
         let usecase = SearchUsecase::new();
 
         let results = usecase
-            .execute(&scraps, "duplicate", 100, SearchLogic::And)
+            .execute(
+                &scraps,
+                "duplicate",
+                100,
+                SearchLogic::And,
+                SearchEngineKind::Fuzzy,
+                &SearchFilter::default(),
+            )
             .unwrap();
 
         // Should find both scraps with the same title but different contexts
@@ -195,10 +423,214 @@ mod tests {
 
         // Search by body-only keyword - should match
         let results = usecase
-            .execute(&scraps, "uniquekeyword", 100, SearchLogic::And)
+            .execute(
+                &scraps,
+                "uniquekeyword",
+                100,
+                SearchLogic::And,
+                SearchEngineKind::Fuzzy,
+                &SearchFilter::default(),
+            )
             .unwrap();
 
         assert_eq!(results.len(), 1, "Body keyword should match scrap");
         assert_eq!(results[0].title.to_string(), "mydoc");
     }
+
+    #[test]
+    fn it_ranks_with_bm25() {
+        let scraps = vec![
+            Scrap::new("notes", &None, "Mentions lifetimes once."),
+            Scrap::new("borrowing", &None, "# Lifetimes\n\nThe borrow checker."),
+            Scrap::new("cooking", &None, "Nothing relevant."),
+        ];
+
+        let usecase = SearchUsecase::new();
+
+        let results = usecase
+            .execute(
+                &scraps,
+                "lifetimes",
+                100,
+                SearchLogic::Or,
+                SearchEngineKind::Bm25,
+                &SearchFilter::default(),
+            )
+            .unwrap();
+        let titles: Vec<String> = results.iter().map(|r| r.title.to_string()).collect();
+        assert_eq!(titles, vec!["borrowing", "notes"]);
+
+        let results = usecase
+            .execute(
+                &scraps,
+                "\"checker the\"",
+                100,
+                SearchLogic::Or,
+                SearchEngineKind::Bm25,
+                &SearchFilter::default(),
+            )
+            .unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn it_indexes_headings_once_with_bm25() {
+        let scrap = Scrap::new("borrowing", &None, "# Lifetimes\n\nThe borrow checker.");
+
+        let item = SearchUsecase::new().search_item(&scrap, SearchEngineKind::Bm25, None);
+
+        assert_eq!(item.headings, vec!["Lifetimes".to_string()]);
+        assert!(!item.body.contains("Lifetimes"));
+        assert!(item.body.contains("The borrow checker."));
+    }
+
+    #[test]
+    fn it_returns_snippets_under_headings() {
+        let scraps = vec![Scrap::new(
+            "mydoc",
+            &None,
+            "# Intro\n\nNothing here.\n\n## Details\n\nThe uniquekeyword lives here.",
+        )];
+
+        let results = SearchUsecase::new()
+            .execute(
+                &scraps,
+                "uniquekeyword",
+                100,
+                SearchLogic::Or,
+                SearchEngineKind::Bm25,
+                &SearchFilter::default(),
+            )
+            .unwrap();
+
+        assert!(results[0].score > 0.0);
+        let snippet = &results[0].snippets[0];
+        assert_eq!(snippet.line, 7);
+        assert_eq!(snippet.heading.as_deref(), Some("Details"));
+        assert_eq!(&snippet.text[snippet.matches[0].clone()], "uniquekeyword");
+    }
+
+    fn journal() -> Vec<Scrap> {
+        vec![
+            Scrap::new("2024-01-01", &Some("journal".into()), "Tried tokio today."),
+            Scrap::new("2024-01-02", &Some("journal/private".into()), "More tokio."),
+            Scrap::new(
+                "tokio",
+                &Some("programming".into()),
+                "#[[rust/async]]\n\n## Runtime\n\n```rust\n#[tokio::main]\n```",
+            ),
+            Scrap::new("async-std", &None, "#[[rust]] An alternative to tokio."),
+        ]
+    }
+
+    fn search(scraps: &[Scrap], query: &str, filter: SearchFilter) -> Vec<String> {
+        let mut keys: Vec<String> = SearchUsecase::new()
+            .execute(
+                scraps,
+                query,
+                100,
+                SearchLogic::Or,
+                SearchEngineKind::Bm25,
+                &filter,
+            )
+            .unwrap()
+            .into_iter()
+            .map(|r| ScrapKey::new(&r.title, &r.ctx).to_string())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn it_filters_by_ctx_and_tag_before_ranking() {
+        let scraps = journal();
+
+        let in_journal = SearchFilter {
+            ctx: Some("journal".into()),
+            exclude_ctx: vec!["journal/private".into()],
+            ..SearchFilter::default()
+        };
+        assert_eq!(
+            search(&scraps, "tokio", in_journal),
+            vec!["journal/2024-01-01"]
+        );
+
+        let tagged = SearchFilter {
+            tag: Some("rust".into()),
+            ..SearchFilter::default()
+        };
+        assert_eq!(
+            search(&scraps, "tokio", tagged),
+            vec!["async-std", "programming/tokio"]
+        );
+    }
+
+    #[test]
+    fn it_limits_the_search_to_one_field() {
+        let scraps = journal();
+        let scoped = |scope| SearchFilter {
+            scope: Some(scope),
+            ..SearchFilter::default()
+        };
+
+        assert_eq!(
+            search(&scraps, "tokio", scoped(SearchScope::Title)),
+            vec!["programming/tokio"]
+        );
+        assert_eq!(
+            search(&scraps, "runtime", scoped(SearchScope::Headings)),
+            vec!["programming/tokio"]
+        );
+        assert_eq!(
+            search(&scraps, "main", scoped(SearchScope::Code)),
+            vec!["programming/tokio"]
+        );
+        assert_eq!(
+            search(&scraps, "journal", scoped(SearchScope::Title)),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            search(&scraps, "alternative", scoped(SearchScope::Body)),
+            vec!["async-std"]
+        );
+    }
+
+    #[test]
+    fn it_cuts_snippets_from_the_searched_field() {
+        let scraps = vec![Scrap::new(
+            "tokio",
+            &None,
+            "## Runtime\n\nThe tokio runtime.\n\n```rust\nlet runtime = 1;\n```",
+        )];
+        let snippets = |scope| {
+            let filter = SearchFilter {
+                scope: Some(scope),
+                ..SearchFilter::default()
+            };
+            SearchUsecase::new()
+                .execute(
+                    &scraps,
+                    "runtime",
+                    100,
+                    SearchLogic::Or,
+                    SearchEngineKind::Bm25,
+                    &filter,
+                )
+                .unwrap()
+                .remove(0)
+                .snippets
+                .into_iter()
+                .map(|s| (s.text, s.line))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            snippets(SearchScope::Headings),
+            vec![("Runtime".to_string(), 1)]
+        );
+        assert_eq!(
+            snippets(SearchScope::Code),
+            vec![("let runtime = 1;".to_string(), 6)]
+        );
+    }
 }
@@ -1,2 +1,4 @@
+pub mod live_reload;
+mod mime;
 mod service;
 pub mod usecase;
@@ -0,0 +1,163 @@
+use std::{
+    convert::Infallible,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+use hyper::body::{Body, Bytes, Frame};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Path of the server-sent events endpoint browsers subscribe to.
+pub const LIVE_RELOAD_PATH: &str = "/__scraps/live-reload";
+
+/// Client side of live reload: reloads the page on `reload` and shows the
+/// message of `build-error` in an overlay until the next successful build.
+const CLIENT_SCRIPT: &str = r#"<script>
+(() => {
+  const source = new EventSource("/__scraps/live-reload");
+  source.addEventListener("reload", () => location.reload());
+  source.addEventListener("build-error", (event) => {
+    let overlay = document.getElementById("scraps-build-error");
+    if (!overlay) {
+      overlay = document.createElement("div");
+      overlay.id = "scraps-build-error";
+      overlay.style.cssText = "position:fixed;inset:0;z-index:2147483647;overflow:auto;padding:2rem;background:rgba(24,24,27,.94);color:#fca5a5;font:14px/1.5 ui-monospace,monospace";
+      const title = document.createElement("strong");
+      title.textContent = "Build failed";
+      const message = document.createElement("pre");
+      message.style.whiteSpace = "pre-wrap";
+      overlay.append(title, message);
+      document.body.append(overlay);
+    }
+    overlay.lastChild.textContent = JSON.parse(event.data);
+  });
+})();
+</script>"#;
+
+/// Connected browsers and the outcome of the last build. Sending is
+/// synchronous so the file watcher thread can notify without a runtime.
+#[derive(Default)]
+pub struct LiveReload {
+    clients: Mutex<Vec<UnboundedSender<Bytes>>>,
+    build_error: Mutex<Option<String>>,
+}
+
+impl LiveReload {
+    pub fn new() -> LiveReload {
+        LiveReload::default()
+    }
+
+    /// Open an event stream for one browser. A pending build error is sent
+    /// right away so pages loaded after a failed build show it too.
+    pub fn subscribe(&self) -> EventStream {
+        let (sender, receiver) = unbounded_channel();
+        if let Some(message) = self.build_error.lock().unwrap().as_ref() {
+            let _ = sender.send(error_event(message));
+        }
+        self.clients.lock().unwrap().push(sender);
+        EventStream { receiver }
+    }
+
+    /// Tell every browser to reload after a successful build.
+    pub fn reload(&self) {
+        self.build_error.lock().unwrap().take();
+        self.broadcast(Bytes::from_static(b"event: reload\ndata: \n\n"));
+    }
+
+    /// Show `message` in every browser until the next successful build.
+    pub fn fail(&self, message: &str) {
+        *self.build_error.lock().unwrap() = Some(message.to_string());
+        self.broadcast(error_event(message));
+    }
+
+    fn broadcast(&self, event: Bytes) {
+        // Browsers that went away have dropped their receiver.
+        self.clients
+            .lock()
+            .unwrap()
+            .retain(|client| client.send(event.clone()).is_ok());
+    }
+
+    /// Add the client script to an HTML page, before `</body>` when present.
+    pub fn inject(html: &[u8]) -> Vec<u8> {
+        let at = html
+            .windows(b"</body>".len())
+            .rposition(|w| w.eq_ignore_ascii_case(b"</body>"))
+            .unwrap_or(html.len());
+        [&html[..at], CLIENT_SCRIPT.as_bytes(), &html[at..]].concat()
+    }
+}
+
+fn error_event(message: &str) -> Bytes {
+    // JSON keeps a multi-line message on the single `data:` line.
+    let data = serde_json::to_string(message).unwrap_or_default();
+    Bytes::from(format!("event: build-error\ndata: {data}\n\n"))
+}
+
+/// Response body of the event stream; it ends when the server shuts down.
+pub struct EventStream {
+    receiver: UnboundedReceiver<Bytes>,
+}
+
+impl Body for EventStream {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        self.receiver
+            .poll_recv(cx)
+            .map(|event| event.map(|bytes| Ok(Frame::data(bytes))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    async fn next_event(stream: &mut EventStream) -> String {
+        let frame = stream.frame().await.unwrap().unwrap();
+        String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap()
+    }
+
+    #[test]
+    fn it_injects_the_script_before_the_closing_body() {
+        let html = LiveReload::inject(b"<html><body><p>hi</p></BODY></html>");
+        let html = String::from_utf8(html).unwrap();
+        assert!(html.starts_with("<html><body><p>hi</p><script>"));
+        assert!(html.ends_with("</script></BODY></html>"));
+
+        let fragment = LiveReload::inject(b"<p>hi</p>");
+        assert!(fragment.ends_with(b"</script>"));
+    }
+
+    #[tokio::test]
+    async fn it_broadcasts_reloads_and_replays_pending_errors() {
+        let live_reload = LiveReload::new();
+        let mut first = live_reload.subscribe();
+
+        live_reload.fail("line 1\nline 2");
+        assert_eq!(
+            next_event(&mut first).await,
+            "event: build-error\ndata: \"line 1\\nline 2\"\n\n"
+        );
+
+        let mut late = live_reload.subscribe();
+        assert!(next_event(&mut late)
+            .await
+            .starts_with("event: build-error"));
+
+        live_reload.reload();
+        assert_eq!(next_event(&mut first).await, "event: reload\ndata: \n\n");
+        assert_eq!(next_event(&mut late).await, "event: reload\ndata: \n\n");
+
+        drop(late);
+        live_reload.reload();
+        assert_eq!(live_reload.clients.lock().unwrap().len(), 1);
+        assert!(live_reload.subscribe().receiver.is_empty());
+    }
+}
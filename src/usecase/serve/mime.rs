@@ -0,0 +1,87 @@
+use std::path::Path;
+
+/// Content type of a served file, by extension.
+pub fn mime_type(file_path: &Path) -> &'static str {
+    let ext = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    match ext.as_deref() {
+        // text
+        Some("html" | "htm") => "text/html; charset=utf-8",
+        Some("css") => "text/css; charset=utf-8",
+        Some("js" | "mjs") => "text/javascript; charset=utf-8",
+        Some("txt") => "text/plain; charset=utf-8",
+        Some("md") => "text/markdown; charset=utf-8",
+        Some("csv") => "text/csv; charset=utf-8",
+        // data
+        Some("json" | "map") => "application/json",
+        Some("jsonld") => "application/ld+json",
+        Some("webmanifest") => "application/manifest+json",
+        Some("xml") => "application/xml",
+        Some("rss") => "application/rss+xml",
+        Some("atom") => "application/atom+xml",
+        Some("wasm") => "application/wasm",
+        Some("pdf") => "application/pdf",
+        Some("zip") => "application/zip",
+        // images
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("avif") => "image/avif",
+        Some("ico") => "image/x-icon",
+        Some("bmp") => "image/bmp",
+        // fonts
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("ttf") => "font/ttf",
+        Some("otf") => "font/otf",
+        // media
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") => "audio/ogg",
+        Some("wav") => "audio/wav",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Whether responses of this type shrink enough to be worth compressing.
+pub fn is_compressible(mime_type: &str) -> bool {
+    mime_type.starts_with("text/")
+        || mime_type.starts_with("image/svg")
+        || mime_type == "application/wasm"
+        || (mime_type.starts_with("application/")
+            && (mime_type.ends_with("json") || mime_type.ends_with("xml")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("index.html", "text/html; charset=utf-8")]
+    #[case("search_index.json", "application/json")]
+    #[case("logo.SVG", "image/svg+xml")]
+    #[case("font.woff2", "font/woff2")]
+    #[case("feed.xml", "application/xml")]
+    #[case("robots.txt", "text/plain; charset=utf-8")]
+    #[case("no_extension", "application/octet-stream")]
+    fn it_maps_extensions(#[case] file: &str, #[case] expected: &str) {
+        assert_eq!(mime_type(Path::new(file)), expected);
+    }
+
+    #[rstest]
+    #[case("text/html; charset=utf-8", true)]
+    #[case("image/svg+xml", true)]
+    #[case("application/atom+xml", true)]
+    #[case("application/json", true)]
+    #[case("image/png", false)]
+    #[case("font/woff2", false)]
+    fn it_compresses_textual_types(#[case] mime: &str, #[case] expected: bool) {
+        assert_eq!(is_compressible(mime), expected);
+    }
+}
@@ -1,111 +1,631 @@
 use std::{
-    fs::File,
+    convert::Infallible,
+    fs,
     future::Future,
-    io::Read,
-    path::{Path, PathBuf},
+    io::Write,
+    ops::Range,
+    path::{Component, Path, PathBuf},
     pin::Pin,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-use crate::error::{anyhow::Context, ScrapsError, ServeError};
-use http_body_util::Full;
+use crate::error::{anyhow::Context, ScrapsError, ScrapsResult, ServeError};
+use crate::usecase::serve::live_reload::{LiveReload, LIVE_RELOAD_PATH};
+use crate::usecase::serve::mime::{is_compressible, mime_type};
+use http_body_util::{combinators::BoxBody, BodyExt, Empty, Full};
 use hyper::{
     body::{Bytes, Incoming},
-    header,
+    header::{self, HeaderMap, HeaderValue},
     service::Service,
-    Request, Response,
+    Method, Request, Response, StatusCode,
 };
 use percent_encoding::percent_decode_str;
 
+type ResponseBody = BoxBody<Bytes, Infallible>;
+
+const NOT_FOUND_PAGE: &str = "404.html";
+
+/// Responses smaller than this are sent uncompressed.
+const MIN_COMPRESS_BYTES: usize = 256;
+
 #[derive(Clone)]
 pub struct ScrapsService {
     pub output_dir_path: PathBuf,
+    /// URL path the site is mounted at, with leading and trailing slashes.
+    base_path: String,
+    live_reload: Option<Arc<LiveReload>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Encoding {
+    Identity,
+    Gzip,
+    Brotli,
+}
+
+impl Encoding {
+    fn token(&self) -> &'static str {
+        match self {
+            Encoding::Identity => "identity",
+            Encoding::Gzip => "gzip",
+            Encoding::Brotli => "br",
+        }
+    }
+
+    /// Pick the preferred encoding the client accepts, by q-value and then
+    /// brotli over gzip.
+    fn negotiate(accept_encoding: &str) -> Encoding {
+        let mut explicit: Vec<(String, f32)> = Vec::new();
+        for entry in accept_encoding.split(',') {
+            let mut parts = entry.split(';');
+            let name = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+            let q = parts
+                .filter_map(|p| p.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            if !name.is_empty() {
+                explicit.push((name, q));
+            }
+        }
+        let q_of = |token: &str| {
+            explicit
+                .iter()
+                .find(|(name, _)| name == token)
+                .or_else(|| explicit.iter().find(|(name, _)| name == "*"))
+                .map(|(_, q)| *q)
+                .unwrap_or(0.0)
+        };
+
+        [Encoding::Brotli, Encoding::Gzip]
+            .into_iter()
+            .map(|encoding| (encoding, q_of(encoding.token())))
+            .filter(|(_, q)| *q > 0.0)
+            .fold(
+                None,
+                |best: Option<(Encoding, f32)>, candidate| match best {
+                    Some(b) if b.1 >= candidate.1 => Some(b),
+                    _ => Some(candidate),
+                },
+            )
+            .map_or(Encoding::Identity, |(encoding, _)| encoding)
+    }
+
+    fn encode(&self, body: &[u8]) -> ScrapsResult<Vec<u8>> {
+        match self {
+            Encoding::Identity => Ok(body.to_vec()),
+            Encoding::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(body)?;
+                Ok(encoder.finish()?)
+            }
+            Encoding::Brotli => {
+                let mut encoder = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+                encoder.write_all(body)?;
+                Ok(encoder.into_inner())
+            }
+        }
+    }
 }
 
 impl ScrapsService {
-    pub fn new(output_dir_path: &Path) -> ScrapsService {
+    pub fn new(
+        output_dir_path: &Path,
+        base_path: &str,
+        live_reload: Option<Arc<LiveReload>>,
+    ) -> ScrapsService {
+        let trimmed = base_path.trim_matches('/');
         ScrapsService {
             output_dir_path: output_dir_path.to_owned(),
+            base_path: if trimmed.is_empty() {
+                "/".to_string()
+            } else {
+                format!("/{trimmed}/")
+            },
+            live_reload,
         }
     }
 
-    fn mk_response(
-        mime_type: &str,
-        contents: Vec<u8>,
-    ) -> Result<Response<Full<Bytes>>, ScrapsError> {
-        Ok(Response::builder()
-            .header(header::CONTENT_TYPE, mime_type)
-            .body(Full::new(Bytes::from(contents)))
-            .unwrap())
+    fn handle<B>(&self, request: &Request<B>) -> Response<ResponseBody> {
+        let method = request.method();
+        if method != Method::GET && method != Method::HEAD {
+            let mut response = Self::mk_status_response(StatusCode::METHOD_NOT_ALLOWED, method);
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static("GET, HEAD"));
+            return response;
+        }
+
+        if let Some(live_reload) = &self.live_reload {
+            if request.uri().path() == LIVE_RELOAD_PATH {
+                return Self::mk_event_stream_response(live_reload);
+            }
+        }
+
+        let path = request.uri().path();
+        let Some(site_path) = path.strip_prefix(self.base_path.as_str()) else {
+            // Send the bare origin and the unslashed mount point to the site.
+            return if path == "/" || path == self.base_path.trim_end_matches('/') {
+                Self::mk_redirect_response(&self.base_path)
+            } else {
+                self.mk_not_found_response(method)
+            };
+        };
+        let file_path = match self.resolve(site_path) {
+            Ok(file_path) => file_path,
+            Err(StatusCode::NOT_FOUND) => return self.mk_not_found_response(method),
+            Err(status) => return Self::mk_status_response(status, method),
+        };
+        match self.mk_file_response(request, &file_path, StatusCode::OK) {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("failed to serve {}: {e:#}", file_path.display());
+                Self::mk_status_response(StatusCode::INTERNAL_SERVER_ERROR, method)
+            }
+        }
     }
 
-    fn mk_not_found_response() -> Result<Response<Full<Bytes>>, ScrapsError> {
-        // Return the 404 Not Found for other routes, and don't increment counter.
-        Self::mk_response("text/plain", "oh no! not found".into())
+    /// Map a request path onto a file in the output directory. Every decoded
+    /// segment must be a plain name, so `..`, absolute and drive-prefixed
+    /// segments are rejected, and symlinks may not lead outside the
+    /// directory. Directories resolve to their `index.html`.
+    fn resolve(&self, request_path: &str) -> Result<PathBuf, StatusCode> {
+        let decoded = percent_decode_str(request_path)
+            .decode_utf8()
+            .map_err(|_| StatusCode::BAD_REQUEST)?;
+
+        let mut file_path = self.output_dir_path.clone();
+        for segment in decoded.split('/').filter(|s| !s.is_empty() && *s != ".") {
+            let mut components = Path::new(segment).components();
+            match (components.next(), components.next()) {
+                (Some(Component::Normal(name)), None) if !segment.contains(['\\', '\0']) => {
+                    file_path.push(name)
+                }
+                _ => return Err(StatusCode::BAD_REQUEST),
+            }
+        }
+        if file_path.is_dir() {
+            file_path.push("index.html");
+        }
+
+        let root = self
+            .output_dir_path
+            .canonicalize()
+            .map_err(|_| StatusCode::NOT_FOUND)?;
+        match file_path.canonicalize() {
+            Ok(real) if real.starts_with(&root) && real.is_file() => Ok(file_path),
+            _ => Err(StatusCode::NOT_FOUND),
+        }
     }
 
-    fn mk_failed_url_decode_response() -> Result<Response<Full<Bytes>>, ScrapsError> {
-        Self::mk_response("text/plain", "oh no! failed url decode by utf8".into())
+    fn mk_file_response<B>(
+        &self,
+        request: &Request<B>,
+        file_path: &Path,
+        status: StatusCode,
+    ) -> ScrapsResult<Response<ResponseBody>> {
+        let headers = request.headers();
+        let metadata = fs::metadata(file_path).context(ServeError::LoadFile)?;
+        let modified = metadata.modified().ok();
+        let mime_type = mime_type(file_path);
+        let compressible = is_compressible(mime_type);
+
+        // Ranges address the identity representation, so they are served
+        // uncompressed; error pages are never ranged or revalidated.
+        let ranged = status == StatusCode::OK && headers.contains_key(header::RANGE);
+        let encoding = match headers
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|v| v.to_str().ok())
+        {
+            Some(accept)
+                if compressible && !ranged && metadata.len() >= MIN_COMPRESS_BYTES as u64 =>
+            {
+                Encoding::negotiate(accept)
+            }
+            _ => Encoding::Identity,
+        };
+
+        let mtime_ns = modified
+            .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos());
+        let etag = match encoding {
+            Encoding::Identity => format!("\"{:x}-{mtime_ns:x}\"", metadata.len()),
+            _ => format!("\"{:x}-{mtime_ns:x}-{}\"", metadata.len(), encoding.token()),
+        };
+
+        let mut builder = Response::builder()
+            .header(header::CONTENT_TYPE, mime_type)
+            .header(header::CACHE_CONTROL, "no-cache");
+        if compressible {
+            builder = builder.header(header::VARY, "Accept-Encoding");
+        }
+        if status == StatusCode::OK {
+            builder = builder
+                .header(header::ETAG, &etag)
+                .header(header::ACCEPT_RANGES, "bytes");
+            if let Some(modified) = modified {
+                builder = builder.header(header::LAST_MODIFIED, httpdate::fmt_http_date(modified));
+            }
+            if Self::is_not_modified(headers, &etag, modified) {
+                return Ok(builder
+                    .status(StatusCode::NOT_MODIFIED)
+                    .body(Self::empty_body())
+                    .unwrap());
+            }
+        }
+
+        let mut contents = fs::read(file_path).context(ServeError::LoadFile)?;
+        if self.live_reload.is_some() && mime_type.starts_with("text/html") {
+            contents = LiveReload::inject(&contents);
+        }
+
+        let mut status = status;
+        if ranged {
+            let range = headers
+                .get(header::RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|spec| Self::parse_range(spec, contents.len()));
+            match range {
+                Some(Ok(range)) => {
+                    builder = builder.header(
+                        header::CONTENT_RANGE,
+                        format!("bytes {}-{}/{}", range.start, range.end - 1, contents.len()),
+                    );
+                    contents = contents[range].to_vec();
+                    status = StatusCode::PARTIAL_CONTENT;
+                }
+                Some(Err(())) => {
+                    return Ok(builder
+                        .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                        .header(header::CONTENT_RANGE, format!("bytes */{}", contents.len()))
+                        .body(Self::empty_body())
+                        .unwrap());
+                }
+                // Unparsable or multiple ranges: serve the whole file.
+                None => {}
+            }
+        } else if encoding != Encoding::Identity {
+            contents = encoding.encode(&contents)?;
+            builder = builder.header(header::CONTENT_ENCODING, encoding.token());
+        }
+
+        Ok(Self::with_body(
+            builder.status(status),
+            request.method(),
+            contents,
+        ))
     }
 
-    fn mk_page_response(
-        mime_type: &str,
-        file: &mut File,
-    ) -> Result<Response<Full<Bytes>>, ScrapsError> {
-        let mut contents = Vec::new();
-        let read = file
-            .read_to_end(&mut contents)
-            .context(ServeError::LoadFile);
+    /// `If-None-Match` takes precedence over `If-Modified-Since`, which has
+    /// one-second resolution.
+    fn is_not_modified(headers: &HeaderMap, etag: &str, modified: Option<SystemTime>) -> bool {
+        if let Some(if_none_match) = headers
+            .get(header::IF_NONE_MATCH)
+            .and_then(|v| v.to_str().ok())
+        {
+            return if_none_match
+                .split(',')
+                .map(|tag| tag.trim().trim_start_matches("W/"))
+                .any(|tag| tag == "*" || tag == etag);
+        }
+        let since = headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| httpdate::parse_http_date(v).ok());
+        match (since, modified) {
+            (Some(since), Some(modified)) => {
+                SystemTime::from(httpdate::HttpDate::from(modified)) <= since
+            }
+            _ => false,
+        }
+    }
 
-        match read {
-            Ok(_) => Self::mk_response(mime_type, contents),
-            _ => Self::mk_not_found_response(),
+    /// Parse a single `bytes=` range against a body of `len` bytes. `None`
+    /// when the header should be ignored, `Err` when it cannot be satisfied.
+    fn parse_range(spec: &str, len: usize) -> Option<Result<Range<usize>, ()>> {
+        let spec = spec.trim().strip_prefix("bytes=")?;
+        if spec.contains(',') {
+            return None;
+        }
+        let (start, end) = spec.split_once('-')?;
+        let (start, end) = match (start.trim(), end.trim()) {
+            ("", suffix) => {
+                let suffix: usize = suffix.parse().ok()?;
+                if suffix == 0 {
+                    return Some(Err(()));
+                }
+                (len.saturating_sub(suffix), len)
+            }
+            (start, "") => (start.parse().ok()?, len),
+            (start, end) => {
+                let (start, end): (usize, usize) = (start.parse().ok()?, end.parse().ok()?);
+                if end < start {
+                    return None;
+                }
+                (start, end.saturating_add(1).min(len))
+            }
+        };
+        if start >= len {
+            return Some(Err(()));
         }
+        Some(Ok(start..end))
+    }
+
+    fn empty_body() -> ResponseBody {
+        Empty::new().boxed()
+    }
+
+    fn with_body(
+        builder: hyper::http::response::Builder,
+        method: &Method,
+        contents: Vec<u8>,
+    ) -> Response<ResponseBody> {
+        let builder = builder.header(header::CONTENT_LENGTH, contents.len());
+        let body = if method == Method::HEAD {
+            Self::empty_body()
+        } else {
+            Full::new(Bytes::from(contents)).boxed()
+        };
+        builder.body(body).unwrap()
+    }
+
+    fn mk_status_response(status: StatusCode, method: &Method) -> Response<ResponseBody> {
+        let reason = status.canonical_reason().unwrap_or("Error");
+        Self::with_body(
+            Response::builder()
+                .status(status)
+                .header(header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            method,
+            reason.into(),
+        )
     }
 
-    fn gen_mime_type_from(file_path: &Path) -> &str {
-        match file_path.extension().and_then(|ext| ext.to_str()) {
-            Some("html") => "text/html",
-            Some("js") => "application/javascript",
-            Some("json") => "text/json",
-            Some("css") => "text/css",
-            Some("wasm") => "application/wasm",
-            _ => "application/octet-stream",
+    /// The site's own `404.html` when it has one, a plain 404 otherwise.
+    fn mk_not_found_response(&self, method: &Method) -> Response<ResponseBody> {
+        let page = self.output_dir_path.join(NOT_FOUND_PAGE);
+        if page.is_file() {
+            let request = Request::builder().method(method).body(()).unwrap();
+            if let Ok(response) = self.mk_file_response(&request, &page, StatusCode::NOT_FOUND) {
+                return response;
+            }
         }
+        Self::mk_status_response(StatusCode::NOT_FOUND, method)
+    }
+
+    fn mk_redirect_response(location: &str) -> Response<ResponseBody> {
+        Response::builder()
+            .status(StatusCode::FOUND)
+            .header(header::LOCATION, location)
+            .body(Self::empty_body())
+            .unwrap()
+    }
+
+    fn mk_event_stream_response(live_reload: &LiveReload) -> Response<ResponseBody> {
+        Response::builder()
+            .header(header::CONTENT_TYPE, "text/event-stream")
+            .header(header::CACHE_CONTROL, "no-cache")
+            .body(live_reload.subscribe().boxed())
+            .unwrap()
     }
 }
 
 impl Service<Request<Incoming>> for ScrapsService {
-    type Response = Response<Full<Bytes>>;
+    type Response = Response<ResponseBody>;
     type Error = ScrapsError;
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
     fn call(&self, request: Request<Incoming>) -> Self::Future {
-        let requested_path = request.uri().path().replacen('/', "", 1); // remove head absolute slash;
-        let allowed_path = self.output_dir_path.join(requested_path);
-        let resolved_index_path = if allowed_path.is_dir() {
-            allowed_path.join("index.html")
-        } else {
-            allowed_path
-        };
-        let decoded_file_name = {
-            let file_name = resolved_index_path
-                .file_name()
-                .map_or("", |s: &std::ffi::OsStr| s.to_str().expect(""));
-            percent_decode_str(file_name).decode_utf8()
-        };
-        let result = match decoded_file_name {
-            Ok(name) => {
-                let file_path = &resolved_index_path.with_file_name(name.to_string());
-                let file = File::open(file_path).context(ServeError::LoadFile);
-                let mime_type = Self::gen_mime_type_from(file_path.as_path());
-                match file {
-                    Ok(mut f) => Self::mk_page_response(mime_type, &mut f),
-                    Err(_) => Self::mk_not_found_response(),
-                }
-            }
-            Err(_) => Self::mk_failed_url_decode_response(),
+        let response = self.handle(&request);
+        Box::pin(async { Ok(response) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{simple_temp_dir, SimpleTempDir};
+    use rstest::rstest;
+    use std::io::Read;
+
+    fn service(temp_dir: &SimpleTempDir) -> ScrapsService {
+        temp_dir
+            .add_file("index.html", b"<html><body>home</body></html>")
+            .add_file("scraps/note.html", b"<p>note</p>")
+            .add_file("static/logo.svg", b"<svg/>")
+            .add_file("data.txt", b"0123456789");
+        ScrapsService::new(&temp_dir.path, "/", None)
+    }
+
+    fn get(path: &str, headers: &[(&str, &str)]) -> Request<()> {
+        let mut builder = Request::builder().uri(path);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(()).unwrap()
+    }
+
+    async fn body(response: Response<ResponseBody>) -> Vec<u8> {
+        response
+            .into_body()
+            .collect()
+            .await
+            .unwrap()
+            .to_bytes()
+            .to_vec()
+    }
+
+    fn header(response: &Response<ResponseBody>, name: header::HeaderName) -> &str {
+        response.headers()[name].to_str().unwrap()
+    }
+
+    #[rstest]
+    #[case::root("/", StatusCode::OK)]
+    #[case::file("/scraps/note.html", StatusCode::OK)]
+    #[case::encoded("/scraps/%6Eote.html", StatusCode::OK)]
+    #[case::missing("/scraps/missing.html", StatusCode::NOT_FOUND)]
+    #[case::parent("/scraps/../index.html", StatusCode::BAD_REQUEST)]
+    #[case::encoded_parent("/%2E%2E/etc/passwd", StatusCode::BAD_REQUEST)]
+    #[case::encoded_slash("/scraps%2F..%2F..%2Fsecret", StatusCode::BAD_REQUEST)]
+    #[case::invalid_utf8("/%FF", StatusCode::BAD_REQUEST)]
+    fn it_resolves_paths_inside_the_output_dir(
+        #[from(simple_temp_dir)] temp_dir: SimpleTempDir,
+        #[case] path: &str,
+        #[case] expected: StatusCode,
+    ) {
+        let service = service(&temp_dir);
+        assert_eq!(service.handle(&get(path, &[])).status(), expected);
+    }
+
+    #[rstest]
+    #[case::mounted("/wiki/scraps/note.html", StatusCode::OK)]
+    #[case::mount_index("/wiki/", StatusCode::OK)]
+    #[case::bare_mount("/wiki", StatusCode::FOUND)]
+    #[case::origin("/", StatusCode::FOUND)]
+    #[case::outside("/scraps/note.html", StatusCode::NOT_FOUND)]
+    fn it_serves_under_the_base_path(
+        #[from(simple_temp_dir)] temp_dir: SimpleTempDir,
+        #[case] path: &str,
+        #[case] expected: StatusCode,
+    ) {
+        let service = ScrapsService {
+            base_path: "/wiki/".to_string(),
+            ..service(&temp_dir)
         };
+        let response = service.handle(&get(path, &[]));
+        assert_eq!(response.status(), expected);
+        if expected == StatusCode::FOUND {
+            assert_eq!(header(&response, header::LOCATION), "/wiki/");
+        }
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_serves_the_custom_not_found_page(#[from(simple_temp_dir)] temp_dir: SimpleTempDir) {
+        let service = service(&temp_dir);
+        let plain = service.handle(&get("/missing", &[]));
+        assert_eq!(plain.status(), StatusCode::NOT_FOUND);
+        assert_eq!(body(plain).await, b"Not Found");
+
+        temp_dir.add_file("404.html", b"<h1>gone</h1>");
+        let custom = service.handle(&get("/missing", &[]));
+        assert_eq!(custom.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            header(&custom, header::CONTENT_TYPE),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(body(custom).await, b"<h1>gone</h1>");
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_answers_head_and_rejects_other_methods(
+        #[from(simple_temp_dir)] temp_dir: SimpleTempDir,
+    ) {
+        let service = service(&temp_dir);
+        let head = Request::head("/data.txt").body(()).unwrap();
+        let response = service.handle(&head);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(header(&response, header::CONTENT_LENGTH), "10");
+        assert!(body(response).await.is_empty());
+
+        let post = Request::post("/data.txt").body(()).unwrap();
+        let response = service.handle(&post);
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert_eq!(header(&response, header::ALLOW), "GET, HEAD");
+    }
+
+    #[rstest]
+    fn it_revalidates_with_etag_and_last_modified(
+        #[from(simple_temp_dir)] temp_dir: SimpleTempDir,
+    ) {
+        let service = service(&temp_dir);
+        let response = service.handle(&get("/static/logo.svg", &[]));
+        assert_eq!(header(&response, header::CONTENT_TYPE), "image/svg+xml");
+        let etag = header(&response, header::ETAG).to_string();
+        let last_modified = header(&response, header::LAST_MODIFIED).to_string();
+
+        let by_etag = service.handle(&get("/static/logo.svg", &[("if-none-match", &etag)]));
+        assert_eq!(by_etag.status(), StatusCode::NOT_MODIFIED);
+        let by_date = service.handle(&get(
+            "/static/logo.svg",
+            &[("if-modified-since", &last_modified)],
+        ));
+        assert_eq!(by_date.status(), StatusCode::NOT_MODIFIED);
+        let stale = service.handle(&get("/static/logo.svg", &[("if-none-match", "\"0-0\"")]));
+        assert_eq!(stale.status(), StatusCode::OK);
+    }
+
+    #[rstest]
+    #[case::bounded("bytes=2-4", StatusCode::PARTIAL_CONTENT, "234", Some("bytes 2-4/10"))]
+    #[case::open_ended("bytes=7-", StatusCode::PARTIAL_CONTENT, "789", Some("bytes 7-9/10"))]
+    #[case::suffix("bytes=-2", StatusCode::PARTIAL_CONTENT, "89", Some("bytes 8-9/10"))]
+    #[case::clamped("bytes=8-99", StatusCode::PARTIAL_CONTENT, "89", Some("bytes 8-9/10"))]
+    #[case::unsatisfiable("bytes=10-", StatusCode::RANGE_NOT_SATISFIABLE, "", Some("bytes */10"))]
+    #[case::multiple("bytes=0-1,4-5", StatusCode::OK, "0123456789", None)]
+    #[tokio::test]
+    async fn it_serves_byte_ranges(
+        #[from(simple_temp_dir)] temp_dir: SimpleTempDir,
+        #[case] range: &str,
+        #[case] status: StatusCode,
+        #[case] expected: &str,
+        #[case] content_range: Option<&str>,
+    ) {
+        let service = service(&temp_dir);
+        let response = service.handle(&get("/data.txt", &[("range", range)]));
+        assert_eq!(response.status(), status);
+        assert_eq!(
+            response
+                .headers()
+                .get(header::CONTENT_RANGE)
+                .map(|v| v.to_str().unwrap()),
+            content_range
+        );
+        assert_eq!(body(response).await, expected.as_bytes());
+    }
+
+    #[rstest]
+    #[case("gzip, deflate, br", Encoding::Brotli)]
+    #[case("gzip;q=1.0, br;q=0.5", Encoding::Gzip)]
+    #[case("br;q=0, gzip", Encoding::Gzip)]
+    #[case("*", Encoding::Brotli)]
+    #[case("deflate", Encoding::Identity)]
+    #[case("", Encoding::Identity)]
+    fn it_negotiates_encodings(#[case] accept: &str, #[case] expected: Encoding) {
+        assert_eq!(Encoding::negotiate(accept), expected);
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn it_compresses_text_responses(#[from(simple_temp_dir)] temp_dir: SimpleTempDir) {
+        let service = service(&temp_dir);
+        let text = "scraps ".repeat(100);
+        temp_dir.add_file("big.txt", text.as_bytes());
+
+        let gzip = service.handle(&get("/big.txt", &[("accept-encoding", "gzip")]));
+        assert_eq!(header(&gzip, header::CONTENT_ENCODING), "gzip");
+        assert_eq!(header(&gzip, header::VARY), "Accept-Encoding");
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&body(gzip).await[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
+
+        let br = service.handle(&get("/big.txt", &[("accept-encoding", "br")]));
+        assert_eq!(header(&br, header::CONTENT_ENCODING), "br");
+        let mut decoded = String::new();
+        brotli::Decompressor::new(&body(br).await[..], 4096)
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, text);
 
-        Box::pin(async { result })
+        // Too small to bother, and ranges stay uncompressed.
+        let small = service.handle(&get("/data.txt", &[("accept-encoding", "gzip")]));
+        assert!(small.headers().get(header::CONTENT_ENCODING).is_none());
+        let ranged = service.handle(&get(
+            "/big.txt",
+            &[("accept-encoding", "gzip"), ("range", "bytes=0-5")],
+        ));
+        assert!(ranged.headers().get(header::CONTENT_ENCODING).is_none());
     }
 }
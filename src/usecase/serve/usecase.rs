@@ -1,35 +1,84 @@
 use std::{
-    net::SocketAddr,
+    io::ErrorKind,
+    net::{SocketAddr, TcpListener as StdTcpListener},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
-use crate::usecase::serve::service::ScrapsService;
+use crate::usecase::serve::{live_reload::LiveReload, service::ScrapsService};
 use hyper::server::conn::http1;
 use hyper_util::rt::TokioIo;
 use tokio::net::TcpListener;
 
 use crate::error::ScrapsResult;
 
+/// How many ports after the requested one to try when it is busy.
+const PORT_FALLBACKS: u16 = 10;
+
 pub struct ServeUsecase {
     output_dir_path: PathBuf,
+    base_path: String,
+    live_reload: Option<Arc<LiveReload>>,
 }
 
 impl ServeUsecase {
     pub fn new(output_dir_path: &Path) -> ServeUsecase {
         ServeUsecase {
             output_dir_path: output_dir_path.to_path_buf(),
+            base_path: "/".to_string(),
+            live_reload: None,
         }
     }
 
+    /// Bind `addr`, moving on to the following ports while it is in use.
+    pub fn bind(addr: &SocketAddr) -> ScrapsResult<StdTcpListener> {
+        let mut candidate = *addr;
+        for _ in 0..=PORT_FALLBACKS {
+            match StdTcpListener::bind(candidate) {
+                Ok(listener) => return Ok(listener),
+                Err(e) if e.kind() == ErrorKind::AddrInUse && candidate.port() != 0 => {
+                    match candidate.port().checked_add(1) {
+                        Some(port) => candidate.set_port(port),
+                        None => return Err(e.into()),
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Err(anyhow::anyhow!(
+            "ports {} to {} are all in use",
+            addr.port(),
+            candidate.port() - 1
+        ))
+    }
+
+    /// Serve the site under `base_path` (e.g. `/wiki/`) instead of `/`.
+    pub fn with_base_path(mut self, base_path: &str) -> ServeUsecase {
+        self.base_path = base_path.to_string();
+        self
+    }
+
+    /// Inject the live reload client into HTML pages and serve its events.
+    pub fn with_live_reload(mut self, live_reload: Arc<LiveReload>) -> ServeUsecase {
+        self.live_reload = Some(live_reload);
+        self
+    }
+
     #[tokio::main]
-    pub async fn execute(&self, addr: &SocketAddr) -> ScrapsResult<()> {
-        let listener = TcpListener::bind(&addr).await?;
+    pub async fn execute(&self, listener: StdTcpListener) -> ScrapsResult<()> {
+        listener.set_nonblocking(true)?;
+        let listener = TcpListener::from_std(listener)?;
+        let service = ScrapsService::new(
+            &self.output_dir_path,
+            &self.base_path,
+            self.live_reload.clone(),
+        );
 
         loop {
             let (stream, _) = listener.accept().await?;
             let io = TokioIo::new(stream);
 
-            let service = ScrapsService::new(&self.output_dir_path);
+            let service = service.clone();
             tokio::task::spawn(async move {
                 if let Err(err) = http1::Builder::new().serve_connection(io, service).await {
                     println!("Failed to serve connection: {err:?}");
@@ -38,3 +87,17 @@ impl ServeUsecase {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_falls_back_to_the_next_free_port() {
+        let busy = StdTcpListener::bind("127.0.0.1:0").unwrap();
+        let busy_addr = busy.local_addr().unwrap();
+
+        let listener = ServeUsecase::bind(&busy_addr).unwrap();
+        assert!(listener.local_addr().unwrap().port() > busy_addr.port());
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use itertools::Itertools;
+use scraps_libs::markdown::query::TaskStatus;
+use scraps_libs::model::{context::Ctx, key::ScrapKey, scrap::Scrap, tags::Tags};
+
+use crate::error::ScrapsResult;
+use crate::usecase::build::model::backlinks_map::BacklinksMap;
+use crate::usecase::lint::rule::LintRule;
+use crate::usecase::lint::rules::{broken_link::BrokenLinkRule, lonely::LonelyRule};
+
+/// Number of hubs and authorities reported.
+const TOP_N: usize = 5;
+/// HITS power iterations. Wikis are small graphs; scores settle well before.
+const HITS_ITERATIONS: usize = 50;
+
+/// Health and growth metrics of a wiki, computed by `scraps stats`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiStats {
+    pub scrap_count: usize,
+    /// Scrap count per context, root scraps under `None`, sorted by context.
+    pub scraps_per_ctx: Vec<(Option<Ctx>, usize)>,
+    pub tag_count: usize,
+    /// Tag count per depth (`a` is 1, `a/b` is 2).
+    pub tag_depths: BTreeMap<usize, usize>,
+    pub total_links: usize,
+    pub broken_links: usize,
+    pub avg_in_degree: f64,
+    pub avg_out_degree: f64,
+    /// Scraps ranked by HITS hub score: they link to many good authorities.
+    pub hubs: Vec<(ScrapKey, f64)>,
+    /// Scraps ranked by HITS authority score: many good hubs link to them.
+    pub authorities: Vec<(ScrapKey, f64)>,
+    /// Scraps not linked from any other scrap (the `lonely` lint rule).
+    pub orphans: usize,
+    /// Size of the largest weakly connected component of the link graph.
+    pub largest_component: usize,
+    pub open_tasks: usize,
+    pub done_tasks: usize,
+    pub deferred_tasks: usize,
+    /// Whitespace-separated words of the markdown source, ignoring bare
+    /// punctuation such as list markers.
+    pub words: usize,
+}
+
+pub struct StatsUsecase;
+
+impl StatsUsecase {
+    pub fn new() -> StatsUsecase {
+        StatsUsecase
+    }
+
+    pub fn execute(&self, scraps: &[Scrap]) -> ScrapsResult<WikiStats> {
+        let backlinks_map = BacklinksMap::new(scraps);
+        let tags = Tags::new(scraps);
+        let keys: HashSet<ScrapKey> = scraps.iter().map(|s| s.self_key()).collect();
+
+        let scraps_per_ctx = scraps
+            .iter()
+            .map(|s| s.ctx().clone())
+            .counts()
+            .into_iter()
+            .sorted_by_key(|(ctx, _)| ctx.as_ref().map(|c| c.to_string()))
+            .collect_vec();
+        let tag_depths = tags
+            .iter()
+            .map(|t| t.depth())
+            .counts()
+            .into_iter()
+            .collect();
+
+        // Resolved edges only, each pair once: broken links are counted, not
+        // walked. Every edge leaves one scrap and enters another, so the
+        // average in- and out-degree agree.
+        let edges: Vec<(ScrapKey, ScrapKey)> = scraps
+            .iter()
+            .flat_map(|s| {
+                s.links()
+                    .iter()
+                    .filter(|l| keys.contains(l))
+                    .map(move |l| (s.self_key(), l.clone()))
+            })
+            .unique()
+            .collect();
+        let total_links: usize = scraps.iter().map(|s| s.links().len()).sum();
+        let avg_degree = if scraps.is_empty() {
+            0.0
+        } else {
+            edges.len() as f64 / scraps.len() as f64
+        };
+        let (avg_in_degree, avg_out_degree) = (avg_degree, avg_degree);
+
+        let (hubs, authorities) = hits(&keys, &edges);
+
+        let (mut open_tasks, mut done_tasks, mut deferred_tasks) = (0, 0, 0);
+        for item in scraps.iter().flat_map(|s| s.document().task_items()) {
+            match item.status {
+                TaskStatus::Open => open_tasks += 1,
+                TaskStatus::Done => done_tasks += 1,
+                TaskStatus::Deferred => deferred_tasks += 1,
+            }
+        }
+
+        Ok(WikiStats {
+            scrap_count: scraps.len(),
+            scraps_per_ctx,
+            tag_count: tags.len(),
+            tag_depths,
+            total_links,
+            broken_links: BrokenLinkRule.check(scraps, &backlinks_map, &tags).len(),
+            avg_in_degree,
+            avg_out_degree,
+            hubs,
+            authorities,
+            orphans: LonelyRule.check(scraps, &backlinks_map, &tags).len(),
+            largest_component: largest_component(&keys, &edges),
+            open_tasks,
+            done_tasks,
+            deferred_tasks,
+            words: scraps
+                .iter()
+                .map(|s| {
+                    s.md_text()
+                        .split_whitespace()
+                        .filter(|w| w.chars().any(char::is_alphanumeric))
+                        .count()
+                })
+                .sum(),
+        })
+    }
+}
+
+type Ranking = Vec<(ScrapKey, f64)>;
+
+/// Kleinberg's HITS over the resolved link graph. Returns the top hubs and
+/// authorities, skipping zero scores, ties broken by key.
+fn hits(keys: &HashSet<ScrapKey>, edges: &[(ScrapKey, ScrapKey)]) -> (Ranking, Ranking) {
+    let mut hub: HashMap<&ScrapKey, f64> = keys.iter().map(|k| (k, 1.0)).collect();
+    let mut auth: HashMap<&ScrapKey, f64> = keys.iter().map(|k| (k, 1.0)).collect();
+
+    for _ in 0..HITS_ITERATIONS {
+        let mut next_auth: HashMap<&ScrapKey, f64> = keys.iter().map(|k| (k, 0.0)).collect();
+        for (from, to) in edges {
+            *next_auth.get_mut(to).unwrap() += hub[from];
+        }
+        normalize(&mut next_auth);
+
+        let mut next_hub: HashMap<&ScrapKey, f64> = keys.iter().map(|k| (k, 0.0)).collect();
+        for (from, to) in edges {
+            *next_hub.get_mut(from).unwrap() += next_auth[to];
+        }
+        normalize(&mut next_hub);
+
+        auth = next_auth;
+        hub = next_hub;
+    }
+
+    (top(hub), top(auth))
+}
+
+fn normalize(scores: &mut HashMap<&ScrapKey, f64>) {
+    let norm = scores.values().map(|v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        scores.values_mut().for_each(|v| *v /= norm);
+    }
+}
+
+fn top(scores: HashMap<&ScrapKey, f64>) -> Ranking {
+    scores
+        .into_iter()
+        .filter(|(_, score)| *score > f64::EPSILON)
+        .sorted_by(|(ka, a), (kb, b)| b.total_cmp(a).then_with(|| ka.cmp(kb)))
+        .take(TOP_N)
+        .map(|(key, score)| (key.clone(), score))
+        .collect()
+}
+
+/// Size of the largest weakly connected component (links read undirected).
+fn largest_component(keys: &HashSet<ScrapKey>, edges: &[(ScrapKey, ScrapKey)]) -> usize {
+    let mut adjacency: HashMap<&ScrapKey, Vec<&ScrapKey>> = HashMap::new();
+    for (from, to) in edges {
+        adjacency.entry(from).or_default().push(to);
+        adjacency.entry(to).or_default().push(from);
+    }
+
+    let mut visited: HashSet<&ScrapKey> = HashSet::new();
+    let mut largest = 0;
+    for start in keys {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut size = 0;
+        let mut stack = vec![start];
+        while let Some(key) = stack.pop() {
+            size += 1;
+            for next in adjacency.get(key).into_iter().flatten() {
+                if visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        largest = largest.max(size);
+    }
+    largest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_execute() {
+        let scraps = vec![
+            Scrap::new("hub", &None, "[[a]] [[b]] [[missing]] #[[lang/rust]]"),
+            Scrap::new("a", &Some("Book".into()), "- [ ] read\n- [x] buy\n"),
+            Scrap::new("b", &None, "[[Book/a]] [[Book/a]] #[[lang]]"),
+            Scrap::new("island", &None, "- [-] someday"),
+        ];
+        // [[a]] resolves to nothing: `a` lives under `Book`.
+
+        let stats = StatsUsecase::new().execute(&scraps).unwrap();
+
+        assert_eq!(stats.scrap_count, 4);
+        assert_eq!(
+            stats.scraps_per_ctx,
+            vec![(None, 3), (Some(Ctx::from("Book")), 1)]
+        );
+        assert_eq!(stats.tag_count, 2);
+        assert_eq!(stats.tag_depths, BTreeMap::from([(1, 1), (2, 1)]));
+        assert_eq!(stats.total_links, 4);
+        assert_eq!(stats.broken_links, 2);
+        // hub -> b and b -> Book/a, the repeated link counted once.
+        assert_eq!(stats.avg_out_degree, 0.5);
+        assert_eq!(stats.avg_in_degree, 0.5);
+        assert_eq!(stats.orphans, 2);
+        assert_eq!(stats.largest_component, 3);
+        assert_eq!(
+            (stats.open_tasks, stats.done_tasks, stats.deferred_tasks),
+            (1, 1, 1)
+        );
+        assert_eq!(stats.words, 11);
+    }
+
+    #[test]
+    fn it_ranks_hubs_and_authorities() {
+        let scraps = vec![
+            Scrap::new("index", &None, "[[x]] [[y]] [[z]]"),
+            Scrap::new("other", &None, "[[x]]"),
+            Scrap::new("x", &None, ""),
+            Scrap::new("y", &None, ""),
+            Scrap::new("z", &None, ""),
+        ];
+
+        let stats = StatsUsecase::new().execute(&scraps).unwrap();
+
+        assert_eq!(stats.hubs[0].0.to_string(), "index");
+        assert_eq!(stats.hubs.len(), 2);
+        assert_eq!(stats.authorities[0].0.to_string(), "x");
+        assert_eq!(stats.authorities.len(), 3);
+    }
+
+    #[test]
+    fn it_execute_empty() {
+        let stats = StatsUsecase::new().execute(&[]).unwrap();
+
+        assert_eq!(stats.scrap_count, 0);
+        assert_eq!(stats.avg_in_degree, 0.0);
+        assert_eq!(stats.largest_component, 0);
+        assert!(stats.hubs.is_empty());
+    }
+}
@@ -1,5 +1,5 @@
 use crate::error::ScrapsResult;
-use scraps_libs::markdown::query::{task_items, TaskStatus};
+use scraps_libs::markdown::query::TaskStatus;
 use scraps_libs::model::context::Ctx;
 use scraps_libs::model::scrap::Scrap;
 use scraps_libs::model::title::Title;
@@ -15,13 +15,13 @@ pub enum StatusFilter {
 
 impl StatusFilter {
     fn matches(self, status: &TaskStatus) -> bool {
-        match (self, status) {
-            (StatusFilter::All, _) => true,
-            (StatusFilter::Open, TaskStatus::Open) => true,
-            (StatusFilter::Done, TaskStatus::Done) => true,
-            (StatusFilter::Deferred, TaskStatus::Deferred) => true,
-            _ => false,
-        }
+        matches!(
+            (self, status),
+            (StatusFilter::All, _)
+                | (StatusFilter::Open, TaskStatus::Open)
+                | (StatusFilter::Done, TaskStatus::Done)
+                | (StatusFilter::Deferred, TaskStatus::Deferred)
+        )
     }
 }
 
@@ -50,15 +50,15 @@ impl TodoUsecase {
         let mut results: Vec<TodoResult> = Vec::new();
 
         for scrap in scraps {
-            for item in task_items(scrap.md_text()) {
+            for item in scrap.document().task_items() {
                 if !status_filter.matches(&item.status) {
                     continue;
                 }
                 results.push(TodoResult {
                     title: scrap.title().clone(),
                     ctx: scrap.ctx().clone(),
-                    status: item.status,
-                    text: item.text,
+                    status: item.status.clone(),
+                    text: item.text.clone(),
                     line: item.line,
                 });
             }